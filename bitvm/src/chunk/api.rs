@@ -1,51 +1,80 @@
+use crate::bn254::fp254impl::Fp254Impl;
+use crate::bn254::fr::Fr;
+use crate::bn254::msm::{BATCH_SIZE_PER_CHUNK, WINDOW_G1_MSM};
 use crate::chunk::api_compiletime_utils::{
     append_bitcom_locking_script_to_partial_scripts, generate_partial_script,
     generate_segments_using_mock_vk_and_mock_proof, partial_scripts_from_segments,
 };
 use crate::chunk::api_runtime_utils::{
     execute_script_from_signature, get_assertion_from_segments, get_assertions_from_signature,
-    get_segments_from_assertion, get_segments_from_groth16_proof,
+    get_segments_from_assertion, get_segments_from_groth16_proof, utils_deserialize_assertions,
 };
 
 use crate::signatures::{Wots, Wots16, Wots32};
-use crate::treepp::*;
 use ark_bn254::Bn254;
 use ark_ec::bn::Bn;
 use bitcoin::ScriptBuf;
 
-use super::api_runtime_utils::{
-    execute_script_from_assertion, get_pubkeys, get_signature_from_assertion,
-};
+use super::api_runtime_utils::{execute_script_from_assertion, get_signature_from_assertion};
+use super::checkpoint::ChunkCheckpoint;
+use super::keys::ChunkSecretKeys;
+use super::progress::{ChunkProgress, NoProgress};
+use super::report::DisproveReport;
 use super::wrap_hasher::BLAKE3_HASH_LENGTH;
 
-pub const NUM_PUBS: usize = 1;
 pub const NUM_U256: usize = 14;
-pub const NUM_HASH: usize = 363;
+// number of intermediate hashes that do not depend upon the number of public inputs
+const NUM_HASH_WITHOUT_MSM: usize = 359;
+// each public input is multiplied with its base from the verifying key over these many tapscripts
+pub const NUM_MSM_TAPS_PER_PUB: usize =
+    <Fr as Fp254Impl>::N_BITS.div_ceil(WINDOW_G1_MSM * BATCH_SIZE_PER_CHUNK) as usize;
 pub const VALIDATING_TAPS: usize = 1;
-pub const HASHING_TAPS: usize = NUM_HASH;
-pub const NUM_TAPS: usize = HASHING_TAPS + VALIDATING_TAPS;
 
-pub type PublicInputs = [ark_bn254::Fr; NUM_PUBS];
+// number of groth16 public inputs the verifying key has been generated for
+pub fn num_pubs(vk: &ark_groth16::VerifyingKey<Bn254>) -> usize {
+    assert!(
+        !vk.gamma_abc_g1.is_empty(),
+        "verifying key should include the constant term of the public input msm"
+    );
+    vk.gamma_abc_g1.len() - 1
+}
+
+pub const fn num_hash(num_pubs: usize) -> usize {
+    NUM_HASH_WITHOUT_MSM + num_pubs * NUM_MSM_TAPS_PER_PUB
+}
+
+pub const fn num_taps(num_pubs: usize) -> usize {
+    num_hash(num_pubs) + VALIDATING_TAPS
+}
+
+// number of bitcommitted values i.e. public inputs, proof elements and intermediate hashes
+pub const fn num_assertions(num_pubs: usize) -> usize {
+    num_pubs + NUM_U256 + num_hash(num_pubs)
+}
+
+pub type PublicInputs = Vec<ark_bn254::Fr>;
 
+// PublicKeys, Signatures and Assertions are collected in order [PublicInputs, ProofInputs, IntermediateHashes]
+// lengths of the first and the last group depend upon the number of public inputs, see num_pubs() and num_hash()
 pub type PublicKeys = (
-    [<Wots32 as Wots>::PublicKey; NUM_PUBS],
+    Vec<<Wots32 as Wots>::PublicKey>,
     [<Wots32 as Wots>::PublicKey; NUM_U256],
-    [<Wots16 as Wots>::PublicKey; NUM_HASH],
+    Vec<<Wots16 as Wots>::PublicKey>,
 );
 
 pub type Signatures = (
-    Box<[<Wots32 as Wots>::Signature; NUM_PUBS]>,
+    Box<[<Wots32 as Wots>::Signature]>,
     Box<[<Wots32 as Wots>::Signature; NUM_U256]>,
-    Box<[<Wots16 as Wots>::Signature; NUM_HASH]>,
+    Box<[<Wots16 as Wots>::Signature]>,
 );
 
 pub type Assertions = (
-    [[u8; 32]; NUM_PUBS],
+    Vec<[u8; 32]>,
     [[u8; 32]; NUM_U256],
-    [[u8; BLAKE3_HASH_LENGTH]; NUM_HASH],
+    Vec<[u8; BLAKE3_HASH_LENGTH]>,
 );
 
-pub fn api_get_signature_from_assertion(assn: Assertions, secrets: Vec<String>) -> Signatures {
+pub fn api_get_signature_from_assertion(assn: Assertions, secrets: &ChunkSecretKeys) -> Signatures {
     get_signature_from_assertion(assn, secrets)
 }
 
@@ -57,7 +86,7 @@ pub mod type_conversion_utils {
     use super::*;
     use crate::chunk::api::Signatures;
     use crate::{
-        chunk::api::{NUM_HASH, NUM_PUBS, NUM_U256},
+        chunk::api::{num_assertions, num_hash, NUM_U256},
         execute_script,
         signatures::GenericWinternitzPublicKey,
         treepp::Script,
@@ -87,36 +116,41 @@ pub mod type_conversion_utils {
     }
 
     #[allow(clippy::needless_range_loop)]
-    pub fn utils_signatures_from_raw_witnesses(raw_wits: &[RawWitness]) -> Signatures {
-        assert_eq!(raw_wits.len(), NUM_PUBS + NUM_U256 + NUM_HASH);
+    pub fn utils_signatures_from_raw_witnesses(
+        raw_wits: &[RawWitness],
+        num_pubs: usize,
+    ) -> Signatures {
+        assert_eq!(raw_wits.len(), num_assertions(num_pubs));
         let mut asigs = vec![];
-        for i in 0..NUM_PUBS {
+        for i in 0..num_pubs {
             let a = Wots32::raw_witness_to_signature(&Witness::from_slice(&raw_wits[i]));
             asigs.push(a);
         }
         let mut bsigs = vec![];
         for i in 0..NUM_U256 {
-            let a = Wots32::raw_witness_to_signature(&Witness::from_slice(&raw_wits[i + NUM_PUBS]));
+            let a = Wots32::raw_witness_to_signature(&Witness::from_slice(&raw_wits[i + num_pubs]));
             bsigs.push(a);
         }
         let mut csigs = vec![];
-        for i in 0..NUM_HASH {
+        for i in 0..num_hash(num_pubs) {
             let a = Wots16::raw_witness_to_signature(&Witness::from_slice(
-                &raw_wits[i + NUM_PUBS + NUM_U256],
+                &raw_wits[i + num_pubs + NUM_U256],
             ));
             csigs.push(a);
         }
-        let asigs = asigs.try_into().unwrap();
-        let bsigs = bsigs.try_into().unwrap();
-        let csigs = csigs.try_into().unwrap();
-        (asigs, bsigs, csigs)
+        let bsigs: [<Wots32 as Wots>::Signature; NUM_U256] = bsigs.try_into().unwrap();
+        (
+            asigs.into_boxed_slice(),
+            Box::new(bsigs),
+            csigs.into_boxed_slice(),
+        )
     }
 
     pub fn utils_raw_witnesses_from_signatures(signatures: &Signatures) -> Vec<RawWitness> {
         // Assume Signatures is a tuple: (asigs, bsigs, csigs) where:
-        // - asigs: Vec<wots256::Signature> of length NUM_PUBS
+        // - asigs: Vec<wots256::Signature> of length num_pubs
         // - bsigs: Vec<wots256::Signature> of length NUM_U256
-        // - csigs: Vec<wots_hash::Signature> of length NUM_HASH (or NUM_PUBS if they are equal)
+        // - csigs: Vec<wots_hash::Signature> of length num_hash(num_pubs)
         let (asigs, bsigs, csigs) = signatures;
         let mut raw_wits = Vec::with_capacity(asigs.len() + bsigs.len() + csigs.len());
 
@@ -144,37 +178,71 @@ pub mod type_conversion_utils {
 
     pub fn utils_typed_pubkey_from_raw(
         commits_public_keys: Vec<&GenericWinternitzPublicKey>,
+        num_pubs: usize,
     ) -> PublicKeys {
         let mut apubs = vec![];
         let mut bpubs = vec![];
         let mut cpubs = vec![];
         for (idx, f) in commits_public_keys.into_iter().enumerate() {
-            if idx < NUM_PUBS {
+            if idx < num_pubs {
                 let p: <Wots32 as Wots>::PublicKey = f.clone().try_into().unwrap();
                 apubs.push(p);
-            } else if idx < NUM_PUBS + NUM_U256 {
+            } else if idx < num_pubs + NUM_U256 {
                 let p: <Wots32 as Wots>::PublicKey = f.clone().try_into().unwrap();
                 bpubs.push(p);
-            } else if idx < NUM_PUBS + NUM_U256 + NUM_HASH {
+            } else if idx < num_assertions(num_pubs) {
                 let p: <Wots16 as Wots>::PublicKey = f.clone().try_into().unwrap();
                 cpubs.push(p);
             }
         }
+        assert_eq!(apubs.len(), num_pubs);
+        assert_eq!(cpubs.len(), num_hash(num_pubs));
 
-        let pks: PublicKeys = (
-            apubs.try_into().unwrap(),
-            bpubs.try_into().unwrap(),
-            cpubs.try_into().unwrap(),
-        );
+        let pks: PublicKeys = (apubs, bpubs.try_into().unwrap(), cpubs);
         pks
     }
 }
 
+// Optional behaviour of the long running steps. Results stored in `checkpoint` let an
+// interrupted run resume from the segments processed so far, `progress` is told about every
+// segment once it is processed.
+#[derive(Clone, Copy)]
+pub struct ChunkOptions<'a> {
+    pub checkpoint: Option<&'a ChunkCheckpoint>,
+    pub progress: &'a dyn ChunkProgress,
+}
+
+impl Default for ChunkOptions<'_> {
+    fn default() -> Self {
+        Self {
+            checkpoint: None,
+            progress: &NoProgress,
+        }
+    }
+}
+
+impl<'a> ChunkOptions<'a> {
+    pub fn with_checkpoint(checkpoint: &'a ChunkCheckpoint) -> Self {
+        Self {
+            checkpoint: Some(checkpoint),
+            ..Default::default()
+        }
+    }
+}
+
 // Step 1
 // The function takes public parameters (here verifying key) and generates partial script
 // partial script is essentially disprove script minus the bitcommitment locking script
 pub fn api_generate_partial_script(vk: &ark_groth16::VerifyingKey<Bn254>) -> Vec<ScriptBuf> {
-    generate_partial_script(vk)
+    api_generate_partial_script_with_options(vk, ChunkOptions::default())
+}
+
+// Step 1, see ChunkOptions
+pub fn api_generate_partial_script_with_options(
+    vk: &ark_groth16::VerifyingKey<Bn254>,
+    options: ChunkOptions,
+) -> Vec<ScriptBuf> {
+    generate_partial_script(vk, options.checkpoint, options.progress)
 }
 
 // Step 2
@@ -184,15 +252,36 @@ pub fn api_generate_full_tapscripts(
     inpubkeys: PublicKeys,
     ops_scripts_per_link: &[ScriptBuf],
 ) -> Vec<ScriptBuf> {
-    println!("api_generate_full_tapscripts; append_bitcom_locking_script_to_partial_scripts");
-    let taps_per_link =
-        append_bitcom_locking_script_to_partial_scripts(inpubkeys, ops_scripts_per_link.to_vec());
+    api_generate_full_tapscripts_with_options(
+        inpubkeys,
+        ops_scripts_per_link,
+        ChunkOptions::default(),
+    )
+}
+
+// Step 2, see ChunkOptions
+#[tracing::instrument(name = "api_generate_full_tapscripts", skip_all)]
+pub fn api_generate_full_tapscripts_with_options(
+    inpubkeys: PublicKeys,
+    ops_scripts_per_link: &[ScriptBuf],
+    options: ChunkOptions,
+) -> Vec<ScriptBuf> {
+    let pass = options
+        .checkpoint
+        .map(|checkpoint| checkpoint.tapscripts_pass(&inpubkeys, ops_scripts_per_link));
+    let taps_per_link = append_bitcom_locking_script_to_partial_scripts(
+        inpubkeys,
+        ops_scripts_per_link.to_vec(),
+        pass.as_ref(),
+        options.progress,
+    );
     assert_eq!(ops_scripts_per_link.len(), taps_per_link.len());
     taps_per_link
 }
 
 // Step 3
 // given public and runtime parameters (proof and scalars) generate Assertions
+#[tracing::instrument(skip_all)]
 pub fn generate_assertions(
     proof: ark_groth16::Proof<Bn<ark_bn254::Config>>,
     scalars: Vec<ark_bn254::Fr>,
@@ -204,11 +293,11 @@ pub fn generate_assertions(
     }
     assert!(success);
     let assts = get_assertion_from_segments(&segments);
-    let exec_res = execute_script_from_assertion(&segments, assts);
+    let exec_res = execute_script_from_assertion(&segments, assts.clone());
 
     if let Some(fault) = exec_res {
-        println!(
-            "generate_assertions; execute_script_from_assertion return fault at script index {}",
+        tracing::warn!(
+            "execute_script_from_assertion return fault at script index {}",
             fault.0
         );
         return Err(format!(
@@ -216,7 +305,7 @@ pub fn generate_assertions(
             fault.0
         ));
     } else {
-        println!("generate_assertions; validated assertion by executing all scripts");
+        tracing::info!("validated assertion by executing all scripts");
     }
     Ok(assts)
 }
@@ -227,33 +316,68 @@ pub fn generate_signatures(
     proof: ark_groth16::Proof<Bn<ark_bn254::Config>>,
     scalars: Vec<ark_bn254::Fr>,
     vk: &ark_groth16::VerifyingKey<Bn254>,
-    secrets: Vec<String>,
+    secrets: &ChunkSecretKeys,
 ) -> Result<Signatures, String> {
-    println!("generate_signatures; get_segments_from_groth16_proof");
+    generate_signatures_with_options(proof, scalars, vk, secrets, ChunkOptions::default())
+}
+
+// Alternate Step 3, see ChunkOptions: the scripts are generated and executed to validate the
+// signatures
+#[tracing::instrument(name = "generate_signatures", skip_all)]
+pub fn generate_signatures_with_options(
+    proof: ark_groth16::Proof<Bn<ark_bn254::Config>>,
+    scalars: Vec<ark_bn254::Fr>,
+    vk: &ark_groth16::VerifyingKey<Bn254>,
+    secrets: &ChunkSecretKeys,
+    options: ChunkOptions,
+) -> Result<Signatures, String> {
+    let ChunkOptions {
+        checkpoint,
+        progress,
+    } = options;
+    let num_pubs = num_pubs(vk);
+    if secrets.num_pubs() != num_pubs {
+        return Err(format!(
+            "generate_signatures; secret keys for {} public inputs, verifying key has {}",
+            secrets.num_pubs(),
+            num_pubs
+        ));
+    }
+    tracing::debug!("get_segments_from_groth16_proof");
     let (success, segments) = get_segments_from_groth16_proof(proof, scalars, vk);
     if !success {
         return Err(format!("generate_signatures; get_segments_from_groth16_proof; success false; num_aggregated segments {}", segments.len()));
     }
-    println!("generate_signatures; get_assertion_from_segments");
+    tracing::debug!("get_assertion_from_segments");
     let assn = get_assertion_from_segments(&segments);
-    println!("generate_signatures; get_signature_from_assertion");
-    let sigs = get_signature_from_assertion(assn, secrets.clone());
-    println!("generate_signatures; get_pubkeys");
-    let pubkeys = get_pubkeys(secrets);
-
-    println!("generate_signatures; partial_scripts_from_segments");
-    let partial_scripts: Vec<ScriptBuf> = partial_scripts_from_segments(&segments);
-    let partial_scripts: [ScriptBuf; NUM_TAPS] = partial_scripts.try_into().unwrap();
-    println!("generate_signatures; append_bitcom_locking_script_to_partial_scripts");
-    let disprove_scripts =
-        append_bitcom_locking_script_to_partial_scripts(pubkeys, partial_scripts.to_vec());
-    let disprove_scripts: [ScriptBuf; NUM_TAPS] = disprove_scripts.try_into().unwrap();
-
-    println!("generate_signatures; execute_script_from_signature");
-    let exec_res = execute_script_from_signature(&segments, sigs.clone(), &disprove_scripts);
+    let sigs = get_signature_from_assertion(assn, secrets);
+    let pubkeys: PublicKeys = secrets.public_keys().into();
+
+    tracing::debug!("partial_scripts_from_segments");
+    let pass = checkpoint.map(|checkpoint| checkpoint.partial_scripts_pass(vk));
+    let partial_scripts: Vec<ScriptBuf> =
+        partial_scripts_from_segments(&segments, pass.as_ref(), progress);
+    assert_eq!(partial_scripts.len(), num_taps(num_pubs));
+    let pass = checkpoint.map(|checkpoint| checkpoint.tapscripts_pass(&pubkeys, &partial_scripts));
+    let disprove_scripts = append_bitcom_locking_script_to_partial_scripts(
+        pubkeys,
+        partial_scripts,
+        pass.as_ref(),
+        progress,
+    );
+
+    tracing::debug!("execute_script_from_signature");
+    let pass = checkpoint.map(|checkpoint| checkpoint.validation_pass(&sigs, &disprove_scripts));
+    let exec_res = execute_script_from_signature(
+        &segments,
+        sigs.clone(),
+        &disprove_scripts,
+        pass.as_ref(),
+        progress,
+    );
     if let Some(fault) = exec_res {
-        println!(
-            "generate_signatures; execute_script_from_assertion return fault at script index {}",
+        tracing::warn!(
+            "execute_script_from_assertion return fault at script index {}",
             fault.0
         );
         return Err(format!(
@@ -261,87 +385,142 @@ pub fn generate_signatures(
             fault.0
         ));
     } else {
-        println!("generate_signatures; validated assertion by executing all scripts");
+        tracing::info!("validated assertion by executing all scripts");
     }
     Ok(sigs)
 }
 
 // Step 4
 // validate signed assertions
-// returns index of disprove script generated in Step 2
-// and the witness required to execute this Disprove Script incase of failure
+// returns a report on the disprove script generated in Step 2 that fails the assertions,
+// including the witness required to execute this Disprove Script incase of failure
 pub fn validate_assertions(
+    vk: &ark_groth16::VerifyingKey<Bn254>,
+    signed_asserts: Signatures,
+    inpubkeys: PublicKeys,
+    disprove_scripts: &[ScriptBuf],
+) -> Option<DisproveReport> {
+    validate_assertions_with_options(
+        vk,
+        signed_asserts,
+        inpubkeys,
+        disprove_scripts,
+        ChunkOptions::default(),
+    )
+}
+
+// Step 4, see ChunkOptions: the checkpoint records the disprove scripts already executed
+#[tracing::instrument(name = "validate_assertions", skip_all)]
+pub fn validate_assertions_with_options(
     vk: &ark_groth16::VerifyingKey<Bn254>,
     signed_asserts: Signatures,
     _inpubkeys: PublicKeys,
-    disprove_scripts: &[ScriptBuf; NUM_TAPS],
-) -> Option<(usize, Script)> {
-    println!("validate_assertions; get_assertions_from_signature");
+    disprove_scripts: &[ScriptBuf],
+    options: ChunkOptions,
+) -> Option<DisproveReport> {
+    let ChunkOptions {
+        checkpoint,
+        progress,
+    } = options;
+    assert_eq!(disprove_scripts.len(), num_taps(num_pubs(vk)));
     let asserts = get_assertions_from_signature(signed_asserts.clone());
-    println!("validate_assertions; get_segments_from_assertion");
+    let (state_pubs, state_fqs, state_hashes) = utils_deserialize_assertions(asserts.clone());
+    let asserted_states = [state_pubs, state_fqs.to_vec(), state_hashes].concat();
+    tracing::debug!("get_segments_from_assertion");
     let (success, segments) = get_segments_from_assertion(asserts, vk.clone());
     if !success {
-        println!("invalid tapscript at segment {}", segments.len());
+        tracing::info!("invalid tapscript at segment {}", segments.len());
     }
-    println!("validate_assertions; execute_script_from_signature");
-    let exec_result = execute_script_from_signature(&segments, signed_asserts, disprove_scripts);
+    tracing::debug!("execute_script_from_signature");
+    let pass =
+        checkpoint.map(|checkpoint| checkpoint.validation_pass(&signed_asserts, disprove_scripts));
+    let exec_result = execute_script_from_signature(
+        &segments,
+        signed_asserts,
+        disprove_scripts,
+        pass.as_ref(),
+        progress,
+    );
     assert_eq!(
         success,
         exec_result.is_none(),
         "ensure script execution matches rust execution match"
     );
-    exec_result
+    exec_result.map(|(tapscript_index, hint_script)| {
+        let report = DisproveReport::new(
+            &segments,
+            &asserted_states,
+            tapscript_index,
+            hint_script,
+            disprove_scripts,
+        );
+        tracing::info!(
+            tapscript_index,
+            script_type = ?report.script_type,
+            reason = ?report.reason,
+            "assertions disproved"
+        );
+        report
+    })
 }
 
 // doesn't crash even if the proof may be incorrect
 // should be used only for test purposes,
 // as in production, its best to throw error
 // if assertion is invalid <- always assuming honest operator
+#[tracing::instrument(skip_all)]
 pub fn generate_signatures_for_any_proof(
     proof: ark_groth16::Proof<Bn<ark_bn254::Config>>,
     scalars: Vec<ark_bn254::Fr>,
     vk: &ark_groth16::VerifyingKey<Bn254>,
-    secrets: Vec<String>,
+    secrets: &ChunkSecretKeys,
 ) -> Signatures {
-    println!("generate_signatures; get_segments_from_groth16_proof");
+    let num_pubs = num_pubs(vk);
+    assert_eq!(secrets.num_pubs(), num_pubs);
+    tracing::debug!("get_segments_from_groth16_proof");
     let (success, mut segments) = get_segments_from_groth16_proof(proof, scalars, vk);
-    if segments.len() != NUM_PUBS + NUM_U256 + NUM_HASH + VALIDATING_TAPS {
-        let mock_segments = generate_segments_using_mock_vk_and_mock_proof();
+    if segments.len() != num_assertions(num_pubs) + VALIDATING_TAPS {
+        let mock_segments = generate_segments_using_mock_vk_and_mock_proof(num_pubs);
         segments.extend_from_slice(&mock_segments[segments.len()..]);
     }
 
-    println!(
-        "generate_signatures; get_segments_from_groth16_proof {}",
-        success
+    tracing::debug!(
+        "get_segments_from_groth16_proof {}; segments len {}",
+        success,
+        segments.len()
     );
-    println!("generate_signatures; segments len{}", segments.len());
-    println!("generate_signatures; get_assertion_from_segments");
+    tracing::debug!("get_assertion_from_segments");
     let assn = get_assertion_from_segments(&segments);
-    println!("generate_signatures; get_signature_from_assertion");
-    let sigs = get_signature_from_assertion(assn, secrets.clone());
-    println!("generate_signatures; get_pubkeys");
-    let pubkeys = get_pubkeys(secrets);
-
-    println!("generate_signatures; partial_scripts_from_segments");
-    let partial_scripts: Vec<ScriptBuf> = partial_scripts_from_segments(&segments)
-        .into_iter()
-        .collect();
-    let partial_scripts: [ScriptBuf; NUM_TAPS] = partial_scripts.try_into().unwrap();
-    println!("generate_signatures; append_bitcom_locking_script_to_partial_scripts");
-    let disprove_scripts =
-        append_bitcom_locking_script_to_partial_scripts(pubkeys, partial_scripts.to_vec());
-    let disprove_scripts: [ScriptBuf; NUM_TAPS] = disprove_scripts.try_into().unwrap();
-
-    println!("generate_signatures; execute_script_from_signature");
-    let exec_res = execute_script_from_signature(&segments, sigs.clone(), &disprove_scripts);
+    let sigs = get_signature_from_assertion(assn, secrets);
+    let pubkeys: PublicKeys = secrets.public_keys().into();
+
+    tracing::debug!("partial_scripts_from_segments");
+    let partial_scripts: Vec<ScriptBuf> =
+        partial_scripts_from_segments(&segments, None, &NoProgress);
+    assert_eq!(partial_scripts.len(), num_taps(num_pubs));
+    let disprove_scripts = append_bitcom_locking_script_to_partial_scripts(
+        pubkeys,
+        partial_scripts,
+        None,
+        &NoProgress,
+    );
+
+    tracing::debug!("execute_script_from_signature");
+    let exec_res = execute_script_from_signature(
+        &segments,
+        sigs.clone(),
+        &disprove_scripts,
+        None,
+        &NoProgress,
+    );
     if exec_res.is_some() {
         let fault = exec_res.unwrap();
-        println!(
+        tracing::info!(
             "execute_script_from_assertion return fault at script index {}",
             fault.0
         );
     } else {
-        println!("generate_signatures; validated signatures by executing all scripts");
+        tracing::info!("validated signatures by executing all scripts");
     }
     sigs
 }
@@ -357,36 +536,42 @@ mod test {
     };
     use crate::chunk::wrap_hasher::BLAKE3_HASH_LENGTH;
     use ark_bn254::Bn254;
+    use ark_crypto_primitives::snark::{CircuitSpecificSetupSNARK, SNARK};
     use ark_ff::UniformRand;
+    use ark_groth16::Groth16;
+    use ark_relations::{
+        lc,
+        r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError, Variable},
+    };
     use ark_serialize::CanonicalDeserialize;
-    use bitcoin::ScriptBuf;
     use rand::{Rng, SeedableRng};
     use rand_chacha::ChaCha20Rng;
     use test_utils::{
         read_asserts_from_file, read_scripts_from_file, write_asserts_to_file,
-        write_scripts_to_file, write_scripts_to_separate_files,
+        write_scripts_to_separate_files,
     };
 
+    use super::type_conversion_utils::RawProof;
     use super::Signatures;
-    use crate::signatures::{Wots, Wots16, Wots32};
+    use crate::chunk::checkpoint::ChunkCheckpoint;
+    use crate::chunk::keys::ChunkSecretKeys;
     use crate::{
         chunk::{
             api::{
-                api_generate_full_tapscripts, api_generate_partial_script, generate_assertions,
-                generate_signatures, validate_assertions, Assertions,
-            },
-            api::{NUM_HASH, NUM_PUBS, NUM_TAPS, NUM_U256},
-            api_runtime_utils::{
-                get_assertions_from_signature, get_pubkeys, get_signature_from_assertion,
+                api_generate_full_tapscripts, api_generate_full_tapscripts_with_options,
+                api_generate_partial_script, api_generate_partial_script_with_options,
+                generate_assertions, generate_signatures, num_assertions, num_hash, num_pubs,
+                num_taps, validate_assertions, Assertions, ChunkOptions, PublicKeys, NUM_U256,
             },
+            api_runtime_utils::{get_assertions_from_signature, get_signature_from_assertion},
+            report::DisproveReason,
         },
-        execute_script,
+        execute_raw_script_with_inputs,
     };
 
     mod test_utils {
+        use crate::chunk::api::num_hash;
         use crate::chunk::api::Assertions;
-        use crate::chunk::api::NUM_HASH;
-        use crate::chunk::api::NUM_PUBS;
         use crate::chunk::api::NUM_U256;
         use crate::chunk::wrap_hasher::BLAKE3_HASH_LENGTH;
         use bitcoin::ScriptBuf;
@@ -419,15 +604,6 @@ mod test {
             Ok(map)
         }
 
-        pub fn write_scripts_to_file(sig_cache: HashMap<u32, Vec<ScriptBuf>>, file: &str) {
-            let mut buf: HashMap<u32, Vec<Vec<u8>>> = HashMap::new();
-            for (k, v) in sig_cache {
-                let vs = v.into_iter().map(|x| x.to_bytes()).collect();
-                buf.insert(k, vs);
-            }
-            write_map_to_file(&buf, file).unwrap();
-        }
-
         pub fn write_scripts_to_separate_files(
             sig_cache: HashMap<u32, Vec<ScriptBuf>>,
             file: &str,
@@ -480,33 +656,31 @@ mod test {
         }
 
         #[allow(clippy::needless_range_loop)]
-        pub fn read_asserts_from_file(filename: &str) -> Assertions {
+        pub fn read_asserts_from_file(filename: &str, num_pubs: usize) -> Assertions {
             let res = read_map_from_file(filename).unwrap();
             let proof_vec = res.get(&0).unwrap();
 
             let mut assert1 = vec![];
-            for i in 0..NUM_PUBS {
+            for i in 0..num_pubs {
                 let v: [u8; 32] = proof_vec[i].clone().try_into().unwrap();
                 assert1.push(v);
             }
-            let assert1: [[u8; 32]; NUM_PUBS] = assert1.try_into().unwrap();
 
             let mut assert2 = vec![];
             for i in 0..NUM_U256 {
-                let v: [u8; 32] = proof_vec[NUM_PUBS + i].clone().try_into().unwrap();
+                let v: [u8; 32] = proof_vec[num_pubs + i].clone().try_into().unwrap();
                 assert2.push(v);
             }
             let assert2: [[u8; 32]; NUM_U256] = assert2.try_into().unwrap();
 
             let mut assert3 = vec![];
-            for i in 0..NUM_HASH {
-                let v: [u8; BLAKE3_HASH_LENGTH] = proof_vec[NUM_PUBS + NUM_U256 + i]
+            for i in 0..num_hash(num_pubs) {
+                let v: [u8; BLAKE3_HASH_LENGTH] = proof_vec[num_pubs + NUM_U256 + i]
                     .clone()
                     .try_into()
                     .unwrap();
                 assert3.push(v);
             }
-            let assert3: [[u8; BLAKE3_HASH_LENGTH]; NUM_HASH] = assert3.try_into().unwrap();
             (assert1, assert2, assert3)
         }
     }
@@ -580,24 +754,22 @@ mod test {
 
         println!("STEP 1 GENERATE TAPSCRIPTS");
         let secret_key: &str = "a138982ce17ac813d505a5b40b665d404e9528e7";
-        let secrets = (0..NUM_PUBS + NUM_U256 + NUM_HASH)
-            .map(|idx| format!("{secret_key}{:04x}", idx))
-            .collect::<Vec<String>>();
-        let pubkeys = get_pubkeys(secrets.clone());
+        let num_pubs = num_pubs(&vk);
+        let secrets = mock_secrets(num_pubs, secret_key);
+        let pubkeys: PublicKeys = secrets.public_keys().into();
 
         let partial_scripts = api_generate_partial_script(&vk);
         let disprove_scripts = api_generate_full_tapscripts(pubkeys, &partial_scripts);
 
         println!("STEP 2 GENERATE SIGNED ASSERTIONS");
-        let proof_sigs =
-            generate_signatures(proof, scalars.to_vec(), &vk, secrets.clone()).unwrap();
+        let proof_sigs = generate_signatures(proof, scalars.to_vec(), &vk, &secrets).unwrap();
 
-        println!("num assertion; 256-bit numbers {}", NUM_PUBS + NUM_U256);
-        println!("num assertion; 160-bit numbers {}", NUM_HASH);
+        println!("num assertion; 256-bit numbers {}", num_pubs + NUM_U256);
+        println!("num assertion; 160-bit numbers {}", num_hash(num_pubs));
 
         let proof_asserts = get_assertions_from_signature(proof_sigs);
-        let signed_asserts = get_signature_from_assertion(proof_asserts, secrets);
-        let disprove_scripts: [ScriptBuf; NUM_TAPS] = disprove_scripts.try_into().unwrap();
+        let signed_asserts = get_signature_from_assertion(proof_asserts, &secrets);
+        assert_eq!(disprove_scripts.len(), num_taps(num_pubs));
 
         let asserts = get_assertions_from_signature(signed_asserts.clone());
         let (success, segments) = get_segments_from_assertion(asserts, vk.clone());
@@ -676,37 +848,39 @@ mod test {
 
         println!("STEP 1 GENERATE TAPSCRIPTS");
         let secret_key: &str = "a138982ce17ac813d505a5b40b665d404e9528e7";
-        let secrets = (0..NUM_PUBS + NUM_U256 + NUM_HASH)
-            .map(|idx| format!("{secret_key}{:04x}", idx))
-            .collect::<Vec<String>>();
-        let pubkeys = get_pubkeys(secrets.clone());
+        let num_pubs = num_pubs(&vk);
+        let secrets = mock_secrets(num_pubs, secret_key);
+        let pubkeys: PublicKeys = secrets.public_keys().into();
 
         let partial_scripts = api_generate_partial_script(&vk);
-        let disprove_scripts = api_generate_full_tapscripts(pubkeys, &partial_scripts);
+        let disprove_scripts = api_generate_full_tapscripts(pubkeys.clone(), &partial_scripts);
 
         println!("STEP 2 GENERATE SIGNED ASSERTIONS");
-        let proof_sigs =
-            generate_signatures(proof, scalars.to_vec(), &vk, secrets.clone()).unwrap();
+        let proof_sigs = generate_signatures(proof, scalars.to_vec(), &vk, &secrets).unwrap();
 
-        println!("num assertion; 256-bit numbers {}", NUM_PUBS + NUM_U256);
-        println!("num assertion; 160-bit numbers {}", NUM_HASH);
+        println!("num assertion; 256-bit numbers {}", num_pubs + NUM_U256);
+        println!("num assertion; 160-bit numbers {}", num_hash(num_pubs));
 
         println!("STEP 3 CORRUPT AND DISPROVE SIGNED ASSERTIONS");
         let mut proof_asserts = get_assertions_from_signature(proof_sigs);
         corrupt_at_random_index(&mut proof_asserts);
-        let corrupt_signed_asserts = get_signature_from_assertion(proof_asserts, secrets);
-        let disprove_scripts: [ScriptBuf; NUM_TAPS] = disprove_scripts.try_into().unwrap();
+        let corrupt_signed_asserts = get_signature_from_assertion(proof_asserts, &secrets);
+        assert_eq!(disprove_scripts.len(), num_taps(num_pubs));
 
         let invalid_tap =
             validate_assertions(&vk, corrupt_signed_asserts, pubkeys, &disprove_scripts);
         assert!(invalid_tap.is_some());
-        let (index, hint_script) = invalid_tap.unwrap();
+        let report = invalid_tap.unwrap();
+        let index = report.tapscript_index;
         println!("STEP 4 EXECUTING DISPROVE SCRIPT at index {}", index);
+        if report.reason != DisproveReason::InvalidInput {
+            assert_ne!(report.expected, report.claimed);
+        }
 
-        let scr = hint_script
-            .clone()
-            .push_script(disprove_scripts[index].clone());
-        let res = execute_script(scr);
+        let res = execute_raw_script_with_inputs(
+            disprove_scripts[index].to_bytes(),
+            report.witness.clone(),
+        );
         if res.final_stack.len() > 1 {
             println!("Stack ");
             for i in 0..res.final_stack.len() {
@@ -719,28 +893,27 @@ mod test {
         println!("DONE");
 
         fn corrupt_at_random_index(proof_asserts: &mut Assertions) {
+            let num_pubs = proof_asserts.0.len();
             let mut rng = rand::thread_rng();
-            let index = rng.gen_range(0..NUM_PUBS + NUM_U256 + NUM_HASH);
+            let index = rng.gen_range(0..num_assertions(num_pubs));
             let mut scramble: [u8; 32] = [0u8; 32];
             scramble[32 / 2] = 37;
             let mut scramble2: [u8; BLAKE3_HASH_LENGTH] = [0u8; BLAKE3_HASH_LENGTH];
             scramble2[BLAKE3_HASH_LENGTH / 2] = 37;
             println!("demo: manually corrupt assertion at index at {:?}", index);
-            if index < NUM_PUBS {
-                if index == 0 {
-                    if proof_asserts.0[0] == scramble {
-                        scramble[16] += 1;
-                    }
-                    proof_asserts.0[0] = scramble;
+            if index < num_pubs {
+                if proof_asserts.0[index] == scramble {
+                    scramble[16] += 1;
                 }
-            } else if index < NUM_PUBS + NUM_U256 {
-                let index = index - NUM_PUBS;
+                proof_asserts.0[index] = scramble;
+            } else if index < num_pubs + NUM_U256 {
+                let index = index - num_pubs;
                 if proof_asserts.1[index] == scramble {
                     scramble[16] += 1;
                 }
                 proof_asserts.1[index] = scramble;
-            } else if index < NUM_PUBS + NUM_U256 + NUM_HASH {
-                let index = index - NUM_PUBS - NUM_U256;
+            } else if index < num_assertions(num_pubs) {
+                let index = index - num_pubs - NUM_U256;
                 if proof_asserts.2[index] == scramble2 {
                     scramble2[10] += 1;
                 }
@@ -818,15 +991,13 @@ mod test {
 
         println!("STEP 1 GENERATE TAPSCRIPTS");
         let secret_key: &str = "a138982ce17ac813d505a5b40b665d404e9528e7";
-        let secrets = (0..NUM_PUBS + NUM_U256 + NUM_HASH)
-            .map(|idx| format!("{secret_key}{:04x}", idx))
-            .collect::<Vec<String>>();
+        let num_pubs = num_pubs(&vk);
+        let secrets = mock_secrets(num_pubs, secret_key);
 
-        let pubkeys = get_pubkeys(secrets.clone());
+        let pubkeys: PublicKeys = secrets.public_keys().into();
 
         let partial_scripts = api_generate_partial_script(&vk);
-        let disprove_scripts = api_generate_full_tapscripts(pubkeys, &partial_scripts);
-        let disprove_scripts = disprove_scripts.try_into().unwrap();
+        let disprove_scripts = api_generate_full_tapscripts(pubkeys.clone(), &partial_scripts);
 
         println!("STEP 2 GENERATE SIGNED ASSERTIONS");
         println!("corrupting proof for demo");
@@ -835,16 +1006,17 @@ mod test {
         incorrect_proof.a = ark_bn254::G1Affine::rand(&mut prng);
 
         let proof_sigs =
-            generate_signatures_for_any_proof(incorrect_proof, scalars.to_vec(), &vk, secrets);
+            generate_signatures_for_any_proof(incorrect_proof, scalars.to_vec(), &vk, &secrets);
 
         let invalid_tap = validate_assertions(&vk, proof_sigs, pubkeys, &disprove_scripts);
         assert!(invalid_tap.is_some());
-        let (index, hint_script) = invalid_tap.unwrap();
+        let report = invalid_tap.unwrap();
+        let index = report.tapscript_index;
         println!("STEP 4 EXECUTING DISPROVE SCRIPT at index {}", index);
-        let scr = hint_script
-            .clone()
-            .push_script(disprove_scripts[index].clone());
-        let res = execute_script(scr);
+        let res = execute_raw_script_with_inputs(
+            disprove_scripts[index].to_bytes(),
+            report.witness.clone(),
+        );
         if res.final_stack.len() > 1 {
             println!("Stack ");
             for i in 0..res.final_stack.len() {
@@ -857,36 +1029,93 @@ mod test {
         println!("DONE");
     }
 
-    #[allow(clippy::needless_range_loop)]
-    fn sign_assertions(assn: Assertions) -> Signatures {
-        let (ps, fs, hs) = (assn.0, assn.1, assn.2);
-        let secret = MOCK_SECRET;
-
-        let mut psig: Vec<<Wots32 as Wots>::Signature> = vec![];
-        for i in 0..NUM_PUBS {
-            let secret = format!("{secret}{:04x}", i);
-            let psi = Wots32::sign(&Wots16::secret_from_str(&secret), &ps[i]);
-            psig.push(psi);
-        }
-        let psig: Box<[<Wots32 as Wots>::Signature; NUM_PUBS]> = Box::new(psig.try_into().unwrap());
+    // Proves the knowledge of a and b, with both their product and their sum as public inputs.
+    #[derive(Copy, Clone)]
+    struct ProductAndSumCircuit {
+        a: Option<ark_bn254::Fr>,
+        b: Option<ark_bn254::Fr>,
+    }
 
-        let mut fsig: Vec<<Wots32 as Wots>::Signature> = vec![];
-        for i in 0..NUM_U256 {
-            let secret = format!("{secret}{:04x}", NUM_PUBS + i);
-            let fsi = Wots32::sign(&Wots16::secret_from_str(&secret), &fs[i]);
-            fsig.push(fsi);
-        }
-        let fsig: Box<[<Wots32 as Wots>::Signature; NUM_U256]> = Box::new(fsig.try_into().unwrap());
+    impl ConstraintSynthesizer<ark_bn254::Fr> for ProductAndSumCircuit {
+        fn generate_constraints(
+            self,
+            cs: ConstraintSystemRef<ark_bn254::Fr>,
+        ) -> Result<(), SynthesisError> {
+            let a = cs.new_witness_variable(|| self.a.ok_or(SynthesisError::AssignmentMissing))?;
+            let b = cs.new_witness_variable(|| self.b.ok_or(SynthesisError::AssignmentMissing))?;
+            let product = cs.new_input_variable(|| {
+                Ok(self.a.ok_or(SynthesisError::AssignmentMissing)?
+                    * self.b.ok_or(SynthesisError::AssignmentMissing)?)
+            })?;
+            let sum = cs.new_input_variable(|| {
+                Ok(self.a.ok_or(SynthesisError::AssignmentMissing)?
+                    + self.b.ok_or(SynthesisError::AssignmentMissing)?)
+            })?;
+
+            cs.enforce_constraint(lc!() + a, lc!() + b, lc!() + product)?;
+            cs.enforce_constraint(lc!() + a + b, lc!() + Variable::One, lc!() + sum)?;
 
-        let mut hsig: Vec<<Wots16 as Wots>::Signature> = vec![];
-        for i in 0..NUM_HASH {
-            let secret = format!("{secret}{:04x}", NUM_PUBS + NUM_U256 + i);
-            let hsi = Wots16::sign(&Wots16::secret_from_str(&secret), &hs[i]);
-            hsig.push(hsi);
+            Ok(())
         }
-        let hsig: Box<[<Wots16 as Wots>::Signature; NUM_HASH]> = Box::new(hsig.try_into().unwrap());
+    }
+
+    #[test]
+    #[ignore]
+    fn full_e2e_execution_with_several_public_inputs() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        let circuit = ProductAndSumCircuit {
+            a: Some(ark_bn254::Fr::rand(&mut prng)),
+            b: Some(ark_bn254::Fr::rand(&mut prng)),
+        };
+        let (pk, vk) = Groth16::<Bn254>::setup(circuit, &mut prng).unwrap();
+        let proof = Groth16::<Bn254>::prove(&pk, circuit, &mut prng).unwrap();
+        let (a, b) = (circuit.a.unwrap(), circuit.b.unwrap());
+        let scalars = vec![a * b, a + b];
+        assert!(Groth16::<Bn254>::verify(&vk, &scalars, &proof).unwrap());
+
+        println!("STEP 1 GENERATE TAPSCRIPTS");
+        let num_pubs = num_pubs(&vk);
+        assert_eq!(num_pubs, 2);
+        let secrets = mock_secrets(num_pubs, MOCK_SECRET);
+        let pubkeys: PublicKeys = secrets.public_keys().into();
+
+        let partial_scripts = api_generate_partial_script(&vk);
+        let disprove_scripts = api_generate_full_tapscripts(pubkeys.clone(), &partial_scripts);
+        assert_eq!(disprove_scripts.len(), num_taps(num_pubs));
+
+        println!("STEP 2 GENERATE SIGNED ASSERTIONS");
+        let proof_sigs = generate_signatures(proof, scalars, &vk, &secrets).unwrap();
+        assert_eq!(proof_sigs.0.len(), num_pubs);
+        assert_eq!(proof_sigs.2.len(), num_hash(num_pubs));
+
+        println!("STEP 3 VALIDATE SIGNED ASSERTIONS");
+        assert!(
+            validate_assertions(&vk, proof_sigs.clone(), pubkeys.clone(), &disprove_scripts)
+                .is_none()
+        );
+
+        println!("STEP 4 CORRUPT THE SECOND PUBLIC INPUT AND DISPROVE");
+        let mut proof_asserts = get_assertions_from_signature(proof_sigs);
+        proof_asserts.0[1] = proof_asserts.0[0];
+        let corrupt_signed_asserts = get_signature_from_assertion(proof_asserts, &secrets);
+        let report = validate_assertions(&vk, corrupt_signed_asserts, pubkeys, &disprove_scripts)
+            .expect("the corrupt public input must be disprovable");
+        println!(
+            "EXECUTING DISPROVE SCRIPT at index {}",
+            report.tapscript_index
+        );
 
-        (psig, fsig, hsig)
+        let res = execute_raw_script_with_inputs(
+            disprove_scripts[report.tapscript_index].to_bytes(),
+            report.witness.clone(),
+        );
+        assert_eq!(res.final_stack.len(), 1);
+        assert!(res.success);
+    }
+
+    fn sign_assertions(assn: Assertions) -> Signatures {
+        let num_pubs = assn.0.len();
+        get_signature_from_assertion(assn, &mock_secrets(num_pubs, MOCK_SECRET))
     }
 
     // Step 1: Anyone can Generate Operation (mul & hash) part of tapscript: same for all vks
@@ -929,8 +1158,6 @@ mod test {
         .to_vec();
         let mock_vk: ark_groth16::VerifyingKey<Bn254> =
             ark_groth16::VerifyingKey::deserialize_uncompressed(&vk_bytes[..]).unwrap();
-        assert_eq!(mock_vk.gamma_abc_g1.len(), NUM_PUBS + 1);
-
         let partial_scripts = api_generate_partial_script(&mock_vk);
 
         let mut script_cache = HashMap::new();
@@ -942,6 +1169,13 @@ mod test {
     }
 
     const MOCK_SECRET: &str = "a138982ce17ac813d505a5b40b665d404e9528e7";
+
+    fn mock_secrets(num_pubs: usize, secret: &str) -> ChunkSecretKeys {
+        let secrets = (0..num_assertions(num_pubs))
+            .map(|idx| format!("{secret}{:04x}", idx))
+            .collect::<Vec<String>>();
+        ChunkSecretKeys::from_strings(num_pubs, &secrets)
+    }
     // Step 2: Operator Generates keypairs and broadcasts pubkeys for a Bitvm setup;
     // Anyone can create Bitcomm part of tapscript; yields complete tapscript
     #[test]
@@ -988,16 +1222,14 @@ mod test {
 
         println!("compiled circuit");
 
-        assert!(mock_vk.gamma_abc_g1.len() == NUM_PUBS + 1);
-        let secrets = (0..NUM_PUBS + NUM_U256 + NUM_HASH)
-            .map(|idx| format!("{MOCK_SECRET}{:04x}", idx))
-            .collect::<Vec<String>>();
+        let num_pubs = num_pubs(&mock_vk);
+        let secrets = mock_secrets(num_pubs, MOCK_SECRET);
 
-        let mock_pubs = get_pubkeys(secrets);
+        let mock_pubs: PublicKeys = secrets.public_keys().into();
         let mut op_scripts = vec![];
 
         println!("load scripts from file");
-        for index in 0..NUM_TAPS {
+        for index in 0..num_taps(num_pubs) {
             let read =
                 read_scripts_from_file(&format!("bridge_data/chunker_data/tapnode_{index}.json"));
             let read_scr = read.get(&(index as u32)).unwrap();
@@ -1007,17 +1239,58 @@ mod test {
         }
         println!("done");
 
-        let ops_scripts: [ScriptBuf; NUM_TAPS] = op_scripts.try_into().unwrap(); //compile_verifier(mock_vk);
-
-        let tapscripts = api_generate_full_tapscripts(mock_pubs, &ops_scripts);
-        assert_eq!(tapscripts.len(), NUM_TAPS);
-        let tapscripts: [ScriptBuf; NUM_TAPS] = tapscripts.try_into().unwrap();
+        let tapscripts = api_generate_full_tapscripts(mock_pubs, &op_scripts);
+        assert_eq!(tapscripts.len(), num_taps(num_pubs));
         println!(
             "tapscript.lens: {:?}",
-            tapscripts.clone().map(|script| script.len())
+            tapscripts
+                .iter()
+                .map(|script| script.len())
+                .collect::<Vec<usize>>()
         );
     }
 
+    // Step 1 and 2 resumed after an interruption, from the segments checkpointed so far
+    #[test]
+    #[ignore]
+    fn test_fn_generate_tapscripts_with_checkpoint() {
+        let vk = RawProof::default().vk;
+        let num_pubs = num_pubs(&vk);
+        let secrets = mock_secrets(num_pubs, MOCK_SECRET);
+        let pubkeys: PublicKeys = secrets.public_keys().into();
+        let checkpoint = ChunkCheckpoint::new(
+            std::env::temp_dir().join("bitvm_test_fn_generate_tapscripts_with_checkpoint"),
+        );
+        checkpoint.clear().unwrap();
+
+        let options = ChunkOptions::with_checkpoint(&checkpoint);
+        let partial_scripts = api_generate_partial_script_with_options(&vk, options);
+        let tapscripts =
+            api_generate_full_tapscripts_with_options(pubkeys.clone(), &partial_scripts, options);
+
+        // drop every other segment, as if the runs had been interrupted
+        for pass_dir in std::fs::read_dir(checkpoint.dir()).unwrap() {
+            let segment_files = std::fs::read_dir(pass_dir.unwrap().path()).unwrap();
+            for segment_file in segment_files.step_by(2) {
+                std::fs::remove_file(segment_file.unwrap().path()).unwrap();
+            }
+        }
+        assert_eq!(
+            api_generate_partial_script_with_options(&vk, options),
+            partial_scripts
+        );
+        assert_eq!(
+            api_generate_full_tapscripts_with_options(pubkeys.clone(), &partial_scripts, options),
+            tapscripts
+        );
+        assert_eq!(api_generate_partial_script(&vk), partial_scripts);
+        assert_eq!(
+            api_generate_full_tapscripts(pubkeys, &partial_scripts),
+            tapscripts
+        );
+        checkpoint.clear().unwrap();
+    }
+
     // Step 3: Operator Generates Assertions, Signs it and submit on chain
 
     #[test]
@@ -1086,14 +1359,17 @@ mod test {
         let scalar: ark_bn254::Fr = ark_bn254::Fr::deserialize_uncompressed(&scalar[..]).unwrap();
         let public_inputs = [scalar];
 
-        assert!(mock_vk.gamma_abc_g1.len() == NUM_PUBS + 1);
+        assert_eq!(num_pubs(&mock_vk), public_inputs.len());
         let proof_asserts = generate_assertions(proof, public_inputs.to_vec(), &mock_vk).unwrap();
         println!("signed_asserts {:?}", proof_asserts);
 
         std::fs::create_dir_all("bridge_data/chunker_data")
             .expect("Failed to create directory structure");
 
-        write_asserts_to_file(proof_asserts, "bridge_data/chunker_data/assert.json");
+        write_asserts_to_file(
+            proof_asserts.clone(),
+            "bridge_data/chunker_data/assert.json",
+        );
         let _signed_asserts = sign_assertions(proof_asserts);
     }
 
@@ -1164,18 +1440,19 @@ mod test {
         let scalar: ark_bn254::Fr = ark_bn254::Fr::deserialize_uncompressed(&scalar[..]).unwrap();
         let public_inputs = [scalar];
 
-        assert!(mock_vk.gamma_abc_g1.len() == NUM_PUBS + 1);
-        let secrets = (0..NUM_PUBS + NUM_U256 + NUM_HASH)
-            .map(|idx| format!("{MOCK_SECRET}{:04x}", idx))
-            .collect::<Vec<String>>();
-        let sigs = generate_signatures(proof, public_inputs.to_vec(), &mock_vk, secrets).unwrap();
+        assert_eq!(num_pubs(&mock_vk), public_inputs.len());
+        let secrets = mock_secrets(public_inputs.len(), MOCK_SECRET);
+        let sigs = generate_signatures(proof, public_inputs.to_vec(), &mock_vk, &secrets).unwrap();
         let proof_asserts = get_assertions_from_signature(sigs);
         println!("signed_asserts {:?}", proof_asserts);
 
         std::fs::create_dir_all("bridge_data/chunker_data")
             .expect("Failed to create directory structure");
 
-        write_asserts_to_file(proof_asserts, "bridge_data/chunker_data/assert.json");
+        write_asserts_to_file(
+            proof_asserts.clone(),
+            "bridge_data/chunker_data/assert.json",
+        );
         let _signed_asserts = sign_assertions(proof_asserts);
     }
 
@@ -1245,11 +1522,11 @@ mod test {
         let scalar: ark_bn254::Fr = ark_bn254::Fr::deserialize_uncompressed(&scalar[..]).unwrap();
         let _public_inputs = [scalar];
 
-        assert!(mock_vk.gamma_abc_g1.len() == NUM_PUBS + 1);
+        let num_pubs = num_pubs(&mock_vk);
 
         let mut op_scripts = vec![];
         println!("load scripts from file");
-        for index in 0..NUM_TAPS {
+        for index in 0..num_taps(num_pubs) {
             let read =
                 read_scripts_from_file(&format!("bridge_data/chunker_data/tapnode_{index}.json"));
             let read_scr = read.get(&(index as u32)).unwrap();
@@ -1258,17 +1535,14 @@ mod test {
             op_scripts.push(tap_node);
         }
         println!("done");
-        let ops_scripts: [ScriptBuf; NUM_TAPS] = op_scripts.try_into().unwrap();
 
-        let secrets = (0..NUM_PUBS + NUM_U256 + NUM_HASH)
-            .map(|idx| format!("{MOCK_SECRET}{:04x}", idx))
-            .collect::<Vec<String>>();
-        let mock_pubks = get_pubkeys(secrets);
-        let verifier_scripts = api_generate_full_tapscripts(mock_pubks, &ops_scripts);
-        let verifier_scripts = verifier_scripts.try_into().unwrap();
+        let secrets = mock_secrets(num_pubs, MOCK_SECRET);
+        let mock_pubks: PublicKeys = secrets.public_keys().into();
+        let verifier_scripts = api_generate_full_tapscripts(mock_pubks.clone(), &op_scripts);
 
         //     // let proof_asserts = generate_proof_assertions(mock_vk.clone(), proof, public_inputs);
-        let proof_asserts = read_asserts_from_file("bridge_data/chunker_data/assert.json");
+        let proof_asserts =
+            read_asserts_from_file("bridge_data/chunker_data/assert.json", num_pubs);
         let signed_asserts = sign_assertions(proof_asserts);
         //     let mock_pubks = mock_pubkeys(MOCK_SECRET);
 
@@ -1344,11 +1618,11 @@ mod test {
         let scalar: ark_bn254::Fr = ark_bn254::Fr::deserialize_uncompressed(&scalar[..]).unwrap();
         let _public_inputs = [scalar];
 
-        assert_eq!(mock_vk.gamma_abc_g1.len(), NUM_PUBS + 1);
+        let num_pubs = num_pubs(&mock_vk);
 
         let mut op_scripts = vec![];
         println!("load scripts from file");
-        for index in 0..NUM_TAPS {
+        for index in 0..num_taps(num_pubs) {
             let read =
                 read_scripts_from_file(&format!("bridge_data/chunker_data/tapnode_{index}.json"));
             let read_scr = read.get(&(index as u32)).unwrap();
@@ -1357,19 +1631,16 @@ mod test {
             op_scripts.push(tap_node);
         }
         println!("done");
-        let ops_scripts: [ScriptBuf; NUM_TAPS] = op_scripts.try_into().unwrap();
 
-        let secrets = (0..NUM_PUBS + NUM_U256 + NUM_HASH)
-            .map(|idx| format!("{MOCK_SECRET}{:04x}", idx))
-            .collect::<Vec<String>>();
-        let mock_pubks = get_pubkeys(secrets);
-        let verifier_scripts = api_generate_full_tapscripts(mock_pubks, &ops_scripts);
-        let verifier_scripts = verifier_scripts.try_into().unwrap();
+        let secrets = mock_secrets(num_pubs, MOCK_SECRET);
+        let mock_pubks: PublicKeys = secrets.public_keys().into();
+        let verifier_scripts = api_generate_full_tapscripts(mock_pubks.clone(), &op_scripts);
 
         fn corrupt(proof_asserts: &mut Assertions, random: Option<usize>) {
             let mut rng = rand::thread_rng();
 
-            let mut index = rng.gen_range(0..NUM_PUBS + NUM_U256 + NUM_HASH);
+            let num_pubs = proof_asserts.0.len();
+            let mut index = rng.gen_range(0..num_assertions(num_pubs));
             if random.is_some() {
                 index = random.unwrap();
             }
@@ -1378,21 +1649,19 @@ mod test {
             let mut scramble2: [u8; BLAKE3_HASH_LENGTH] = [0u8; BLAKE3_HASH_LENGTH];
             scramble2[BLAKE3_HASH_LENGTH / 2] = 37;
             println!("corrupted assertion at index {}", index);
-            if index < NUM_PUBS {
-                if index == 0 {
-                    if proof_asserts.0[0] == scramble {
-                        scramble[16] += 1;
-                    }
-                    proof_asserts.0[0] = scramble;
+            if index < num_pubs {
+                if proof_asserts.0[index] == scramble {
+                    scramble[16] += 1;
                 }
-            } else if index < NUM_PUBS + NUM_U256 {
-                let index = index - NUM_PUBS;
+                proof_asserts.0[index] = scramble;
+            } else if index < num_pubs + NUM_U256 {
+                let index = index - num_pubs;
                 if proof_asserts.1[index] == scramble {
                     scramble[16] += 1;
                 }
                 proof_asserts.1[index] = scramble;
-            } else if index < NUM_PUBS + NUM_U256 + NUM_HASH {
-                let index = index - NUM_PUBS - NUM_U256;
+            } else if index < num_assertions(num_pubs) {
+                let index = index - num_pubs - NUM_U256;
                 if proof_asserts.2[index] == scramble2 {
                     scramble2[10] += 1;
                 }
@@ -1400,32 +1669,36 @@ mod test {
             }
         }
 
-        let _total = NUM_PUBS + NUM_U256 + NUM_HASH;
+        let _total = num_assertions(num_pubs);
         const RESERVED_SPACE: usize = 16000; // blockreservedweight=8000 + extra (8000)
         for i in 0.._total {
             println!("ITERATION {:?}", i);
-            let mut proof_asserts = read_asserts_from_file("bridge_data/chunker_data/assert.json");
+            let mut proof_asserts =
+                read_asserts_from_file("bridge_data/chunker_data/assert.json", num_pubs);
             corrupt(&mut proof_asserts, Some(i));
             let signed_asserts = sign_assertions(proof_asserts);
 
-            let fault =
-                validate_assertions(&mock_vk, signed_asserts, mock_pubks, &verifier_scripts);
+            let fault = validate_assertions(
+                &mock_vk,
+                signed_asserts,
+                mock_pubks.clone(),
+                &verifier_scripts,
+            );
             assert!(fault.is_some());
             if fault.is_some() {
-                let (index, hint_script) = fault.unwrap();
+                let report = fault.unwrap();
+                let index = report.tapscript_index;
                 println!("taproot index {:?}", index);
-                let scr = hint_script
-                    .clone()
-                    .push_script(verifier_scripts[index].clone());
-                assert!(scr.len() < 4_000_000 - RESERVED_SPACE);
-                let res = execute_script(scr);
+                assert!(report.witness_size < 4_000_000 - RESERVED_SPACE);
+                let res = execute_raw_script_with_inputs(
+                    verifier_scripts[index].to_bytes(),
+                    report.witness.clone(),
+                );
                 for i in 0..res.final_stack.len() {
                     println!("{i:} {:?}", res.final_stack.get(i));
                 }
-                let mut disprove_map: HashMap<u32, Vec<ScriptBuf>> = HashMap::new();
                 let disprove_f = &format!("bridge_data/chunker_data/disprove_{index}.json");
-                disprove_map.insert(index as u32, vec![hint_script.compile()]);
-                write_scripts_to_file(disprove_map, disprove_f);
+                std::fs::write(disprove_f, serde_json::to_string_pretty(&report).unwrap()).unwrap();
                 assert!(res.success);
             }
         }
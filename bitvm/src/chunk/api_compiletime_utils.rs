@@ -5,7 +5,7 @@ use crate::bn254::ell_coeffs::AffinePairing;
 use crate::bn254::ell_coeffs::BnAffinePairing;
 use crate::bn254::fp254impl::Fp254Impl;
 use crate::bn254::fq::Fq;
-use crate::chunk::api::{NUM_HASH, NUM_PUBS, NUM_TAPS, NUM_U256, VALIDATING_TAPS};
+use crate::chunk::api::{num_assertions, num_pubs, num_taps, VALIDATING_TAPS};
 use crate::chunk::elements::ElementType;
 use crate::treepp;
 use ark_bn254::Bn254;
@@ -15,12 +15,15 @@ use ark_ff::Field;
 use bitcoin::ScriptBuf;
 use bitcoin_script::script;
 use num_bigint::BigUint;
+use rayon::prelude::*;
 use std::hash::{DefaultHasher, Hash, Hasher};
 use std::ops::Neg;
 use treepp::Script;
 
 use super::api::PublicKeys;
+use super::checkpoint::{par_map_checkpointed, ChunkCheckpoint, PassCheckpoint};
 use super::g16_runner_core::{InputProof, PublicParams};
+use super::progress::{ChunkProgress, ChunkStage, StageProgress};
 use super::wrap_hasher::hash_messages;
 use super::wrap_wots::checksig_verify_to_limbs;
 use super::{
@@ -39,9 +42,14 @@ pub(crate) struct Vkey {
     pub(crate) vky0: ark_bn254::G1Affine,
 }
 
-pub(crate) fn generate_partial_script(vk: &ark_groth16::VerifyingKey<Bn254>) -> Vec<ScriptBuf> {
-    println!("generate_partial_script");
-    assert!(vk.gamma_abc_g1.len() == NUM_PUBS + 1);
+#[tracing::instrument(skip_all)]
+pub(crate) fn generate_partial_script(
+    vk: &ark_groth16::VerifyingKey<Bn254>,
+    checkpoint: Option<&ChunkCheckpoint>,
+    progress: &dyn ChunkProgress,
+) -> Vec<ScriptBuf> {
+    let num_pubs = num_pubs(vk);
+    let pass = checkpoint.map(|checkpoint| checkpoint.partial_scripts_pass(vk));
 
     let p1 = vk.alpha_g1;
     let (q3, q2, q1) = (
@@ -64,11 +72,12 @@ pub(crate) fn generate_partial_script(vk: &ark_groth16::VerifyingKey<Bn254>) ->
         vky0,
     };
 
-    println!("generate_partial_script; generate_segments_using_mock_proof");
+    tracing::debug!("generate_segments_using_mock_proof");
     let segments = generate_segments_using_mock_proof(vk, false);
-    println!("generate_partial_script; partial_scripts_from_segments");
-    let op_scripts: Vec<ScriptBuf> = partial_scripts_from_segments(&segments);
-    assert_eq!(op_scripts.len(), NUM_TAPS);
+    tracing::debug!("partial_scripts_from_segments");
+    let op_scripts: Vec<ScriptBuf> =
+        partial_scripts_from_segments(&segments, pass.as_ref(), progress);
+    assert_eq!(op_scripts.len(), num_taps(num_pubs));
 
     op_scripts
 }
@@ -76,31 +85,37 @@ pub(crate) fn generate_partial_script(vk: &ark_groth16::VerifyingKey<Bn254>) ->
 // we can use mock_vk and mock_proof here because generating bitcommitments only requires knowledge
 // of how the chunks are connected and the public keys to generate locking_script
 // we do not need values at the input or outputs of tapscript
+// the locking scripts are generated and prepended in parallel, tapscript by tapscript
+#[tracing::instrument(skip_all)]
 pub(crate) fn append_bitcom_locking_script_to_partial_scripts(
     inpubkeys: PublicKeys,
     ops_scripts: Vec<ScriptBuf>,
+    checkpoint: Option<&PassCheckpoint>,
+    progress: &dyn ChunkProgress,
 ) -> Vec<ScriptBuf> {
-    println!("append_bitcom_locking_script_to_partial_scripts; generage_segments_using_mock_vk_and_mock_proof");
+    tracing::debug!("generate_segments_using_mock_vk_and_mock_proof");
     // mock_vk can be used because generating locking_script doesn't depend upon values or partial scripts; it's only a function of pubkey and ordering of input/outputs
-    let mock_segments = generate_segments_using_mock_vk_and_mock_proof();
-
-    println!("append_bitcom_locking_script_to_partial_scripts; bitcom_scripts_from_segments");
-    let bitcom_scripts: Vec<treepp::Script> =
-        bitcom_scripts_from_segments(&mock_segments, inpubkeys)
-            .into_iter()
-            .filter(|f| !f.is_empty())
-            .collect();
-    assert_eq!(ops_scripts.len(), bitcom_scripts.len());
-    let res: Vec<ScriptBuf> = ops_scripts
-        .into_iter()
-        .zip(bitcom_scripts)
-        .map(|(op_scr, bit_scr)| {
-            let mut full_script_bytes = bit_scr.compile().to_bytes();
-            full_script_bytes.extend_from_slice(op_scr.as_bytes());
-            ScriptBuf::from_bytes(full_script_bytes)
-        })
+    let mock_segments = generate_segments_using_mock_vk_and_mock_proof(inpubkeys.0.len());
+
+    tracing::debug!("bitcom_locking_script");
+    let bitcommitted_msgs: Vec<Vec<u32>> = mock_segments
+        .iter()
+        .filter(|seg| seg.scr_type != ScriptType::NonDeterministic)
+        .map(bitcommitted_msg_indices)
+        .filter(|indices| !indices.is_empty())
         .collect();
-    res
+    assert_eq!(ops_scripts.len(), bitcommitted_msgs.len());
+    let pubkeys_arr = wots_pubkeys_in_order(inpubkeys);
+    let progress = StageProgress::new(ChunkStage::Tapscripts, progress);
+    par_map_checkpointed(ops_scripts.len(), checkpoint, progress, |index| {
+        let bit_scr = bitcom_locking_script(&bitcommitted_msgs[index], &pubkeys_arr);
+        let mut full_script_bytes = bit_scr.compile().to_bytes();
+        full_script_bytes.extend_from_slice(ops_scripts[index].as_bytes());
+        full_script_bytes
+    })
+    .into_iter()
+    .map(ScriptBuf::from_bytes)
+    .collect()
 }
 
 fn generate_segments_using_mock_proof(vk: Vkey, skip_evaluation: bool) -> Vec<Segment> {
@@ -147,6 +162,7 @@ fn generate_segments_using_mock_proof(vk: Vkey, skip_evaluation: bool) -> Vec<Se
     );
     let t1 = ark_bn254::G1Affine::new(g1x, g1y);
 
+    let num_pubs = vk.p3vk.len();
     let mut segments: Vec<Segment> = vec![];
     let g1 = t1;
     let g2 = t2;
@@ -157,7 +173,7 @@ fn generate_segments_using_mock_proof(vk: Vkey, skip_evaluation: bool) -> Vec<Se
         p4: g1,
         q4: g2,
         c,
-        ks: vec![fr.into(); NUM_PUBS],
+        ks: vec![fr.into(); num_pubs],
     };
 
     // public values known at compile time
@@ -175,18 +191,15 @@ fn generate_segments_using_mock_proof(vk: Vkey, skip_evaluation: bool) -> Vec<Se
         pubs,
         &mut None,
     );
-    assert_eq!(
-        segments.len(),
-        NUM_PUBS + NUM_U256 + NUM_HASH + VALIDATING_TAPS
-    );
+    assert_eq!(segments.len(), num_assertions(num_pubs) + VALIDATING_TAPS);
     segments
 }
 
-pub(crate) fn generate_segments_using_mock_vk_and_mock_proof() -> Vec<Segment> {
+pub(crate) fn generate_segments_using_mock_vk_and_mock_proof(num_pubs: usize) -> Vec<Segment> {
     let mock_vk = Vkey {
         q2: ark_bn254::G2Affine::identity(),
         q3: ark_bn254::G2Affine::identity(),
-        p3vk: (0..NUM_PUBS)
+        p3vk: (0..num_pubs)
             .map(|_| ark_bn254::G1Affine::identity())
             .collect(),
         p1q1: ark_bn254::Fq12::ONE,
@@ -195,7 +208,12 @@ pub(crate) fn generate_segments_using_mock_vk_and_mock_proof() -> Vec<Segment> {
     generate_segments_using_mock_proof(mock_vk, true)
 }
 
-pub(crate) fn partial_scripts_from_segments(segments: &[Segment]) -> Vec<ScriptBuf> {
+// the partial scripts are compiled in parallel, segment by segment
+pub(crate) fn partial_scripts_from_segments(
+    segments: &[Segment],
+    checkpoint: Option<&PassCheckpoint>,
+    progress: &dyn ChunkProgress,
+) -> Vec<ScriptBuf> {
     fn serialize_element_types(elems: &[ElementType]) -> String {
         // 1. Convert each variant to its string representation.
         let joined = elems
@@ -213,70 +231,72 @@ pub(crate) fn partial_scripts_from_segments(segments: &[Segment]) -> Vec<ScriptB
         format!("{}|{}", joined, unique_hash)
     }
 
-    let mut op_scripts: Vec<ScriptBuf> = vec![];
+    fn elem_types_to_hash(seg: &Segment) -> Vec<ElementType> {
+        let mut elem_types_to_hash: Vec<ElementType> = seg
+            .parameter_ids
+            .iter()
+            .rev()
+            .map(|(_, param_seg_type)| *param_seg_type)
+            .collect();
+        elem_types_to_hash.push(seg.result.1);
+        elem_types_to_hash
+    }
+
+    let segments: Vec<&Segment> = segments
+        .iter()
+        .filter(|seg| seg.scr_type != ScriptType::NonDeterministic)
+        .collect();
 
     // cache hashing script as it is repititive
-    let mut hashing_script_cache: HashMap<String, Script> = HashMap::new();
-    for s in segments {
-        if s.scr_type.is_final_script() || s.scr_type == ScriptType::NonDeterministic {
+    let mut distinct_elem_types: HashMap<String, Vec<ElementType>> = HashMap::new();
+    for seg in segments.iter() {
+        if seg.scr_type.is_final_script() {
             continue;
         }
-        let mut elem_types_to_hash: Vec<ElementType> =
-            s.parameter_ids.iter().rev().map(|f| f.1).collect();
-        elem_types_to_hash.push(s.result.1);
-        let elem_types_str_as_key = serialize_element_types(&elem_types_to_hash);
-        hashing_script_cache
-            .entry(elem_types_str_as_key)
-            .or_insert_with(|| {
-                let hash_scr = script! {
-                    {hash_messages(elem_types_to_hash)}
-                    OP_TRUE
-                };
-                hash_scr
-            });
+        let elem_types = elem_types_to_hash(seg);
+        distinct_elem_types
+            .entry(serialize_element_types(&elem_types))
+            .or_insert(elem_types);
     }
+    let hashing_script_cache: HashMap<String, Script> = distinct_elem_types
+        .into_par_iter()
+        .map(|(key, elem_types)| {
+            let hash_scr = script! {
+                {hash_messages(elem_types)}
+                OP_TRUE
+            };
+            (key, hash_scr)
+        })
+        .collect();
 
-    for seg in segments {
-        let scr_type = seg.scr_type.clone();
-        if scr_type == ScriptType::NonDeterministic {
-            continue;
-        }
-
+    let progress = StageProgress::new(ChunkStage::PartialScripts, progress);
+    par_map_checkpointed(segments.len(), checkpoint, progress, |index| {
+        let seg = segments[index];
         let op_scr = seg.scr.clone();
 
         if seg.scr_type.is_final_script() {
             // validating segments do not have output hash, so don't add hashing layer; they are self sufficient
-            op_scripts.push(op_scr);
+            op_scr.into_bytes()
         } else {
             // fetch hashing script from cache for these element types
-            let mut elem_types_to_hash: Vec<ElementType> = seg
-                .parameter_ids
-                .iter()
-                .rev()
-                .map(|(_, param_seg_type)| *param_seg_type)
-                .collect();
-            elem_types_to_hash.push(seg.result.1);
-            let elem_types_str = serialize_element_types(&elem_types_to_hash);
+            let elem_types_str = serialize_element_types(&elem_types_to_hash(seg));
             let hash_scr = hashing_script_cache.get(&elem_types_str).unwrap();
 
-            op_scripts.push(
-                script! {
-                    {script!().push_script(op_scr)}
-                    {hash_scr.clone()}
-                }
-                .compile(),
-            );
+            script! {
+                {script!().push_script(op_scr)}
+                {hash_scr.clone()}
+            }
+            .compile()
+            .into_bytes()
         }
-    }
-    op_scripts
+    })
+    .into_iter()
+    .map(ScriptBuf::from_bytes)
+    .collect()
 }
 
-pub(crate) fn bitcom_scripts_from_segments(
-    segments: &[Segment],
-    wots_pubkeys: PublicKeys,
-) -> Vec<treepp::Script> {
-    let mut bitcom_scripts: Vec<treepp::Script> = vec![];
-
+// ordered as the bitcommitted messages, see PublicKeys
+fn wots_pubkeys_in_order(wots_pubkeys: PublicKeys) -> Vec<WOTSPubKey> {
     let mut pubkeys_arr = vec![];
     pubkeys_arr.extend_from_slice(
         &wots_pubkeys
@@ -299,42 +319,46 @@ pub(crate) fn bitcom_scripts_from_segments(
             .map(|f| WOTSPubKey::PHash(*f))
             .collect::<Vec<WOTSPubKey>>(),
     );
+    pubkeys_arr
+}
 
-    for seg in segments {
-        if seg.scr_type == ScriptType::NonDeterministic {
-            continue;
-        }
+// index of the messages bitcommitted in the tapscript of the segment: output, then inputs
+fn bitcommitted_msg_indices(seg: &Segment) -> Vec<u32> {
+    let mut index_of_bitcommitted_msg = vec![];
+    if !seg.scr_type.is_final_script() {
+        index_of_bitcommitted_msg.push(seg.id);
+    };
+    let sec_in: Vec<u32> = seg.parameter_ids.iter().map(|(f, _)| *f).collect();
+    index_of_bitcommitted_msg.extend_from_slice(&sec_in);
+    index_of_bitcommitted_msg
+}
 
-        let mut index_of_bitcommitted_msg = vec![];
-        if !seg.scr_type.is_final_script() {
-            index_of_bitcommitted_msg.push(seg.id);
+fn bitcom_locking_script(
+    index_of_bitcommitted_msg: &[u32],
+    pubkeys_arr: &[WOTSPubKey],
+) -> treepp::Script {
+    let mut locking_scr = script! {};
+    for index in index_of_bitcommitted_msg {
+        locking_scr = script! {
+            {locking_scr}
+            {checksig_verify_to_limbs(&pubkeys_arr[*index as usize])}
+            {Fq::toaltstack()}
         };
-        let sec_in: Vec<u32> = seg.parameter_ids.iter().map(|(f, _)| *f).collect();
-        index_of_bitcommitted_msg.extend_from_slice(&sec_in);
-
-        let mut locking_scr = script! {};
-        for index in index_of_bitcommitted_msg {
-            locking_scr = script! {
-                {locking_scr}
-                {checksig_verify_to_limbs(&pubkeys_arr[index as usize])}
-                {Fq::toaltstack()}
-            };
-        }
-        bitcom_scripts.push(locking_scr);
     }
-    bitcom_scripts
+    locking_scr
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::chunk::api::{num_assertions, num_taps, NUM_U256, VALIDATING_TAPS};
     use crate::chunk::api_compiletime_utils::generate_segments_using_mock_vk_and_mock_proof;
 
     #[test]
     fn test_generate_segments_using_mock_proof() {
-        let segments = generate_segments_using_mock_vk_and_mock_proof();
-        assert_eq!(
-            segments.len(),
-            super::NUM_PUBS + super::NUM_U256 + super::NUM_HASH + super::VALIDATING_TAPS
-        );
+        for num_pubs in [1, 3] {
+            let segments = generate_segments_using_mock_vk_and_mock_proof(num_pubs);
+            assert_eq!(segments.len(), num_assertions(num_pubs) + VALIDATING_TAPS);
+            assert_eq!(segments.len() - num_pubs - NUM_U256, num_taps(num_pubs));
+        }
     }
 }
@@ -18,10 +18,14 @@ use ark_ec::{AffineRepr, CurveGroup};
 use ark_ff::Field;
 use bitcoin::ScriptBuf;
 use bitcoin_script::script;
+use rayon::prelude::*;
 
-use super::api::{Assertions, PublicKeys, Signatures, NUM_HASH, NUM_PUBS, NUM_TAPS, NUM_U256};
+use super::api::{num_assertions, num_hash, num_pubs, num_taps, Assertions, Signatures, NUM_U256};
+use super::checkpoint::PassCheckpoint;
 use super::elements::CompressedStateObject;
 use super::g16_runner_utils::{ScriptType, Segment};
+use super::keys::ChunkSecretKeys;
+use super::progress::{ChunkProgress, ChunkStage, NoProgress, StageProgress};
 use super::wrap_hasher::BLAKE3_HASH_LENGTH;
 use crate::signatures::{CompactWots, Wots, Wots16, Wots32};
 use crate::{bn254::utils::Hint, execute_script};
@@ -50,14 +54,17 @@ pub(crate) fn get_assertion_from_segments(segments: &[Segment]) -> Assertions {
     // Serialize and Collect:
     // Segments that were collected in order [PublicInputSegments, ProofInputSegments, IntermediateHashSegment, FinalScriptSegment]
     // are now serialized in the same order and collected as such => [PublicInputAssertion, ProofInputAssertion, IntermediateHashAssertion]
+    // the number of public inputs is implied by the number of output states, see num_assertions()
+    let num_pubs = (0..=arr_of_output_state.len())
+        .find(|num_pubs| num_assertions(*num_pubs) == arr_of_output_state.len())
+        .expect("number of segments should correspond to a valid number of public inputs");
+
     let mut public_input_assertion_data = vec![];
-    for i in 0..NUM_PUBS {
+    for i in 0..num_pubs {
         let val = &arr_of_output_state[i];
         let val: [u8; 32] = val.serialize_to_byte_array().try_into().unwrap();
         public_input_assertion_data.push(val);
     }
-    let public_input_assertion_data: [[u8; 32]; NUM_PUBS] =
-        public_input_assertion_data.try_into().unwrap();
 
     let len = public_input_assertion_data.len();
     let mut proof_input_assertion_data = vec![];
@@ -71,36 +78,34 @@ pub(crate) fn get_assertion_from_segments(segments: &[Segment]) -> Assertions {
 
     let len = public_input_assertion_data.len() + proof_input_assertion_data.len();
     let mut intermediate_hash_assertion_data = vec![];
-    for i in 0..NUM_HASH {
+    for i in 0..num_hash(num_pubs) {
         let val = &arr_of_output_state[i + len];
         let val: [u8; BLAKE3_HASH_LENGTH] = val.serialize_to_byte_array().try_into().unwrap();
         intermediate_hash_assertion_data.push(val);
     }
-    let batch3: [[u8; BLAKE3_HASH_LENGTH]; NUM_HASH] =
-        intermediate_hash_assertion_data.try_into().unwrap();
 
     (
         public_input_assertion_data,
         proof_input_assertion_data,
-        batch3,
+        intermediate_hash_assertion_data,
     )
 }
 
 // deserialize assertions to CompressedState (i.e. concrete types of bigint and hasbytes) and get proof
-fn utils_deserialize_assertions(
+#[allow(clippy::type_complexity)]
+pub(crate) fn utils_deserialize_assertions(
     asserts: Assertions,
 ) -> (
-    [CompressedStateObject; NUM_PUBS],
+    Vec<CompressedStateObject>,
     [CompressedStateObject; NUM_U256],
-    [CompressedStateObject; NUM_HASH],
+    Vec<CompressedStateObject>,
 ) {
     let mut cobj_pubs = vec![];
-    for i in 0..NUM_PUBS {
+    for i in 0..asserts.0.len() {
         let nibs = asserts.0[i].to_vec();
         let cobj = CompressedStateObject::deserialize_from_byte_array(nibs);
         cobj_pubs.push(cobj);
     }
-    let cobj_pubs: [CompressedStateObject; NUM_PUBS] = cobj_pubs.try_into().unwrap();
 
     let mut cobj_fqs = vec![];
     for i in 0..NUM_U256 {
@@ -111,20 +116,13 @@ fn utils_deserialize_assertions(
     let cobj_fqs: [CompressedStateObject; NUM_U256] = cobj_fqs.try_into().unwrap();
 
     let mut cobj_hashes = vec![];
-    for i in 0..NUM_HASH {
+    for i in 0..asserts.2.len() {
         let nibs = asserts.2[i].to_vec();
         let cobj = CompressedStateObject::deserialize_from_byte_array(nibs);
         cobj_hashes.push(cobj);
     }
-    let cobj_hashes: [CompressedStateObject; NUM_HASH] = cobj_hashes.try_into().unwrap();
-
-    let cobjs: (
-        [CompressedStateObject; NUM_PUBS],
-        [CompressedStateObject; NUM_U256],
-        [CompressedStateObject; NUM_HASH],
-    ) = (cobj_pubs, cobj_fqs, cobj_hashes);
 
-    cobjs
+    (cobj_pubs, cobj_fqs, cobj_hashes)
 }
 
 // mirror of the funtion get_assertion_from_segments
@@ -133,7 +131,7 @@ pub(crate) fn get_segments_from_assertion(
     vk: ark_groth16::VerifyingKey<Bn254>,
 ) -> (bool, Vec<Segment>) {
     fn extract_proof_from_assertions(
-        state_pubs: [CompressedStateObject; NUM_PUBS],
+        state_pubs: Vec<CompressedStateObject>,
         state_fqs: [CompressedStateObject; NUM_U256],
     ) -> Option<InputProofRaw> {
         let mut ks: Vec<ark_ff::BigInt<4>> = vec![];
@@ -144,7 +142,6 @@ pub(crate) fn get_segments_from_assertion(
                 return None;
             }
         }
-        let ks: [ark_ff::BigInt<4>; NUM_PUBS] = ks.try_into().unwrap();
 
         let mut numfqs: Vec<ark_ff::BigInt<4>> = vec![];
         for cobj in state_fqs {
@@ -180,7 +177,7 @@ pub(crate) fn get_segments_from_assertion(
     }
 
     fn extract_hashes_from_assertions(
-        state_hashes: [CompressedStateObject; NUM_HASH],
+        state_hashes: Vec<CompressedStateObject>,
     ) -> Option<Vec<HashBytes>> {
         // Intermediates
         let mut hashes: Vec<HashBytes> = vec![];
@@ -219,6 +216,9 @@ pub(crate) fn get_segments_from_assertion(
         pubs
     }
 
+    let num_pubs = num_pubs(&vk);
+    assert_eq!(assertions.0.len(), num_pubs);
+    assert_eq!(assertions.2.len(), num_hash(num_pubs));
     let states = utils_deserialize_assertions(assertions);
 
     let proof_raw = extract_proof_from_assertions(states.0, states.1);
@@ -243,7 +243,8 @@ pub(crate) fn get_segments_from_groth16_proof(
     scalars: Vec<ark_bn254::Fr>,
     vk: &ark_groth16::VerifyingKey<Bn254>,
 ) -> (bool, Vec<Segment>) {
-    assert_eq!(scalars.len(), NUM_PUBS);
+    let num_pubs = num_pubs(vk);
+    assert_eq!(scalars.len(), num_pubs);
 
     let mut msm_scalar = scalars.clone();
     msm_scalar.reverse();
@@ -252,7 +253,7 @@ pub(crate) fn get_segments_from_groth16_proof(
     let vky0 = msm_gs.pop().unwrap();
 
     let mut p3 = vky0 * ark_bn254::Fr::ONE;
-    for i in 0..NUM_PUBS {
+    for i in 0..num_pubs {
         p3 += msm_gs[i] * msm_scalar[i];
     }
     let p3 = p3.into_affine();
@@ -287,7 +288,7 @@ pub(crate) fn get_segments_from_groth16_proof(
     };
 
     let mut segments: Vec<Segment> = vec![];
-    println!("get_segments_from_groth16_proof; groth16_generate_segments");
+    tracing::debug!("groth16_generate_segments");
     let success =
         groth16_generate_segments(false, &mut segments, eval_ins.to_raw(), pubs, &mut None);
     (success, segments)
@@ -295,48 +296,50 @@ pub(crate) fn get_segments_from_groth16_proof(
 
 // wots sign byte array using secrets
 // mirror of get_assertions_from_signature
-pub(crate) fn get_signature_from_assertion(assn: Assertions, secrets: Vec<String>) -> Signatures {
-    println!("get_signature_from_assertion");
+#[tracing::instrument(skip_all)]
+pub(crate) fn get_signature_from_assertion(
+    assn: Assertions,
+    secrets: &ChunkSecretKeys,
+) -> Signatures {
     // sign and return Signatures
     let (ps, fs, hs) = (assn.0, assn.1, assn.2);
+    assert_eq!(secrets.num_pubs(), ps.len());
+    assert_eq!(secrets.intermediate_hashes().len(), hs.len());
 
-    let mut psig: Vec<<Wots32 as Wots>::Signature> = vec![];
-    for i in 0..NUM_PUBS {
-        let secret = Wots32::secret_from_str(secrets[i].as_str());
-        let psi = Wots32::sign(&secret, &ps[i]);
-        psig.push(psi);
-    }
-    let psig: Box<[<Wots32 as Wots>::Signature; NUM_PUBS]> = Box::new(psig.try_into().unwrap());
+    let psig: Vec<<Wots32 as Wots>::Signature> = secrets
+        .public_inputs()
+        .iter()
+        .zip(ps.iter())
+        .map(|(secret, p)| Wots32::sign(secret, p))
+        .collect();
 
-    let mut fsig: Vec<<Wots32 as Wots>::Signature> = vec![];
-    for i in 0..fs.len() {
-        let secret = Wots32::secret_from_str(secrets[i + NUM_PUBS].as_str());
-        let fsi = Wots32::sign(&secret, &fs[i]);
-        fsig.push(fsi);
-    }
+    let fsig: Vec<<Wots32 as Wots>::Signature> = secrets
+        .proof()
+        .iter()
+        .zip(fs.iter())
+        .map(|(secret, f)| Wots32::sign(secret, f))
+        .collect();
     let fsig: Box<[<Wots32 as Wots>::Signature; NUM_U256]> = Box::new(fsig.try_into().unwrap());
 
-    let mut hsig: Vec<<Wots16 as Wots>::Signature> = vec![];
-    for i in 0..hs.len() {
-        let secret = Wots16::secret_from_str(secrets[i + NUM_PUBS + NUM_U256].as_str());
-        let hsi = Wots16::sign(&secret, &hs[i]);
-        hsig.push(hsi);
-    }
-    let hsig: Box<[<Wots16 as Wots>::Signature; NUM_HASH]> = Box::new(hsig.try_into().unwrap());
+    let hsig: Vec<<Wots16 as Wots>::Signature> = secrets
+        .intermediate_hashes()
+        .iter()
+        .zip(hs.iter())
+        .map(|(secret, h)| Wots16::sign(secret, h))
+        .collect();
 
-    (psig, fsig, hsig)
+    (psig.into_boxed_slice(), fsig, hsig.into_boxed_slice())
 }
 
 // decode signature to assertion
 // mirror of get_signature_from_assertion
+#[tracing::instrument(skip_all)]
 pub(crate) fn get_assertions_from_signature(signed_asserts: Signatures) -> Assertions {
-    println!("get_assertions_from_signature");
     let mut ks: Vec<[u8; 32]> = vec![];
-    for i in 0..NUM_PUBS {
+    for i in 0..signed_asserts.0.len() {
         let nibs = Wots32::signature_to_message(&signed_asserts.0[i]);
         ks.push(nibs);
     }
-    let ks: [[u8; 32]; NUM_PUBS] = ks.try_into().unwrap();
 
     let mut numfqs: Vec<[u8; 32]> = vec![];
     for i in 0..NUM_U256 {
@@ -346,14 +349,12 @@ pub(crate) fn get_assertions_from_signature(signed_asserts: Signatures) -> Asser
     let num_fqs: [[u8; 32]; NUM_U256] = numfqs.try_into().unwrap();
 
     let mut numhashes: Vec<[u8; BLAKE3_HASH_LENGTH]> = vec![];
-    for i in 0..NUM_HASH {
+    for i in 0..signed_asserts.2.len() {
         let nibs = Wots16::signature_to_message(&signed_asserts.2[i]);
         numhashes.push(nibs);
     }
 
-    let num_hashes: [[u8; BLAKE3_HASH_LENGTH]; NUM_HASH] = numhashes.try_into().unwrap();
-
-    let asst: Assertions = (ks, num_fqs, num_hashes);
+    let asst: Assertions = (ks, num_fqs, numhashes);
     asst
 }
 
@@ -386,57 +387,78 @@ fn utils_collect_mul_hints_per_segment(segments: &[Segment]) -> Vec<Vec<Hint>> {
     aux_hints
 }
 
+// executes the tapscripts in parallel and returns the first one, in tapscript order, that
+// succeeds i.e. that disproves the assertions, with the witness it succeeds with
+// tapscripts which fail are recorded in the checkpoint and are not executed again on resume
 fn utils_execute_chunked_g16(
     aux_hints: Vec<Vec<Hint>>,
     bc_hints: Vec<Script>,
     segments: &[Segment],
-    disprove_scripts: &[ScriptBuf; NUM_TAPS],
+    disprove_scripts: &[ScriptBuf],
+    checkpoint: Option<&PassCheckpoint>,
+    progress: &dyn ChunkProgress,
 ) -> Option<(usize, Script)> {
-    let mut tap_script_index = 0;
-    for i in 0..aux_hints.len() {
-        if segments[i].scr_type == ScriptType::NonDeterministic {
-            continue;
-        }
-        let hint_script = script! {
-            for h in &aux_hints[i] {
-                {h.push()}
-            }
-            {bc_hints[i].clone()}
-        };
-        let total_script = hint_script
-            .clone()
-            .push_script(disprove_scripts[tap_script_index].clone());
-        let exec_result = execute_script(total_script);
-        if exec_result.final_stack.len() > 1 {
-            for i in 0..exec_result.final_stack.len() {
-                println!("{i:} {:?}", exec_result.final_stack.get(i));
+    let taps: Vec<(usize, usize)> = (0..aux_hints.len())
+        .filter(|i| segments[*i].scr_type != ScriptType::NonDeterministic)
+        .enumerate()
+        .map(|(tap_script_index, i)| (i, tap_script_index))
+        .collect();
+    let num_taps = taps.len();
+    let progress = StageProgress::new(ChunkStage::Validation, progress);
+    taps.into_par_iter()
+        .find_map_first(|(i, tap_script_index)| {
+            if checkpoint.is_some_and(|checkpoint| checkpoint.load(tap_script_index).is_some()) {
+                progress.segment_done(tap_script_index, num_taps);
+                return None;
             }
-        }
-        if !exec_result.success {
-            if exec_result.final_stack.len() != 1 {
-                println!("final {:?}", i);
-                println!("final {:?}", segments[i].scr_type);
-                panic!();
+            let hint_script = script! {
+                for h in &aux_hints[i] {
+                    {h.push()}
+                }
+                {bc_hints[i].clone()}
+            };
+            let total_script = hint_script
+                .clone()
+                .push_script(disprove_scripts[tap_script_index].clone());
+            let exec_result = execute_script(total_script);
+            progress.segment_done(tap_script_index, num_taps);
+            if exec_result.final_stack.len() > 1 {
+                for i in 0..exec_result.final_stack.len() {
+                    tracing::trace!("{i:} {:?}", exec_result.final_stack.get(i));
+                }
             }
-            if exec_result.remaining_script != "OP_PUSHNUM_1" && exec_result.remaining_script != ""
-            {
-                println!(
-                    "Script terminated early {:?} {:?}",
-                    exec_result.remaining_script, segments[i].scr_type
+            if !exec_result.success {
+                if exec_result.final_stack.len() != 1 {
+                    panic!(
+                        "segment {} ({:?}) left {} elements on the stack",
+                        i,
+                        segments[i].scr_type,
+                        exec_result.final_stack.len()
+                    );
+                }
+                if exec_result.remaining_script != "OP_PUSHNUM_1"
+                    && exec_result.remaining_script != ""
+                {
+                    panic!(
+                        "Script terminated early {:?} {:?}",
+                        exec_result.remaining_script, segments[i].scr_type
+                    );
+                }
+                if let Some(checkpoint) = checkpoint {
+                    checkpoint.store(tap_script_index, &[]);
+                }
+                None
+            } else {
+                tracing::info!(
+                    "disprove script {}: tapindex {}, {:?}",
+                    i,
+                    tap_script_index,
+                    segments[i].scr_type
                 );
-                panic!();
+                let disprove_hint = (tap_script_index, hint_script);
+                Some(disprove_hint)
             }
-        } else {
-            println!(
-                "disprove script {}: tapindex {}, {:?}",
-                i, tap_script_index, segments[i].scr_type
-            );
-            let disprove_hint = (tap_script_index, hint_script);
-            return Some(disprove_hint);
-        }
-        tap_script_index += 1;
-    }
-    None
+        })
 }
 
 /// This is a duplicate of [`utils_execute_chunked_g16`], just to analyze worst case scenarios
@@ -444,7 +466,7 @@ fn utils_analyze_largest_segments(
     aux_hints: Vec<Vec<Hint>>,
     bc_hints: Vec<Script>,
     segments: &[Segment],
-    disprove_scripts: &[ScriptBuf; NUM_TAPS],
+    disprove_scripts: &[ScriptBuf],
 ) {
     let mut max_script_size = 0;
     let mut max_script_size_index = 0;
@@ -479,13 +501,15 @@ fn utils_analyze_largest_segments(
         tap_script_index += 1;
     }
 
-    println!(
+    tracing::info!(
         "Max script size with the current VK is {} at index {}",
-        max_script_size, max_script_size_index
+        max_script_size,
+        max_script_size_index
     );
-    println!(
+    tracing::info!(
         "(This shouldn't change with the VK) Max stack depth used is {} at index {}",
-        max_stack_depth, max_stack_depth_index
+        max_stack_depth,
+        max_stack_depth_index
     );
 }
 
@@ -541,22 +565,31 @@ pub(crate) fn execute_script_from_assertion(
     }
 
     // collect partial scripts
-    let partial_scripts: Vec<ScriptBuf> = partial_scripts_from_segments(segments)
-        .into_iter()
-        .collect();
-    let partial_scripts: [ScriptBuf; NUM_TAPS] = partial_scripts.try_into().unwrap();
+    let num_pubs = assts.0.len();
+    let partial_scripts: Vec<ScriptBuf> =
+        partial_scripts_from_segments(segments, None, &NoProgress);
+    assert_eq!(partial_scripts.len(), num_taps(num_pubs));
     // collect witness
     let mul_hints = utils_collect_mul_hints_per_segment(segments);
     let bc_hints = collect_wots_msg_as_witness_per_segment(segments, assts);
 
     // execute_chunked_g16
-    utils_execute_chunked_g16(mul_hints, bc_hints, segments, &partial_scripts)
+    utils_execute_chunked_g16(
+        mul_hints,
+        bc_hints,
+        segments,
+        &partial_scripts,
+        None,
+        &NoProgress,
+    )
 }
 
 pub(crate) fn execute_script_from_signature(
     segments: &[Segment],
     signed_assts: Signatures,
-    disprove_scripts: &[ScriptBuf; NUM_TAPS],
+    disprove_scripts: &[ScriptBuf],
+    checkpoint: Option<&PassCheckpoint>,
+    progress: &dyn ChunkProgress,
 ) -> Option<(usize, Script)> {
     // if there is a disprove script; with locking script; i can use bitcom witness
     // segments and signatures
@@ -628,14 +661,21 @@ pub(crate) fn execute_script_from_signature(
     let bc_hints = collect_wots_sig_as_witness_per_segment(segments, signed_assts);
 
     // execute_chunked_g16
-    utils_execute_chunked_g16(mul_hints, bc_hints, segments, disprove_scripts)
+    utils_execute_chunked_g16(
+        mul_hints,
+        bc_hints,
+        segments,
+        disprove_scripts,
+        checkpoint,
+        progress,
+    )
 }
 
 /// This is a duplicate of [`execute_script_from_signature`], just to analyze worst case scenarios
 pub fn analyze_largest_segments_from_signatures(
     segments: &[Segment],
     signed_assts: Signatures,
-    disprove_scripts: &[ScriptBuf; NUM_TAPS],
+    disprove_scripts: &[ScriptBuf],
 ) {
     // if there is a disprove script; with locking script; i can use bitcom witness
     // segments and signatures
@@ -710,32 +750,6 @@ pub fn analyze_largest_segments_from_signatures(
 }
 
 #[allow(clippy::needless_range_loop)]
-pub(crate) fn get_pubkeys(secret_key: Vec<String>) -> PublicKeys {
-    let mut pubins = vec![];
-    for i in 0..NUM_PUBS {
-        let secret = Wots32::secret_from_str(secret_key[i].as_str());
-        pubins.push(Wots32::generate_public_key(&secret));
-    }
-    let mut fq_arr = vec![];
-    for i in 0..NUM_U256 {
-        let secret = Wots32::secret_from_str(secret_key[i + NUM_PUBS].as_str());
-        let p256 = Wots32::generate_public_key(&secret);
-        fq_arr.push(p256);
-    }
-    let mut h_arr = vec![];
-    for i in 0..NUM_HASH {
-        let secret = Wots16::secret_from_str(secret_key[i + NUM_PUBS + NUM_U256].as_str());
-        let phash = Wots16::generate_public_key(&secret);
-        h_arr.push(phash);
-    }
-    let wotspubkey: PublicKeys = (
-        pubins.try_into().unwrap(),
-        fq_arr.try_into().unwrap(),
-        h_arr.try_into().unwrap(),
-    );
-    wotspubkey
-}
-
 #[cfg(test)]
 mod test {
     use crate::chunk::api_compiletime_utils::append_bitcom_locking_script_to_partial_scripts;
@@ -820,16 +834,16 @@ mod test {
         println!("get_assertion_from_segments");
         let assts = get_assertion_from_segments(&segments);
         println!("execute_script_from_assertion");
-        let res = execute_script_from_assertion(&segments, assts);
+        let res = execute_script_from_assertion(&segments, assts.clone());
         assert!(res.is_none());
 
         println!("get_segments_from_assertion");
-        let (success, new_segments) = get_segments_from_assertion(assts, vk);
+        let (success, new_segments) = get_segments_from_assertion(assts.clone(), vk.clone());
         assert!(success);
         println!("again get_assertion_from_segments");
         let new_assts = get_assertion_from_segments(&new_segments);
         println!("again execute_script_from_assertion");
-        let res = execute_script_from_assertion(&new_segments, new_assts);
+        let res = execute_script_from_assertion(&new_segments, new_assts.clone());
         assert!(res.is_none());
 
         println!("ensure reruns match");
@@ -838,27 +852,39 @@ mod test {
         // get_sig from assts
         const MOCK_SECRET: &str = "a238982ce17ac813d505a5b40b665d404e9528e7";
         println!("get_signature_from_assertion");
-        let secrets = (0..NUM_PUBS + NUM_U256 + NUM_HASH)
-            .map(|idx| format!("{MOCK_SECRET}{:04x}", idx))
-            .collect::<Vec<String>>();
-        let signed_assts = get_signature_from_assertion(assts, secrets.clone());
+        let num_pubs = num_pubs(&vk);
+        let secrets = ChunkSecretKeys::from_strings(
+            num_pubs,
+            &(0..num_assertions(num_pubs))
+                .map(|idx| format!("{MOCK_SECRET}{:04x}", idx))
+                .collect::<Vec<String>>(),
+        );
+        let signed_assts = get_signature_from_assertion(assts.clone(), &secrets);
 
         println!("get_assertions_from_signature");
         let new_assts = get_assertions_from_signature(signed_assts.clone());
         assert_eq!(assts, new_assts);
 
-        println!("get_pubkeys");
-        let secrets = (0..NUM_PUBS + NUM_U256 + NUM_HASH)
-            .map(|idx| format!("{MOCK_SECRET}{:04x}", idx))
-            .collect::<Vec<String>>();
-        let pubkeys = get_pubkeys(secrets);
+        println!("public_keys");
+        let pubkeys = secrets.public_keys().into();
         println!("execute_script_from_signature");
-        let partial_scripts: Vec<ScriptBuf> = partial_scripts_from_segments(&segments);
-        let disprove_scripts =
-            append_bitcom_locking_script_to_partial_scripts(pubkeys, partial_scripts.to_vec());
-        let disprove_scripts: [ScriptBuf; NUM_TAPS] = disprove_scripts.try_into().unwrap();
-
-        let res = execute_script_from_signature(&segments, signed_assts, &disprove_scripts);
+        let partial_scripts: Vec<ScriptBuf> =
+            partial_scripts_from_segments(&segments, None, &NoProgress);
+        let disprove_scripts = append_bitcom_locking_script_to_partial_scripts(
+            pubkeys,
+            partial_scripts.to_vec(),
+            None,
+            &NoProgress,
+        );
+        assert_eq!(disprove_scripts.len(), num_taps(num_pubs));
+
+        let res = execute_script_from_signature(
+            &segments,
+            signed_assts,
+            &disprove_scripts,
+            None,
+            &NoProgress,
+        );
         assert!(res.is_none());
         println!("finished test");
     }
@@ -0,0 +1,370 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use ark_bn254::Bn254;
+use ark_serialize::CanonicalSerialize;
+use bitcoin::hashes::Hash;
+use bitcoin::taproot::LeafVersion;
+use bitcoin::{ScriptBuf, TapLeafHash};
+
+use super::api::{
+    api_generate_partial_script, api_generate_partial_script_with_options, num_pubs, num_taps,
+    ChunkOptions,
+};
+
+const MAGIC: [u8; 8] = *b"BITVMDSB";
+// bumped whenever the layout below changes
+pub const DISPROVE_SCRIPT_BUNDLE_FORMAT_VERSION: u32 = 1;
+const CHECKSUM_LENGTH: usize = 32;
+
+// hash identifying the verifying key a bundle has been generated for
+pub fn verifying_key_hash(vk: &ark_groth16::VerifyingKey<Bn254>) -> [u8; 32] {
+    let mut vk_bytes = vec![];
+    vk.serialize_compressed(&mut vk_bytes)
+        .expect("verifying key should serialize");
+    *blake3::hash(&vk_bytes).as_bytes()
+}
+
+#[derive(Debug)]
+pub enum DisproveScriptBundleError {
+    Io(io::Error),
+    Malformed(&'static str),
+    UnsupportedFormatVersion(u32),
+    ChecksumMismatch,
+    CrateVersionMismatch { expected: String, found: String },
+    VerifyingKeyMismatch,
+    NumTapsMismatch { expected: usize, found: usize },
+    LeafHashMismatch(usize),
+}
+
+impl fmt::Display for DisproveScriptBundleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "failed to read disprove script bundle: {e}"),
+            Self::Malformed(reason) => write!(f, "malformed disprove script bundle: {reason}"),
+            Self::UnsupportedFormatVersion(version) => write!(
+                f,
+                "unsupported disprove script bundle format version {version}, expected {}",
+                DISPROVE_SCRIPT_BUNDLE_FORMAT_VERSION
+            ),
+            Self::ChecksumMismatch => write!(f, "disprove script bundle checksum mismatch"),
+            Self::CrateVersionMismatch { expected, found } => write!(
+                f,
+                "disprove script bundle built by bitvm {found}, expected {expected}"
+            ),
+            Self::VerifyingKeyMismatch => {
+                write!(f, "disprove script bundle built for another verifying key")
+            }
+            Self::NumTapsMismatch { expected, found } => write!(
+                f,
+                "disprove script bundle has {found} scripts, expected {expected}"
+            ),
+            Self::LeafHashMismatch(index) => {
+                write!(
+                    f,
+                    "disprove script bundle leaf hash {index} does not match its script"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for DisproveScriptBundleError {}
+
+impl From<io::Error> for DisproveScriptBundleError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/*
+  Partial disprove scripts of a verifying key (Step 1), to be generated once and shared by every
+  party setting up graphs for that key, the bitcommitment part being appended per operator with
+  api_generate_full_tapscripts.
+
+  Layout, integers little endian:
+    magic (8 bytes) | format version (u32) | crate version length (u16) | crate version
+    | verifying key hash (32 bytes) | number of scripts (u32)
+    | for each script: length (u32) | script
+    | for each script: taproot leaf hash (32 bytes)
+    | blake3 checksum of everything before (32 bytes)
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DisproveScriptBundle {
+    crate_version: String,
+    vk_hash: [u8; 32],
+    partial_scripts: Vec<ScriptBuf>,
+    leaf_hashes: Vec<TapLeafHash>,
+}
+
+impl DisproveScriptBundle {
+    pub fn generate(vk: &ark_groth16::VerifyingKey<Bn254>) -> Self {
+        Self::from_partial_scripts(vk, api_generate_partial_script(vk))
+    }
+
+    pub fn generate_with_options(
+        vk: &ark_groth16::VerifyingKey<Bn254>,
+        options: ChunkOptions,
+    ) -> Self {
+        Self::from_partial_scripts(vk, api_generate_partial_script_with_options(vk, options))
+    }
+
+    pub fn from_partial_scripts(
+        vk: &ark_groth16::VerifyingKey<Bn254>,
+        partial_scripts: Vec<ScriptBuf>,
+    ) -> Self {
+        assert_eq!(
+            partial_scripts.len(),
+            num_taps(num_pubs(vk)),
+            "one partial script expected per tapscript"
+        );
+        let leaf_hashes = partial_scripts.iter().map(leaf_hash).collect();
+        DisproveScriptBundle {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            vk_hash: verifying_key_hash(vk),
+            partial_scripts,
+            leaf_hashes,
+        }
+    }
+
+    pub fn vk_hash(&self) -> [u8; 32] {
+        self.vk_hash
+    }
+
+    pub fn num_taps(&self) -> usize {
+        self.partial_scripts.len()
+    }
+
+    pub fn partial_scripts(&self) -> &[ScriptBuf] {
+        &self.partial_scripts
+    }
+
+    pub fn leaf_hashes(&self) -> &[TapLeafHash] {
+        &self.leaf_hashes
+    }
+
+    pub fn into_partial_scripts(self) -> Vec<ScriptBuf> {
+        self.partial_scripts
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend(DISPROVE_SCRIPT_BUNDLE_FORMAT_VERSION.to_le_bytes());
+        bytes.extend((self.crate_version.len() as u16).to_le_bytes());
+        bytes.extend(self.crate_version.as_bytes());
+        bytes.extend(self.vk_hash);
+        bytes.extend((self.partial_scripts.len() as u32).to_le_bytes());
+        for script in self.partial_scripts.iter() {
+            bytes.extend((script.len() as u32).to_le_bytes());
+            bytes.extend(script.as_bytes());
+        }
+        for leaf_hash in self.leaf_hashes.iter() {
+            bytes.extend(leaf_hash.as_byte_array());
+        }
+        let checksum = blake3::hash(&bytes);
+        bytes.extend(checksum.as_bytes());
+        bytes
+    }
+
+    // refuses bundles that are corrupted, or were built by another version of this crate or for
+    // another verifying key
+    pub fn from_bytes(
+        bytes: &[u8],
+        vk: &ark_groth16::VerifyingKey<Bn254>,
+    ) -> Result<Self, DisproveScriptBundleError> {
+        let mut reader = Reader { bytes, position: 0 };
+        if reader.take(MAGIC.len())? != MAGIC.as_slice() {
+            return Err(DisproveScriptBundleError::Malformed(
+                "not a disprove script bundle",
+            ));
+        }
+        let format_version = reader.read_u32()?;
+        if format_version != DISPROVE_SCRIPT_BUNDLE_FORMAT_VERSION {
+            return Err(DisproveScriptBundleError::UnsupportedFormatVersion(
+                format_version,
+            ));
+        }
+        let Some(content_length) = bytes.len().checked_sub(CHECKSUM_LENGTH) else {
+            return Err(DisproveScriptBundleError::Malformed("missing checksum"));
+        };
+        if blake3::hash(&bytes[..content_length]).as_bytes()[..] != bytes[content_length..] {
+            return Err(DisproveScriptBundleError::ChecksumMismatch);
+        }
+        let mut reader = Reader {
+            bytes: &bytes[..content_length],
+            position: reader.position,
+        };
+
+        let crate_version_length = reader.read_u16()? as usize;
+        let crate_version = String::from_utf8(reader.take(crate_version_length)?.to_vec())
+            .map_err(|_| DisproveScriptBundleError::Malformed("crate version is not utf-8"))?;
+        if crate_version != env!("CARGO_PKG_VERSION") {
+            return Err(DisproveScriptBundleError::CrateVersionMismatch {
+                expected: env!("CARGO_PKG_VERSION").to_string(),
+                found: crate_version,
+            });
+        }
+        let vk_hash: [u8; 32] = reader.take(32)?.try_into().unwrap();
+        if vk_hash != verifying_key_hash(vk) {
+            return Err(DisproveScriptBundleError::VerifyingKeyMismatch);
+        }
+        let expected_num_taps = num_taps(num_pubs(vk));
+        let num_taps = reader.read_u32()? as usize;
+        if num_taps != expected_num_taps {
+            return Err(DisproveScriptBundleError::NumTapsMismatch {
+                expected: expected_num_taps,
+                found: num_taps,
+            });
+        }
+
+        let mut partial_scripts = Vec::with_capacity(num_taps);
+        for _ in 0..num_taps {
+            let script_length = reader.read_u32()? as usize;
+            partial_scripts.push(ScriptBuf::from_bytes(reader.take(script_length)?.to_vec()));
+        }
+        let mut leaf_hashes = Vec::with_capacity(num_taps);
+        for (index, script) in partial_scripts.iter().enumerate() {
+            let hash = TapLeafHash::from_byte_array(reader.take(32)?.try_into().unwrap());
+            if hash != leaf_hash(script) {
+                return Err(DisproveScriptBundleError::LeafHashMismatch(index));
+            }
+            leaf_hashes.push(hash);
+        }
+        if reader.position != reader.bytes.len() {
+            return Err(DisproveScriptBundleError::Malformed("trailing bytes"));
+        }
+
+        Ok(DisproveScriptBundle {
+            crate_version,
+            vk_hash,
+            partial_scripts,
+            leaf_hashes,
+        })
+    }
+
+    pub fn write_to_file(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_bytes())
+    }
+
+    pub fn read_from_file(
+        path: &Path,
+        vk: &ark_groth16::VerifyingKey<Bn254>,
+    ) -> Result<Self, DisproveScriptBundleError> {
+        Self::from_bytes(&fs::read(path)?, vk)
+    }
+}
+
+fn leaf_hash(script: &ScriptBuf) -> TapLeafHash {
+    TapLeafHash::from_script(script, LeafVersion::TapScript)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], DisproveScriptBundleError> {
+        let end = self
+            .position
+            .checked_add(length)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or(DisproveScriptBundleError::Malformed(
+                "unexpected end of bundle",
+            ))?;
+        let bytes = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn read_u16(&mut self) -> Result<u16, DisproveScriptBundleError> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn read_u32(&mut self) -> Result<u32, DisproveScriptBundleError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::api::type_conversion_utils::RawProof;
+
+    // stands in for the partial scripts, which take a while to compile
+    fn mock_bundle(vk: &ark_groth16::VerifyingKey<Bn254>) -> DisproveScriptBundle {
+        let partial_scripts = (0..num_taps(num_pubs(vk)))
+            .map(|index| ScriptBuf::from_bytes((index as u32).to_le_bytes().repeat(index % 7)))
+            .collect();
+        DisproveScriptBundle::from_partial_scripts(vk, partial_scripts)
+    }
+
+    #[test]
+    fn test_disprove_script_bundle_roundtrip() {
+        let vk = RawProof::default().vk;
+        let bundle = mock_bundle(&vk);
+        assert_eq!(bundle.num_taps(), num_taps(num_pubs(&vk)));
+
+        let path = std::env::temp_dir().join("bitvm_test_disprove_script_bundle.bin");
+        bundle.write_to_file(&path).unwrap();
+        assert_eq!(
+            DisproveScriptBundle::read_from_file(&path, &vk).unwrap(),
+            bundle
+        );
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_disprove_script_bundle_rejects_mismatches() {
+        let vk = RawProof::default().vk;
+        let bundle = mock_bundle(&vk);
+        let bytes = bundle.to_bytes();
+
+        let mut other_vk = vk.clone();
+        other_vk.beta_g2 = other_vk.gamma_g2;
+        assert!(matches!(
+            DisproveScriptBundle::from_bytes(&bytes, &other_vk),
+            Err(DisproveScriptBundleError::VerifyingKeyMismatch)
+        ));
+
+        let mut corrupted = bytes.clone();
+        corrupted[bytes.len() / 2] ^= 1;
+        assert!(matches!(
+            DisproveScriptBundle::from_bytes(&corrupted, &vk),
+            Err(DisproveScriptBundleError::ChecksumMismatch)
+        ));
+        assert!(matches!(
+            DisproveScriptBundle::from_bytes(&bytes[..bytes.len() - 1], &vk),
+            Err(DisproveScriptBundleError::ChecksumMismatch)
+        ));
+
+        let mut future_format = bytes.clone();
+        future_format[MAGIC.len()..MAGIC.len() + 4]
+            .copy_from_slice(&(DISPROVE_SCRIPT_BUNDLE_FORMAT_VERSION + 1).to_le_bytes());
+        assert!(matches!(
+            DisproveScriptBundle::from_bytes(&future_format, &vk),
+            Err(DisproveScriptBundleError::UnsupportedFormatVersion(_))
+        ));
+
+        let mut wrong_leaf_hash = bundle.clone();
+        wrong_leaf_hash.leaf_hashes[1] = wrong_leaf_hash.leaf_hashes[0];
+        assert!(matches!(
+            DisproveScriptBundle::from_bytes(&wrong_leaf_hash.to_bytes(), &vk),
+            Err(DisproveScriptBundleError::LeafHashMismatch(1))
+        ));
+
+        let older_crate = DisproveScriptBundle {
+            crate_version: "0.0.0".to_string(),
+            ..bundle
+        };
+        assert!(matches!(
+            DisproveScriptBundle::from_bytes(&older_crate.to_bytes(), &vk),
+            Err(DisproveScriptBundleError::CrateVersionMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,215 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use ark_bn254::Bn254;
+use ark_serialize::CanonicalSerialize;
+use bitcoin::ScriptBuf;
+use rayon::prelude::*;
+
+use super::api::{PublicKeys, Signatures};
+use super::progress::StageProgress;
+
+// to be bumped whenever the encoding of the stored results changes
+const CHECKPOINT_FORMAT: &[u8] = b"bitvm-chunk-checkpoint-v1";
+
+// Directory in which the passes over the segments (partial script compilation, bitcommitment
+// appending, validation) store their result for every segment as soon as it is computed, one
+// file per segment index. An interrupted run given the same directory only processes the
+// segments missing on disk.
+// Every pass writes to its own subdirectory named after a digest of its inputs, so results
+// computed from other inputs (e.g. another verifying key or other public keys) are never reused.
+// The digest also covers the crate version and CHECKPOINT_FORMAT, so results stored by another
+// release, whose scripts may differ for the same inputs, are not reused either.
+#[derive(Clone, Debug)]
+pub struct ChunkCheckpoint {
+    dir: PathBuf,
+}
+
+impl ChunkCheckpoint {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    // removes the results of all passes, e.g. once the scripts they produced are cached elsewhere
+    pub fn clear(&self) -> io::Result<()> {
+        remove_dir(&self.dir)
+    }
+
+    // the partial scripts only depend upon the verifying key
+    pub fn partial_scripts_pass(&self, vk: &ark_groth16::VerifyingKey<Bn254>) -> PassCheckpoint {
+        let mut vk_bytes = vec![];
+        vk.serialize_compressed(&mut vk_bytes)
+            .expect("verifying key should serialize");
+        self.pass("partial_scripts", &[&vk_bytes])
+    }
+
+    pub fn tapscripts_pass(
+        &self,
+        pubkeys: &PublicKeys,
+        partial_scripts: &[ScriptBuf],
+    ) -> PassCheckpoint {
+        let mut inputs: Vec<&[u8]> = vec![];
+        inputs.extend(pubkeys.0.iter().map(|pk| pk.as_flattened()));
+        inputs.extend(pubkeys.1.iter().map(|pk| pk.as_flattened()));
+        inputs.extend(pubkeys.2.iter().map(|pk| pk.as_flattened()));
+        inputs.extend(partial_scripts.iter().map(|script| script.as_bytes()));
+        self.pass("tapscripts", &inputs)
+    }
+
+    // the segments the scripts are executed with are derived from the signed assertions
+    pub fn validation_pass(
+        &self,
+        signatures: &Signatures,
+        disprove_scripts: &[ScriptBuf],
+    ) -> PassCheckpoint {
+        let mut inputs: Vec<&[u8]> = vec![];
+        inputs.extend(signatures.0.iter().map(|sig| sig.as_flattened()));
+        inputs.extend(signatures.1.iter().map(|sig| sig.as_flattened()));
+        inputs.extend(signatures.2.iter().map(|sig| sig.as_flattened()));
+        inputs.extend(disprove_scripts.iter().map(|script| script.as_bytes()));
+        self.pass("validation", &inputs)
+    }
+
+    fn pass(&self, name: &str, inputs: &[&[u8]]) -> PassCheckpoint {
+        let mut hasher = blake3::Hasher::new();
+        let version: &[&[u8]] = &[CHECKPOINT_FORMAT, env!("CARGO_PKG_VERSION").as_bytes()];
+        for input in version.iter().chain(inputs) {
+            hasher.update(&(input.len() as u64).to_le_bytes());
+            hasher.update(input);
+        }
+        let digest = hasher.finalize().to_hex();
+        PassCheckpoint {
+            dir: self.dir.join(format!("{}_{}", name, &digest[..32])),
+        }
+    }
+}
+
+// The results of a single pass, see ChunkCheckpoint.
+pub struct PassCheckpoint {
+    dir: PathBuf,
+}
+
+impl PassCheckpoint {
+    // removes the results of this pass only, other passes sharing the directory are kept
+    pub fn clear(&self) -> io::Result<()> {
+        remove_dir(&self.dir)
+    }
+
+    fn path(&self, index: usize) -> PathBuf {
+        self.dir.join(format!("{index}.bin"))
+    }
+
+    pub(crate) fn load(&self, index: usize) -> Option<Vec<u8>> {
+        fs::read(self.path(index)).ok()
+    }
+
+    // written to a temporary file first, so an interrupted write never leaves a truncated result
+    pub(crate) fn store(&self, index: usize, bytes: &[u8]) {
+        let path = self.path(index);
+        let tmp_path = path.with_extension("tmp");
+        fs::create_dir_all(&self.dir)
+            .and_then(|_| fs::write(&tmp_path, bytes))
+            .and_then(|_| fs::rename(&tmp_path, &path))
+            .inspect_err(|e| tracing::warn!("Failed to store checkpoint {}: {}", path.display(), e))
+            .ok();
+    }
+}
+
+fn remove_dir(dir: &Path) -> io::Result<()> {
+    match fs::remove_dir_all(dir) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+// Computes `f` for the indices `0..len` in parallel and returns the results in index order.
+// Results found in the checkpoint are reused, new ones are stored as they complete.
+// Every index is reported to `progress` once its result is available.
+pub(crate) fn par_map_checkpointed<F>(
+    len: usize,
+    checkpoint: Option<&PassCheckpoint>,
+    progress: StageProgress,
+    f: F,
+) -> Vec<Vec<u8>>
+where
+    F: Fn(usize) -> Vec<u8> + Sync,
+{
+    (0..len)
+        .into_par_iter()
+        .map(|index| {
+            let bytes = match checkpoint.and_then(|checkpoint| checkpoint.load(index)) {
+                Some(bytes) => bytes,
+                None => {
+                    let bytes = f(index);
+                    if let Some(checkpoint) = checkpoint {
+                        checkpoint.store(index, &bytes);
+                    }
+                    bytes
+                }
+            };
+            progress.segment_done(index, len);
+            bytes
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::chunk::progress::ChunkStage;
+
+    #[test]
+    fn test_resumes_from_stored_segments() {
+        let checkpoint =
+            ChunkCheckpoint::new(std::env::temp_dir().join("bitvm_test_chunk_checkpoint"));
+        checkpoint.clear().unwrap();
+        let pass = checkpoint.pass("square", &[b"inputs".as_slice()]);
+
+        // an interrupted run that only got to compute a few segments
+        for index in [0, 3, 4] {
+            pass.store(index, &[(index * index) as u8]);
+        }
+
+        let calls = AtomicUsize::new(0);
+        let reported = Mutex::new(vec![]);
+        let on_segment = |stage: ChunkStage, index: usize, total: usize| {
+            assert_eq!((stage, total), (ChunkStage::PartialScripts, 8));
+            reported.lock().unwrap().push(index);
+        };
+        let progress = StageProgress::new(ChunkStage::PartialScripts, &on_segment);
+        let results = par_map_checkpointed(8, Some(&pass), progress, |index| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            vec![(index * index) as u8]
+        });
+        assert_eq!(calls.load(Ordering::Relaxed), 5);
+        // segments loaded from the checkpoint are reported too
+        let mut reported = reported.into_inner().unwrap();
+        reported.sort();
+        assert_eq!(reported, (0..8).collect::<Vec<_>>());
+        assert_eq!(
+            results,
+            (0..8).map(|i| vec![(i * i) as u8]).collect::<Vec<_>>()
+        );
+        assert!((0..8).all(|index| pass.load(index).is_some()));
+
+        // other inputs do not see these results
+        let other_pass = checkpoint.pass("square", &[b"other inputs".as_slice()]);
+        assert!(other_pass.load(0).is_none());
+
+        other_pass.store(0, &[0]);
+        pass.clear().unwrap();
+        assert!(pass.load(0).is_none());
+        assert!(other_pass.load(0).is_some());
+
+        checkpoint.clear().unwrap();
+        assert!(other_pass.load(0).is_none());
+    }
+}
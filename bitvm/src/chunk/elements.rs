@@ -233,10 +233,7 @@ impl DataType {
             (ElementType::ScalarElem, DataType::U256Data(r)) => {
                 as_hints_scalarelemtype_u256data(*r)
             }
-            _ => {
-                println!("Unhandled ElementType {:?} ", elem_type);
-                unreachable!();
-            }
+            _ => unreachable!("Unhandled ElementType {:?}", elem_type),
         }
     }
 }
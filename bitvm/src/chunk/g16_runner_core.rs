@@ -6,7 +6,6 @@ use ark_ff::{Field, PrimeField};
 use bitcoin::ScriptBuf;
 
 use super::{
-    api::NUM_PUBS,
     api_compiletime_utils::ATE_LOOP_COUNT,
     elements::{DataType, ElementType, HashBytes},
 };
@@ -51,7 +50,7 @@ impl InputProof {
             p4: [p4x, p4y],
             q4: [q4x0, q4x1, q4y0, q4y1],
             c: c.try_into().unwrap(),
-            ks: ks.try_into().unwrap(),
+            ks,
         }
     }
 }
@@ -62,7 +61,7 @@ pub(crate) struct InputProofRaw {
     pub(crate) p4: [ark_ff::BigInt<4>; 2],
     pub(crate) q4: [ark_ff::BigInt<4>; 4],
     pub(crate) c: [ark_ff::BigInt<4>; 6],
-    pub(crate) ks: [ark_ff::BigInt<4>; NUM_PUBS],
+    pub(crate) ks: Vec<ark_ff::BigInt<4>>,
 }
 
 fn compare(hint_out: &DataType, claimed_assertions: &mut Option<Vec<HashBytes>>) -> Option<bool> {
@@ -127,8 +126,6 @@ pub(crate) fn groth16_generate_segments(
     );
     let gc = gc.to_vec();
 
-    let pub_scalars = pub_scalars.to_vec();
-
     let p4 = wrap_hints_precompute_p(skip_evaluation, all_output_hints.len(), &gp4y, &gp4x);
     push_compare_or_return!(p4);
 
@@ -177,7 +174,7 @@ pub(crate) fn groth16_generate_segments(
 
     for j in (1..ATE_LOOP_COUNT.len()).rev() {
         if !skip_evaluation {
-            println!("Processing {:?}-th iteration of Miller Loop", j);
+            tracing::trace!("Processing {:?}-th iteration of Miller Loop", j);
         }
         let ate = ATE_LOOP_COUNT[j - 1];
         let sq = wrap_hint_squaring(skip_evaluation, all_output_hints.len(), &f_acc);
@@ -358,7 +355,7 @@ fn raw_input_proof_to_segments(
     [Segment; 2],
     [Segment; 4],
     [Segment; 6],
-    [Segment; NUM_PUBS],
+    Vec<Segment>,
 ) {
     let pub_scalars: Vec<Segment> = eval_ins
         .ks
@@ -438,7 +435,7 @@ fn raw_input_proof_to_segments(
         [gp4x.clone(), gp4y.clone()],
         temp_q4.try_into().unwrap(),
         gc.try_into().unwrap(),
-        pub_scalars.try_into().unwrap(),
+        pub_scalars,
     )
 }
 
@@ -455,10 +452,7 @@ mod test {
 
     use crate::{
         bn254::ell_coeffs::BnAffinePairing,
-        chunk::{
-            api::NUM_PUBS,
-            taps_point_ops::{chunk_point_ops_and_multiply_line_evals_step_1, frob_q_power},
-        },
+        chunk::taps_point_ops::{chunk_point_ops_and_multiply_line_evals_step_1, frob_q_power},
         groth16::offchain_checker::compute_c_wi,
     };
 
@@ -537,7 +531,7 @@ mod test {
         let vky0 = msm_gs.pop().unwrap();
 
         let mut pp3 = vky0 * ark_bn254::Fr::ONE;
-        for i in 0..NUM_PUBS {
+        for i in 0..msm_gs.len() {
             pp3 += msm_gs[i] * msm_scalar[i];
         }
         let p3 = pp3.into_affine();
@@ -1124,7 +1118,7 @@ mod test {
         msm_gs.reverse();
         let vky0 = msm_gs.pop().unwrap();
         let mut p3 = vky0 * ark_bn254::Fr::ONE;
-        for i in 0..NUM_PUBS {
+        for i in 0..msm_gs.len() {
             p3 += msm_gs[i] * msm_scalar[i];
         }
         let p3 = p3.into_affine();
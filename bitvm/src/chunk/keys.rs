@@ -0,0 +1,361 @@
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+use super::api::{num_assertions, num_hash, PublicKeys, NUM_U256};
+use crate::signatures::{GenericWinternitzPublicKey, WinternitzSecret, Wots, Wots16, Wots32};
+
+const SEED_DERIVATION_CONTEXT: &str = "BitVM chunk Winternitz secret keys";
+const SECRET_KEY_LENGTH: usize = 20;
+
+// Keys of the assertions of a disprove script setup, in the slots of PublicKeys: public inputs
+// and proof elements are committed to with Wots32, intermediate hashes with Wots16.
+// The number of intermediate hashes is checked against the number of public inputs on
+// construction, so keys of mismatched lengths never reach the scripts.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    try_from = "KeySlots<WinternitzSecret>",
+    into = "KeySlots<WinternitzSecret>"
+)]
+pub struct ChunkSecretKeys {
+    public_inputs: Vec<WinternitzSecret>,
+    proof: [WinternitzSecret; NUM_U256],
+    intermediate_hashes: Vec<WinternitzSecret>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    try_from = "KeySlots<GenericWinternitzPublicKey>",
+    into = "KeySlots<GenericWinternitzPublicKey>"
+)]
+pub struct ChunkPublicKeys {
+    public_inputs: Vec<<Wots32 as Wots>::PublicKey>,
+    proof: [<Wots32 as Wots>::PublicKey; NUM_U256],
+    intermediate_hashes: Vec<<Wots16 as Wots>::PublicKey>,
+}
+
+impl ChunkSecretKeys {
+    pub fn new(
+        public_inputs: Vec<WinternitzSecret>,
+        proof: [WinternitzSecret; NUM_U256],
+        intermediate_hashes: Vec<WinternitzSecret>,
+    ) -> Self {
+        assert_eq!(
+            intermediate_hashes.len(),
+            num_hash(public_inputs.len()),
+            "one intermediate hash secret key expected per hash"
+        );
+        ChunkSecretKeys {
+            public_inputs,
+            proof,
+            intermediate_hashes,
+        }
+    }
+
+    // secret keys listed in assertion order, i.e. public inputs, proof elements then hashes
+    pub fn from_ordered(num_pubs: usize, mut secrets: Vec<WinternitzSecret>) -> Self {
+        assert_eq!(
+            secrets.len(),
+            num_assertions(num_pubs),
+            "one secret key expected per assertion"
+        );
+        let intermediate_hashes = secrets.split_off(num_pubs + NUM_U256);
+        let proof = secrets.split_off(num_pubs).try_into().unwrap();
+        Self::new(secrets, proof, intermediate_hashes)
+    }
+
+    // deterministic keys, the key of each assertion being derived from the seed and its index
+    pub fn from_seed(num_pubs: usize, seed: &[u8]) -> Self {
+        let secrets = (0..num_assertions(num_pubs) as u32)
+            .map(|index| {
+                let mut hasher = blake3::Hasher::new_derive_key(SEED_DERIVATION_CONTEXT);
+                hasher.update(seed);
+                hasher.update(&index.to_le_bytes());
+                hasher.finalize().as_bytes()[..SECRET_KEY_LENGTH].to_vec()
+            })
+            .collect();
+        Self::from_ordered(num_pubs, secrets)
+    }
+
+    pub fn random(num_pubs: usize) -> Self {
+        let secrets = (0..num_assertions(num_pubs))
+            .map(|_| Wots32::generate_secret_key())
+            .collect();
+        Self::from_ordered(num_pubs, secrets)
+    }
+
+    // secret strings in assertion order, converted the way the chunk api used to take them
+    #[allow(deprecated)]
+    pub fn from_strings(num_pubs: usize, secrets: &[String]) -> Self {
+        Self::from_ordered(
+            num_pubs,
+            secrets
+                .iter()
+                .map(|secret| Wots32::secret_from_str(secret))
+                .collect(),
+        )
+    }
+
+    pub fn num_pubs(&self) -> usize {
+        self.public_inputs.len()
+    }
+
+    pub fn public_inputs(&self) -> &[WinternitzSecret] {
+        &self.public_inputs
+    }
+
+    pub fn proof(&self) -> &[WinternitzSecret; NUM_U256] {
+        &self.proof
+    }
+
+    pub fn intermediate_hashes(&self) -> &[WinternitzSecret] {
+        &self.intermediate_hashes
+    }
+
+    pub fn public_keys(&self) -> ChunkPublicKeys {
+        ChunkPublicKeys {
+            public_inputs: self
+                .public_inputs
+                .iter()
+                .map(Wots32::generate_public_key)
+                .collect(),
+            proof: self.proof.each_ref().map(Wots32::generate_public_key),
+            intermediate_hashes: self
+                .intermediate_hashes
+                .iter()
+                .map(Wots16::generate_public_key)
+                .collect(),
+        }
+    }
+}
+
+impl Drop for ChunkSecretKeys {
+    fn drop(&mut self) {
+        self.public_inputs.zeroize();
+        self.proof.iter_mut().for_each(|secret| secret.zeroize());
+        self.intermediate_hashes.zeroize();
+    }
+}
+
+impl ChunkPublicKeys {
+    pub fn new(
+        public_inputs: Vec<<Wots32 as Wots>::PublicKey>,
+        proof: [<Wots32 as Wots>::PublicKey; NUM_U256],
+        intermediate_hashes: Vec<<Wots16 as Wots>::PublicKey>,
+    ) -> Self {
+        assert_eq!(
+            intermediate_hashes.len(),
+            num_hash(public_inputs.len()),
+            "one intermediate hash public key expected per hash"
+        );
+        ChunkPublicKeys {
+            public_inputs,
+            proof,
+            intermediate_hashes,
+        }
+    }
+
+    // public keys listed in assertion order, i.e. public inputs, proof elements then hashes
+    pub fn from_ordered(num_pubs: usize, public_keys: Vec<GenericWinternitzPublicKey>) -> Self {
+        assert_eq!(
+            public_keys.len(),
+            num_assertions(num_pubs),
+            "one public key expected per assertion"
+        );
+        KeySlots {
+            public_inputs: public_keys[..num_pubs].to_vec(),
+            proof: public_keys[num_pubs..num_pubs + NUM_U256].to_vec(),
+            intermediate_hashes: public_keys[num_pubs + NUM_U256..].to_vec(),
+        }
+        .try_into()
+        .unwrap()
+    }
+
+    pub fn num_pubs(&self) -> usize {
+        self.public_inputs.len()
+    }
+
+    pub fn public_inputs(&self) -> &[<Wots32 as Wots>::PublicKey] {
+        &self.public_inputs
+    }
+
+    pub fn proof(&self) -> &[<Wots32 as Wots>::PublicKey; NUM_U256] {
+        &self.proof
+    }
+
+    pub fn intermediate_hashes(&self) -> &[<Wots16 as Wots>::PublicKey] {
+        &self.intermediate_hashes
+    }
+}
+
+impl From<ChunkPublicKeys> for PublicKeys {
+    fn from(public_keys: ChunkPublicKeys) -> Self {
+        (
+            public_keys.public_inputs,
+            public_keys.proof,
+            public_keys.intermediate_hashes,
+        )
+    }
+}
+
+impl From<PublicKeys> for ChunkPublicKeys {
+    fn from(public_keys: PublicKeys) -> Self {
+        Self::new(public_keys.0, public_keys.1, public_keys.2)
+    }
+}
+
+// Serialized form of the keys, the lengths of the slots are checked when deserializing
+#[derive(Clone, Serialize, Deserialize)]
+struct KeySlots<T> {
+    public_inputs: Vec<T>,
+    proof: Vec<T>,
+    intermediate_hashes: Vec<T>,
+}
+
+impl<T> KeySlots<T> {
+    fn check_lengths(&self) -> Result<(), String> {
+        if self.proof.len() != NUM_U256 {
+            return Err(format!(
+                "expected {} proof keys, found {}",
+                NUM_U256,
+                self.proof.len()
+            ));
+        }
+        let num_hash = num_hash(self.public_inputs.len());
+        if self.intermediate_hashes.len() != num_hash {
+            return Err(format!(
+                "expected {} intermediate hash keys for {} public inputs, found {}",
+                num_hash,
+                self.public_inputs.len(),
+                self.intermediate_hashes.len()
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<KeySlots<WinternitzSecret>> for ChunkSecretKeys {
+    type Error = String;
+
+    fn try_from(slots: KeySlots<WinternitzSecret>) -> Result<Self, Self::Error> {
+        slots.check_lengths()?;
+        Ok(ChunkSecretKeys {
+            public_inputs: slots.public_inputs,
+            proof: slots.proof.try_into().unwrap(),
+            intermediate_hashes: slots.intermediate_hashes,
+        })
+    }
+}
+
+impl From<ChunkSecretKeys> for KeySlots<WinternitzSecret> {
+    fn from(secrets: ChunkSecretKeys) -> Self {
+        KeySlots {
+            public_inputs: secrets.public_inputs.clone(),
+            proof: secrets.proof.to_vec(),
+            intermediate_hashes: secrets.intermediate_hashes.clone(),
+        }
+    }
+}
+
+impl TryFrom<KeySlots<GenericWinternitzPublicKey>> for ChunkPublicKeys {
+    type Error = String;
+
+    fn try_from(slots: KeySlots<GenericWinternitzPublicKey>) -> Result<Self, Self::Error> {
+        fn typed<P: TryFrom<GenericWinternitzPublicKey>>(
+            public_keys: Vec<GenericWinternitzPublicKey>,
+        ) -> Result<Vec<P>, String> {
+            public_keys
+                .into_iter()
+                .map(|public_key| {
+                    let length = public_key.len();
+                    P::try_from(public_key)
+                        .map_err(|_| format!("unexpected public key length {}", length))
+                })
+                .collect()
+        }
+
+        slots.check_lengths()?;
+        Ok(ChunkPublicKeys {
+            public_inputs: typed(slots.public_inputs)?,
+            proof: typed(slots.proof)?.try_into().unwrap(),
+            intermediate_hashes: typed(slots.intermediate_hashes)?,
+        })
+    }
+}
+
+impl From<ChunkPublicKeys> for KeySlots<GenericWinternitzPublicKey> {
+    fn from(public_keys: ChunkPublicKeys) -> Self {
+        KeySlots {
+            public_inputs: public_keys
+                .public_inputs
+                .iter()
+                .map(|public_key| public_key.to_vec())
+                .collect(),
+            proof: public_keys
+                .proof
+                .iter()
+                .map(|public_key| public_key.to_vec())
+                .collect(),
+            intermediate_hashes: public_keys
+                .intermediate_hashes
+                .iter()
+                .map(|public_key| public_key.to_vec())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NUM_PUBS: usize = 1;
+
+    #[test]
+    fn test_chunk_keys_from_seed() {
+        let secrets = ChunkSecretKeys::from_seed(NUM_PUBS, b"seed");
+        assert_eq!(secrets.num_pubs(), NUM_PUBS);
+        assert_eq!(secrets.intermediate_hashes().len(), num_hash(NUM_PUBS));
+        assert!(secrets == ChunkSecretKeys::from_seed(NUM_PUBS, b"seed"));
+        assert!(secrets != ChunkSecretKeys::from_seed(NUM_PUBS, b"other seed"));
+
+        let public_keys = secrets.public_keys();
+        assert_eq!(
+            public_keys.intermediate_hashes()[0],
+            Wots16::generate_public_key(&secrets.intermediate_hashes()[0])
+        );
+        let ordered = [
+            secrets.public_inputs(),
+            secrets.proof().as_slice(),
+            secrets.intermediate_hashes(),
+        ]
+        .concat();
+        assert!(ChunkSecretKeys::from_ordered(NUM_PUBS, ordered) == secrets);
+    }
+
+    #[test]
+    fn test_chunk_keys_serde() {
+        let secrets = ChunkSecretKeys::from_seed(NUM_PUBS, b"seed");
+        let json = serde_json::to_string(&secrets).unwrap();
+        assert!(serde_json::from_str::<ChunkSecretKeys>(&json).unwrap() == secrets);
+
+        let public_keys = secrets.public_keys();
+        let json = serde_json::to_value(&public_keys).unwrap();
+        assert_eq!(
+            serde_json::from_value::<ChunkPublicKeys>(json.clone()).unwrap(),
+            public_keys
+        );
+
+        // keys for another number of public inputs
+        let mut wrong_length = json.clone();
+        wrong_length["public_inputs"]
+            .as_array_mut()
+            .unwrap()
+            .push(json["proof"][0].clone());
+        assert!(serde_json::from_value::<ChunkPublicKeys>(wrong_length).is_err());
+
+        // Wots32 key in a Wots16 slot
+        let mut wrong_type = json.clone();
+        wrong_type["intermediate_hashes"][0] = json["proof"][0].clone();
+        assert!(serde_json::from_value::<ChunkPublicKeys>(wrong_type).is_err());
+    }
+}
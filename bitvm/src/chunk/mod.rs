@@ -7,6 +7,11 @@ mod helpers;
 pub mod api;
 pub mod api_compiletime_utils;
 mod api_runtime_utils;
+pub mod bundle;
+pub mod checkpoint;
+pub mod keys;
+pub mod progress;
+pub mod report;
 
 pub mod g16_runner_core;
 pub mod g16_runner_utils;
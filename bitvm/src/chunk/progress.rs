@@ -0,0 +1,52 @@
+// Passes over the segments of the groth16 verifier that report their progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChunkStage {
+    // compiling the partial script of every segment
+    PartialScripts,
+    // appending the bitcommitment locking script to every partial script
+    Tapscripts,
+    // executing the disprove script of every segment with the assertions as witness
+    Validation,
+}
+
+// Receives the index of every segment processed by a stage along with the number of segments
+// the stage processes. Segments processed in parallel are reported in the order they complete,
+// segments loaded from a checkpoint are reported as well.
+pub trait ChunkProgress: Sync {
+    fn on_segment(&self, stage: ChunkStage, segment_index: usize, total: usize);
+}
+
+impl<F> ChunkProgress for F
+where
+    F: Fn(ChunkStage, usize, usize) + Sync,
+{
+    fn on_segment(&self, stage: ChunkStage, segment_index: usize, total: usize) {
+        self(stage, segment_index, total)
+    }
+}
+
+// progress of the api functions not given a callback
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoProgress;
+
+impl ChunkProgress for NoProgress {
+    fn on_segment(&self, _stage: ChunkStage, _segment_index: usize, _total: usize) {}
+}
+
+// reports the segments processed by one stage
+#[derive(Clone, Copy)]
+pub(crate) struct StageProgress<'a> {
+    stage: ChunkStage,
+    progress: &'a dyn ChunkProgress,
+}
+
+impl<'a> StageProgress<'a> {
+    pub(crate) fn new(stage: ChunkStage, progress: &'a dyn ChunkProgress) -> Self {
+        Self { stage, progress }
+    }
+
+    pub(crate) fn segment_done(&self, segment_index: usize, total: usize) {
+        tracing::trace!(stage = ?self.stage, segment_index, total, "segment done");
+        self.progress.on_segment(self.stage, segment_index, total);
+    }
+}
@@ -0,0 +1,211 @@
+use bitcoin::hex::{DisplayHex, FromHex};
+use bitcoin::{ScriptBuf, Witness};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::chunk::api::type_conversion_utils::{script_to_witness, RawWitness};
+use crate::treepp::Script;
+
+use super::elements::CompressedStateObject;
+use super::g16_runner_utils::{ScriptType, Segment};
+
+// Why the signed assertions are inconsistent at the failing segment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DisproveReason {
+    // the asserted output differs from the one computed from the asserted inputs
+    OutputMismatch,
+    // the asserted inputs are not valid elements of their type, e.g. a point not on the curve
+    InvalidInput,
+    // the asserted values are consistent, but the groth16 proof does not verify
+    ProofRejected,
+}
+
+// An asserted intermediate hash or field element, hex encoded as it is bitcommitted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type", content = "value")]
+pub enum ChunkValue {
+    Hash(String),
+    FieldElement(String),
+}
+
+impl From<&CompressedStateObject> for ChunkValue {
+    fn from(state: &CompressedStateObject) -> Self {
+        let hex = state.serialize_to_byte_array().to_lower_hex_string();
+        match state {
+            CompressedStateObject::Hash(_) => ChunkValue::Hash(hex),
+            CompressedStateObject::U256(_) => ChunkValue::FieldElement(hex),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssertedValue {
+    // index of the assertion in the order [PublicInputs, ProofInputs, IntermediateHashes]
+    pub assertion_index: usize,
+    pub value: ChunkValue,
+}
+
+// Explains which tapscript disproves the signed assertions and why, along with the witness
+// the tapscript has to be spent with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisproveReport {
+    pub tapscript_index: usize,
+    pub segment_index: usize,
+    pub script_type: ScriptType,
+    pub reason: DisproveReason,
+    pub inputs: Vec<AssertedValue>,
+    // final script doesn't have output
+    pub output: Option<AssertedValue>,
+    // OutputMismatch: output computed from the inputs and the asserted output
+    // ProofRejected: one and the result of the final verification
+    // InvalidInput: none
+    pub expected: Option<ChunkValue>,
+    pub claimed: Option<ChunkValue>,
+    #[serde(with = "hex_witness")]
+    pub witness: RawWitness,
+    // size of the witness spending the tapscript i.e. the stack, the tapscript and its control
+    // block, which is also its weight as witness data counts one weight unit per byte. The weight
+    // of the rest of the transaction is up to the protocol spending it.
+    pub witness_size: usize,
+}
+
+impl DisproveReport {
+    pub(crate) fn new(
+        segments: &[Segment],
+        asserted: &[CompressedStateObject],
+        tapscript_index: usize,
+        hint_script: Script,
+        disprove_scripts: &[ScriptBuf],
+    ) -> Self {
+        // tapscripts are generated for all but the non-deterministic segments, in segment order
+        let segment_index = (0..segments.len())
+            .filter(|i| segments[*i].scr_type != ScriptType::NonDeterministic)
+            .nth(tapscript_index)
+            .expect("tapscript index should correspond to a segment");
+        let segment = &segments[segment_index];
+
+        let asserted_value = |assertion_index: usize| AssertedValue {
+            assertion_index,
+            value: ChunkValue::from(&asserted[assertion_index]),
+        };
+        let inputs = segment
+            .parameter_ids
+            .iter()
+            .map(|(param_seg_id, _)| asserted_value(*param_seg_id as usize))
+            .collect();
+        let output =
+            (!segment.scr_type.is_final_script()).then(|| asserted_value(segment.id as usize));
+
+        let computed = ChunkValue::from(&segment.result.0.to_hash());
+        let (reason, expected, claimed) = if segment.scr_type.is_final_script() {
+            let one = CompressedStateObject::U256(ark_ff::BigInt::<4>::one());
+            (
+                DisproveReason::ProofRejected,
+                Some(ChunkValue::from(&one)),
+                Some(computed),
+            )
+        } else if !segment.is_valid_input {
+            (DisproveReason::InvalidInput, None, None)
+        } else {
+            let claimed = output.as_ref().map(|output| output.value.clone());
+            (DisproveReason::OutputMismatch, Some(computed), claimed)
+        };
+
+        let witness = script_to_witness(hint_script);
+        let witness_size = disprove_witness_size(
+            &witness,
+            &disprove_scripts[tapscript_index],
+            disprove_scripts.len(),
+        );
+
+        DisproveReport {
+            tapscript_index,
+            segment_index,
+            script_type: segment.scr_type.clone(),
+            reason,
+            inputs,
+            output,
+            expected,
+            claimed,
+            witness,
+            witness_size,
+        }
+    }
+}
+
+// The disprove scripts are leaves of equal weight in a huffman tree, so the control block
+// proves a path of at most ceil(log2(num_taps)) nodes
+fn disprove_witness_size(
+    witness: &RawWitness,
+    disprove_script: &ScriptBuf,
+    num_taps: usize,
+) -> usize {
+    let merkle_path_len = (usize::BITS - num_taps.saturating_sub(1).leading_zeros()) as usize;
+    let mut witness = Witness::from_slice(witness);
+    witness.push(disprove_script.as_bytes());
+    witness.push(vec![0u8; 33 + 32 * merkle_path_len]);
+    witness.size()
+}
+
+mod hex_witness {
+    use super::*;
+
+    pub(super) fn serialize<S: Serializer>(
+        witness: &RawWitness,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(witness.iter().map(|item| item.to_lower_hex_string()))
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<RawWitness, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|item| Vec::<u8>::from_hex(item).map_err(serde::de::Error::custom))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disprove_witness_size() {
+        let witness = vec![vec![], vec![1], vec![2; 100]];
+        let script = ScriptBuf::from_bytes(vec![0x51; 300]);
+        // 5 items: 1 + (1 + 0) + (1 + 1) + (1 + 100) + (3 + 300) + (3 + 33 + 32 * 9)
+        assert_eq!(disprove_witness_size(&witness, &script, 400), 732);
+        assert_eq!(disprove_witness_size(&witness, &script, 512), 732);
+        assert_eq!(disprove_witness_size(&witness, &script, 513), 764);
+    }
+
+    #[test]
+    fn test_report_json_roundtrip() {
+        let report = DisproveReport {
+            tapscript_index: 3,
+            segment_index: 17,
+            script_type: ScriptType::MillerPointOpsStep1(true, Some(-1), None),
+            reason: DisproveReason::OutputMismatch,
+            inputs: vec![AssertedValue {
+                assertion_index: 2,
+                value: ChunkValue::FieldElement("00".repeat(32)),
+            }],
+            output: Some(AssertedValue {
+                assertion_index: 17,
+                value: ChunkValue::Hash("ab".repeat(20)),
+            }),
+            expected: Some(ChunkValue::Hash("cd".repeat(20))),
+            claimed: Some(ChunkValue::Hash("ab".repeat(20))),
+            witness: vec![vec![], vec![0xde, 0xad]],
+            witness_size: 732,
+        };
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["reason"], "output_mismatch");
+        assert_eq!(json["claimed"]["type"], "hash");
+        assert_eq!(json["witness"], serde_json::json!(["", "dead"]));
+        let decoded: DisproveReport = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded, report);
+    }
+}
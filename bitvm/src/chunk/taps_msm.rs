@@ -3,7 +3,6 @@ use crate::bn254::fr::Fr;
 use crate::bn254::g1::G1Affine;
 use crate::bn254::msm;
 use crate::bn254::utils::Hint;
-use crate::chunk::api::NUM_PUBS;
 use crate::{bn254::fp254impl::Fp254Impl, treepp::*};
 use ark_ec::CurveGroup;
 use ark_ff::{AdditiveGroup, Field, PrimeField};
@@ -16,8 +15,7 @@ pub(crate) fn chunk_msm(
     input_ks: Vec<ark_ff::BigInt<4>>,
     qs: Vec<ark_bn254::G1Affine>,
 ) -> Vec<(ark_bn254::G1Affine, bool, Script, Vec<Hint>)> {
-    assert_eq!(qs.len(), NUM_PUBS);
-    assert_eq!(input_ks.len(), NUM_PUBS);
+    assert_eq!(qs.len(), input_ks.len());
     let num_pubs = input_ks.len();
 
     let ks = input_ks
@@ -1,5 +1,6 @@
 pub mod constants;
 pub mod offchain_checker;
+pub mod risc0;
 
 #[cfg(test)]
 mod test;
@@ -0,0 +1,102 @@
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ff::PrimeField;
+use ark_groth16::Proof;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+/// Length of the byte encoding of a risc0 Groth16 seal: the 8 coordinates of A, B and C.
+pub const RISC0_SEAL_LENGTH: usize = 256;
+/// The risc0 to BitVM Groth16 circuit commits to the first 31 bytes of the output hash, so that
+/// the public input fits in a BN254 scalar.
+pub const RISC0_PUBLIC_INPUT_LENGTH: usize = 31;
+
+/// The single public input of the risc0 to BitVM Groth16 circuit:
+/// Blake3(constants_digest || journal), truncated to 31 bytes and read as a big endian integer.
+/// The constants digest commits to the succinct verifier parameters and the method id of the
+/// guest.
+pub fn risc0_public_input(constants_digest: &[u8; 32], journal: &[u8]) -> Fr {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(constants_digest);
+    hasher.update(journal);
+    Fr::from_be_bytes_mod_order(&hasher.finalize().as_bytes()[..RISC0_PUBLIC_INPUT_LENGTH])
+}
+
+/// Decodes the 256 byte encoding of a risc0 seal: big endian coordinates of A, B and C, with the
+/// Fq2 coordinates of B ordered [c1, c0] as for the Ethereum verifier.
+pub fn decode_risc0_seal(seal: &[u8]) -> Result<Proof<Bn254>, String> {
+    if seal.len() != RISC0_SEAL_LENGTH {
+        return Err(format!("Seal must be {RISC0_SEAL_LENGTH} bytes long"));
+    }
+    let fq = |index: usize| fq_from_be_bytes(&seal[index * 32..(index + 1) * 32]);
+
+    let a = G1Affine::new_unchecked(fq(0)?, fq(1)?);
+    let b = G2Affine::new_unchecked(Fq2::new(fq(3)?, fq(2)?), Fq2::new(fq(5)?, fq(4)?));
+    let c = G1Affine::new_unchecked(fq(6)?, fq(7)?);
+    if !a.is_on_curve()
+        || !b.is_on_curve()
+        || !c.is_on_curve()
+        || !b.is_in_correct_subgroup_assuming_on_curve()
+    {
+        return Err("Seal point is not on the curve".to_string());
+    }
+
+    Ok(Proof { a, b, c })
+}
+
+/// Inverse of [`decode_risc0_seal`].
+pub fn encode_risc0_seal(proof: &Proof<Bn254>) -> Vec<u8> {
+    [
+        proof.a.x,
+        proof.a.y,
+        proof.b.x.c1,
+        proof.b.x.c0,
+        proof.b.y.c1,
+        proof.b.y.c0,
+        proof.c.x,
+        proof.c.y,
+    ]
+    .iter()
+    .flat_map(|coordinate| {
+        let mut bytes = vec![];
+        coordinate
+            .serialize_uncompressed(&mut bytes)
+            .expect("Failed to serialize field element");
+        bytes.reverse();
+        bytes
+    })
+    .collect()
+}
+
+// Fails for values that are not reduced, the arkworks encoding is little endian.
+fn fq_from_be_bytes(big_endian: &[u8]) -> Result<Fq, String> {
+    let mut little_endian = big_endian.to_vec();
+    little_endian.reverse();
+    Fq::deserialize_uncompressed(&*little_endian).map_err(|e| format!("Invalid field element: {e}"))
+}
+
+#[cfg(test)]
+mod test {
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_std::UniformRand;
+
+    use super::*;
+
+    #[test]
+    fn test_risc0_seal_round_trip() {
+        let mut rng = ark_std::test_rng();
+        let proof = Proof::<Bn254> {
+            a: (G1Affine::generator() * Fr::rand(&mut rng)).into_affine(),
+            b: (G2Affine::generator() * Fr::rand(&mut rng)).into_affine(),
+            c: (G1Affine::generator() * Fr::rand(&mut rng)).into_affine(),
+        };
+
+        let seal = encode_risc0_seal(&proof);
+        assert_eq!(seal.len(), RISC0_SEAL_LENGTH);
+        assert_eq!(decode_risc0_seal(&seal).unwrap(), proof);
+
+        assert!(decode_risc0_seal(&seal[1..]).is_err());
+        let mut swapped = seal.clone();
+        swapped[64..128].rotate_left(32);
+        assert!(decode_risc0_seal(&swapped).is_err());
+        assert!(decode_risc0_seal(&[0xff; RISC0_SEAL_LENGTH]).is_err());
+    }
+}
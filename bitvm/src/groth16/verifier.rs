@@ -59,8 +59,8 @@ impl Verifier {
         let (c, wi) = compute_c_wi(f_without_3);
         let c_inv = c.inverse().unwrap();
         let result = f_without_3 * wi * (c_inv.pow(LAMBDA.to_u64_digits()));
-        println!("f_without_3: {:?}", f_without_3);
-        println!("result: {:?}", result);
+        tracing::trace!("f_without_3: {:?}", f_without_3);
+        tracing::trace!("result: {:?}", result);
 
         let q_prepared = [
             G2Prepared::from_affine(q1),
@@ -1,6 +1,7 @@
 use bitcoin::hex::DisplayHex;
 use bitcoin::Witness;
 use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
 
 use crate::treepp::{script, Script};
 use crate::{
@@ -40,7 +41,9 @@ impl WinternitzSecret {
         let mut rng = rand::rngs::OsRng;
         rand::RngCore::fill_bytes(&mut rng, &mut buffer);
 
-        Self::from_bytes(message_len, buffer.to_lower_hex_string().into())
+        let secret = Self::from_bytes(message_len, buffer.to_lower_hex_string().into());
+        buffer.zeroize();
+        secret
     }
 
     /// Creates a secret key from the given `secret_bytes`,
@@ -68,6 +71,14 @@ impl WinternitzSecret {
     }
 }
 
+/// The secret key is zeroized when the secret is dropped.
+/// Its fields cannot be moved out of the secret, clone the secret key instead.
+impl Drop for WinternitzSecret {
+    fn drop(&mut self) {
+        self.secret_key.zeroize();
+    }
+}
+
 #[derive(Serialize, Deserialize, Eq, PartialEq, Hash, Clone)]
 pub struct WinternitzPublicKey {
     pub public_key: PublicKey,
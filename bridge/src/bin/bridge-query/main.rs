@@ -18,6 +18,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
         .subcommand(QueryCommand::peg_in_graphs_command())
         .arg(arg!(-e --environment <ENVIRONMENT> "Specify the Bitcoin and L2 network environment (mainnet, testnet, local)").required(false)
         .default_value("testnet"))
+        .arg(arg!(-b --"bitcoin-backend" <BACKEND> "Specify the Bitcoin backend used to query and broadcast transactions (esplora, bitcoind)").required(false).default_value("esplora").env("BITCOIN_BACKEND"))
         .arg(arg!(-p --prefix <PREFIX> "Prefix for local file cache path").required(false));
 
     let matches = command.clone().get_matches();
@@ -31,9 +32,13 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 std::process::exit(1);
             }
         };
+    let bitcoin_backend = matches
+        .get_one::<String>("bitcoin-backend")
+        .map(|s| s.as_str());
     let prefix = matches.get_one::<String>("prefix").map(|s| s.as_str());
 
-    let mut query = QueryCommand::new(source_network, destination_network, prefix).await;
+    let mut query =
+        QueryCommand::new(source_network, destination_network, bitcoin_backend, prefix).await;
     let resp = match matches.subcommand() {
         Some(("depositor", sub)) => query.handle_depositor(sub).await,
         Some(("withdrawer", sub)) => query.handle_withdrawer(sub, destination_network).await,
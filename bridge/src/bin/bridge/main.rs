@@ -24,7 +24,16 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 .value_parser(clap::value_parser!(PublicKey))
                 .env("VERIFIERS"),
         )
+        .arg(
+            arg!(--operators [OPERATOR_PUBKEYS] "Comma-separated list of the public keys of the operators of new peg-ins")
+                .required(false)
+                .num_args(0..1000)
+                .value_delimiter(',')
+                .value_parser(clap::value_parser!(PublicKey))
+                .env("OPERATORS"),
+        )
         .arg(arg!(-e --environment <ENVIRONMENT> "Specify the Bitcoin network environment (mainnet, testnet, regtest)").required(false).default_value("testnet").env("ENVIRONMENT"))
+        .arg(arg!(-b --"bitcoin-backend" <BACKEND> "Specify the Bitcoin backend used to query and broadcast transactions (esplora, bitcoind)").required(false).default_value("esplora").env("BITCOIN_BACKEND"))
         .arg(arg!(-p --"user-profile" <USER_PROFILE> "Name of the protocol participant (e.g. 'operator_one', 'verifier_0'). Used as a namespace separator in the local file path for storing private and public client data").required(false).default_value("default_user").env("USER_PROFILE"))
         .subcommand(KeysCommand::get_command())
         .subcommand(ClientCommand::get_funding_amounts_command())
@@ -38,6 +47,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
         .subcommand(ClientCommand::get_push_signature_command())
         .subcommand(ClientCommand::get_mock_l2_pegout_event_command())
         .subcommand(ClientCommand::get_status_command())
+        .subcommand(ClientCommand::get_liquidity_report_command())
         .subcommand(ClientCommand::get_broadcast_command())
         .subcommand(ClientCommand::get_automatic_command())
         .subcommand(ClientCommand::get_interactive_command());
@@ -49,7 +59,11 @@ async fn main() -> Result<(), Box<dyn Error>> {
         verifiers: matches
             .get_many::<PublicKey>("verifiers")
             .map(|x| x.cloned().collect::<Vec<PublicKey>>()),
+        operators: matches
+            .get_many::<PublicKey>("operators")
+            .map(|x| x.cloned().collect::<Vec<PublicKey>>()),
         environment: matches.get_one::<String>("environment").cloned(),
+        bitcoin_backend: matches.get_one::<String>("bitcoin-backend").cloned(),
         path_prefix: matches.get_one::<String>("user-profile").cloned(),
     };
 
@@ -100,6 +114,11 @@ async fn main() -> Result<(), Box<dyn Error>> {
     } else if matches.subcommand_matches("status").is_some() {
         let mut client_command = ClientCommand::new(global_args).await;
         let _ = client_command.handle_status_command().await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("liquidity-report") {
+        let mut client_command = ClientCommand::new(global_args).await;
+        let _ = client_command
+            .handle_liquidity_report_command(sub_matches)
+            .await;
     } else if let Some(sub_matches) = matches.subcommand_matches("broadcast") {
         let mut client_command = ClientCommand::new(global_args).await;
         let _ = client_command.handle_broadcast_command(sub_matches).await;
@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bitcoin::{block::Header, Address, FeeRate, Network, Transaction, Txid};
+
+pub use esplora_client::{TxStatus, Utxo, UtxoStatus};
+
+use crate::{
+    client::esplora::get_esplora_url, error::BitcoinBackendError, graphs::base::MIN_RELAY_FEE_RATE,
+};
+
+use super::{
+    bitcoind_backend::{BitcoindBackend, BitcoindConfig},
+    esplora_backend::EsploraBackend,
+    memory_backend::MemoryBackend,
+};
+
+#[async_trait]
+pub trait BitcoinBackend: Send + Sync {
+    // Unknown transactions are reported as unconfirmed rather than as an error.
+    async fn get_tx_status(&self, txid: &Txid) -> Result<TxStatus, BitcoinBackendError>;
+    async fn get_tx(&self, txid: &Txid) -> Result<Option<Transaction>, BitcoinBackendError>;
+    async fn get_height(&self) -> Result<u32, BitcoinBackendError>;
+    // Transactions of the block at `height` in the current best chain.
+    async fn get_block_txs(&self, height: u32) -> Result<Vec<Transaction>, BitcoinBackendError>;
+    // Header of the block at `height` in the current best chain.
+    async fn get_block_header(&self, height: u32) -> Result<Header, BitcoinBackendError>;
+    async fn get_address_utxo(&self, address: Address) -> Result<Vec<Utxo>, BitcoinBackendError>;
+    async fn broadcast(&self, tx: &Transaction) -> Result<(), BitcoinBackendError>;
+    // Fee rate for a transaction to confirm within `target_blocks` blocks. Falls back to the
+    // minimum relay fee rate if the backend has no estimate yet, e.g. on regtest.
+    async fn estimate_fee_rate(&self, target_blocks: u16) -> Result<FeeRate, BitcoinBackendError>;
+}
+
+pub fn min_relay_fee_rate() -> FeeRate {
+    FeeRate::from_sat_per_vb_unchecked(MIN_RELAY_FEE_RATE)
+}
+
+pub enum BitcoinBackendConfig {
+    Esplora(Option<String>), // falls back to the default esplora url of the network
+    Bitcoind(Option<BitcoindConfig>), // falls back to the BRIDGE_BITCOIND_* environment variables
+    Memory,
+}
+
+pub fn get_bitcoin_backend(
+    network: Network,
+    config: BitcoinBackendConfig,
+) -> Arc<dyn BitcoinBackend> {
+    match config {
+        BitcoinBackendConfig::Esplora(url) => Arc::new(EsploraBackend::new(
+            url.as_deref().unwrap_or(get_esplora_url(network)),
+        )),
+        BitcoinBackendConfig::Bitcoind(config) => Arc::new(BitcoindBackend::new(config)),
+        BitcoinBackendConfig::Memory => Arc::new(MemoryBackend::new()),
+    }
+}
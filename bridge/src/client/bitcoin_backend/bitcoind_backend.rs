@@ -0,0 +1,263 @@
+use std::str::FromStr;
+
+use alloy::transports::http::reqwest::{Client, Url};
+use async_trait::async_trait;
+use bitcoin::{
+    block::Header,
+    consensus::encode::{deserialize_hex, serialize_hex},
+    Address, Amount, Block, BlockHash, FeeRate, Transaction, Txid,
+};
+use dotenv;
+use serde_json::{json, Value};
+
+use crate::error::BitcoinBackendError;
+
+use super::bitcoin_backend::{min_relay_fee_rate, BitcoinBackend, TxStatus, Utxo, UtxoStatus};
+
+// See bitcoind src/rpc/protocol.h
+const RPC_INVALID_ADDRESS_OR_KEY: i64 = -5;
+
+pub struct BitcoindConfig {
+    pub rpc_url: Url,
+    pub rpc_user: String,
+    pub rpc_password: String,
+}
+
+// Talks to bitcoind over JSON-RPC. Looking up arbitrary transactions requires the node to run
+// with `-txindex=1`, and address UTXOs are collected with `scantxoutset`, which only sees
+// confirmed outputs.
+pub struct BitcoindBackend {
+    client: Client,
+    config: BitcoindConfig,
+}
+
+impl BitcoindBackend {
+    pub fn new(config: Option<BitcoindConfig>) -> Self {
+        if let Some(_config) = config {
+            Self::from_config(_config)
+        } else {
+            dotenv::dotenv().ok();
+            let rpc_url_str = dotenv::var("BRIDGE_BITCOIND_RPC_URL")
+                .expect("Failed to read BRIDGE_BITCOIND_RPC_URL variable");
+            let rpc_user = dotenv::var("BRIDGE_BITCOIND_RPC_USER")
+                .expect("Failed to read BRIDGE_BITCOIND_RPC_USER variable");
+            let rpc_password = dotenv::var("BRIDGE_BITCOIND_RPC_PASSWORD")
+                .expect("Failed to read BRIDGE_BITCOIND_RPC_PASSWORD variable");
+
+            Self::from_config(BitcoindConfig {
+                rpc_url: rpc_url_str.parse::<Url>().unwrap(),
+                rpc_user,
+                rpc_password,
+            })
+        }
+    }
+
+    fn from_config(config: BitcoindConfig) -> Self {
+        Self {
+            client: Client::new(),
+            config,
+        }
+    }
+
+    // Returns `None` if bitcoind reports the requested object as unknown.
+    async fn call_optional(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Result<Option<Value>, BitcoinBackendError> {
+        let request = json!({
+            "jsonrpc": "1.0",
+            "id": "bridge",
+            "method": method,
+            "params": params,
+        });
+        // bitcoind answers RPC errors with a non-success http status, but the body still holds
+        // the JSON-RPC error object, so the status code is not checked here.
+        let response = self
+            .client
+            .post(self.config.rpc_url.clone())
+            .basic_auth(&self.config.rpc_user, Some(&self.config.rpc_password))
+            .header("content-type", "application/json")
+            .body(request.to_string())
+            .send()
+            .await
+            .map_err(|e| BitcoinBackendError::Rpc(format!("{method}: {e}")))?
+            .text()
+            .await
+            .map_err(|e| BitcoinBackendError::Rpc(format!("{method}: {e}")))?;
+        let mut response: Value = serde_json::from_str(&response)
+            .map_err(|e| BitcoinBackendError::Rpc(format!("{method}: {e}: {response}")))?;
+
+        match response.get("error") {
+            Some(error) if !error.is_null() => {
+                if error["code"].as_i64() == Some(RPC_INVALID_ADDRESS_OR_KEY) {
+                    Ok(None)
+                } else {
+                    Err(BitcoinBackendError::Rpc(format!("{method}: {error}")))
+                }
+            }
+            _ => Ok(Some(response["result"].take())),
+        }
+    }
+
+    async fn call(&self, method: &str, params: Value) -> Result<Value, BitcoinBackendError> {
+        self.call_optional(method, params)
+            .await?
+            .ok_or_else(|| BitcoinBackendError::Rpc(format!("{method}: invalid address or key")))
+    }
+}
+
+fn parse_block_hash(value: &Value) -> Result<Option<BlockHash>, BitcoinBackendError> {
+    value
+        .as_str()
+        .map(BlockHash::from_str)
+        .transpose()
+        .map_err(|e| BitcoinBackendError::Rpc(e.to_string()))
+}
+
+#[async_trait]
+impl BitcoinBackend for BitcoindBackend {
+    async fn get_tx_status(&self, txid: &Txid) -> Result<TxStatus, BitcoinBackendError> {
+        let unconfirmed = TxStatus {
+            confirmed: false,
+            block_height: None,
+            block_hash: None,
+            block_time: None,
+        };
+        let tx = match self
+            .call_optional("getrawtransaction", json!([txid.to_string(), true]))
+            .await?
+        {
+            Some(tx) => tx,
+            None => return Ok(unconfirmed),
+        };
+        let block_hash = match parse_block_hash(&tx["blockhash"])? {
+            Some(block_hash) => block_hash,
+            None => return Ok(unconfirmed),
+        };
+
+        let header = self
+            .call("getblockheader", json!([block_hash.to_string(), true]))
+            .await?;
+        Ok(TxStatus {
+            confirmed: true,
+            block_height: header["height"].as_u64().map(|height| height as u32),
+            block_hash: Some(block_hash),
+            block_time: header["time"].as_u64(),
+        })
+    }
+
+    async fn get_tx(&self, txid: &Txid) -> Result<Option<Transaction>, BitcoinBackendError> {
+        let tx_hex = self
+            .call_optional("getrawtransaction", json!([txid.to_string(), false]))
+            .await?;
+        match tx_hex.as_ref().and_then(Value::as_str) {
+            Some(tx_hex) => deserialize_hex(tx_hex)
+                .map(Some)
+                .map_err(|e| BitcoinBackendError::Rpc(e.to_string())),
+            None => Ok(None),
+        }
+    }
+
+    async fn get_height(&self) -> Result<u32, BitcoinBackendError> {
+        let height = self.call("getblockcount", json!([])).await?;
+        height
+            .as_u64()
+            .map(|height| height as u32)
+            .ok_or_else(|| BitcoinBackendError::Rpc(format!("getblockcount: {height}")))
+    }
+
+    async fn get_block_txs(&self, height: u32) -> Result<Vec<Transaction>, BitcoinBackendError> {
+        let block_hash = self.call("getblockhash", json!([height])).await?;
+        let block_hex = self.call("getblock", json!([block_hash, 0])).await?;
+        match block_hex.as_str() {
+            Some(block_hex) => deserialize_hex::<Block>(block_hex)
+                .map(|block| block.txdata)
+                .map_err(|e| BitcoinBackendError::Rpc(e.to_string())),
+            None => Err(BitcoinBackendError::Rpc(format!("getblock: {block_hex}"))),
+        }
+    }
+
+    async fn get_block_header(&self, height: u32) -> Result<Header, BitcoinBackendError> {
+        let block_hash = self.call("getblockhash", json!([height])).await?;
+        let header_hex = self
+            .call("getblockheader", json!([block_hash, false]))
+            .await?;
+        match header_hex.as_str() {
+            Some(header_hex) => deserialize_hex::<Header>(header_hex)
+                .map_err(|e| BitcoinBackendError::Rpc(e.to_string())),
+            None => Err(BitcoinBackendError::Rpc(format!(
+                "getblockheader: {header_hex}"
+            ))),
+        }
+    }
+
+    async fn get_address_utxo(&self, address: Address) -> Result<Vec<Utxo>, BitcoinBackendError> {
+        let scan = self
+            .call(
+                "scantxoutset",
+                json!(["start", [format!("addr({address})")]]),
+            )
+            .await?;
+        let unspents = scan["unspents"].as_array().cloned().unwrap_or_default();
+
+        unspents
+            .iter()
+            .map(|unspent| {
+                let txid = unspent["txid"]
+                    .as_str()
+                    .and_then(|txid| Txid::from_str(txid).ok());
+                let vout = unspent["vout"].as_u64();
+                let value = unspent["amount"]
+                    .as_f64()
+                    .and_then(|amount| Amount::from_btc(amount).ok());
+                match (txid, vout, value) {
+                    (Some(txid), Some(vout), Some(value)) => Ok(Utxo {
+                        txid,
+                        vout: vout as u32,
+                        status: UtxoStatus {
+                            confirmed: true,
+                            block_height: unspent["height"].as_u64().map(|height| height as u32),
+                            block_hash: parse_block_hash(&unspent["blockhash"])?,
+                            block_time: None,
+                        },
+                        value,
+                    }),
+                    _ => Err(BitcoinBackendError::Rpc(format!(
+                        "scantxoutset: unexpected unspent {unspent}"
+                    ))),
+                }
+            })
+            .collect()
+    }
+
+    async fn broadcast(&self, tx: &Transaction) -> Result<(), BitcoinBackendError> {
+        self.call("sendrawtransaction", json!([serialize_hex(tx)]))
+            .await
+            .map(|_| ())
+            .map_err(|e| match e {
+                BitcoinBackendError::Rpc(reason) => {
+                    BitcoinBackendError::TransactionRejected(tx.compute_txid(), reason)
+                }
+                e => e,
+            })
+    }
+
+    async fn estimate_fee_rate(&self, target_blocks: u16) -> Result<FeeRate, BitcoinBackendError> {
+        let estimate = self
+            .call("estimatesmartfee", json!([target_blocks]))
+            .await?;
+        // `feerate` is in BTC/kvB and missing while bitcoind has not seen enough blocks
+        let sat_per_kvb = estimate["feerate"]
+            .as_f64()
+            .and_then(|btc_per_kvb| Amount::from_btc(btc_per_kvb).ok())
+            .map(|amount| amount.to_sat());
+
+        Ok(match sat_per_kvb {
+            Some(sat_per_kvb) => {
+                FeeRate::from_sat_per_kwu(sat_per_kvb.div_ceil(4)).max(min_relay_fee_rate())
+            }
+            None => min_relay_fee_rate(),
+        })
+    }
+}
@@ -0,0 +1,113 @@
+use async_trait::async_trait;
+use bitcoin::{block::Header, Address, FeeRate, Transaction, Txid};
+use esplora_client::{AsyncClient, Builder};
+
+use crate::error::BitcoinBackendError;
+
+use super::bitcoin_backend::{min_relay_fee_rate, BitcoinBackend, TxStatus, Utxo};
+
+pub struct EsploraBackend {
+    client: AsyncClient,
+}
+
+impl EsploraBackend {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: Builder::new(url)
+                .build_async()
+                .expect("Could not build esplora client"),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        self.client.url()
+    }
+}
+
+#[async_trait]
+impl BitcoinBackend for EsploraBackend {
+    async fn get_tx_status(&self, txid: &Txid) -> Result<TxStatus, BitcoinBackendError> {
+        self.client
+            .get_tx_status(txid)
+            .await
+            .map_err(BitcoinBackendError::Esplora)
+    }
+
+    async fn get_tx(&self, txid: &Txid) -> Result<Option<Transaction>, BitcoinBackendError> {
+        self.client
+            .get_tx(txid)
+            .await
+            .map_err(BitcoinBackendError::Esplora)
+    }
+
+    async fn get_height(&self) -> Result<u32, BitcoinBackendError> {
+        self.client
+            .get_height()
+            .await
+            .map_err(BitcoinBackendError::Esplora)
+    }
+
+    async fn get_block_txs(&self, height: u32) -> Result<Vec<Transaction>, BitcoinBackendError> {
+        let block_hash = self
+            .client
+            .get_block_hash(height)
+            .await
+            .map_err(BitcoinBackendError::Esplora)?;
+        let block = self
+            .client
+            .get_block_by_hash(&block_hash)
+            .await
+            .map_err(BitcoinBackendError::Esplora)?;
+        block
+            .map(|block| block.txdata)
+            .ok_or(BitcoinBackendError::BlockNotFound(height))
+    }
+
+    async fn get_block_header(&self, height: u32) -> Result<Header, BitcoinBackendError> {
+        let block_hash = self
+            .client
+            .get_block_hash(height)
+            .await
+            .map_err(BitcoinBackendError::Esplora)?;
+        self.client
+            .get_header_by_hash(&block_hash)
+            .await
+            .map_err(BitcoinBackendError::Esplora)
+    }
+
+    async fn get_address_utxo(&self, address: Address) -> Result<Vec<Utxo>, BitcoinBackendError> {
+        self.client
+            .get_address_utxo(address)
+            .await
+            .map_err(BitcoinBackendError::Esplora)
+    }
+
+    async fn broadcast(&self, tx: &Transaction) -> Result<(), BitcoinBackendError> {
+        self.client
+            .broadcast(tx)
+            .await
+            .map_err(BitcoinBackendError::Esplora)
+    }
+
+    async fn estimate_fee_rate(&self, target_blocks: u16) -> Result<FeeRate, BitcoinBackendError> {
+        let estimates = self
+            .client
+            .get_fee_estimates()
+            .await
+            .map_err(BitcoinBackendError::Esplora)?;
+        // esplora only estimates some targets: use the closest one confirming no later than
+        // requested, or the fastest one if the target is shorter than all of them.
+        let sat_per_vb = estimates
+            .iter()
+            .filter(|(target, _)| **target <= target_blocks)
+            .max_by_key(|(target, _)| **target)
+            .or_else(|| estimates.iter().min_by_key(|(target, _)| **target))
+            .map(|(_, sat_per_vb)| *sat_per_vb);
+
+        Ok(match sat_per_vb {
+            Some(sat_per_vb) => FeeRate::from_sat_per_kwu((sat_per_vb * 250.0).ceil() as u64)
+                .max(min_relay_fee_rate()),
+            None => min_relay_fee_rate(),
+        })
+    }
+}
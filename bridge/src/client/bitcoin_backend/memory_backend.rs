@@ -0,0 +1,415 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Mutex, MutexGuard},
+};
+
+use async_trait::async_trait;
+use bitcoin::{
+    absolute, block, blockdata::constants::genesis_block, hashes::Hash, merkle_tree, relative,
+    transaction::Version, Address, Amount, FeeRate, Network, OutPoint, ScriptBuf, Transaction,
+    TxMerkleNode, TxOut, Txid, Weight,
+};
+use bitvm::dry_run_taproots;
+
+use crate::error::BitcoinBackendError;
+
+use super::bitcoin_backend::{min_relay_fee_rate, BitcoinBackend, TxStatus, Utxo, UtxoStatus};
+
+const BLOCK_INTERVAL_SECONDS: u64 = 10 * 60;
+// Timestamp of the regtest genesis block
+const GENESIS_BLOCK_TIME: u64 = 1296688602;
+
+struct Block {
+    header: block::Header,
+    txids: Vec<Txid>,
+}
+
+#[derive(Default)]
+struct MemoryChain {
+    blocks: Vec<Block>,
+    // txid -> (transaction, confirmation height); unconfirmed transactions are in the mempool
+    transactions: HashMap<Txid, (Transaction, Option<u32>)>,
+    mempool: Vec<Txid>,
+    // outputs not spent by any confirmed or mempool transaction
+    utxos: HashMap<OutPoint, TxOut>,
+    // outpoint -> txid of the spending transaction
+    spent_outpoints: HashMap<OutPoint, Txid>,
+    // minimum fee rate of the transactions included in mined blocks, none to mine everything
+    fee_rate: Option<FeeRate>,
+}
+
+impl MemoryChain {
+    fn height(&self) -> u32 {
+        self.blocks.len() as u32
+    }
+
+    fn block_time(&self, height: u32) -> u64 {
+        match height {
+            0 => GENESIS_BLOCK_TIME,
+            height => self.blocks[height as usize - 1].header.time as u64,
+        }
+    }
+
+    fn status(&self, height: Option<u32>) -> TxStatus {
+        match height {
+            Some(height) => {
+                let block = &self.blocks[height as usize - 1];
+                TxStatus {
+                    confirmed: true,
+                    block_height: Some(height),
+                    block_hash: Some(block.header.block_hash()),
+                    block_time: Some(block.header.time as u64),
+                }
+            }
+            None => TxStatus {
+                confirmed: false,
+                block_height: None,
+                block_hash: None,
+                block_time: None,
+            },
+        }
+    }
+
+    fn header(&self, height: u32) -> block::Header {
+        match height {
+            0 => genesis_block(Network::Regtest).header,
+            height => self.blocks[height as usize - 1].header,
+        }
+    }
+
+    // Blocks have no coinbase transaction and their headers are not mined, but they are otherwise
+    // well formed, so their hashes can be used like the ones of a real chain.
+    fn mine_block(&mut self) {
+        let height = self.height() + 1;
+        let txids = self.block_txids();
+        let header = block::Header {
+            version: block::Version::TWO,
+            prev_blockhash: self.header(height - 1).block_hash(),
+            merkle_root: merkle_tree::calculate_root(txids.iter().map(|txid| txid.to_raw_hash()))
+                .map(TxMerkleNode::from_raw_hash)
+                .unwrap_or_else(TxMerkleNode::all_zeros),
+            time: (GENESIS_BLOCK_TIME + height as u64 * BLOCK_INTERVAL_SECONDS) as u32,
+            bits: genesis_block(Network::Regtest).header.bits,
+            nonce: 0,
+        };
+        self.mempool.retain(|txid| !txids.contains(txid));
+        for txid in txids.iter() {
+            self.transactions.get_mut(txid).unwrap().1 = Some(height);
+        }
+        self.blocks.push(Block { header, txids });
+    }
+
+    // Mempool transactions included in the next block, in mempool order. Without a fee rate all of
+    // them are. Otherwise a transaction is included together with its unconfirmed ancestors once
+    // the fee rate of that package reaches the fee rate, so a CPFP child pulls its parent in.
+    // Transactions without inputs stand for coinbase outputs and are always included.
+    fn block_txids(&self) -> Vec<Txid> {
+        let Some(fee_rate) = self.fee_rate else {
+            return self.mempool.clone();
+        };
+
+        let mut included = HashSet::new();
+        loop {
+            let mut has_changed = false;
+            for txid in self.mempool.iter() {
+                if included.contains(txid) {
+                    continue;
+                }
+                let package = self.unconfirmed_ancestors(txid, &included);
+                let (fee, weight) =
+                    package
+                        .iter()
+                        .fold((Amount::ZERO, Weight::ZERO), |(fee, weight), txid| {
+                            let tx = &self.transactions[txid].0;
+                            (fee + self.fee(tx), weight + tx.weight())
+                        });
+                if self.transactions[txid].0.input.is_empty()
+                    || fee_rate
+                        .fee_wu(weight)
+                        .is_some_and(|required| fee >= required)
+                {
+                    included.extend(package);
+                    has_changed = true;
+                }
+            }
+            if !has_changed {
+                break;
+            }
+        }
+
+        self.mempool
+            .iter()
+            .filter(|txid| included.contains(*txid))
+            .cloned()
+            .collect()
+    }
+
+    // `txid` and its ancestors still in the mempool and not in `excluded`.
+    fn unconfirmed_ancestors(&self, txid: &Txid, excluded: &HashSet<Txid>) -> HashSet<Txid> {
+        let mut ancestors = HashSet::new();
+        let mut pending = vec![*txid];
+        while let Some(txid) = pending.pop() {
+            if excluded.contains(&txid) || !ancestors.insert(txid) {
+                continue;
+            }
+            for input in self.transactions[&txid].0.input.iter() {
+                if self
+                    .transactions
+                    .get(&input.previous_output.txid)
+                    .is_some_and(|(_, height)| height.is_none())
+                {
+                    pending.push(input.previous_output.txid);
+                }
+            }
+        }
+
+        ancestors
+    }
+
+    fn fee(&self, tx: &Transaction) -> Amount {
+        let input_value: Amount = tx
+            .input
+            .iter()
+            .map(|input| {
+                self.transactions[&input.previous_output.txid].0.output
+                    [input.previous_output.vout as usize]
+                    .value
+            })
+            .sum();
+        let output_value: Amount = tx.output.iter().map(|output| output.value).sum();
+
+        input_value
+            .checked_sub(output_value)
+            .unwrap_or(Amount::ZERO)
+    }
+
+    fn add_transaction(&mut self, tx: Transaction) {
+        let txid = tx.compute_txid();
+        for input in tx.input.iter() {
+            self.utxos.remove(&input.previous_output);
+            self.spent_outpoints.insert(input.previous_output, txid);
+        }
+        for (vout, output) in tx.output.iter().enumerate() {
+            self.utxos
+                .insert(OutPoint::new(txid, vout as u32), output.clone());
+        }
+        self.transactions.insert(txid, (tx, None));
+        self.mempool.push(txid);
+    }
+
+    // Applies the checks bitcoind runs before accepting a transaction into its mempool that matter
+    // for the bridge protocol: inputs must exist and be unspent, fees must not be negative,
+    // absolute and relative timelocks must be satisfied by the next block, and every taproot
+    // script path spend must execute successfully. The rejection reasons match bitcoind's.
+    // Mempool policy (standardness, minimum relay fee, RBF) and non-taproot scripts are not
+    // checked. The tip's timestamp stands in for the median time past.
+    fn check_transaction(&self, tx: &Transaction) -> Result<(), String> {
+        if tx.input.is_empty() {
+            return Err("bad-txns-vin-empty".to_string());
+        }
+
+        let mut prevouts = vec![];
+        for input in tx.input.iter() {
+            if let Some(spending_txid) = self.spent_outpoints.get(&input.previous_output) {
+                return Err(format!(
+                    "input {} already spent by {}",
+                    input.previous_output, spending_txid
+                ));
+            }
+            match self.utxos.get(&input.previous_output) {
+                Some(prevout) => prevouts.push(prevout.clone()),
+                None => {
+                    return Err(format!(
+                        "bad-txns-inputs-missingorspent: {}",
+                        input.previous_output
+                    ))
+                }
+            }
+        }
+
+        let input_value: Amount = prevouts.iter().map(|prevout| prevout.value).sum();
+        let output_value: Amount = tx.output.iter().map(|output| output.value).sum();
+        if input_value < output_value {
+            return Err(format!(
+                "bad-txns-in-belowout, value in ({input_value}) < value out ({output_value})"
+            ));
+        }
+
+        let height = self.height();
+        let tip_time = self.block_time(height);
+        if tx.is_lock_time_enabled()
+            && !tx.lock_time.is_satisfied_by(
+                absolute::Height::from_consensus(height).unwrap(),
+                absolute::Time::from_consensus(tip_time as u32).unwrap(),
+            )
+        {
+            return Err("non-final".to_string());
+        }
+
+        if tx.version >= Version::TWO {
+            for input in tx.input.iter() {
+                let Some(lock_time) = input.sequence.to_relative_lock_time() else {
+                    continue;
+                };
+                // outputs of mempool transactions are treated as confirming in the next block
+                let prevout_height = self.transactions[&input.previous_output.txid]
+                    .1
+                    .unwrap_or(height + 1);
+                let blocks = height + 1 - prevout_height;
+                // BIP68 measures time from the block preceding the one that confirmed the prevout
+                let seconds = tip_time.saturating_sub(self.block_time(prevout_height - 1));
+                if !lock_time.is_satisfied_by(
+                    relative::Height::from(blocks.min(u16::MAX as u32) as u16),
+                    relative::Time::from_512_second_intervals(
+                        (seconds / 512).min(u16::MAX as u64) as u16
+                    ),
+                ) {
+                    return Err("non-BIP68-final".to_string());
+                }
+            }
+        }
+
+        dry_run_taproots(tx, &prevouts).map_err(|info| match info.error {
+            Some(error) => format!("mandatory-script-verify-flag-failed ({error:?})"),
+            None => "mandatory-script-verify-flag-failed".to_string(),
+        })
+    }
+}
+
+// A simulated chain kept in memory. Broadcast transactions are validated against the UTXO set,
+// the current height and their taproot scripts (see `MemoryChain::check_transaction`), then wait
+// in the mempool until the next call to `mine_blocks`, which confirms all of them in a single
+// block. Nothing advances the height on its own, so tests decide exactly when timelocks expire.
+#[derive(Default)]
+pub struct MemoryBackend {
+    chain: Mutex<MemoryChain>,
+}
+
+impl MemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn chain(&self) -> MutexGuard<'_, MemoryChain> {
+        self.chain.lock().unwrap()
+    }
+
+    // Simulates a fee market: fee estimates return `fee_rate` and mined blocks leave out the
+    // mempool transactions paying less (see `MemoryChain::block_txids`).
+    pub fn set_fee_rate(&self, fee_rate: FeeRate) {
+        self.chain().fee_rate = Some(fee_rate);
+    }
+
+    pub fn mine_blocks(&self, count: u32) {
+        let mut chain = self.chain();
+        for _ in 0..count {
+            chain.mine_block();
+        }
+    }
+
+    // Creates a confirmed output of `amount` paying to `script_pubkey`, the equivalent of
+    // sending coins from a faucet and mining a block.
+    pub fn fund(&self, script_pubkey: ScriptBuf, amount: Amount) -> OutPoint {
+        let mut chain = self.chain();
+        let funding_tx = Transaction {
+            version: Version(2),
+            // the lock time makes every funding transaction unique
+            lock_time: absolute::LockTime::from_consensus(chain.transactions.len() as u32),
+            input: vec![],
+            output: vec![TxOut {
+                value: amount,
+                script_pubkey,
+            }],
+        };
+        let txid = funding_tx.compute_txid();
+        chain.add_transaction(funding_tx);
+        chain.mine_block();
+
+        OutPoint { txid, vout: 0 }
+    }
+}
+
+#[async_trait]
+impl BitcoinBackend for MemoryBackend {
+    async fn get_tx_status(&self, txid: &Txid) -> Result<TxStatus, BitcoinBackendError> {
+        let chain = self.chain();
+        let height = chain.transactions.get(txid).and_then(|(_, height)| *height);
+        Ok(chain.status(height))
+    }
+
+    async fn get_tx(&self, txid: &Txid) -> Result<Option<Transaction>, BitcoinBackendError> {
+        Ok(self
+            .chain()
+            .transactions
+            .get(txid)
+            .map(|(tx, _)| tx.clone()))
+    }
+
+    async fn get_height(&self) -> Result<u32, BitcoinBackendError> {
+        Ok(self.chain().height())
+    }
+
+    async fn get_block_txs(&self, height: u32) -> Result<Vec<Transaction>, BitcoinBackendError> {
+        let chain = self.chain();
+        match height {
+            0 => Ok(vec![]),
+            height if height <= chain.height() => Ok(chain.blocks[height as usize - 1]
+                .txids
+                .iter()
+                .map(|txid| chain.transactions[txid].0.clone())
+                .collect()),
+            height => Err(BitcoinBackendError::BlockNotFound(height)),
+        }
+    }
+
+    async fn get_block_header(&self, height: u32) -> Result<block::Header, BitcoinBackendError> {
+        let chain = self.chain();
+        match height {
+            height if height <= chain.height() => Ok(chain.header(height)),
+            height => Err(BitcoinBackendError::BlockNotFound(height)),
+        }
+    }
+
+    async fn get_address_utxo(&self, address: Address) -> Result<Vec<Utxo>, BitcoinBackendError> {
+        let chain = self.chain();
+        let script_pubkey = address.script_pubkey();
+        let mut utxos = vec![];
+        for (outpoint, output) in chain.utxos.iter() {
+            if output.script_pubkey != script_pubkey {
+                continue;
+            }
+            let status = chain.status(chain.transactions[&outpoint.txid].1);
+            utxos.push(Utxo {
+                txid: outpoint.txid,
+                vout: outpoint.vout,
+                status: UtxoStatus {
+                    confirmed: status.confirmed,
+                    block_height: status.block_height,
+                    block_hash: status.block_hash,
+                    block_time: status.block_time,
+                },
+                value: output.value,
+            });
+        }
+
+        Ok(utxos)
+    }
+
+    async fn broadcast(&self, tx: &Transaction) -> Result<(), BitcoinBackendError> {
+        let mut chain = self.chain();
+        let txid = tx.compute_txid();
+        if chain.transactions.contains_key(&txid) {
+            return Ok(());
+        }
+        chain
+            .check_transaction(tx)
+            .map_err(|reason| BitcoinBackendError::TransactionRejected(txid, reason))?;
+        chain.add_transaction(tx.clone());
+
+        Ok(())
+    }
+
+    async fn estimate_fee_rate(&self, _target_blocks: u16) -> Result<FeeRate, BitcoinBackendError> {
+        Ok(self.chain().fee_rate.unwrap_or_else(min_relay_fee_rate))
+    }
+}
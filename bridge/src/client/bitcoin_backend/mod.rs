@@ -0,0 +1,4 @@
+pub mod bitcoin_backend;
+pub mod bitcoind_backend;
+pub mod esplora_backend;
+pub mod memory_backend;
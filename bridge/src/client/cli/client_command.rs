@@ -1,14 +1,16 @@
 use super::key_command::{Config, KeysCommand};
 use super::utils::get_mock_chain_service;
+use crate::client::bitcoin_backend::bitcoin_backend::{get_bitcoin_backend, BitcoinBackendConfig};
 use crate::client::chain::chain_adaptor::get_chain_adaptor;
 use crate::client::client::BitVMClient;
-use crate::client::esplora::get_esplora_url;
-use crate::commitments::CommitmentMessageId;
+use crate::client::watchtower::Watchtower;
 use crate::common::ZkProofVerifyingKey;
 use crate::constants::DestinationNetwork;
 use crate::contexts::base::generate_keys_from_secret;
 use crate::graphs::base::{PEG_IN_FEE, PEG_OUT_FEE};
-use crate::proof::{get_proof, invalidate_proof};
+use crate::proof::invalidate_proof;
+use crate::proof_provider::get_proof_provider;
+use crate::timelock_policy::get_timelock_policy;
 use crate::transactions::base::{Input, MIN_RELAY_FEE_PEG_OUT};
 use ark_serialize::CanonicalDeserialize;
 
@@ -22,10 +24,14 @@ use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::time::sleep;
 
+const WATCHTOWER_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
 pub struct CommonArgs {
     pub key_dir: Option<String>,
     pub verifiers: Option<Vec<PublicKey>>,
+    pub operators: Option<Vec<PublicKey>>,
     pub environment: Option<String>,
+    pub bitcoin_backend: Option<String>,
     pub path_prefix: Option<String>,
 }
 
@@ -46,6 +52,15 @@ impl ClientCommand {
             }
         };
 
+        let bitcoin_backend_config = match common_args.bitcoin_backend.as_deref() {
+            Some("esplora") | None => BitcoinBackendConfig::Esplora(None),
+            Some("bitcoind") => BitcoinBackendConfig::Bitcoind(None),
+            _ => {
+                eprintln!("Invalid Bitcoin backend. Use esplora or bitcoind.");
+                std::process::exit(1);
+            }
+        };
+
         let keys_command = KeysCommand::new(common_args.key_dir);
         let config = keys_command
             .read_config()
@@ -59,8 +74,20 @@ impl ClientCommand {
             verifying_key = Some(ZkProofVerifyingKey::deserialize_compressed(&*bytes).unwrap());
         }
 
-        let bitvm_client = BitVMClient::new(
-            Some(get_esplora_url(source_network)),
+        // Only the operator asserts proofs.
+        let proof_provider = config.keys.operator.as_ref().map(|_| {
+            get_proof_provider(source_network, verifying_key.as_ref()).unwrap_or_else(|e| {
+                eprintln!("Invalid proof provider configuration: {e}");
+                std::process::exit(1);
+            })
+        });
+        let timelock_policy = get_timelock_policy(source_network).unwrap_or_else(|e| {
+            eprintln!("Invalid timelock policy: {e:?}");
+            std::process::exit(1);
+        });
+
+        let mut bitvm_client = BitVMClient::new(
+            Some(get_bitcoin_backend(source_network, bitcoin_backend_config)),
             source_network,
             destination_network,
             Some(get_chain_adaptor(DestinationNetwork::Local, None, None)), // TODO: Will be replaced with a destination network specific adaptor once Ethereum support is added.
@@ -73,6 +100,13 @@ impl ClientCommand {
             verifying_key,
         )
         .await;
+        if let Some(proof_provider) = proof_provider {
+            bitvm_client.set_proof_provider(proof_provider);
+        }
+        bitvm_client.set_timelock_policy(timelock_policy);
+        if let Some(operator_public_keys) = common_args.operators {
+            bitvm_client.set_operator_public_keys(&operator_public_keys);
+        }
 
         Self {
             client: bitvm_client,
@@ -86,16 +120,19 @@ impl ClientCommand {
             .expect("Could not parse the provided UTXO, please see help for the correct format.");
         let tx = self
             .client
-            .esplora
+            .bitcoin_backend
             .get_tx(&outpoint.txid)
             .await
             .map_err(|e| {
                 io::Error::new(
                     io::ErrorKind::Other,
-                    format!("Esplora failed to retrieve tx: {e}"),
+                    format!("Bitcoin backend failed to retrieve tx: {e}"),
                 )
             })?;
-        let tx = tx.expect(&format!("Esplora did not find a txid {}", outpoint.txid));
+        let tx = tx.expect(&format!(
+            "Bitcoin backend did not find a txid {}",
+            outpoint.txid
+        ));
 
         Ok(Input {
             outpoint,
@@ -286,11 +323,12 @@ impl ClientCommand {
             .get_funding_utxo_input(sub_matches.get_one::<String>("utxo"))
             .await?;
 
-        let peg_out_id = self.client.create_peg_out_graph(
-            peg_in_id,
-            input,
-            CommitmentMessageId::generate_commitment_secrets(),
-        );
+        let commitment_secrets = self.client.derive_commitment_secrets(peg_in_id);
+        self.client.fetch_disprove_script_bundle().await;
+        let peg_out_id = self
+            .client
+            .create_peg_out_graph(peg_in_id, input, commitment_secrets)
+            .map_err(|e| io::Error::other(e.to_string()))?;
 
         self.client.flush().await;
 
@@ -310,7 +348,10 @@ impl ClientCommand {
         let graph_id = sub_matches.get_one::<String>("id").unwrap();
 
         self.client.sync().await;
-        self.client.push_verifier_nonces(graph_id);
+        if let Err(e) = self.client.push_verifier_nonces(graph_id) {
+            eprintln!("Failed to push nonces for graph {graph_id}: {e}");
+            return Ok(());
+        }
         self.client.flush().await;
 
         Ok(())
@@ -331,7 +372,10 @@ impl ClientCommand {
         let graph_id = sub_matches.get_one::<String>("id").unwrap();
 
         self.client.sync().await;
-        self.client.push_verifier_signature(graph_id);
+        if let Err(e) = self.client.push_verifier_signature(graph_id) {
+            eprintln!("Failed to push signatures for graph {graph_id}: {e}");
+            return Ok(());
+        }
         self.client.flush().await;
 
         Ok(())
@@ -379,24 +423,33 @@ impl ClientCommand {
     pub fn get_automatic_command() -> Command {
         Command::new("automatic")
             .short_flag('a')
-            .about("Automatic mode: Poll for status updates and sign or broadcast transactions")
+            .about("Automatic mode: Follow the chain and sign or broadcast transactions")
+            .after_help("Follow new blocks and the mempool, react when outputs of known peg-out graphs are created or spent, and broadcast timeout and take transactions at the height they become valid.")
     }
 
     pub async fn handle_automatic_command(&mut self) -> io::Result<()> {
+        let height = self
+            .client
+            .bitcoin_backend
+            .get_height()
+            .await
+            .map_err(|e| io::Error::other(e.to_string()))?;
+        let mut watchtower = Watchtower::new(height);
+
         loop {
             self.client.sync().await;
 
             let old_data = self.client.data().clone();
 
             self.client.process_peg_ins().await;
-            self.client.process_peg_outs().await;
+            for err in self.client.process_watchtower_events(&mut watchtower).await {
+                eprintln!("Automatic mode: {err}");
+            }
 
-            // A bit inefficient, but fine for now: only flush if data changed
             if self.client.data() != &old_data {
                 self.client.flush().await;
-            } else {
-                sleep(Duration::from_millis(250)).await;
             }
+            sleep(WATCHTOWER_POLL_INTERVAL).await;
         }
     }
 
@@ -459,10 +512,10 @@ impl ClientCommand {
 
         match subcommand.unwrap().1.subcommand() {
             Some(("assert_commits", _)) => {
-                let result = self
-                    .client
-                    .broadcast_assert_commits(graph_id, &get_proof())
-                    .await;
+                let result = match self.client.get_proof(graph_id) {
+                    Ok(proof) => self.client.broadcast_assert_commits(graph_id, &proof).await,
+                    Err(e) => Err(e),
+                };
                 if let Err(e) = result {
                     println!("Failed to broadcast transaction: {e}");
                 }
@@ -485,16 +538,22 @@ impl ClientCommand {
                     "kick_off_2" => self.client.broadcast_kick_off_2(graph_id).await,
                     "start_time" => self.client.broadcast_start_time(graph_id).await,
                     "assert_initial" => self.client.broadcast_assert_initial(graph_id).await,
-                    "assert_commit_1_invalid" => {
-                        self.client
-                            .broadcast_assert_commit_1(graph_id, &invalidate_proof(&get_proof()))
-                            .await
-                    }
-                    "assert_commit_2_invalid" => {
-                        self.client
-                            .broadcast_assert_commit_2(graph_id, &invalidate_proof(&get_proof()))
-                            .await
-                    }
+                    "assert_commit_1_invalid" => match self.client.get_proof(graph_id) {
+                        Ok(proof) => {
+                            self.client
+                                .broadcast_assert_commit_1(graph_id, &invalidate_proof(&proof))
+                                .await
+                        }
+                        Err(e) => Err(e),
+                    },
+                    "assert_commit_2_invalid" => match self.client.get_proof(graph_id) {
+                        Ok(proof) => {
+                            self.client
+                                .broadcast_assert_commit_2(graph_id, &invalidate_proof(&proof))
+                                .await
+                        }
+                        Err(e) => Err(e),
+                    },
                     "assert_final" => self.client.broadcast_assert_final(graph_id).await,
                     "take_1" => self.client.broadcast_take_1(graph_id).await,
                     "take_2" => self.client.broadcast_take_2(graph_id).await,
@@ -532,6 +591,30 @@ impl ClientCommand {
         Ok(())
     }
 
+    pub fn get_liquidity_report_command() -> Command {
+        Command::new("liquidity-report")
+            .short_flag('l')
+            .about("Show the capital of the operator across its peg-out graphs")
+            .after_help("Show the withdrawals the operator fronted or is asked to front, the reimbursements it can claim, upcoming timelock deadlines and whether its UTXOs cover the pending withdrawals.")
+            .arg(arg!(--json "Print the report as JSON").required(false))
+    }
+
+    pub async fn handle_liquidity_report_command(
+        &mut self,
+        sub_matches: &ArgMatches,
+    ) -> io::Result<()> {
+        self.client.sync().await;
+        match self.client.liquidity_report().await {
+            Ok(report) if sub_matches.get_flag("json") => {
+                println!("{}", serde_json::to_string_pretty(&report).unwrap())
+            }
+            Ok(report) => print!("{report}"),
+            Err(e) => println!("Failed to create the liquidity report: {e}"),
+        }
+
+        Ok(())
+    }
+
     pub fn get_interactive_command() -> Command {
         Command::new("interactive")
             .short_flag('i')
@@ -602,6 +685,8 @@ impl ClientCommand {
                     .await?;
             } else if matches.subcommand_matches("status").is_some() {
                 self.handle_status_command().await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("liquidity-report") {
+                self.handle_liquidity_report_command(sub_matches).await?;
             } else if let Some(sub_matches) = matches.subcommand_matches("broadcast") {
                 self.handle_broadcast_command(sub_matches).await?;
             } else if matches.subcommand_matches("automatic").is_some() {
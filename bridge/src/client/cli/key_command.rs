@@ -7,7 +7,9 @@ use std::fs::{self, OpenOptions};
 use std::io::{self, Read, Write};
 use std::path::PathBuf;
 use toml;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
+use crate::client::keystore::{get_keystore_passphrase, Keystore};
 use crate::contexts::base::generate_keys_from_secret;
 
 #[derive(Serialize, Deserialize, Default)]
@@ -15,7 +17,7 @@ pub struct Config {
     pub keys: Keys,
 }
 
-#[derive(Serialize, Deserialize, Default)]
+#[derive(Serialize, Deserialize, Default, Zeroize, ZeroizeOnDrop)]
 pub struct Keys {
     pub depositor: Option<String>,
     pub operator: Option<String>,
@@ -24,6 +26,16 @@ pub struct Keys {
     pub verifying_key: Option<String>,
 }
 
+// Layout of bridge.toml. The keys are stored encrypted in `keystore`, `keys` is only read to
+// migrate the plaintext keys written by earlier versions.
+#[derive(Serialize, Deserialize, Default)]
+struct StoredConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keystore: Option<Keystore>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keys: Option<Keys>,
+}
+
 const BRIDGE_KEY_DIR_NAME: &str = ".bitvm-bridge";
 const BRIDGE_TOML: &str = "bridge.toml";
 
@@ -169,16 +181,53 @@ impl KeysCommand {
     pub fn read_config(&self) -> io::Result<Config> {
         if self.config_path.exists() {
             let mut file = OpenOptions::new().read(true).open(&self.config_path)?;
-            let mut content = String::new();
+            let mut content = Zeroizing::new(String::new());
             file.read_to_string(&mut content)?;
-            Ok(toml::from_str(&content).unwrap_or_default())
+            let stored_config: StoredConfig = toml::from_str(&content).unwrap_or_default();
+
+            match stored_config {
+                StoredConfig {
+                    keystore: Some(keystore),
+                    ..
+                } => {
+                    let plaintext = keystore.decrypt(&passphrase()?).map_err(|e| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("Could not unlock {}: {e}", self.config_path.display()),
+                        )
+                    })?;
+                    let keys = toml::from_slice(&plaintext)
+                        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                    Ok(Config { keys })
+                }
+                StoredConfig {
+                    keys: Some(keys), ..
+                } => {
+                    println!(
+                        "Migrating plaintext keys in {} to an encrypted keystore...",
+                        self.config_path.display()
+                    );
+                    let config = Config { keys };
+                    self.write_config(&config)?;
+                    Ok(config)
+                }
+                _ => Ok(Config::default()),
+            }
         } else {
             Ok(Config::default())
         }
     }
 
     pub fn write_config(&self, config: &Config) -> io::Result<()> {
-        let toml_string = toml::to_string(config).expect("Failed to serialize config");
+        let plaintext =
+            Zeroizing::new(toml::to_string(&config.keys).expect("Failed to serialize keys"));
+        let keystore = Keystore::encrypt(plaintext.as_bytes(), &passphrase()?)
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+        let toml_string = toml::to_string(&StoredConfig {
+            keystore: Some(keystore),
+            keys: None,
+        })
+        .expect("Failed to serialize config");
         let mut file = OpenOptions::new()
             .create(true)
             .write(true)
@@ -198,6 +247,11 @@ impl KeysCommand {
     }
 }
 
+// Only read once a keystore has to be opened or written, so the other commands run without it.
+fn passphrase() -> io::Result<Zeroizing<String>> {
+    get_keystore_passphrase().map_err(|e| io::Error::new(io::ErrorKind::NotFound, e.to_string()))
+}
+
 // TODO: Technically this should use the source network specified by the user. However, since this
 // is only used in console output as an ID, we can leave it for now.
 fn pubkey_of(private_key: &str) -> PublicKey {
@@ -9,14 +9,15 @@ use super::{
 };
 use crate::{
     client::{
+        bitcoin_backend::bitcoin_backend::{get_bitcoin_backend, BitcoinBackendConfig},
         chain::chain_adaptor::get_chain_adaptor,
         client::BitVMClient,
-        esplora::get_esplora_url,
         sdk::{query::ClientCliQuery, query_contexts::depositor_signatures::DepositorSignatures},
     },
     constants::DestinationNetwork,
     contexts::base::generate_keys_from_secret,
     scripts::generate_pay_to_pubkey_script_address,
+    timelock_policy::get_timelock_policy,
     transactions::base::Input,
 };
 
@@ -34,15 +35,30 @@ impl QueryCommand {
     pub async fn new(
         source_network: Network,
         destination_network: DestinationNetwork,
+        bitcoin_backend: Option<&str>,
         path_prefix: Option<&str>,
     ) -> Self {
+        let bitcoin_backend_config = match bitcoin_backend {
+            Some("esplora") | None => BitcoinBackendConfig::Esplora(None),
+            Some("bitcoind") => BitcoinBackendConfig::Bitcoind(None),
+            _ => {
+                eprintln!("Invalid Bitcoin backend. Use esplora or bitcoind.");
+                std::process::exit(1);
+            }
+        };
+
         let (_, verifier_0_public_key) =
             generate_keys_from_secret(source_network, VERIFIER_0_SECRET);
 
         let n_of_n_public_keys: Vec<PublicKey> = vec![verifier_0_public_key];
 
-        let bitvm_client = BitVMClient::new(
-            Some(get_esplora_url(source_network)),
+        let timelock_policy = get_timelock_policy(source_network).unwrap_or_else(|e| {
+            eprintln!("Invalid timelock policy: {e:?}");
+            std::process::exit(1);
+        });
+
+        let mut bitvm_client = BitVMClient::new(
+            Some(get_bitcoin_backend(source_network, bitcoin_backend_config)),
             source_network,
             destination_network,
             Some(get_chain_adaptor(DestinationNetwork::Local, None, None)), // TODO: Update this according to the requirements for query command.
@@ -55,6 +71,7 @@ impl QueryCommand {
             None,
         )
         .await;
+        bitvm_client.set_timelock_policy(timelock_policy);
 
         Self {
             client: bitvm_client,
@@ -554,10 +571,10 @@ impl QueryCommand {
             .await
             .unwrap_or_else(|| {
                 panic!(
-                    "Fund {:?} with {} sats at {}",
+                    "Fund {:?} with {} sats on {}",
                     funding_utxo_address,
                     input_value.to_sat(),
-                    client.esplora.url(),
+                    self.network,
                 );
             });
         OutPoint {
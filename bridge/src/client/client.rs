@@ -1,51 +1,67 @@
 use bitcoin::{
-    absolute::Height, consensus::encode::serialize_hex, Address, Amount, Network, OutPoint,
-    PublicKey, ScriptBuf, Transaction, Txid, XOnlyPublicKey,
+    absolute::Height, consensus::encode::serialize_hex, hex::DisplayHex, Address, Amount, Network,
+    OutPoint, PublicKey, ScriptBuf, Transaction, Txid, XOnlyPublicKey,
 };
 use colored::Colorize;
-use esplora_client::{AsyncClient, Builder, TxStatus, Utxo};
 use futures::future::join_all;
 use human_bytes::human_bytes;
-use musig2::SecNonce;
+use musig2::{BinaryEncoding, PubNonce, SecNonce};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::{
     collections::HashMap,
     path::{Path, PathBuf},
+    sync::Arc,
 };
+use zeroize::Zeroizing;
 
 use crate::{
     client::{
-        chain::ethereum_adaptor::EthereumAdaptor, esplora::get_esplora_url,
+        bitcoin_backend::{
+            bitcoin_backend::{BitcoinBackend, TxStatus, Utxo},
+            esplora_backend::EsploraBackend,
+        },
+        chain::ethereum_adaptor::EthereumAdaptor,
+        esplora::get_esplora_url,
+        fee_bumper::{FeeBumpPolicy, FeeBumper},
         files::DEFAULT_PATH_PREFIX,
+        liquidity::{
+            peg_out_fee, CapitalState, LiquidityDeadline, LiquidityReport, PegOutLiquidity,
+        },
+        watchtower::{Watchtower, WatchtowerEvent},
     },
-    commitments::CommitmentMessageId,
+    commitments::{CommitmentMessageId, CommitmentSeed},
     common::ZkProofVerifyingKey,
-    connectors::{base::TaprootConnector, connector_0::Connector0, connector_z::ConnectorZ},
+    connectors::{
+        base::TaprootConnector, connector_0::Connector0, connector_c::fetch_disprove_script_bundle,
+        connector_z::ConnectorZ,
+    },
     constants::DestinationNetwork,
     contexts::base::generate_n_of_n_public_key,
-    error::{ClientError, Error},
+    error::{ClientError, Error, GraphError, NonceError, ProofError},
     graphs::{
         base::{
             broadcast_and_verify, get_tx_statuses, GraphId, PEG_OUT_FEE, REWARD_MULTIPLIER,
             REWARD_PRECISION,
         },
         peg_in::{PegInDepositorStatus, PegInVerifierStatus},
-        peg_out::PegOutOperatorStatus,
+        peg_out::{PegOutOperatorStatus, PegOutTimelockedTx},
     },
-    proof::get_proof,
+    proof_provider::{validate_proof, ProofProvider},
     scripts::generate_pay_to_pubkey_script_address,
     serialization::{serialize, try_deserialize_slice},
+    timelock_policy::TimelockPolicy,
     transactions::{
-        peg_in_confirm::PegInConfirmTransaction, peg_in_deposit::PegInDepositTransaction,
-        peg_in_refund::PegInRefundTransaction, pre_signed_musig2::PreSignedMusig2Transaction,
+        base::anchor_vout, peg_in_confirm::PegInConfirmTransaction,
+        peg_in_deposit::PegInDepositTransaction, peg_in_refund::PegInRefundTransaction,
+        pre_signed_musig2::PreSignedMusig2Transaction,
     },
 };
 
 use bitvm::{
     // chunker::disprove_execution::RawProof,
     chunk::api::type_conversion_utils::RawProof,
-    signatures::signing_winternitz::WinternitzSecret,
+    signatures::signing_winternitz::{WinternitzPublicKey, WinternitzSecret},
 };
 
 use super::{
@@ -70,6 +86,7 @@ use super::{
         get_private_data_file_path, get_private_data_from_file, save_local_private_file,
         save_local_public_file, BRIDGE_DATA_DIRECTORY_NAME,
     },
+    keystore::get_keystore_passphrase,
     sdk::{
         query::{ClientCliQuery, GraphCliQuery},
         query_contexts::depositor_signatures::DepositorSignatures,
@@ -99,6 +116,8 @@ impl BitVMClientPublicData {
     }
 }
 
+// Stored in an encrypted keystore, see `files::get_private_data_from_file`.
+// Winternitz secrets are zeroized when dropped.
 #[derive(Serialize, Deserialize, Eq, PartialEq)]
 pub struct BitVMClientPrivateData {
     // Peg in and peg out nonces all go into the same file for now
@@ -109,10 +128,133 @@ pub struct BitVMClientPrivateData {
     // Operator public key -> Graph ID -> Message ID -> Winternitz secret
     pub commitment_secrets:
         HashMap<PublicKey, HashMap<String, HashMap<CommitmentMessageId, WinternitzSecret>>>,
+    // Public nonces whose secret nonces were used to sign, and deleted.
+    // Verifier public key -> Public nonce (hex) -> Signed input
+    #[serde(default)]
+    pub consumed_nonces: HashMap<PublicKey, HashMap<String, ConsumedNonce>>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct ConsumedNonce {
+    pub graph_id: GraphId,
+    pub txid: Txid,
+    pub input_index: usize,
+}
+
+impl BitVMClientPrivateData {
+    /*
+      Secret nonces go through generate -> publish -> consume -> delete. This takes the secret
+      nonces of a graph out of the private data to sign all its inputs once, and records them as
+      consumed. The private data must be saved before the signatures are published.
+      Returns `None` if the verifier has already signed the graph, e.g. when the private data was
+      restored from a backup taken before signing, in which case leftover secret nonces are
+      deleted and nothing must be signed again.
+      Fails if a nonce is missing, consumed, or does not match the nonce published in the graph.
+    */
+    #[allow(clippy::type_complexity)]
+    pub fn take_secret_nonces(
+        &mut self,
+        verifier_public_key: &PublicKey,
+        graph_id: &GraphId,
+        transactions: &[&dyn PreSignedMusig2Transaction],
+    ) -> Result<Option<HashMap<Txid, HashMap<usize, SecNonce>>>, NonceError> {
+        let signed_inputs: Vec<(Txid, usize, bool)> = transactions
+            .iter()
+            .flat_map(|tx| {
+                let txid = tx.tx().compute_txid();
+                tx.verifier_inputs().into_iter().map(move |input_index| {
+                    let signed = tx
+                        .musig2_signatures()
+                        .get(&input_index)
+                        .is_some_and(|signatures| signatures.contains_key(verifier_public_key));
+                    (txid, input_index, signed)
+                })
+            })
+            .collect();
+
+        if signed_inputs.iter().all(|(_, _, signed)| *signed) {
+            if let Some(graph_nonces) = self
+                .secret_nonces
+                .get_mut(verifier_public_key)
+                .and_then(|nonces| nonces.remove(graph_id))
+            {
+                self.consume_nonces(verifier_public_key, graph_id, &graph_nonces);
+            }
+            return Ok(None);
+        }
+        if let Some((txid, input_index, _)) = signed_inputs.iter().find(|(_, _, signed)| *signed) {
+            return Err(NonceError::NonceAlreadyConsumed(*txid, *input_index));
+        }
+
+        let graph_nonces = self
+            .secret_nonces
+            .get(verifier_public_key)
+            .and_then(|nonces| nonces.get(graph_id));
+        let consumed_nonces = self.consumed_nonces.get(verifier_public_key);
+        for tx in transactions {
+            let txid = tx.tx().compute_txid();
+            for input_index in tx.verifier_inputs() {
+                let public_nonce = tx
+                    .musig2_nonces()
+                    .get(&input_index)
+                    .and_then(|nonces| nonces.get(verifier_public_key))
+                    .ok_or(NonceError::NonceNotPublished(txid, input_index))?;
+                if consumed_nonces
+                    .is_some_and(|consumed| consumed.contains_key(&nonce_key(public_nonce)))
+                {
+                    return Err(NonceError::NonceAlreadyConsumed(txid, input_index));
+                }
+                let secret_nonce = graph_nonces
+                    .and_then(|nonces| nonces.get(&txid))
+                    .and_then(|nonces| nonces.get(&input_index))
+                    .ok_or(NonceError::SecretNonceNotFound(txid, input_index))?;
+                if secret_nonce.public_nonce() != *public_nonce {
+                    return Err(NonceError::NonceMismatch(txid, input_index));
+                }
+            }
+        }
+
+        let graph_nonces = self
+            .secret_nonces
+            .get_mut(verifier_public_key)
+            .and_then(|nonces| nonces.remove(graph_id))
+            .unwrap_or_default();
+        self.consume_nonces(verifier_public_key, graph_id, &graph_nonces);
+
+        Ok(Some(graph_nonces))
+    }
+
+    fn consume_nonces(
+        &mut self,
+        verifier_public_key: &PublicKey,
+        graph_id: &GraphId,
+        secret_nonces: &HashMap<Txid, HashMap<usize, SecNonce>>,
+    ) {
+        let consumed_nonces = self
+            .consumed_nonces
+            .entry(*verifier_public_key)
+            .or_default();
+        for (txid, nonces) in secret_nonces {
+            for (input_index, secret_nonce) in nonces {
+                consumed_nonces.insert(
+                    nonce_key(&secret_nonce.public_nonce()),
+                    ConsumedNonce {
+                        graph_id: graph_id.clone(),
+                        txid: *txid,
+                        input_index: *input_index,
+                    },
+                );
+            }
+        }
+    }
+}
+
+fn nonce_key(public_nonce: &PubNonce) -> String {
+    public_nonce.to_bytes().as_slice().to_lower_hex_string()
 }
 
 pub struct BitVMClient {
-    pub esplora: AsyncClient,
+    pub bitcoin_backend: Arc<dyn BitcoinBackend>,
     pub source_network: Network,
 
     depositor_context: Option<DepositorContext>,
@@ -126,17 +268,21 @@ pub struct BitVMClient {
     remote_file_path: String,
     local_file_path: PathBuf,
 
-    private_data: BitVMClientPrivateData,
+    private_data: Option<BitVMClientPrivateData>, // read on first use, see `load_private_data`
 
     chain_service: Chain,
 
     zkproof_verifying_key: Option<ZkProofVerifyingKey>,
+    proof_provider: Option<Box<dyn ProofProvider>>,
+    timelock_policy: TimelockPolicy,
+    operator_public_keys: Vec<PublicKey>,
+    fee_bumper: FeeBumper,
 }
 
 impl BitVMClient {
     #[allow(clippy::too_many_arguments)]
     pub async fn new(
-        esplora_url: Option<&str>,
+        bitcoin_backend: Option<Arc<dyn BitcoinBackend>>,
         source_network: Network,
         destination_network: DestinationNetwork,
         chain_adaptor: Option<Box<dyn ChainAdaptor>>,
@@ -184,13 +330,9 @@ impl BitVMClient {
 
         let data_store = DataStore::new().await;
 
-        let private_data =
-            get_private_data_from_file(&get_private_data_file_path(&local_file_path));
-
         Self {
-            esplora: Builder::new(esplora_url.unwrap_or(get_esplora_url(source_network)))
-                .build_async()
-                .expect("Could not build esplora client"),
+            bitcoin_backend: bitcoin_backend
+                .unwrap_or_else(|| Arc::new(EsploraBackend::new(get_esplora_url(source_network)))),
             source_network,
 
             depositor_context,
@@ -204,16 +346,63 @@ impl BitVMClient {
             remote_file_path,
             local_file_path,
 
-            private_data,
+            private_data: None,
 
             chain_service: Chain::new(
                 chain_adaptor.unwrap_or_else(|| Box::new(EthereumAdaptor::new(None))),
             ),
 
             zkproof_verifying_key,
+            proof_provider: None,
+            timelock_policy: TimelockPolicy::for_network(source_network),
+            operator_public_keys: vec![],
+            fee_bumper: FeeBumper::new(source_network, FeeBumpPolicy::default()),
         }
     }
 
+    // The operator cannot assert without a proof provider, none is set by default.
+    pub fn set_proof_provider(&mut self, proof_provider: Box<dyn ProofProvider>) {
+        self.proof_provider = Some(proof_provider);
+    }
+
+    // Timelocks of the graphs this client creates. Defaults to the policy of the source network.
+    pub fn set_timelock_policy(&mut self, timelock_policy: TimelockPolicy) {
+        self.timelock_policy = timelock_policy;
+    }
+
+    // Operators recorded in the peg-in graphs this client creates, each of which must create a
+    // peg-out graph before the peg-in is confirmed. Defaults to none, letting any single operator
+    // serve the peg-in.
+    pub fn set_operator_public_keys(&mut self, operator_public_keys: &[PublicKey]) {
+        self.operator_public_keys = operator_public_keys.to_vec();
+    }
+
+    pub fn set_fee_bump_policy(&mut self, fee_bump_policy: FeeBumpPolicy) {
+        self.fee_bumper.set_policy(fee_bump_policy);
+    }
+
+    // The proof is checked against the verifying key of the graph, which its disprove scripts
+    // were generated for.
+    pub fn get_proof(&self, peg_out_graph_id: &GraphId) -> Result<RawProof, Error> {
+        let peg_out_graph = self
+            .data
+            .peg_out_graphs
+            .iter()
+            .find(|peg_out_graph| peg_out_graph.id().eq(peg_out_graph_id))
+            .ok_or(Error::Client(ClientError::PegOutGraphNotFound(
+                peg_out_graph_id.clone(),
+            )))?;
+        let proof = self
+            .proof_provider
+            .as_ref()
+            .ok_or(Error::Proof(ProofError::ProofProviderNotDefined))?
+            .get_proof(peg_out_graph_id)
+            .map_err(Error::Proof)?;
+        validate_proof(&proof, peg_out_graph.verifying_key()).map_err(Error::Proof)?;
+
+        Ok(proof)
+    }
+
     pub fn data(&self) -> &BitVMClientPublicData {
         &self.data
     }
@@ -239,8 +428,8 @@ impl BitVMClient {
     }
 
     // TODO: This should be private. Currently used in the fees test. See if it can be refactored.
-    pub fn private_data(&self) -> &BitVMClientPrivateData {
-        &self.private_data
+    pub fn private_data(&mut self) -> Result<&BitVMClientPrivateData, Error> {
+        Self::load_private_data(&mut self.private_data, &self.local_file_path).map(|data| &*data)
     }
 
     // TODO: This fn is only used in tests. Consider refactoring, so it can be removed.
@@ -248,8 +437,37 @@ impl BitVMClient {
         self.chain_service = chain_service;
     }
 
-    fn save_private_data(&self) {
-        save_local_private_file(&self.local_file_path, &serialize(&self.private_data));
+    // The private data is only read from its keystore when it is first needed, so clients that
+    // never sign or create peg-out graphs do not need the keystore passphrase.
+    fn load_private_data<'a>(
+        private_data: &'a mut Option<BitVMClientPrivateData>,
+        local_file_path: &Path,
+    ) -> Result<&'a mut BitVMClientPrivateData, Error> {
+        if private_data.is_none() {
+            *private_data = Some(
+                get_private_data_from_file(
+                    &get_private_data_file_path(local_file_path),
+                    get_keystore_passphrase,
+                )
+                .map_err(Error::Keystore)?,
+            );
+        }
+
+        Ok(private_data.as_mut().unwrap())
+    }
+
+    fn save_private_data(&self) -> Result<(), Error> {
+        // nothing to save if it was never loaded
+        if let Some(private_data) = &self.private_data {
+            save_local_private_file(
+                &self.local_file_path,
+                &Zeroizing::new(serialize(private_data)),
+                &get_keystore_passphrase().map_err(Error::Keystore)?,
+            )
+            .map_err(Error::Keystore)?;
+        }
+
+        Ok(())
     }
 
     pub async fn sync(&mut self) {
@@ -526,7 +744,7 @@ impl BitVMClient {
             if let (Some(content), encoded_size) = result.unwrap() {
                 let data = try_deserialize_slice(&content);
                 if let Ok(data) = data {
-                    if Self::validate_data(&self.esplora, &data).await {
+                    if Self::validate_data(&self.bitcoin_backend, &data).await {
                         return (Some(data), content.len(), encoded_size);
                     }
                 } else {
@@ -538,7 +756,7 @@ impl BitVMClient {
         (None, 0, 0)
     }
 
-    pub async fn validate_data(client: &AsyncClient, data: &BitVMClientPublicData) -> bool {
+    pub async fn validate_data(client: &dyn BitcoinBackend, data: &BitVMClientPublicData) -> bool {
         println!(
             "Validating {} PEG-IN graphs and {} PEG-OUT graphs...",
             data.peg_in_graphs.len(),
@@ -556,6 +774,23 @@ impl BitVMClient {
             }
         }
         for peg_out_graph in data.peg_out_graphs.iter() {
+            let peg_in_graph = data
+                .peg_in_graphs
+                .iter()
+                .find(|peg_in_graph| peg_in_graph.id() == &peg_out_graph.peg_in_graph_id);
+            if peg_in_graph.is_some_and(|peg_in_graph| {
+                !peg_in_graph.accepts_operator(peg_out_graph.operator_public_key())
+            }) {
+                eprintln!(
+                    "Encountered invalid peg-out graph (graph ID: {}), with error: {}",
+                    peg_out_graph.id(),
+                    Error::Graph(GraphError::UnexpectedOperator(
+                        *peg_out_graph.operator_public_key()
+                    )),
+                );
+
+                return false;
+            }
             if let Err(err) = peg_out_graph.validate(client).await {
                 eprintln!(
                     "Encountered invalid peg-out graph (graph ID: {}), with error: {}",
@@ -587,7 +822,13 @@ impl BitVMClient {
         for peg_in_graph in data.peg_in_graphs.iter() {
             let graph = peg_in_graphs_by_id.get_mut(peg_in_graph.id());
             if let Some(graph) = graph {
-                graph.merge(peg_in_graph);
+                if let Err(err) = graph.merge(peg_in_graph) {
+                    eprintln!(
+                        "Skipped merging peg-in graph (graph ID: {}), with error: {}",
+                        peg_in_graph.id(),
+                        err,
+                    );
+                }
             } else {
                 peg_in_graphs_to_add.push(peg_in_graph);
             }
@@ -668,7 +909,7 @@ impl BitVMClient {
             .depositor_public_key;
         for peg_in_graph in self.data.peg_in_graphs.iter() {
             if peg_in_graph.depositor_public_key.eq(depositor_public_key) {
-                let status = peg_in_graph.depositor_status(&self.esplora).await;
+                let status = peg_in_graph.depositor_status(&self.bitcoin_backend).await;
                 println!(
                     "[DEPOSITOR]: Peg-in graph ID: {} status: {}\n",
                     peg_in_graph.id(),
@@ -691,14 +932,19 @@ impl BitVMClient {
         let operator_public_key = &self.operator_context.as_ref().unwrap().operator_public_key;
         for peg_in_graph in self.data.peg_in_graphs.iter() {
             let peg_out_graph_id = peg_out_generate_id(peg_in_graph, operator_public_key);
-            if !peg_out_graphs_by_id.contains_key(&peg_out_graph_id) {
+            if !peg_in_graph.accepts_operator(operator_public_key) {
+                println!(
+                    "[OPERATOR]: Peg-in graph ID: {} status: Served by other operators.\n",
+                    peg_in_graph.id()
+                );
+            } else if !peg_out_graphs_by_id.contains_key(&peg_out_graph_id) {
                 println!(
                     "[OPERATOR]: Peg-in graph ID: {} status: Missing peg out graph.\n",
                     peg_in_graph.id() // TODO update this to ask the operator to create a new peg out graph
                 );
             } else {
                 let peg_out_graph = peg_out_graphs_by_id.get(&peg_out_graph_id).unwrap();
-                let status = peg_out_graph.operator_status(&self.esplora).await;
+                let status = peg_out_graph.operator_status(&self.bitcoin_backend).await;
                 println!(
                     "[OPERATOR]: Peg-out graph ID: {} status: {}\n",
                     peg_out_graph.id(),
@@ -708,11 +954,143 @@ impl BitVMClient {
         }
     }
 
+    // Liquidity of the operator across its peg-out graphs. Pending withdrawals are the peg-out
+    // events on L2 assigned to the operator that it has not fronted with a peg-out yet.
+    pub async fn liquidity_report(&self) -> Result<LiquidityReport, Error> {
+        let operator_public_key = self
+            .operator_context
+            .as_ref()
+            .ok_or(Error::Client(ClientError::OperatorContextNotDefined))?
+            .operator_public_key;
+        let height = self
+            .bitcoin_backend
+            .get_height()
+            .await
+            .map_err(Error::BitcoinBackend)?;
+
+        let mut peg_outs = vec![];
+        let mut deadlines = vec![];
+        let operator_graphs =
+            self.data.peg_out_graphs.iter().filter(|peg_out_graph| {
+                peg_out_graph.operator_public_key() == &operator_public_key
+            });
+        for peg_out_graph in operator_graphs.clone() {
+            let status = peg_out_graph.operator_status(&self.bitcoin_backend).await;
+            let state =
+                CapitalState::from_status(&status, peg_out_graph.peg_out_transaction.is_some());
+            let take_tx = match status {
+                PegOutOperatorStatus::PegOutTake2Available => PegOutTimelockedTx::Take2,
+                _ => PegOutTimelockedTx::Take1,
+            };
+            if matches!(state, CapitalState::InFlight | CapitalState::Reclaimable) {
+                deadlines.extend(self.timelock_deadlines(peg_out_graph, height).await?);
+            }
+            peg_outs.push(PegOutLiquidity {
+                graph_id: peg_out_graph.id().clone(),
+                status: status.to_string(),
+                state,
+                withdrawal_amount: peg_out_graph
+                    .peg_out_chain_event
+                    .as_ref()
+                    .map_or(0, |event| event.amount.to_sat()),
+                reimbursement_amount: peg_out_graph.timelocked_tx(take_tx).output[0]
+                    .value
+                    .to_sat(),
+            });
+        }
+
+        let fronted_outpoints = operator_graphs
+            .filter(|peg_out_graph| peg_out_graph.peg_out_transaction.is_some())
+            .filter_map(|peg_out_graph| peg_out_graph.peg_out_chain_event.as_ref())
+            .map(|event| event.source_outpoint)
+            .collect::<Vec<_>>();
+        let pending_events = self
+            .chain_service
+            .get_peg_out_init()
+            .await
+            .map_err(Error::Other)?
+            .into_iter()
+            .filter(|event| {
+                event.operator_public_key == operator_public_key
+                    && !fronted_outpoints.contains(&event.source_outpoint)
+            })
+            .collect::<Vec<_>>();
+        let utxos = self
+            .bitcoin_backend
+            .get_address_utxo(self.get_operator_address())
+            .await
+            .map_err(Error::BitcoinBackend)?;
+        let fee_rate = self
+            .bitcoin_backend
+            .estimate_fee_rate(self.fee_bumper.policy().target_blocks)
+            .await
+            .map_err(Error::BitcoinBackend)?;
+
+        Ok(LiquidityReport::new(
+            operator_public_key,
+            height,
+            peg_outs,
+            deadlines,
+            &utxos,
+            &pending_events,
+            peg_out_fee(fee_rate),
+        ))
+    }
+
+    // Timelocked transactions of the graph whose timelock started and that can still be
+    // broadcast, i.e. none of their inputs was spent by another confirmed graph transaction.
+    async fn timelock_deadlines(
+        &self,
+        peg_out_graph: &PegOutGraph,
+        height: u32,
+    ) -> Result<Vec<LiquidityDeadline>, Error> {
+        let txs = peg_out_graph.transactions();
+        let txids = txs.iter().map(|tx| tx.compute_txid()).collect::<Vec<_>>();
+        let mut confirmation_heights = HashMap::new();
+        let mut spent_outpoints = vec![];
+        for (tx, status) in txs
+            .iter()
+            .zip(get_tx_statuses(&self.bitcoin_backend, &txids).await)
+        {
+            let status = status.map_err(Error::BitcoinBackend)?;
+            match status.block_height {
+                Some(block_height) if status.confirmed => {
+                    confirmation_heights.insert(tx.compute_txid(), block_height);
+                    spent_outpoints.extend(tx.input.iter().map(|input| input.previous_output));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(peg_out_graph
+            .timelocked_txs()
+            .into_iter()
+            .filter_map(|(tx, start_txid, num_blocks)| {
+                let start_height = confirmation_heights.get(&start_txid)?;
+                let timelocked_tx = peg_out_graph.timelocked_tx(tx);
+                let is_spent = timelocked_tx
+                    .input
+                    .iter()
+                    .any(|input| spent_outpoints.contains(&input.previous_output));
+                if is_spent {
+                    return None;
+                }
+                let deadline = start_height + num_blocks;
+                Some(LiquidityDeadline {
+                    graph_id: peg_out_graph.id().clone(),
+                    tx,
+                    height: deadline,
+                    blocks_remaining: deadline.saturating_sub(height),
+                })
+            })
+            .collect())
+    }
+
     // TODO: refactor, see note on self.process_peg_in_as_verifier
     pub async fn process_peg_in_as_depositor(&mut self, peg_in_graph_id: &GraphId) {
         if self.depositor_context.is_some() {
             if let Ok(peg_in_graph) = self.get_peg_in_graph(peg_in_graph_id) {
-                let status = peg_in_graph.depositor_status(&self.esplora).await;
+                let status = peg_in_graph.depositor_status(&self.bitcoin_backend).await;
                 match status {
                     PegInDepositorStatus::PegInDepositWait => {
                         let _ = self.broadcast_peg_in_deposit(peg_in_graph_id).await;
@@ -739,19 +1117,23 @@ impl BitVMClient {
                     .filter(|peg_out| peg_in_graph.peg_out_graphs.contains(peg_out.id()))
                     .collect::<Vec<_>>();
                 let status = peg_in_graph
-                    .verifier_status(&self.esplora, context, &peg_outs_for_this_peg_in)
+                    .verifier_status(&self.bitcoin_backend, context, &peg_outs_for_this_peg_in)
                     .await;
                 match status {
                     PegInVerifierStatus::PendingOurNonces(graph_ids) => {
                         println!("Pushing nonces for graphs {graph_ids:?}");
                         for graph_id in graph_ids {
-                            self.push_verifier_nonces(&graph_id);
+                            if let Err(e) = self.push_verifier_nonces(&graph_id) {
+                                eprintln!("Failed to push nonces for graph {graph_id}: {e}");
+                            }
                         }
                     }
                     PegInVerifierStatus::PendingOurSignature(graph_ids) => {
                         println!("Pushing signature for graphs {graph_ids:?}");
                         for graph_id in graph_ids {
-                            self.push_verifier_signature(&graph_id);
+                            if let Err(e) = self.push_verifier_signature(&graph_id) {
+                                eprintln!("Failed to push signature for graph {graph_id}: {e}");
+                            }
                         }
                     }
                     PegInVerifierStatus::ReadyToSubmit => {
@@ -772,10 +1154,11 @@ impl BitVMClient {
             if let Ok(peg_in_graph) = self.get_peg_in_graph(peg_in_graph_id) {
                 let peg_out_graph_id =
                     peg_out_generate_id(peg_in_graph, &context.operator_public_key);
-                if !peg_in_graph
-                    .peg_out_graphs
-                    .iter()
-                    .any(|x| x == &peg_out_graph_id)
+                if peg_in_graph.accepts_operator(&context.operator_public_key)
+                    && !peg_in_graph
+                        .peg_out_graphs
+                        .iter()
+                        .any(|x| x == &peg_out_graph_id)
                 {
                     let deposit_amount =
                         peg_in_graph.peg_in_deposit_transaction.tx().output[0].value;
@@ -788,7 +1171,7 @@ impl BitVMClient {
                             &context.operator_public_key,
                         );
                         let utxos = self
-                            .esplora
+                            .bitcoin_backend
                             .get_address_utxo(address.clone())
                             .await
                             .unwrap();
@@ -806,11 +1189,15 @@ impl BitVMClient {
                             },
                         }
                     };
-                    self.create_peg_out_graph(
-                        peg_in_graph_id,
-                        input,
-                        CommitmentMessageId::generate_commitment_secrets(),
-                    );
+                    let commitment_secrets = self.derive_commitment_secrets(peg_in_graph_id);
+                    self.fetch_disprove_script_bundle().await;
+                    if let Err(e) =
+                        self.create_peg_out_graph(peg_in_graph_id, input, commitment_secrets)
+                    {
+                        eprintln!(
+                            "Failed to create peg-out graph for peg-in {peg_in_graph_id}: {e}"
+                        );
+                    }
                 }
             }
         }
@@ -824,49 +1211,154 @@ impl BitVMClient {
         }
     }
 
-    // TODO: handle internal errors
     pub async fn process_peg_outs(&mut self) {
         let peg_out_graphs = self.data().peg_out_graphs.clone();
         for peg_out_graph in peg_out_graphs.iter() {
-            let status = peg_out_graph.operator_status(&self.esplora).await;
-            match status {
-                PegOutOperatorStatus::PegOutStartTimeAvailable => {
-                    let _ = self.broadcast_start_time(peg_out_graph.id()).await;
-                }
-                PegOutOperatorStatus::PegOutPegOutConfirmAvailable => {
-                    let _ = self.broadcast_peg_out_confirm(peg_out_graph.id()).await;
-                }
-                PegOutOperatorStatus::PegOutKickOff1Available => {
-                    let _ = self.broadcast_kick_off_1(peg_out_graph.id()).await;
-                }
-                PegOutOperatorStatus::PegOutKickOff2Available => {
-                    let _ = self.broadcast_kick_off_2(peg_out_graph.id()).await;
-                }
-                PegOutOperatorStatus::PegOutAssertInitialAvailable => {
-                    let _ = self.broadcast_assert_initial(peg_out_graph.id()).await;
-                }
-                PegOutOperatorStatus::PegOutAssertCommit1Available => {
-                    let _ = self
-                        .broadcast_assert_commit_1(peg_out_graph.id(), &get_proof())
-                        .await;
-                }
-                PegOutOperatorStatus::PegOutAssertCommit2Available => {
-                    let _ = self
-                        .broadcast_assert_commit_2(peg_out_graph.id(), &get_proof())
-                        .await;
+            self.process_peg_out(peg_out_graph).await;
+        }
+    }
+
+    // TODO: handle internal errors
+    async fn process_peg_out(&mut self, peg_out_graph: &PegOutGraph) {
+        let status = peg_out_graph.operator_status(&self.bitcoin_backend).await;
+        match status {
+            PegOutOperatorStatus::PegOutStartTimeAvailable => {
+                let _ = self.broadcast_start_time(peg_out_graph.id()).await;
+            }
+            PegOutOperatorStatus::PegOutPegOutConfirmAvailable => {
+                let _ = self.broadcast_peg_out_confirm(peg_out_graph.id()).await;
+            }
+            PegOutOperatorStatus::PegOutKickOff1Available => {
+                let _ = self.broadcast_kick_off_1(peg_out_graph.id()).await;
+            }
+            PegOutOperatorStatus::PegOutKickOff2Available => {
+                let _ = self.broadcast_kick_off_2(peg_out_graph.id()).await;
+            }
+            PegOutOperatorStatus::PegOutAssertInitialAvailable => {
+                let _ = self.broadcast_assert_initial(peg_out_graph.id()).await;
+            }
+            PegOutOperatorStatus::PegOutAssertCommit1Available => {
+                match self.get_proof(peg_out_graph.id()) {
+                    Ok(proof) => {
+                        let _ = self
+                            .broadcast_assert_commit_1(peg_out_graph.id(), &proof)
+                            .await;
+                    }
+                    Err(e) => eprintln!("No valid proof for graph {}: {e}", peg_out_graph.id()),
                 }
-                PegOutOperatorStatus::PegOutAssertFinalAvailable => {
-                    let _ = self.broadcast_assert_final(peg_out_graph.id()).await;
+            }
+            PegOutOperatorStatus::PegOutAssertCommit2Available => {
+                match self.get_proof(peg_out_graph.id()) {
+                    Ok(proof) => {
+                        let _ = self
+                            .broadcast_assert_commit_2(peg_out_graph.id(), &proof)
+                            .await;
+                    }
+                    Err(e) => eprintln!("No valid proof for graph {}: {e}", peg_out_graph.id()),
                 }
-                PegOutOperatorStatus::PegOutTake1Available => {
-                    let _ = self.broadcast_take_1(peg_out_graph.id()).await;
+            }
+            PegOutOperatorStatus::PegOutAssertFinalAvailable => {
+                let _ = self.broadcast_assert_final(peg_out_graph.id()).await;
+            }
+            PegOutOperatorStatus::PegOutTake1Available => {
+                let _ = self.broadcast_take_1(peg_out_graph.id()).await;
+            }
+            PegOutOperatorStatus::PegOutTake2Available => {
+                let _ = self.broadcast_take_2(peg_out_graph.id()).await;
+            }
+            _ => {}
+        }
+    }
+
+    // Starts watching peg-out graphs that are new to the watchtower, then reacts to the chain
+    // events it reports: graphs whose outputs were created or spent are processed again, and
+    // timelocked transactions are broadcast once they become valid. Finally, the fees of the graph
+    // transactions this client broadcast and that are still unconfirmed are bumped if needed.
+    // A failing step does not prevent the others from running, the errors of all the failed
+    // steps are returned. Graphs that could not be watched are watched again on the next call.
+    pub async fn process_watchtower_events(&mut self, watchtower: &mut Watchtower) -> Vec<Error> {
+        let mut errors = vec![];
+        let mut events = vec![];
+        let peg_out_graphs = self.data().peg_out_graphs.clone();
+        for peg_out_graph in peg_out_graphs.iter() {
+            if watchtower.is_watching(peg_out_graph) {
+                continue;
+            }
+            match watchtower
+                .watch(self.bitcoin_backend.as_ref(), peg_out_graph)
+                .await
+            {
+                Ok(graph_events) => events.extend(graph_events),
+                Err(err) => errors.push(Error::BitcoinBackend(err)),
+            }
+        }
+        match watchtower.poll(self.bitcoin_backend.as_ref()).await {
+            Ok(poll_events) => events.extend(poll_events),
+            Err(err) => errors.push(Error::BitcoinBackend(err)),
+        }
+
+        let mut graph_ids: Vec<GraphId> = vec![];
+        for event in events {
+            match event {
+                WatchtowerEvent::TimelockExpired { graph_id, tx, .. } => {
+                    if let Err(err) = self.broadcast_timelocked_tx(&graph_id, tx).await {
+                        errors.push(err);
+                    }
                 }
-                PegOutOperatorStatus::PegOutTake2Available => {
-                    let _ = self.broadcast_take_2(peg_out_graph.id()).await;
+                event => {
+                    if !graph_ids.contains(event.graph_id()) {
+                        graph_ids.push(event.graph_id().clone());
+                    }
                 }
-                _ => {}
             }
         }
+        for peg_out_graph in peg_out_graphs
+            .iter()
+            .filter(|graph| graph_ids.contains(graph.id()))
+        {
+            self.process_peg_out(peg_out_graph).await;
+        }
+
+        if let Err(err) = self
+            .fee_bumper
+            .poll(self.bitcoin_backend.as_ref(), watchtower)
+            .await
+        {
+            errors.push(Error::BitcoinBackend(err));
+        }
+
+        errors
+    }
+
+    // Timeouts are broadcast by verifiers, who receive the output; take 1 and take 2 by the
+    // operator. Returns `Ok(None)` if this client has no role allowing it to broadcast `tx`.
+    async fn broadcast_timelocked_tx(
+        &mut self,
+        peg_out_graph_id: &GraphId,
+        tx: PegOutTimelockedTx,
+    ) -> Result<Option<Txid>, Error> {
+        let verifier_script_pubkey = self.verifier_context.as_ref().map(|context| {
+            generate_pay_to_pubkey_script_address(self.source_network, &context.verifier_public_key)
+                .script_pubkey()
+        });
+        let is_operator = self.operator_context.is_some();
+        match (tx, verifier_script_pubkey) {
+            (PegOutTimelockedTx::StartTimeTimeout, Some(script_pubkey)) => self
+                .broadcast_start_time_timeout(peg_out_graph_id, script_pubkey)
+                .await
+                .map(Some),
+            (PegOutTimelockedTx::KickOffTimeout, Some(script_pubkey)) => self
+                .broadcast_kick_off_timeout(peg_out_graph_id, script_pubkey)
+                .await
+                .map(Some),
+            (PegOutTimelockedTx::Take1, _) if is_operator => {
+                self.broadcast_take_1(peg_out_graph_id).await.map(Some)
+            }
+            (PegOutTimelockedTx::Take2, _) if is_operator => {
+                self.broadcast_take_2(peg_out_graph_id).await.map(Some)
+            }
+            _ => Ok(None),
+        }
     }
 
     async fn verifier_status(&self) {
@@ -888,7 +1380,7 @@ impl BitVMClient {
                 .collect::<Vec<_>>();
             let peg_in_status = peg_in_graph
                 .verifier_status(
-                    &self.esplora,
+                    &self.bitcoin_backend,
                     self.verifier_context.as_ref().unwrap(),
                     &peg_outs,
                 )
@@ -897,11 +1389,15 @@ impl BitVMClient {
             if peg_in_status == PegInVerifierStatus::Complete {
                 for peg_out_graph in peg_outs {
                     let peg_out_status = peg_out_graph
-                        .verifier_status(&self.esplora, self.verifier_context.as_ref().unwrap())
+                        .verifier_status(
+                            &self.bitcoin_backend,
+                            self.verifier_context.as_ref().unwrap(),
+                        )
                         .await;
                     println!(
-                        "[VERIFIER]: Peg-out graph ID: {} status: {}\n",
+                        "[VERIFIER]: Peg-out graph ID: {} operator: {} status: {}\n",
                         peg_out_graph.id(),
+                        peg_out_graph.operator_public_key(),
                         peg_out_status
                     );
                 }
@@ -911,6 +1407,13 @@ impl BitVMClient {
                 peg_in_graph.id(),
                 peg_in_status
             );
+            if peg_in_status == PegInVerifierStatus::AwaitingPegOutCreation {
+                for operator_public_key in peg_in_graph.missing_operators(&peg_outs) {
+                    println!(
+                        "[VERIFIER]: Awaiting peg-out graph of operator: {operator_public_key}\n"
+                    );
+                }
+            }
         }
     }
 
@@ -919,8 +1422,13 @@ impl BitVMClient {
             panic!("Depositor context must be initialized");
         }
 
-        let peg_in_graph =
-            PegInGraph::new(self.depositor_context.as_ref().unwrap(), input, evm_address);
+        let peg_in_graph = PegInGraph::new(
+            self.depositor_context.as_ref().unwrap(),
+            input,
+            evm_address,
+            &self.operator_public_keys,
+            &self.timelock_policy,
+        );
 
         let peg_in_graph_id = peg_in_generate_id(&peg_in_graph.peg_in_deposit_transaction);
 
@@ -943,7 +1451,7 @@ impl BitVMClient {
         peg_in_graph_id: &String,
     ) -> Result<Txid, Error> {
         let graph = Self::find_peg_in_or_fail(&mut self.data, peg_in_graph_id)?;
-        let tx = graph.deposit(&self.esplora).await?;
+        let tx = graph.deposit(&self.bitcoin_backend).await?;
         self.broadcast_tx(&tx).await
     }
 
@@ -952,7 +1460,7 @@ impl BitVMClient {
         peg_in_graph_id: &String,
     ) -> Result<Txid, Error> {
         let graph = Self::find_peg_in_or_fail(&mut self.data, peg_in_graph_id)?;
-        let tx = graph.refund(&self.esplora).await?;
+        let tx = graph.refund(&self.bitcoin_backend).await?;
         self.broadcast_tx(&tx).await
     }
 
@@ -961,16 +1469,29 @@ impl BitVMClient {
         peg_in_graph_id: &String,
     ) -> Result<Txid, Error> {
         let graph = Self::find_peg_in_or_fail(&mut self.data, peg_in_graph_id)?;
-        let tx = graph.confirm(&self.esplora).await?;
+        let tx = graph.confirm(&self.bitcoin_backend).await?;
         self.broadcast_tx(&tx).await
     }
 
+    // Fetches the disprove script bundle of the verifying key from the data store, so creating a
+    // peg-out graph does not generate the partial scripts again. Returns whether a bundle is
+    // available, in the data store or on disk.
+    pub async fn fetch_disprove_script_bundle(&self) -> bool {
+        let vk = self
+            .zkproof_verifying_key
+            .as_ref()
+            .expect("Zk proof verifying key must be defined");
+        fetch_disprove_script_bundle(&self.data_store, vk)
+            .await
+            .is_some()
+    }
+
     pub fn create_peg_out_graph(
         &mut self,
         peg_in_graph_id: &str,
         peg_out_confirm_input: Input,
         commitment_secrets: HashMap<CommitmentMessageId, WinternitzSecret>,
-    ) -> String {
+    ) -> Result<String, Error> {
         if self.operator_context.is_none() {
             panic!("Operator context must be initialized");
         }
@@ -981,7 +1502,14 @@ impl BitVMClient {
             .peg_in_graphs
             .iter_mut()
             .find(|peg_in_graph| peg_in_graph.id().eq(peg_in_graph_id))
-            .unwrap_or_else(|| panic!("Invalid graph ID"));
+            .ok_or(Error::Client(ClientError::PegInGraphNotFound(
+                peg_in_graph_id.to_string(),
+            )))?;
+        if !peg_in_graph.accepts_operator(operator_public_key) {
+            return Err(Error::Graph(GraphError::UnexpectedOperator(
+                *operator_public_key,
+            )));
+        }
 
         let peg_out_graph_id = peg_out_generate_id(peg_in_graph, operator_public_key);
         let peg_out_graph = self
@@ -992,24 +1520,106 @@ impl BitVMClient {
         if peg_out_graph.is_some() {
             panic!("Peg out graph already exists");
         }
+        // read before the graph is added, so its secrets can be saved
+        let private_data = Self::load_private_data(&mut self.private_data, &self.local_file_path)?;
 
         let peg_out_graph = PegOutGraph::new(
             self.operator_context.as_ref().unwrap(),
             peg_in_graph,
             peg_out_confirm_input,
             &commitment_secrets,
+            &self.timelock_policy,
+            self.zkproof_verifying_key
+                .as_ref()
+                .expect("Zk proof verifying key must be defined"),
         );
 
         self.data.peg_out_graphs.push(peg_out_graph);
         peg_in_graph.peg_out_graphs.push(peg_out_graph_id.clone());
 
-        self.private_data.commitment_secrets = HashMap::from([(
-            *operator_public_key,
-            HashMap::from([(peg_out_graph_id.to_string(), commitment_secrets)]),
-        )]);
-        self.save_private_data();
+        private_data
+            .commitment_secrets
+            .entry(*operator_public_key)
+            .or_default()
+            .insert(peg_out_graph_id.to_string(), commitment_secrets);
+        self.save_private_data()?;
 
-        peg_out_graph_id
+        Ok(peg_out_graph_id)
+    }
+
+    // Derives the commitment secrets of the operator's peg-out graph for the given peg-in graph.
+    pub fn derive_commitment_secrets(
+        &self,
+        peg_in_graph_id: &str,
+    ) -> HashMap<CommitmentMessageId, WinternitzSecret> {
+        let operator_context = self
+            .operator_context
+            .as_ref()
+            .expect("Operator context must be initialized");
+        let peg_in_graph = self
+            .data
+            .peg_in_graphs
+            .iter()
+            .find(|peg_in_graph| peg_in_graph.id().eq(peg_in_graph_id))
+            .unwrap_or_else(|| panic!("Invalid graph ID"));
+
+        CommitmentMessageId::derive_commitment_secrets(
+            &CommitmentSeed::from_operator_keypair(&operator_context.operator_keypair),
+            &peg_out_generate_id(peg_in_graph, &operator_context.operator_public_key),
+            self.zkproof_verifying_key
+                .as_ref()
+                .expect("Zk proof verifying key must be defined"),
+        )
+    }
+
+    // Secrets of graphs created before the secrets were derived are only in the private data,
+    // the others are re-derived if they are missing, e.g. after restoring the operator key.
+    // Re-derived secrets are checked against the public keys committed in the graph, so a graph
+    // whose secrets were generated randomly is not signed with unrelated keys.
+    fn get_commitment_secrets(
+        &mut self,
+        peg_out_graph_id: &String,
+    ) -> Result<HashMap<CommitmentMessageId, WinternitzSecret>, Error> {
+        let operator_context = self
+            .operator_context
+            .as_ref()
+            .ok_or(Error::Client(ClientError::OperatorContextNotDefined))?;
+
+        Self::load_private_data(&mut self.private_data, &self.local_file_path)?
+            .commitment_secrets
+            .get(&operator_context.operator_public_key)
+            .and_then(|secrets| secrets.get(peg_out_graph_id))
+            .cloned()
+            .map_or_else(
+                || {
+                    let peg_out_graph = self
+                        .data
+                        .peg_out_graphs
+                        .iter()
+                        .find(|peg_out_graph| peg_out_graph.id().eq(peg_out_graph_id))
+                        .ok_or(Error::Client(ClientError::PegOutGraphNotFound(
+                            peg_out_graph_id.clone(),
+                        )))?;
+                    let commitment_secrets = CommitmentMessageId::derive_commitment_secrets(
+                        &CommitmentSeed::from_operator_keypair(&operator_context.operator_keypair),
+                        peg_out_graph_id,
+                        peg_out_graph.verifying_key(),
+                    );
+                    let commitment_public_keys = peg_out_graph.commitment_public_keys();
+                    let matches = commitment_public_keys.len() == commitment_secrets.len()
+                        && commitment_secrets.iter().all(|(message_id, secret)| {
+                            commitment_public_keys.get(message_id)
+                                == Some(&WinternitzPublicKey::from(secret))
+                        });
+                    match matches {
+                        true => Ok(commitment_secrets),
+                        false => Err(Error::Client(ClientError::CommitmentSecretsMismatch(
+                            peg_out_graph_id.clone(),
+                        ))),
+                    }
+                },
+                Ok,
+            )
     }
 
     pub async fn broadcast_peg_out(
@@ -1021,7 +1631,7 @@ impl BitVMClient {
         if self.operator_context.is_some() {
             let tx = graph
                 .peg_out(
-                    &self.esplora,
+                    &self.bitcoin_backend,
                     self.operator_context.as_ref().unwrap(),
                     input,
                 )
@@ -1037,46 +1647,50 @@ impl BitVMClient {
         peg_out_graph_id: &String,
     ) -> Result<Txid, Error> {
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
-        let tx = graph.peg_out_confirm(&self.esplora).await?;
+        let tx = graph.peg_out_confirm(&self.bitcoin_backend).await?;
         self.broadcast_tx(&tx).await
     }
 
     pub async fn broadcast_kick_off_1(&mut self, peg_out_graph_id: &String) -> Result<Txid, Error> {
+        let commitment_secrets = self.get_commitment_secrets(peg_out_graph_id)?;
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
 
         if self.operator_context.is_some() {
             let tx = graph
                 .kick_off_1(
-                    &self.esplora,
+                    &self.bitcoin_backend,
                     self.operator_context.as_ref().unwrap(),
-                    &self.private_data.commitment_secrets
-                        [&self.operator_context.as_ref().unwrap().operator_public_key]
-                        [peg_out_graph_id][&CommitmentMessageId::PegOutTxIdSourceNetwork],
-                    &self.private_data.commitment_secrets
-                        [&self.operator_context.as_ref().unwrap().operator_public_key]
-                        [peg_out_graph_id][&CommitmentMessageId::PegOutTxIdDestinationNetwork],
+                    &commitment_secrets[&CommitmentMessageId::PegOutTxIdSourceNetwork],
+                    &commitment_secrets[&CommitmentMessageId::PegOutTxIdDestinationNetwork],
                 )
                 .await?;
-            self.broadcast_tx(&tx).await
+            let txid = self.broadcast_tx(&tx).await?;
+            self.track_fee_bump(peg_out_graph_id, &tx, None);
+            Ok(txid)
         } else {
             Err(Error::Client(ClientError::OperatorContextNotDefined))
         }
     }
 
     pub async fn broadcast_start_time(&mut self, peg_out_graph_id: &String) -> Result<Txid, Error> {
+        let commitment_secrets = self.get_commitment_secrets(peg_out_graph_id)?;
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
 
         if self.operator_context.is_some() {
             let tx = graph
                 .start_time(
-                    &self.esplora,
+                    &self.bitcoin_backend,
                     self.operator_context.as_ref().unwrap(),
-                    &self.private_data.commitment_secrets
-                        [&self.operator_context.as_ref().unwrap().operator_public_key]
-                        [peg_out_graph_id][&CommitmentMessageId::StartTime],
+                    &commitment_secrets[&CommitmentMessageId::StartTime],
                 )
                 .await?;
-            self.broadcast_tx(&tx).await
+            let txid = self.broadcast_tx(&tx).await?;
+            self.track_fee_bump(
+                peg_out_graph_id,
+                &tx,
+                Some(PegOutTimelockedTx::StartTimeTimeout),
+            );
+            Ok(txid)
         } else {
             Err(Error::Client(ClientError::OperatorContextNotDefined))
         }
@@ -1089,26 +1703,31 @@ impl BitVMClient {
     ) -> Result<Txid, Error> {
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
         let tx = graph
-            .start_time_timeout(&self.esplora, output_script_pubkey)
+            .start_time_timeout(&self.bitcoin_backend, output_script_pubkey)
             .await?;
-        self.broadcast_tx(&tx).await
+        let txid = self.broadcast_tx(&tx).await?;
+        self.track_fee_bump(peg_out_graph_id, &tx, None);
+        Ok(txid)
     }
 
     pub async fn broadcast_kick_off_2(&mut self, peg_out_graph_id: &String) -> Result<Txid, Error> {
+        let commitment_secrets = self.get_commitment_secrets(peg_out_graph_id)?;
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
         let tx = graph
             .kick_off_2(
-                &self.esplora,
+                &self.bitcoin_backend,
                 self.operator_context.as_ref().unwrap(),
-                &self.private_data.commitment_secrets
-                    [&self.operator_context.as_ref().unwrap().operator_public_key]
-                    [peg_out_graph_id][&CommitmentMessageId::Superblock],
-                &self.private_data.commitment_secrets
-                    [&self.operator_context.as_ref().unwrap().operator_public_key]
-                    [peg_out_graph_id][&CommitmentMessageId::SuperblockHash],
+                &commitment_secrets[&CommitmentMessageId::Superblock],
+                &commitment_secrets[&CommitmentMessageId::SuperblockHash],
             )
             .await?;
-        self.broadcast_tx(&tx).await
+        let txid = self.broadcast_tx(&tx).await?;
+        self.track_fee_bump(
+            peg_out_graph_id,
+            &tx,
+            Some(PegOutTimelockedTx::KickOffTimeout),
+        );
+        Ok(txid)
     }
 
     pub async fn broadcast_kick_off_timeout(
@@ -1118,9 +1737,11 @@ impl BitVMClient {
     ) -> Result<Txid, Error> {
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
         let tx = graph
-            .kick_off_timeout(&self.esplora, output_script_pubkey)
+            .kick_off_timeout(&self.bitcoin_backend, output_script_pubkey)
             .await?;
-        self.broadcast_tx(&tx).await
+        let txid = self.broadcast_tx(&tx).await?;
+        self.track_fee_bump(peg_out_graph_id, &tx, None);
+        Ok(txid)
     }
 
     pub async fn broadcast_challenge(
@@ -1146,7 +1767,7 @@ impl BitVMClient {
 
         let tx = graph
             .challenge(
-                &self.esplora,
+                &self.bitcoin_backend,
                 crowdfundng_inputs,
                 keypair,
                 output_script_pubkey,
@@ -1160,8 +1781,10 @@ impl BitVMClient {
         peg_out_graph_id: &String,
     ) -> Result<Txid, Error> {
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
-        let tx = graph.assert_initial(&self.esplora).await?;
-        self.broadcast_tx(&tx).await
+        let tx = graph.assert_initial(&self.bitcoin_backend).await?;
+        let txid = self.broadcast_tx(&tx).await?;
+        self.track_fee_bump(peg_out_graph_id, &tx, None);
+        Ok(txid)
     }
 
     pub async fn broadcast_assert_commit_1(
@@ -1169,17 +1792,14 @@ impl BitVMClient {
         peg_out_graph_id: &String,
         proof: &RawProof,
     ) -> Result<Txid, Error> {
+        let commitment_secrets = self.get_commitment_secrets(peg_out_graph_id)?;
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
         let tx = graph
-            .assert_commit_1(
-                &self.esplora,
-                &self.private_data.commitment_secrets
-                    [&self.operator_context.as_ref().unwrap().operator_public_key]
-                    [peg_out_graph_id],
-                proof,
-            )
+            .assert_commit_1(&self.bitcoin_backend, &commitment_secrets, proof)
             .await?;
-        self.broadcast_tx(&tx).await
+        let txid = self.broadcast_tx(&tx).await?;
+        self.track_fee_bump(peg_out_graph_id, &tx, None);
+        Ok(txid)
     }
 
     pub async fn broadcast_assert_commit_2(
@@ -1187,17 +1807,14 @@ impl BitVMClient {
         peg_out_graph_id: &String,
         proof: &RawProof,
     ) -> Result<Txid, Error> {
+        let commitment_secrets = self.get_commitment_secrets(peg_out_graph_id)?;
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
         let tx = graph
-            .assert_commit_2(
-                &self.esplora,
-                &self.private_data.commitment_secrets
-                    [&self.operator_context.as_ref().unwrap().operator_public_key]
-                    [peg_out_graph_id],
-                proof,
-            )
+            .assert_commit_2(&self.bitcoin_backend, &commitment_secrets, proof)
             .await?;
-        self.broadcast_tx(&tx).await
+        let txid = self.broadcast_tx(&tx).await?;
+        self.track_fee_bump(peg_out_graph_id, &tx, None);
+        Ok(txid)
     }
 
     // use this when possible
@@ -1207,20 +1824,16 @@ impl BitVMClient {
         peg_out_graph_id: &String,
         proof: &RawProof,
     ) -> Result<(Txid, Txid), Error> {
+        let commitment_secrets = self.get_commitment_secrets(peg_out_graph_id)?;
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
         let (commit1_tx, commit2_tx) = graph
-            .assert_commits(
-                &self.esplora,
-                &self.private_data.commitment_secrets
-                    [&self.operator_context.as_ref().unwrap().operator_public_key]
-                    [peg_out_graph_id],
-                proof,
-            )
+            .assert_commits(&self.bitcoin_backend, &commitment_secrets, proof)
             .await?;
-        Ok((
-            self.broadcast_tx(&commit1_tx).await?,
-            self.broadcast_tx(&commit2_tx).await?,
-        ))
+        let commit1_txid = self.broadcast_tx(&commit1_tx).await?;
+        self.track_fee_bump(peg_out_graph_id, &commit1_tx, None);
+        let commit2_txid = self.broadcast_tx(&commit2_tx).await?;
+        self.track_fee_bump(peg_out_graph_id, &commit2_tx, None);
+        Ok((commit1_txid, commit2_txid))
     }
 
     pub async fn broadcast_assert_final(
@@ -1228,8 +1841,10 @@ impl BitVMClient {
         peg_out_graph_id: &String,
     ) -> Result<Txid, Error> {
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
-        let tx = graph.assert_final(&self.esplora).await?;
-        self.broadcast_tx(&tx).await
+        let tx = graph.assert_final(&self.bitcoin_backend).await?;
+        let txid = self.broadcast_tx(&tx).await?;
+        self.track_fee_bump(peg_out_graph_id, &tx, None);
+        Ok(txid)
     }
 
     pub async fn broadcast_disprove(
@@ -1239,15 +1854,11 @@ impl BitVMClient {
     ) -> Result<Txid, Error> {
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
         let tx = graph
-            .disprove(
-                &self.esplora,
-                output_script_pubkey,
-                self.zkproof_verifying_key
-                    .as_ref()
-                    .ok_or(Error::Client(ClientError::ZkProofVerifyingKeyNotDefined))?,
-            )
+            .disprove(&self.bitcoin_backend, output_script_pubkey)
             .await?;
-        self.broadcast_tx(&tx).await
+        let txid = self.broadcast_tx(&tx).await?;
+        self.track_fee_bump(peg_out_graph_id, &tx, Some(PegOutTimelockedTx::Take2));
+        Ok(txid)
     }
 
     pub async fn broadcast_disprove_chain(
@@ -1257,27 +1868,40 @@ impl BitVMClient {
     ) -> Result<Txid, Error> {
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
         let tx = graph
-            .disprove_chain(&self.esplora, output_script_pubkey)
+            .disprove_chain(&self.bitcoin_backend, output_script_pubkey)
             .await?;
-        self.broadcast_tx(&tx).await
+        let txid = self.broadcast_tx(&tx).await?;
+        self.track_fee_bump(peg_out_graph_id, &tx, Some(PegOutTimelockedTx::Take1));
+        Ok(txid)
     }
 
     pub async fn broadcast_take_1(&mut self, peg_out_graph_id: &String) -> Result<Txid, Error> {
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
-        let tx = graph.take_1(&self.esplora).await?;
-        self.broadcast_tx(&tx).await
+        let tx = graph.take_1(&self.bitcoin_backend).await?;
+        let txid = self.broadcast_tx(&tx).await?;
+        self.track_fee_bump(peg_out_graph_id, &tx, None);
+        Ok(txid)
     }
 
     pub async fn broadcast_take_2(&mut self, peg_out_graph_id: &String) -> Result<Txid, Error> {
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
         let tx = graph
-            .take_2(&self.esplora, self.operator_context.as_ref().unwrap())
+            .take_2(
+                &self.bitcoin_backend,
+                self.operator_context.as_ref().unwrap(),
+            )
             .await?;
-        self.broadcast_tx(&tx).await
+        let txid = self.broadcast_tx(&tx).await?;
+        self.track_fee_bump(peg_out_graph_id, &tx, None);
+        Ok(txid)
     }
 
     pub async fn get_initial_utxo(&self, address: Address, amount: Amount) -> Option<Utxo> {
-        let utxos = self.esplora.get_address_utxo(address).await.unwrap();
+        let utxos = self
+            .bitcoin_backend
+            .get_address_utxo(address)
+            .await
+            .unwrap();
 
         let possible_utxos = utxos
             .into_iter()
@@ -1291,7 +1915,11 @@ impl BitVMClient {
     }
 
     pub async fn get_initial_utxos(&self, address: Address, amount: Amount) -> Option<Vec<Utxo>> {
-        let utxos: Vec<Utxo> = self.esplora.get_address_utxo(address).await.unwrap();
+        let utxos: Vec<Utxo> = self
+            .bitcoin_backend
+            .get_address_utxo(address)
+            .await
+            .unwrap();
         let possible_utxos = utxos
             .into_iter()
             .filter(|utxo| utxo.value == amount)
@@ -1312,7 +1940,7 @@ impl BitVMClient {
     }
 
     pub async fn get_operator_utxos(&self) -> Vec<Utxo> {
-        self.esplora
+        self.bitcoin_backend
             .get_address_utxo(self.get_operator_address())
             .await
             .unwrap()
@@ -1327,21 +1955,34 @@ impl BitVMClient {
     }
 
     pub async fn get_depositor_utxos(&self) -> Vec<Utxo> {
-        self.esplora
+        self.bitcoin_backend
             .get_address_utxo(self.get_depositor_address())
             .await
             .unwrap()
     }
 
-    pub fn push_verifier_nonces(&mut self, graph_id: &GraphId) {
+    pub fn push_verifier_nonces(&mut self, graph_id: &GraphId) -> Result<(), Error> {
         if self.verifier_context.is_none() {
             panic!("Can only be called by a verifier!");
         }
+        if let (Some(peg_out_graph), Some(verifying_key)) = (
+            self.data.peg_out_graphs.iter().find(|x| x.id() == graph_id),
+            &self.zkproof_verifying_key,
+        ) {
+            // the disprove scripts must check the proofs of the circuit the verifier expects
+            if peg_out_graph.verifying_key() != verifying_key {
+                return Err(Error::Client(ClientError::VerifyingKeyMismatch(
+                    graph_id.clone(),
+                )));
+            }
+        }
 
         let graph = self.data.graph_mut(graph_id);
         let secret_nonces = graph.push_verifier_nonces(self.verifier_context.as_ref().unwrap());
-        self.merge_secret_nonces(graph_id, secret_nonces);
-        self.save_private_data();
+        self.merge_secret_nonces(graph_id, secret_nonces)?;
+        self.save_private_data()?;
+
+        Ok(())
     }
 
     fn get_peg_in_graph(&self, peg_in_graph_id: &String) -> Result<&PegInGraph, Error> {
@@ -1388,8 +2029,46 @@ impl BitVMClient {
         }
     }
 
+    // Hands a broadcast graph transaction to the fee bumper, which attaches CPFP children to it
+    // while it is unconfirmed. Children spend its anchor, funded by the operator or else the
+    // verifier, or its output paying to one of them, e.g. the reward of a timeout. Transactions
+    // without such an output, like the crowdfunded challenge, are not tracked.
+    fn track_fee_bump(
+        &mut self,
+        peg_out_graph_id: &GraphId,
+        tx: &Transaction,
+        deadline_tx: Option<PegOutTimelockedTx>,
+    ) {
+        let mut keypairs = [
+            self.operator_context
+                .as_ref()
+                .map(|context| (context.operator_keypair, context.operator_public_key)),
+            self.verifier_context
+                .as_ref()
+                .map(|context| (context.verifier_keypair, context.verifier_public_key)),
+        ]
+        .into_iter()
+        .flatten();
+        let bump_output = match anchor_vout(tx) {
+            Some(vout) => keypairs.next().map(|(keypair, _)| (vout, keypair)),
+            None => keypairs.find_map(|(keypair, public_key)| {
+                let script_pubkey =
+                    generate_pay_to_pubkey_script_address(self.source_network, &public_key)
+                        .script_pubkey();
+                tx.output
+                    .iter()
+                    .position(|output| output.script_pubkey == script_pubkey)
+                    .map(|vout| (vout as u32, keypair))
+            }),
+        };
+        if let Some((vout, keypair)) = bump_output {
+            self.fee_bumper
+                .track(peg_out_graph_id, tx.clone(), vout, &keypair, deadline_tx);
+        }
+    }
+
     async fn broadcast_tx(&self, tx: &Transaction) -> Result<Txid, Error> {
-        let status_message = broadcast_and_verify(&self.esplora, tx).await?;
+        let status_message = broadcast_and_verify(&self.bitcoin_backend, tx).await?;
 
         let txid = tx.compute_txid();
         println!("{} Txid: {}", status_message, txid.to_string().green());
@@ -1401,30 +2080,16 @@ impl BitVMClient {
         &mut self,
         graph_id: &str,
         secret_nonces: HashMap<Txid, HashMap<usize, SecNonce>>,
-    ) {
-        self.private_data
+    ) -> Result<(), Error> {
+        Self::load_private_data(&mut self.private_data, &self.local_file_path)?
             .secret_nonces
             .entry(self.verifier_context.as_ref().unwrap().verifier_public_key)
-            .or_default();
-
-        if !self.private_data.secret_nonces
-            [&self.verifier_context.as_ref().unwrap().verifier_public_key]
-            .contains_key(graph_id)
-        {
-            self.private_data
-                .secret_nonces
-                .get_mut(&self.verifier_context.as_ref().unwrap().verifier_public_key)
-                .unwrap()
-                .insert(graph_id.to_string(), HashMap::new());
-        }
-
-        self.private_data
-            .secret_nonces
-            .get_mut(&self.verifier_context.as_ref().unwrap().verifier_public_key)
-            .unwrap()
-            .get_mut(graph_id)
-            .unwrap()
+            .or_default()
+            .entry(graph_id.to_string())
+            .or_default()
             .extend(secret_nonces);
+
+        Ok(())
     }
 
     pub fn generate_connector_z_taproot_address(
@@ -1442,6 +2107,7 @@ impl BitVMClient {
                 .as_ref()
                 .unwrap()
                 .n_of_n_taproot_public_key,
+            &self.timelock_policy,
         );
         connector_z.generate_taproot_address()
     }
@@ -1463,6 +2129,7 @@ impl BitVMClient {
                 .as_ref()
                 .unwrap()
                 .n_of_n_taproot_public_key,
+            &self.timelock_policy,
         );
         let connector_0 = Connector0::new(
             source_network,
@@ -1511,6 +2178,7 @@ impl BitVMClient {
                 .as_ref()
                 .unwrap()
                 .n_of_n_taproot_public_key,
+            &self.timelock_policy,
         );
         let mut peg_in_deposit_tx = PegInDepositTransaction::new_for_validation(
             source_network,
@@ -1539,6 +2207,7 @@ impl BitVMClient {
                 .as_ref()
                 .unwrap()
                 .n_of_n_taproot_public_key,
+            &self.timelock_policy,
         );
         let mut peg_in_refund_tx = PegInRefundTransaction::new_for_validation(
             source_network,
@@ -1549,18 +2218,31 @@ impl BitVMClient {
         serialize_hex(&(peg_in_refund_tx.tx_mut()))
     }
 
-    pub fn push_verifier_signature(&mut self, graph_id: &GraphId) {
+    pub fn push_verifier_signature(&mut self, graph_id: &GraphId) -> Result<(), Error> {
         let verifier = self
             .verifier_context
             .as_ref()
             .expect("Can only be called by a verifier!");
 
-        let graph = self.data.graph_mut(graph_id);
-        graph.verifier_sign(
-            verifier,
-            &self.private_data.secret_nonces
-                [&self.verifier_context.as_ref().unwrap().verifier_public_key][graph_id],
-        );
+        let secret_nonces = Self::load_private_data(&mut self.private_data, &self.local_file_path)?
+            .take_secret_nonces(
+                &verifier.verifier_public_key,
+                graph_id,
+                &self.data.graph_mut(graph_id).musig2_transactions(),
+            )
+            .map_err(Error::Nonce)?;
+        // Persist that the nonces are consumed before any signature made with them is published.
+        self.save_private_data()?;
+
+        match secret_nonces {
+            Some(secret_nonces) => self
+                .data
+                .graph_mut(graph_id)
+                .verifier_sign(self.verifier_context.as_ref().unwrap(), &secret_nonces),
+            None => println!("Graph {graph_id} is already signed, skipping"),
+        }
+
+        Ok(())
     }
 
     // pub async fn execute_possible_txs(
@@ -1653,32 +2335,45 @@ impl BitVMClient {
 
 impl ClientCliQuery for BitVMClient {
     async fn get_unused_peg_in_graphs(&self) -> Vec<Value> {
-        join_all(self.data.peg_in_graphs.iter().filter_map(|peg_in| {
-            Some(async move {
-                match peg_in.depositor_status(&self.esplora).await {
-                    PegInDepositorStatus::PegInConfirmComplete => match self.data.peg_out_graphs.iter().find(|peg_out| peg_out.peg_in_graph_id == *peg_in.id()) {
-                        Some(peg_out) => match peg_out.operator_status(&self.esplora).await {
-                            PegOutOperatorStatus::PegOutWait => Some(json!({
-                                "graph_id": peg_in.id(),
-                                "amount": peg_in.peg_in_confirm_transaction.prev_outs()[0].value.to_sat(),
-                                "source_outpoint": {
-                                    "txid": peg_in.peg_in_confirm_transaction.tx().compute_txid(),
-                                    "vout": 0
-                                },
-                            })),
-                            _ => None,
+        join_all(self.data.peg_in_graphs.iter().map(|peg_in| async move {
+            match peg_in.depositor_status(&self.bitcoin_backend).await {
+                PegInDepositorStatus::PegInConfirmComplete => {
+                    let peg_outs = self
+                        .data
+                        .peg_out_graphs
+                        .iter()
+                        .filter(|peg_out| peg_out.peg_in_graph_id == *peg_in.id())
+                        .collect::<Vec<_>>();
+                    // a peg-in is used once any of its operators fronted a withdrawal
+                    let mut operator_public_keys = vec![];
+                    for peg_out in peg_outs.iter() {
+                        match peg_out.operator_status(&self.bitcoin_backend).await {
+                            PegOutOperatorStatus::PegOutWait => {
+                                operator_public_keys.push(peg_out.operator_public_key().to_string())
+                            }
+                            _ => return None,
+                        }
+                    }
+                    if operator_public_keys.is_empty() {
+                        return None;
+                    }
+
+                    Some(json!({
+                        "graph_id": peg_in.id(),
+                        "amount": peg_in.peg_in_confirm_transaction.prev_outs()[0].value.to_sat(),
+                        "source_outpoint": {
+                            "txid": peg_in.peg_in_confirm_transaction.tx().compute_txid(),
+                            "vout": 0
                         },
-                        None => None,
-                    },
-                    _ => None,
+                        "operator_public_keys": operator_public_keys,
+                    }))
                 }
-            })
+                _ => None,
+            }
         }))
         .await
-        .iter()
-        .filter_map(|v| {
-            v.clone()
-        })
+        .into_iter()
+        .flatten()
         .collect()
     }
 
@@ -1694,8 +2389,8 @@ impl ClientCliQuery for BitVMClient {
                         graph.peg_in_confirm_transaction.tx().compute_txid(),
                         graph.peg_in_refund_transaction.tx().compute_txid(),
                     ];
-                    let tx_statuses_results = get_tx_statuses(&self.esplora, &tx_ids).await;
-                    let blockchain_height = self.esplora.get_height().await;
+                    let tx_statuses_results = get_tx_statuses(&self.bitcoin_backend, &tx_ids).await;
+                    let blockchain_height = self.bitcoin_backend.get_height().await;
                     let status = graph.interpret_depositor_status(
                         &tx_statuses_results[0],
                         &tx_statuses_results[1],
@@ -1734,6 +2429,19 @@ impl ClientCliQuery for BitVMClient {
                             }})
                         })
                         .collect::<Vec<_>>();
+                    let peg_out_json_values = self
+                        .data
+                        .peg_out_graphs
+                        .iter()
+                        .filter(|peg_out| peg_out.peg_in_graph_id == *graph.id())
+                        .map(|peg_out| {
+                            json!({
+                                "graph_id": peg_out.id(),
+                                "operator_public_key": peg_out.operator_public_key().to_string(),
+                                "peg_out_initiated": peg_out.is_peg_out_initiated(),
+                            })
+                        })
+                        .collect::<Vec<_>>();
 
                     json!({
                         "type": "peg_in",
@@ -1742,6 +2450,7 @@ impl ClientCliQuery for BitVMClient {
                         "amount": graph.peg_in_deposit_transaction.prev_outs()[0].value.to_sat(),
                         "destination_address": graph.depositor_evm_address,
                         "txs" : tx_json_values,
+                        "peg_out_graphs": peg_out_json_values,
                     })
                 }),
         )
@@ -1768,7 +2477,7 @@ impl ClientCliQuery for BitVMClient {
                     let (tx_json_value, tx_status_result) = match &graph.peg_out_transaction {
                         Some(tx) => {
                             let txid = tx.tx().compute_txid();
-                            let tx_status_result = self.esplora.get_tx_status(&txid).await;
+                            let tx_status_result = self.bitcoin_backend.get_tx_status(&txid).await;
                             let tx_status = tx_status_result.as_ref().unwrap_or(&TxStatus {
                                 confirmed: false,
                                 block_height: None,
@@ -1805,6 +2514,7 @@ impl ClientCliQuery for BitVMClient {
                         "status": status.to_string(),
                         "amount": peg_out_amount,
                         "destination_address": destination_address,
+                        "operator_public_key": graph.operator_public_key().to_string(),
                         "txs": tx_json_value,
                     })
                 }),
@@ -1840,6 +2550,8 @@ impl ClientCliQuery for BitVMClient {
             n_of_n_taproot_public_key,
             depositor_evm_address,
             deposit_input,
+            &self.operator_public_keys,
+            &self.timelock_policy,
         );
 
         Ok(json!({
@@ -1879,6 +2591,8 @@ impl ClientCliQuery for BitVMClient {
             depositor_evm_address,
             deposit_input,
             signatures,
+            &self.operator_public_keys,
+            &self.timelock_policy,
         );
 
         let peg_in_graph_id = peg_in_generate_id(&peg_in_graph.peg_in_deposit_transaction);
@@ -1894,7 +2608,7 @@ impl ClientCliQuery for BitVMClient {
 
         self.data.peg_in_graphs.push(peg_in_graph.clone());
 
-        match peg_in_graph.broadcast_deposit(&self.esplora).await {
+        match peg_in_graph.broadcast_deposit(&self.bitcoin_backend).await {
             Ok(_) => Ok(json!({"graph_id": peg_in_graph_id})),
             Err(e) => Err(e),
         }
@@ -1910,7 +2624,7 @@ impl ClientCliQuery for BitVMClient {
             return Err("Peg in graph not found".into());
         };
 
-        match peg_in_graph.broadcast_deposit(&self.esplora).await {
+        match peg_in_graph.broadcast_deposit(&self.bitcoin_backend).await {
             Ok(_) => Ok(json!({"graph_id": peg_in_graph_id})),
             Err(e) => Err(e),
         }
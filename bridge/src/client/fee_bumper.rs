@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+
+use bitcoin::{
+    key::Keypair, Amount, FeeRate, Network, OutPoint, PublicKey, Transaction, TxOut, Txid,
+};
+
+use crate::{
+    error::{BitcoinBackendError, Error},
+    graphs::{base::GraphId, peg_out::PegOutTimelockedTx},
+    scripts::generate_pay_to_pubkey_script_address,
+    transactions::cpfp::{generate_cpfp_tx, package_fee_rate},
+};
+
+use super::{bitcoin_backend::bitcoin_backend::BitcoinBackend, watchtower::Watchtower};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeeBumpPolicy {
+    // Confirmation target of the fee estimates while the deadline of a transaction is far away.
+    pub target_blocks: u16,
+    // Number of blocks a transaction may stay unconfirmed before it is bumped again.
+    pub stuck_blocks: u32,
+    // Once the deadline of a transaction is this close, estimates target the next block.
+    pub urgent_blocks: u32,
+    // Fee rate no bump goes above.
+    pub max_fee_rate: FeeRate,
+}
+
+impl Default for FeeBumpPolicy {
+    fn default() -> Self {
+        FeeBumpPolicy {
+            target_blocks: 6,
+            stuck_blocks: 3,
+            urgent_blocks: 6,
+            max_fee_rate: FeeRate::from_sat_per_vb_unchecked(500),
+        }
+    }
+}
+
+struct PendingTx {
+    graph_id: GraphId,
+    tx: Transaction,
+    // output spent by the first child, an anchor or an output paying to `keypair`
+    bump_vout: u32,
+    keypair: Keypair,
+    // the timelocked transaction that can be broadcast instead of `tx` once it is valid
+    deadline_tx: Option<PegOutTimelockedTx>,
+    deadline: Option<u32>,
+    // height of the last bump, or at which the transaction was first polled
+    last_bump_height: Option<u32>,
+    // unconfirmed CPFP children and their fees, each spending the change of the previous one
+    children: Vec<(Transaction, Amount)>,
+}
+
+// Keeps the graph transactions broadcast by the client in the mempool until they confirm, and
+// raises their fee rate with CPFP children when they are stuck. Transactions racing a timelock,
+// e.g. kick off 2 against the kick off timeout, are bumped to confirm in the next block once the
+// timelock is about to expire. The deadlines are the heights the watchtower scheduled the
+// timelocked transactions for.
+pub struct FeeBumper {
+    network: Network,
+    policy: FeeBumpPolicy,
+    pending: HashMap<Txid, PendingTx>,
+}
+
+impl FeeBumper {
+    pub fn new(network: Network, policy: FeeBumpPolicy) -> Self {
+        Self {
+            network,
+            policy,
+            pending: HashMap::new(),
+        }
+    }
+
+    pub fn policy(&self) -> &FeeBumpPolicy {
+        &self.policy
+    }
+
+    pub fn set_policy(&mut self, policy: FeeBumpPolicy) {
+        self.policy = policy;
+    }
+
+    pub fn is_tracking(&self, txid: &Txid) -> bool {
+        self.pending.contains_key(txid)
+    }
+
+    // Children are funded from the outputs paying to `keypair`, see `generate_cpfp_tx`.
+    pub fn track(
+        &mut self,
+        graph_id: &GraphId,
+        tx: Transaction,
+        bump_vout: u32,
+        keypair: &Keypair,
+        deadline_tx: Option<PegOutTimelockedTx>,
+    ) {
+        self.pending
+            .entry(tx.compute_txid())
+            .or_insert_with(|| PendingTx {
+                graph_id: graph_id.clone(),
+                tx,
+                bump_vout,
+                keypair: *keypair,
+                deadline_tx,
+                deadline: None,
+                last_bump_height: None,
+                children: vec![],
+            });
+    }
+
+    // Stops tracking confirmed transactions, broadcasts the unconfirmed ones and their children
+    // again in case they were evicted from the mempool, and attaches a new child to the ones
+    // that are stuck or close to their deadline. Returns the txids of the new children.
+    pub async fn poll(
+        &mut self,
+        client: &dyn BitcoinBackend,
+        watchtower: &Watchtower,
+    ) -> Result<Vec<Txid>, BitcoinBackendError> {
+        let height = client.get_height().await?;
+        let txids: Vec<Txid> = self.pending.keys().cloned().collect();
+        let mut children = vec![];
+        for txid in txids {
+            if client.get_tx_status(&txid).await?.confirmed {
+                self.pending.remove(&txid);
+                continue;
+            }
+            match self.bump(client, watchtower, &txid, height).await {
+                Ok(Some(child_txid)) => children.push(child_txid),
+                Ok(None) => {}
+                Err(err) => eprintln!("Failed to bump the fee of {}: {}", txid, err),
+            }
+        }
+
+        Ok(children)
+    }
+
+    async fn bump(
+        &mut self,
+        client: &dyn BitcoinBackend,
+        watchtower: &Watchtower,
+        txid: &Txid,
+        height: u32,
+    ) -> Result<Option<Txid>, Error> {
+        let policy = self.policy;
+        let network = self.network;
+        let pending = self.pending.get_mut(txid).unwrap();
+        for tx in [&pending.tx]
+            .into_iter()
+            .chain(pending.children.iter().map(|(child, _)| child))
+        {
+            let is_known = client
+                .get_tx(&tx.compute_txid())
+                .await
+                .map_err(Error::BitcoinBackend)?
+                .is_some();
+            if !is_known {
+                client.broadcast(tx).await.map_err(Error::BitcoinBackend)?;
+            }
+        }
+
+        if pending.deadline.is_none() {
+            pending.deadline = pending.deadline_tx.and_then(|deadline_tx| {
+                watchtower
+                    .scheduled(&pending.graph_id)
+                    .into_iter()
+                    .find(|(tx, _)| *tx == deadline_tx)
+                    .map(|(_, height)| height)
+            });
+        }
+        let is_urgent = pending
+            .deadline
+            .is_some_and(|deadline| height + policy.urgent_blocks >= deadline);
+        let last_bump_height = *pending.last_bump_height.get_or_insert(height);
+        let is_stuck = height >= last_bump_height + policy.stuck_blocks;
+        if !is_urgent && !is_stuck {
+            return Ok(None);
+        }
+
+        let target_blocks = if is_urgent { 1 } else { policy.target_blocks };
+        let fee_rate = client
+            .estimate_fee_rate(target_blocks)
+            .await
+            .map_err(Error::BitcoinBackend)?
+            .min(policy.max_fee_rate);
+        let fee = transaction_fee(client, &pending.tx)
+            .await
+            .map_err(Error::BitcoinBackend)?;
+        let package: Vec<(Transaction, Amount)> = [(pending.tx.clone(), fee)]
+            .into_iter()
+            .chain(pending.children.iter().cloned())
+            .collect();
+        if package_fee_rate(&package) >= fee_rate {
+            pending.last_bump_height = Some(height);
+            return Ok(None);
+        }
+
+        let spent_output = match pending.children.last() {
+            Some((child, _)) => (
+                OutPoint::new(child.compute_txid(), 0),
+                child.output[0].clone(),
+            ),
+            None => (
+                OutPoint::new(*txid, pending.bump_vout),
+                pending.tx.output[pending.bump_vout as usize].clone(),
+            ),
+        };
+        let address = generate_pay_to_pubkey_script_address(
+            network,
+            &PublicKey::new(pending.keypair.public_key()),
+        );
+        let funding_utxos: Vec<(OutPoint, TxOut)> = client
+            .get_address_utxo(address.clone())
+            .await
+            .map_err(Error::BitcoinBackend)?
+            .into_iter()
+            .filter(|utxo| utxo.status.confirmed)
+            .map(|utxo| {
+                (
+                    OutPoint::new(utxo.txid, utxo.vout),
+                    TxOut {
+                        value: utxo.value,
+                        script_pubkey: address.script_pubkey(),
+                    },
+                )
+            })
+            .collect();
+
+        let (child, child_fee) = generate_cpfp_tx(
+            network,
+            &pending.keypair,
+            &package,
+            spent_output,
+            &funding_utxos,
+            fee_rate,
+        )
+        .map_err(Error::FeeBump)?;
+        client
+            .broadcast(&child)
+            .await
+            .map_err(Error::BitcoinBackend)?;
+        let child_txid = child.compute_txid();
+        pending.children.push((child, child_fee));
+        pending.last_bump_height = Some(height);
+
+        Ok(Some(child_txid))
+    }
+}
+
+// Looks up the outputs spent by `tx`, which must be confirmed or in the mempool.
+async fn transaction_fee(
+    client: &dyn BitcoinBackend,
+    tx: &Transaction,
+) -> Result<Amount, BitcoinBackendError> {
+    let mut input_value = Amount::ZERO;
+    for input in tx.input.iter() {
+        let prev_tx = client
+            .get_tx(&input.previous_output.txid)
+            .await?
+            .ok_or_else(|| {
+                BitcoinBackendError::Rpc(format!(
+                    "transaction {} not found",
+                    input.previous_output.txid
+                ))
+            })?;
+        input_value += prev_tx.output[input.previous_output.vout as usize].value;
+    }
+    let output_value: Amount = tx.output.iter().map(|output| output.value).sum();
+
+    Ok(input_value
+        .checked_sub(output_value)
+        .unwrap_or(Amount::ZERO))
+}
@@ -4,9 +4,14 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use crate::serialization::try_deserialize;
+use zeroize::Zeroizing;
 
-use super::client::BitVMClientPrivateData;
+use crate::{
+    error::KeystoreError,
+    serialization::{serialize, try_deserialize, try_deserialize_slice},
+};
+
+use super::{client::BitVMClientPrivateData, keystore::Keystore};
 
 pub const BRIDGE_DATA_DIRECTORY_NAME: &str = "bridge_data";
 pub const DEFAULT_PATH_PREFIX: &str = "default_user";
@@ -42,13 +47,46 @@ pub fn create_directories_if_non_existent(data_root_path: &Path) {
     }
 }
 
-pub fn get_private_data_from_file(path: &Path) -> BitVMClientPrivateData {
-    match read_file(path) {
-        Some(data) => try_deserialize::<BitVMClientPrivateData>(&data)
-            .expect("Could not deserialize private data"),
-        None => BitVMClientPrivateData {
-            secret_nonces: HashMap::new(),
-            commitment_secrets: HashMap::new(),
+/*
+  Private data is stored in an encrypted keystore unlocked with the passphrase returned by
+  `get_passphrase`, which is only called if the file exists.
+  Plaintext private data written by earlier versions of the client is migrated to a keystore
+  the first time it is read. Files that are neither are reported as an invalid keystore.
+*/
+pub fn get_private_data_from_file(
+    path: &Path,
+    get_passphrase: impl FnOnce() -> Result<Zeroizing<String>, KeystoreError>,
+) -> Result<BitVMClientPrivateData, KeystoreError> {
+    let data = match read_file(path) {
+        Some(data) => Zeroizing::new(data),
+        None => {
+            return Ok(BitVMClientPrivateData {
+                secret_nonces: HashMap::new(),
+                commitment_secrets: HashMap::new(),
+                consumed_nonces: HashMap::new(),
+            })
+        }
+    };
+
+    match try_deserialize::<Keystore>(&data) {
+        Ok(keystore) => {
+            let plaintext = keystore.decrypt(&get_passphrase()?)?;
+            try_deserialize_slice::<BitVMClientPrivateData>(&plaintext)
+                .map_err(|e| KeystoreError::InvalidFormat(format!("Invalid private data: {e}")))
+        }
+        Err(keystore_error) => match try_deserialize::<BitVMClientPrivateData>(&data) {
+            Ok(private_data) => {
+                println!(
+                    "Migrating plaintext private data in {} to an encrypted keystore...",
+                    path.display()
+                );
+                write_private_file(path, &data, &get_passphrase()?)?;
+                Ok(private_data)
+            }
+            Err(_) => Err(KeystoreError::InvalidFormat(format!(
+                "Invalid keystore in {}: {keystore_error}",
+                path.display()
+            ))),
         },
     }
 }
@@ -77,9 +115,25 @@ pub fn save_local_public_file(data_root_path: &Path, file_name: &String, content
     .expect("Unable to write a file");
 }
 
-pub fn save_local_private_file(data_root_path: &Path, contents: &String) {
+pub fn save_local_private_file(
+    data_root_path: &Path,
+    contents: &str,
+    passphrase: &str,
+) -> Result<(), KeystoreError> {
     create_directories_if_non_existent(data_root_path);
     println!("Saving private data in local file...");
-    fs::write(get_private_data_file_path(data_root_path), contents)
-        .expect("Unable to write a file");
+    write_private_file(
+        &get_private_data_file_path(data_root_path),
+        contents,
+        passphrase,
+    )
+}
+
+// Replaces the file atomically, so a crash never leaves a partially written keystore behind.
+fn write_private_file(path: &Path, contents: &str, passphrase: &str) -> Result<(), KeystoreError> {
+    let keystore = Keystore::encrypt(contents.as_bytes(), passphrase)?;
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, serialize(&keystore)).expect("Unable to write a file");
+    fs::rename(tmp_path, path).expect("Unable to write a file");
+    Ok(())
 }
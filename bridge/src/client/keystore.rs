@@ -0,0 +1,151 @@
+use argon2::{Algorithm, Argon2, Params, Version};
+use bitcoin::hex::{DisplayHex, FromHex};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    Key, XChaCha20Poly1305, XNonce,
+};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::error::KeystoreError;
+
+pub const KEYSTORE_VERSION: u32 = 1;
+pub const KEYSTORE_PASSPHRASE_ENV_VAR: &str = "BRIDGE_KEYSTORE_PASSPHRASE";
+
+const KDF_ALGORITHM: &str = "argon2id";
+const CIPHER: &str = "xchacha20poly1305";
+const KEY_LENGTH: usize = 32;
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 24;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct KdfParameters {
+    pub algorithm: String,
+    pub memory_cost_kib: u32,
+    pub time_cost: u32,
+    pub parallelism: u32,
+    pub salt: String,
+}
+
+/*
+  Secrets encrypted at rest with a key derived from a passphrase.
+  The key is derived with Argon2id and a random salt, and the plaintext is encrypted with
+  XChaCha20-Poly1305 under a random nonce. The version and KDF parameters are authenticated as
+  associated data, so they cannot be downgraded without failing decryption.
+*/
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Keystore {
+    pub version: u32,
+    pub cipher: String,
+    pub nonce: String,
+    pub ciphertext: String,
+    // Last, since TOML requires tables after values.
+    pub kdf: KdfParameters,
+}
+
+impl Keystore {
+    pub fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<Self, KeystoreError> {
+        let mut salt = [0u8; SALT_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+        let mut nonce = [0u8; NONCE_LENGTH];
+        OsRng.fill_bytes(&mut nonce);
+
+        let mut keystore = Keystore {
+            version: KEYSTORE_VERSION,
+            cipher: CIPHER.to_string(),
+            nonce: nonce.to_lower_hex_string(),
+            ciphertext: String::new(),
+            kdf: KdfParameters {
+                algorithm: KDF_ALGORITHM.to_string(),
+                memory_cost_kib: Params::DEFAULT_M_COST,
+                time_cost: Params::DEFAULT_T_COST,
+                parallelism: Params::DEFAULT_P_COST,
+                salt: salt.to_lower_hex_string(),
+            },
+        };
+
+        let key = keystore.derive_key(passphrase)?;
+        let ciphertext = XChaCha20Poly1305::new(Key::from_slice(&key[..]))
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext,
+                    aad: &keystore.associated_data(),
+                },
+            )
+            .map_err(|_| KeystoreError::EncryptionFailed)?;
+        keystore.ciphertext = ciphertext.to_lower_hex_string();
+
+        Ok(keystore)
+    }
+
+    // Fails with `DecryptionFailed` if the passphrase is wrong or the keystore was tampered with.
+    pub fn decrypt(&self, passphrase: &str) -> Result<Zeroizing<Vec<u8>>, KeystoreError> {
+        if self.version != KEYSTORE_VERSION {
+            return Err(KeystoreError::UnsupportedVersion(self.version));
+        }
+        if self.kdf.algorithm != KDF_ALGORITHM || self.cipher != CIPHER {
+            return Err(KeystoreError::InvalidFormat(format!(
+                "Unsupported KDF {} or cipher {}",
+                self.kdf.algorithm, self.cipher
+            )));
+        }
+
+        let nonce = decode_hex(&self.nonce, "nonce")?;
+        if nonce.len() != NONCE_LENGTH {
+            return Err(KeystoreError::InvalidFormat(format!(
+                "Nonce must be {NONCE_LENGTH} bytes long"
+            )));
+        }
+        let ciphertext = decode_hex(&self.ciphertext, "ciphertext")?;
+
+        let key = self.derive_key(passphrase)?;
+        XChaCha20Poly1305::new(Key::from_slice(&key[..]))
+            .decrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: &self.associated_data(),
+                },
+            )
+            .map(Zeroizing::new)
+            .map_err(|_| KeystoreError::DecryptionFailed)
+    }
+
+    fn derive_key(&self, passphrase: &str) -> Result<Zeroizing<[u8; KEY_LENGTH]>, KeystoreError> {
+        let salt = decode_hex(&self.kdf.salt, "salt")?;
+        let params = Params::new(
+            self.kdf.memory_cost_kib,
+            self.kdf.time_cost,
+            self.kdf.parallelism,
+            Some(KEY_LENGTH),
+        )
+        .map_err(|e| KeystoreError::KeyDerivationFailed(e.to_string()))?;
+
+        let mut key = Zeroizing::new([0u8; KEY_LENGTH]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key[..])
+            .map_err(|e| KeystoreError::KeyDerivationFailed(e.to_string()))?;
+
+        Ok(key)
+    }
+
+    fn associated_data(&self) -> Vec<u8> {
+        serde_json::to_vec(&(self.version, &self.kdf, &self.cipher))
+            .expect("Failed to serialize keystore associated data")
+    }
+}
+
+fn decode_hex(value: &str, name: &str) -> Result<Vec<u8>, KeystoreError> {
+    Vec::<u8>::from_hex(value)
+        .map_err(|e| KeystoreError::InvalidFormat(format!("Invalid {name} hex: {e}")))
+}
+
+// Reads the passphrase that unlocks the keystores from the environment (or the .env file).
+pub fn get_keystore_passphrase() -> Result<Zeroizing<String>, KeystoreError> {
+    dotenv::dotenv().ok();
+    dotenv::var(KEYSTORE_PASSPHRASE_ENV_VAR)
+        .map(Zeroizing::new)
+        .map_err(|_| KeystoreError::PassphraseNotSet)
+}
@@ -0,0 +1,270 @@
+use std::{collections::BTreeMap, fmt};
+
+use bitcoin::{Amount, FeeRate, OutPoint, PublicKey};
+use serde::Serialize;
+
+use crate::{
+    graphs::{
+        base::GraphId,
+        peg_out::{PegOutOperatorStatus, PegOutTimelockedTx},
+    },
+    transactions::base::{MIN_RELAY_FEE_PEG_OUT, PEG_OUT_VSIZE, RELAY_FEE_BUFFER_MULTIPLIER},
+};
+
+use super::{bitcoin_backend::bitcoin_backend::Utxo, chain::chain::PegOutEvent};
+
+// Where the capital of the operator stands in a peg-out graph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CapitalState {
+    Idle,        // no withdrawal assigned to the graph yet
+    Requested,   // withdrawal requested, the operator still has to front it with the peg-out
+    InFlight,    // withdrawal fronted, reimbursement not available yet
+    Reclaimable, // take 1 or take 2 can be broadcast
+    Reimbursed,
+    Lost, // timed out or disproven
+}
+
+impl CapitalState {
+    // `has_fronted` tells whether the peg-out transaction was created, which `PegOutWait` does
+    // not distinguish.
+    pub fn from_status(status: &PegOutOperatorStatus, has_fronted: bool) -> Self {
+        match status {
+            PegOutOperatorStatus::PegOutWait if has_fronted => CapitalState::InFlight,
+            PegOutOperatorStatus::PegOutWait => CapitalState::Idle,
+            PegOutOperatorStatus::PegOutStartPegOut => CapitalState::Requested,
+            PegOutOperatorStatus::PegOutComplete => CapitalState::Reimbursed,
+            PegOutOperatorStatus::PegOutFailed => CapitalState::Lost,
+            PegOutOperatorStatus::PegOutTake1Available
+            | PegOutOperatorStatus::PegOutTake2Available => CapitalState::Reclaimable,
+            _ => CapitalState::InFlight,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PegOutLiquidity {
+    pub graph_id: GraphId,
+    pub status: String,
+    pub state: CapitalState,
+    // amount of the withdrawal, zero while no withdrawal is assigned
+    pub withdrawal_amount: u64,
+    // amount take 1 or take 2 pays back to the operator
+    pub reimbursement_amount: u64,
+}
+
+// Height at which a timelocked transaction of a graph becomes valid. Timeouts must be preempted
+// by the operator before that height, takes reimburse the operator from that height on.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct LiquidityDeadline {
+    pub graph_id: GraphId,
+    pub tx: PegOutTimelockedTx,
+    pub height: u32,
+    pub blocks_remaining: u32,
+}
+
+// Withdrawal requested from the operator on L2 and not fronted yet.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PendingWithdrawal {
+    pub source_outpoint: OutPoint,
+    pub amount: u64,
+    // input the peg-out transaction would spend, none if no single operator UTXO is large enough
+    pub funding_utxo: Option<OutPoint>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct StatusTotal {
+    pub count: usize,
+    pub withdrawal_amount: u64,
+}
+
+/*
+  Capital of an operator across its peg-out graphs: what it is asked to front, what it fronted
+  and is waiting to be reimbursed for, and what it can reclaim now. Pending withdrawals are
+  matched against the operator UTXOs in the order they were requested, each peg-out spending a
+  single UTXO that covers the withdrawal and the peg-out fee at the current fee estimate.
+*/
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct LiquidityReport {
+    pub operator_public_key: PublicKey,
+    pub height: u32,
+    pub peg_outs: Vec<PegOutLiquidity>,
+    pub totals_by_status: BTreeMap<String, StatusTotal>,
+    pub requested_amount: u64,
+    pub in_flight_amount: u64,
+    pub reclaimable_amount: u64,
+    pub deadlines: Vec<LiquidityDeadline>,
+    pub available_amount: u64,
+    // fee each pending peg-out is expected to pay at the current fee estimate
+    pub peg_out_fee: u64,
+    pub pending_withdrawals: Vec<PendingWithdrawal>,
+    pub warnings: Vec<String>,
+}
+
+impl LiquidityReport {
+    pub fn new(
+        operator_public_key: PublicKey,
+        height: u32,
+        peg_outs: Vec<PegOutLiquidity>,
+        mut deadlines: Vec<LiquidityDeadline>,
+        utxos: &[Utxo],
+        pending_events: &[PegOutEvent],
+        peg_out_fee: u64,
+    ) -> Self {
+        let mut totals_by_status: BTreeMap<String, StatusTotal> = BTreeMap::new();
+        for peg_out in peg_outs.iter() {
+            let total = totals_by_status.entry(peg_out.status.clone()).or_default();
+            total.count += 1;
+            total.withdrawal_amount += peg_out.withdrawal_amount;
+        }
+        let sum = |state: CapitalState, amount: fn(&PegOutLiquidity) -> u64| -> u64 {
+            peg_outs
+                .iter()
+                .filter(|peg_out| peg_out.state == state)
+                .map(amount)
+                .sum()
+        };
+        let requested_amount = sum(CapitalState::Requested, |peg_out| peg_out.withdrawal_amount);
+        let in_flight_amount = sum(CapitalState::InFlight, |peg_out| peg_out.withdrawal_amount);
+        let reclaimable_amount = sum(CapitalState::Reclaimable, |peg_out| {
+            peg_out.reimbursement_amount
+        });
+        deadlines.sort_by_key(|deadline| deadline.height);
+
+        let mut warnings = vec![];
+        let pending_withdrawals = match_pending_withdrawals(utxos, pending_events, peg_out_fee);
+        let available_amount = utxos.iter().map(|utxo| utxo.value.to_sat()).sum();
+        let required_amount: u64 = pending_withdrawals
+            .iter()
+            .map(|withdrawal| withdrawal.amount + peg_out_fee)
+            .sum();
+        if required_amount > available_amount {
+            warnings.push(format!(
+                "Operator UTXOs ({}) cannot cover the {} pending withdrawals ({} with fees)",
+                Amount::from_sat(available_amount),
+                pending_withdrawals.len(),
+                Amount::from_sat(required_amount),
+            ));
+        }
+        for withdrawal in pending_withdrawals
+            .iter()
+            .filter(|withdrawal| withdrawal.funding_utxo.is_none())
+        {
+            warnings.push(format!(
+                "No operator UTXO of at least {} left to front the withdrawal of peg-in {}",
+                Amount::from_sat(withdrawal.amount + peg_out_fee),
+                withdrawal.source_outpoint,
+            ));
+        }
+
+        LiquidityReport {
+            operator_public_key,
+            height,
+            peg_outs,
+            totals_by_status,
+            requested_amount,
+            in_flight_amount,
+            reclaimable_amount,
+            deadlines,
+            available_amount,
+            peg_out_fee,
+            pending_withdrawals,
+            warnings,
+        }
+    }
+}
+
+// Fee of a peg-out transaction at `fee_rate`, never below the minimum relay fee the peg-out
+// transaction is built with.
+pub fn peg_out_fee(fee_rate: FeeRate) -> u64 {
+    let vsize = (PEG_OUT_VSIZE as f32 * RELAY_FEE_BUFFER_MULTIPLIER) as u64;
+    (vsize * fee_rate.to_sat_per_vb_ceil()).max(MIN_RELAY_FEE_PEG_OUT)
+}
+
+// Assigns the smallest unused UTXO covering each withdrawal, oldest withdrawal first.
+fn match_pending_withdrawals(
+    utxos: &[Utxo],
+    pending_events: &[PegOutEvent],
+    peg_out_fee: u64,
+) -> Vec<PendingWithdrawal> {
+    let mut events = pending_events.iter().collect::<Vec<_>>();
+    events.sort_by_key(|event| event.timestamp);
+    let mut unused_utxos = utxos.iter().collect::<Vec<_>>();
+    unused_utxos.sort_by_key(|utxo| utxo.value);
+
+    events
+        .into_iter()
+        .map(|event| {
+            let required_amount = event.amount + Amount::from_sat(peg_out_fee);
+            let funding_utxo = unused_utxos
+                .iter()
+                .position(|utxo| utxo.value >= required_amount)
+                .map(|index| {
+                    let utxo = unused_utxos.remove(index);
+                    OutPoint::new(utxo.txid, utxo.vout)
+                });
+
+            PendingWithdrawal {
+                source_outpoint: event.source_outpoint,
+                amount: event.amount.to_sat(),
+                funding_utxo,
+            }
+        })
+        .collect()
+}
+
+impl fmt::Display for LiquidityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Operator {} at block height {}",
+            self.operator_public_key, self.height
+        )?;
+        writeln!(
+            f,
+            "Requested: {}, in flight: {}, reclaimable: {}, available: {}",
+            Amount::from_sat(self.requested_amount),
+            Amount::from_sat(self.in_flight_amount),
+            Amount::from_sat(self.reclaimable_amount),
+            Amount::from_sat(self.available_amount),
+        )?;
+        for (status, total) in self.totals_by_status.iter() {
+            writeln!(
+                f,
+                "  {} peg-out graph(s), {}: {}",
+                total.count,
+                Amount::from_sat(total.withdrawal_amount),
+                status
+            )?;
+        }
+        for deadline in self.deadlines.iter() {
+            writeln!(
+                f,
+                "Peg-out graph {}: {:?} valid at height {} ({} blocks remaining)",
+                deadline.graph_id, deadline.tx, deadline.height, deadline.blocks_remaining
+            )?;
+        }
+        for withdrawal in self.pending_withdrawals.iter() {
+            match withdrawal.funding_utxo {
+                Some(funding_utxo) => writeln!(
+                    f,
+                    "Pending withdrawal of {} from peg-in {}, funded by {}",
+                    Amount::from_sat(withdrawal.amount),
+                    withdrawal.source_outpoint,
+                    funding_utxo
+                )?,
+                None => writeln!(
+                    f,
+                    "Pending withdrawal of {} from peg-in {}, not funded",
+                    Amount::from_sat(withdrawal.amount),
+                    withdrawal.source_outpoint
+                )?,
+            }
+        }
+        for warning in self.warnings.iter() {
+            writeln!(f, "Warning: {}", warning)?;
+        }
+
+        Ok(())
+    }
+}
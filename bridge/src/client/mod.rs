@@ -1,9 +1,14 @@
 #![allow(clippy::module_inception)]
+pub mod bitcoin_backend;
 pub mod chain;
 pub mod cli;
 pub mod client;
 pub mod data_store;
 pub mod esplora;
+pub mod fee_bumper;
 pub mod files;
+pub mod keystore;
+pub mod liquidity;
 pub mod memory_cache;
 pub mod sdk;
+pub mod watchtower;
@@ -1,10 +1,9 @@
 use std::future::Future;
 
 use bitcoin::{PublicKey, XOnlyPublicKey};
-use esplora_client::AsyncClient;
 use serde_json::Value;
 
-use crate::transactions::base::Input;
+use crate::{client::bitcoin_backend::bitcoin_backend::BitcoinBackend, transactions::base::Input};
 
 use super::query_contexts::depositor_signatures::DepositorSignatures;
 
@@ -40,5 +39,8 @@ pub trait ClientCliQuery {
 }
 
 pub trait GraphCliQuery {
-    fn broadcast_deposit(&self, client: &AsyncClient) -> impl Future<Output = Result<(), String>>;
+    fn broadcast_deposit(
+        &self,
+        client: &dyn BitcoinBackend,
+    ) -> impl Future<Output = Result<(), String>>;
 }
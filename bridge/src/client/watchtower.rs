@@ -0,0 +1,447 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use bitcoin::{OutPoint, Transaction, Txid};
+
+use crate::{
+    error::BitcoinBackendError,
+    graphs::{
+        base::GraphId,
+        peg_out::{PegOutGraph, PegOutTimelockedTx},
+    },
+};
+
+use super::bitcoin_backend::bitcoin_backend::BitcoinBackend;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WatchtowerEvent {
+    // An output of a graph transaction was created. `height` is `None` while the transaction is
+    // in the mempool.
+    OutputCreated {
+        graph_id: GraphId,
+        outpoint: OutPoint,
+        height: Option<u32>,
+    },
+    // An outpoint spent by one of the graph transactions was spent by `txid`, which is not
+    // necessarily the graph transaction (e.g. a crowdfunded challenge).
+    OutputSpent {
+        graph_id: GraphId,
+        outpoint: OutPoint,
+        txid: Txid,
+        height: Option<u32>,
+    },
+    // The timelock of `tx` is satisfied by the block after `height`, so `tx` can be broadcast
+    // once the tip is at `height`.
+    TimelockExpired {
+        graph_id: GraphId,
+        tx: PegOutTimelockedTx,
+        height: u32,
+    },
+}
+
+impl WatchtowerEvent {
+    pub fn graph_id(&self) -> &GraphId {
+        match self {
+            WatchtowerEvent::OutputCreated { graph_id, .. }
+            | WatchtowerEvent::OutputSpent { graph_id, .. }
+            | WatchtowerEvent::TimelockExpired { graph_id, .. } => graph_id,
+        }
+    }
+}
+
+struct WatchedGraph {
+    txs: Vec<Transaction>,
+    timelocked_txs: Vec<(PegOutTimelockedTx, Txid, u32)>,
+}
+
+// Follows the chain block by block and reports the events relevant to the watched peg-out
+// graphs. Every outpoint a graph transaction creates or spends is indexed, so a block only
+// produces events if it touches one of them. Timelocked transactions are scheduled as soon as
+// their preceding transaction is confirmed, for the tip height from which they can be broadcast
+// to be mined in the next block.
+//
+// Between blocks, only the graph transactions whose inputs are all available are looked up in
+// the mempool. Reorgs are not handled: blocks are assumed final once processed.
+pub struct Watchtower {
+    height: u32,
+    graphs: HashMap<GraphId, WatchedGraph>,
+    // outpoint -> graphs that create or spend it
+    outpoints: HashMap<OutPoint, Vec<GraphId>>,
+    // indexed outpoints that were created and not spent yet
+    unspent: HashSet<OutPoint>,
+    confirmed_txids: HashSet<Txid>,
+    mempool_txids: HashSet<Txid>,
+    // tip height -> timelocked transactions that can be broadcast from that height
+    schedule: BTreeMap<u32, Vec<(GraphId, PegOutTimelockedTx)>>,
+}
+
+impl Watchtower {
+    // Only blocks above `start_height` are scanned; graphs confirmed earlier are caught up with
+    // in `watch`.
+    pub fn new(start_height: u32) -> Self {
+        Self {
+            height: start_height,
+            graphs: HashMap::new(),
+            outpoints: HashMap::new(),
+            unspent: HashSet::new(),
+            confirmed_txids: HashSet::new(),
+            mempool_txids: HashSet::new(),
+            schedule: BTreeMap::new(),
+        }
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    // False for graphs that are unknown or gained transactions since they were last watched,
+    // e.g. once the peg-out transaction is created.
+    pub fn is_watching(&self, peg_out_graph: &PegOutGraph) -> bool {
+        self.graphs
+            .get(peg_out_graph.id())
+            .is_some_and(|graph| graph.txs.len() == peg_out_graph.transactions().len())
+    }
+
+    // Heights at which timelocked transactions of the graph are scheduled.
+    pub fn scheduled(&self, graph_id: &GraphId) -> Vec<(PegOutTimelockedTx, u32)> {
+        self.schedule
+            .iter()
+            .flat_map(|(height, txs)| {
+                txs.iter()
+                    .filter(|(id, _)| id == graph_id)
+                    .map(|(_, tx)| (*tx, *height))
+            })
+            .collect()
+    }
+
+    // Indexes the outpoints of the graph and reports the graph transactions that were already
+    // confirmed up to the current height. Watching a graph again only reports transactions that
+    // were not processed before.
+    pub async fn watch(
+        &mut self,
+        client: &dyn BitcoinBackend,
+        peg_out_graph: &PegOutGraph,
+    ) -> Result<Vec<WatchtowerEvent>, BitcoinBackendError> {
+        self.watch_transactions(
+            client,
+            peg_out_graph.id().clone(),
+            peg_out_graph.transactions().into_iter().cloned().collect(),
+            peg_out_graph.timelocked_txs().to_vec(),
+        )
+        .await
+    }
+
+    async fn watch_transactions(
+        &mut self,
+        client: &dyn BitcoinBackend,
+        graph_id: GraphId,
+        txs: Vec<Transaction>,
+        timelocked_txs: Vec<(PegOutTimelockedTx, Txid, u32)>,
+    ) -> Result<Vec<WatchtowerEvent>, BitcoinBackendError> {
+        for tx in txs.iter() {
+            let txid = tx.compute_txid();
+            let outpoints = tx
+                .input
+                .iter()
+                .map(|input| input.previous_output)
+                .chain((0..tx.output.len()).map(|vout| OutPoint::new(txid, vout as u32)));
+            for outpoint in outpoints {
+                let graph_ids = self.outpoints.entry(outpoint).or_default();
+                if !graph_ids.contains(&graph_id) {
+                    graph_ids.push(graph_id.clone());
+                }
+            }
+        }
+        self.graphs.insert(
+            graph_id,
+            WatchedGraph {
+                txs: txs.clone(),
+                timelocked_txs,
+            },
+        );
+
+        let mut confirmed_txs = vec![];
+        for tx in txs {
+            let status = client.get_tx_status(&tx.compute_txid()).await?;
+            match status.block_height {
+                Some(height) if status.confirmed && height <= self.height => {
+                    confirmed_txs.push((height, tx))
+                }
+                _ => {}
+            }
+        }
+        confirmed_txs.sort_by_key(|(height, _)| *height);
+
+        let mut events = vec![];
+        for (height, tx) in confirmed_txs {
+            self.process_tx(&tx, Some(height), &mut events);
+        }
+
+        Ok(events)
+    }
+
+    // Processes the blocks mined since the last call, fires the timelocked transactions that
+    // became valid and looks up the graph transactions that can enter the mempool next.
+    pub async fn poll(
+        &mut self,
+        client: &dyn BitcoinBackend,
+    ) -> Result<Vec<WatchtowerEvent>, BitcoinBackendError> {
+        let mut events = vec![];
+        let tip = client.get_height().await?;
+        while self.height < tip {
+            let height = self.height + 1;
+            for tx in client.get_block_txs(height).await? {
+                self.process_tx(&tx, Some(height), &mut events);
+            }
+            self.height = height;
+        }
+
+        let due_heights: Vec<u32> = self
+            .schedule
+            .range(..=self.height)
+            .map(|(h, _)| *h)
+            .collect();
+        for height in due_heights {
+            for (graph_id, tx) in self.schedule.remove(&height).unwrap() {
+                events.push(WatchtowerEvent::TimelockExpired {
+                    graph_id,
+                    tx,
+                    height,
+                });
+            }
+        }
+
+        for tx in self.mempool_candidates() {
+            if client.get_tx(&tx.compute_txid()).await?.is_some() {
+                self.process_tx(&tx, None, &mut events);
+            }
+        }
+
+        Ok(events)
+    }
+
+    // Graph transactions neither confirmed nor in the mempool whose inputs are either unspent
+    // graph outputs or outputs of transactions outside of the graph.
+    fn mempool_candidates(&self) -> Vec<Transaction> {
+        let mut candidates = vec![];
+        for graph in self.graphs.values() {
+            let graph_txids: HashSet<Txid> = graph.txs.iter().map(|tx| tx.compute_txid()).collect();
+            for tx in graph.txs.iter() {
+                let txid = tx.compute_txid();
+                if self.confirmed_txids.contains(&txid) || self.mempool_txids.contains(&txid) {
+                    continue;
+                }
+                let is_available = |outpoint: &OutPoint| {
+                    !graph_txids.contains(&outpoint.txid) || self.unspent.contains(outpoint)
+                };
+                if tx
+                    .input
+                    .iter()
+                    .all(|input| is_available(&input.previous_output))
+                {
+                    candidates.push(tx.clone());
+                }
+            }
+        }
+
+        candidates
+    }
+
+    fn process_tx(
+        &mut self,
+        tx: &Transaction,
+        height: Option<u32>,
+        events: &mut Vec<WatchtowerEvent>,
+    ) {
+        let txid = tx.compute_txid();
+        if self.confirmed_txids.contains(&txid)
+            || (height.is_none() && self.mempool_txids.contains(&txid))
+        {
+            return;
+        }
+
+        for input in tx.input.iter() {
+            if let Some(graph_ids) = self.outpoints.get(&input.previous_output) {
+                self.unspent.remove(&input.previous_output);
+                for graph_id in graph_ids {
+                    events.push(WatchtowerEvent::OutputSpent {
+                        graph_id: graph_id.clone(),
+                        outpoint: input.previous_output,
+                        txid,
+                        height,
+                    });
+                }
+            }
+        }
+        for vout in 0..tx.output.len() {
+            let outpoint = OutPoint::new(txid, vout as u32);
+            if let Some(graph_ids) = self.outpoints.get(&outpoint) {
+                self.unspent.insert(outpoint);
+                for graph_id in graph_ids {
+                    events.push(WatchtowerEvent::OutputCreated {
+                        graph_id: graph_id.clone(),
+                        outpoint,
+                        height,
+                    });
+                }
+            }
+        }
+
+        match height {
+            Some(height) => {
+                self.mempool_txids.remove(&txid);
+                self.confirmed_txids.insert(txid);
+                for (graph_id, graph) in self.graphs.iter() {
+                    for &(timelocked_tx, preceding_txid, num_blocks) in graph.timelocked_txs.iter()
+                    {
+                        if preceding_txid == txid {
+                            self.schedule
+                                .entry(height + num_blocks - 1)
+                                .or_default()
+                                .push((graph_id.clone(), timelocked_tx));
+                        }
+                    }
+                }
+            }
+            None => {
+                self.mempool_txids.insert(txid);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        absolute, transaction::Version, Amount, ScriptBuf, Sequence, TxIn, TxOut, Witness,
+    };
+
+    use super::*;
+    use crate::client::bitcoin_backend::memory_backend::MemoryBackend;
+
+    const TIMELOCK: u32 = 3;
+
+    fn spending_tx(outpoint: OutPoint, sequence: Sequence, value: Amount) -> Transaction {
+        Transaction {
+            version: Version(2),
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: outpoint,
+                script_sig: ScriptBuf::new(),
+                sequence,
+                witness: Witness::default(),
+            }],
+            output: vec![TxOut {
+                value,
+                script_pubkey: ScriptBuf::new(),
+            }],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_watchtower_reports_outputs_and_schedules_timelocked_txs() {
+        let backend = MemoryBackend::new();
+        let funding_outpoint = backend.fund(ScriptBuf::new(), Amount::from_sat(100_000));
+        let kick_off_tx = spending_tx(funding_outpoint, Sequence::MAX, Amount::from_sat(99_000));
+        let kick_off_txid = kick_off_tx.compute_txid();
+        let take_tx = spending_tx(
+            OutPoint::new(kick_off_txid, 0),
+            Sequence::from_height(TIMELOCK as u16),
+            Amount::from_sat(98_000),
+        );
+        let graph_id: GraphId = "graph".to_string();
+
+        let mut watchtower = Watchtower::new(backend.get_height().await.unwrap());
+        let events = watchtower
+            .watch_transactions(
+                &backend,
+                graph_id.clone(),
+                vec![kick_off_tx.clone(), take_tx],
+                vec![(PegOutTimelockedTx::Take1, kick_off_txid, TIMELOCK)],
+            )
+            .await
+            .unwrap();
+        assert!(events.is_empty());
+        assert!(watchtower.poll(&backend).await.unwrap().is_empty());
+
+        backend.broadcast(&kick_off_tx).await.unwrap();
+        let kick_off_output = OutPoint::new(kick_off_txid, 0);
+        assert_eq!(
+            watchtower.poll(&backend).await.unwrap(),
+            vec![
+                WatchtowerEvent::OutputSpent {
+                    graph_id: graph_id.clone(),
+                    outpoint: funding_outpoint,
+                    txid: kick_off_txid,
+                    height: None,
+                },
+                WatchtowerEvent::OutputCreated {
+                    graph_id: graph_id.clone(),
+                    outpoint: kick_off_output,
+                    height: None,
+                },
+            ]
+        );
+        // seen in the mempool already, not reported again until confirmed
+        assert!(watchtower.poll(&backend).await.unwrap().is_empty());
+
+        backend.mine_blocks(1);
+        let events = watchtower.poll(&backend).await.unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events.contains(&WatchtowerEvent::OutputCreated {
+            graph_id: graph_id.clone(),
+            outpoint: kick_off_output,
+            height: Some(2),
+        }));
+        assert_eq!(
+            watchtower.scheduled(&graph_id),
+            vec![(PegOutTimelockedTx::Take1, 1 + TIMELOCK)]
+        );
+
+        backend.mine_blocks(TIMELOCK - 2);
+        assert!(watchtower.poll(&backend).await.unwrap().is_empty());
+
+        backend.mine_blocks(1);
+        assert_eq!(
+            watchtower.poll(&backend).await.unwrap(),
+            vec![WatchtowerEvent::TimelockExpired {
+                graph_id: graph_id.clone(),
+                tx: PegOutTimelockedTx::Take1,
+                height: 1 + TIMELOCK,
+            }]
+        );
+        assert!(watchtower.scheduled(&graph_id).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_watchtower_catches_up_on_confirmed_graph_txs() {
+        let backend = MemoryBackend::new();
+        let funding_outpoint = backend.fund(ScriptBuf::new(), Amount::from_sat(100_000));
+        let kick_off_tx = spending_tx(funding_outpoint, Sequence::MAX, Amount::from_sat(99_000));
+        let kick_off_txid = kick_off_tx.compute_txid();
+        backend.broadcast(&kick_off_tx).await.unwrap();
+        backend.mine_blocks(TIMELOCK + 1);
+
+        let graph_id: GraphId = "graph".to_string();
+        let mut watchtower = Watchtower::new(backend.get_height().await.unwrap());
+        let events = watchtower
+            .watch_transactions(
+                &backend,
+                graph_id.clone(),
+                vec![kick_off_tx],
+                vec![(PegOutTimelockedTx::KickOffTimeout, kick_off_txid, TIMELOCK)],
+            )
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 2);
+
+        // the timelock expired before the graph was watched, so it fires on the next poll
+        assert_eq!(
+            watchtower.poll(&backend).await.unwrap(),
+            vec![WatchtowerEvent::TimelockExpired {
+                graph_id,
+                tx: PegOutTimelockedTx::KickOffTimeout,
+                height: 1 + TIMELOCK,
+            }]
+        );
+    }
+}
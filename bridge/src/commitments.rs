@@ -1,15 +1,28 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
+use bitcoin::{
+    hashes::{
+        hmac::{Hmac, HmacEngine},
+        sha256, Hash, HashEngine,
+    },
+    hex::DisplayHex,
+    key::Keypair,
+};
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumIter, IntoEnumIterator};
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use bitvm::{
-    chunk::api::{NUM_HASH, NUM_PUBS, NUM_U256},
-    signatures::signing_winternitz::WinternitzSecret,
+    chunk::{
+        api::{num_hash, num_pubs, NUM_U256},
+        keys::{ChunkPublicKeys, ChunkSecretKeys},
+    },
+    signatures::signing_winternitz::{WinternitzPublicKey, WinternitzSecret},
     signatures::HASH_LEN,
 };
 
 use super::{
+    common::ZkProofVerifyingKey,
     constants::{
         DESTINATION_NETWORK_TXID_LENGTH, SOURCE_NETWORK_TXID_LENGTH, START_TIME_MESSAGE_LENGTH,
     },
@@ -75,65 +88,189 @@ impl TryFrom<String> for CommitmentMessageId {
 }
 
 impl CommitmentMessageId {
+    // Length in bytes of the message committed to with this id.
+    pub fn message_length(&self) -> usize {
+        match self {
+            CommitmentMessageId::PegOutTxIdSourceNetwork => SOURCE_NETWORK_TXID_LENGTH,
+            CommitmentMessageId::PegOutTxIdDestinationNetwork => DESTINATION_NETWORK_TXID_LENGTH,
+            CommitmentMessageId::StartTime => START_TIME_MESSAGE_LENGTH,
+            CommitmentMessageId::Superblock => SUPERBLOCK_MESSAGE_LENGTH,
+            CommitmentMessageId::SuperblockHash => SUPERBLOCK_HASH_MESSAGE_LENGTH,
+            CommitmentMessageId::Groth16IntermediateValues((_, size)) => *size,
+        }
+    }
+
     // btree map is a copy of chunker related commitments
-    pub fn generate_commitment_secrets() -> HashMap<CommitmentMessageId, WinternitzSecret> {
-        println!("Generating commitment secrets ...");
-        let mut commitment_map = HashMap::from([
-            (
-                CommitmentMessageId::PegOutTxIdSourceNetwork,
-                WinternitzSecret::new(SOURCE_NETWORK_TXID_LENGTH),
-            ),
-            (
-                CommitmentMessageId::PegOutTxIdDestinationNetwork,
-                WinternitzSecret::new(DESTINATION_NETWORK_TXID_LENGTH),
-            ),
-            (
-                CommitmentMessageId::StartTime,
-                WinternitzSecret::new(START_TIME_MESSAGE_LENGTH),
-            ),
-            (
-                CommitmentMessageId::Superblock,
-                WinternitzSecret::new(SUPERBLOCK_MESSAGE_LENGTH),
-            ),
-            (
-                CommitmentMessageId::SuperblockHash,
-                WinternitzSecret::new(SUPERBLOCK_HASH_MESSAGE_LENGTH),
-            ),
-        ]);
+    pub fn commitment_message_ids(vk: &ZkProofVerifyingKey) -> Vec<CommitmentMessageId> {
+        let mut ids = vec![
+            CommitmentMessageId::PegOutTxIdSourceNetwork,
+            CommitmentMessageId::PegOutTxIdDestinationNetwork,
+            CommitmentMessageId::StartTime,
+            CommitmentMessageId::Superblock,
+            CommitmentMessageId::SuperblockHash,
+        ];
 
-        for i in 0..NUM_PUBS {
-            commitment_map.insert(
-                CommitmentMessageId::Groth16IntermediateValues((format!("{}", i), 32)),
-                WinternitzSecret::new(32),
-            );
+        // one assertion per public input of the verifying key the graph's disprove scripts check
+        let num_pubs = num_pubs(vk);
+        for i in 0..num_pubs {
+            ids.push(CommitmentMessageId::Groth16IntermediateValues((
+                format!("{}", i),
+                32,
+            )));
         }
         for i in 0..NUM_U256 {
-            commitment_map.insert(
-                CommitmentMessageId::Groth16IntermediateValues((format!("{}", i + NUM_PUBS), 32)),
-                WinternitzSecret::new(32),
-            );
+            ids.push(CommitmentMessageId::Groth16IntermediateValues((
+                format!("{}", i + num_pubs),
+                32,
+            )));
         }
-        for i in 0..NUM_HASH {
-            commitment_map.insert(
-                CommitmentMessageId::Groth16IntermediateValues((
-                    format!("{}", i + NUM_PUBS + NUM_U256),
-                    HASH_LEN,
-                )),
-                WinternitzSecret::new(HASH_LEN),
-            );
+        for i in 0..num_hash(num_pubs) {
+            ids.push(CommitmentMessageId::Groth16IntermediateValues((
+                format!("{}", i + num_pubs + NUM_U256),
+                HASH_LEN,
+            )));
         }
 
-        commitment_map
+        ids
+    }
+
+    pub fn generate_commitment_secrets(
+        vk: &ZkProofVerifyingKey,
+    ) -> HashMap<CommitmentMessageId, WinternitzSecret> {
+        println!("Generating commitment secrets ...");
+        Self::commitment_message_ids(vk)
+            .into_iter()
+            .map(|id| {
+                let secret = WinternitzSecret::new(id.message_length());
+                (id, secret)
+            })
+            .collect()
+    }
+
+    /*
+      Derives the commitment secrets of a peg-out graph from the operator seed, so that they can be
+      re-derived from a backup of the seed alone. Each secret is HMAC-SHA256(seed, graph id || 0x00
+      || message id) truncated to 160 bits, the size of the secrets created by WinternitzSecret::new.
+    */
+    pub fn derive_commitment_secrets(
+        seed: &CommitmentSeed,
+        graph_id: &str,
+        vk: &ZkProofVerifyingKey,
+    ) -> HashMap<CommitmentMessageId, WinternitzSecret> {
+        Self::commitment_message_ids(vk)
+            .into_iter()
+            .map(|id| {
+                let secret = id.derive_secret(seed, graph_id);
+                (id, secret)
+            })
+            .collect()
+    }
+
+    pub fn derive_secret(&self, seed: &CommitmentSeed, graph_id: &str) -> WinternitzSecret {
+        let mut engine = HmacEngine::<sha256::Hash>::new(&seed.0[..]);
+        engine.input(graph_id.as_bytes());
+        engine.input(&[0]);
+        engine.input(String::from(self.clone()).as_bytes());
+        let mut hmac = Hmac::<sha256::Hash>::from_engine(engine).to_byte_array();
+
+        let secret = WinternitzSecret::from_bytes(
+            self.message_length(),
+            hmac[..COMMITMENT_SECRET_LENGTH]
+                .to_lower_hex_string()
+                .into(),
+        );
+        hmac.zeroize();
+        secret
+    }
+}
+
+// Values of the Groth16 intermediate value commitments, ordered by the index they are named after,
+// which is the order of the assertions of the chunk api.
+fn groth16_intermediate_values_in_order<'a, V: 'a>(
+    commitments: impl IntoIterator<Item = (&'a CommitmentMessageId, &'a V)>,
+) -> Vec<&'a V> {
+    let mut values = commitments
+        .into_iter()
+        .filter_map(|(message_id, value)| match message_id {
+            CommitmentMessageId::Groth16IntermediateValues((name, _)) => {
+                Some((name.parse::<u32>().unwrap(), value))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    values.sort_by_key(|(index, _)| *index);
+    values.into_iter().map(|(_, value)| value).collect()
+}
+
+pub fn groth16_chunk_secret_keys(
+    commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
+    num_pubs: usize,
+) -> ChunkSecretKeys {
+    ChunkSecretKeys::from_ordered(
+        num_pubs,
+        groth16_intermediate_values_in_order(commitment_secrets)
+            .into_iter()
+            .map(|secret| secret.secret_key.clone())
+            .collect(),
+    )
+}
+
+pub fn groth16_chunk_public_keys(
+    commitment_public_keys: &BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
+    num_pubs: usize,
+) -> ChunkPublicKeys {
+    ChunkPublicKeys::from_ordered(
+        num_pubs,
+        groth16_intermediate_values_in_order(commitment_public_keys)
+            .into_iter()
+            .map(|public_key| public_key.public_key.clone())
+            .collect(),
+    )
+}
+
+const COMMITMENT_SEED_TAG: &[u8] = b"BitVM/bridge/commitment-seed";
+const COMMITMENT_SECRET_LENGTH: usize = 20;
+
+// Master seed from which all the commitment secrets of an operator are derived.
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct CommitmentSeed([u8; 32]);
+
+impl CommitmentSeed {
+    pub fn from_bytes(seed: [u8; 32]) -> Self {
+        CommitmentSeed(seed)
+    }
+
+    // The operator key is the seed that is already backed up, the commitment seed is derived from
+    // it with a domain separated hash so that it is not the signing key itself.
+    pub fn from_operator_keypair(operator_keypair: &Keypair) -> Self {
+        let mut engine = HmacEngine::<sha256::Hash>::new(COMMITMENT_SEED_TAG);
+        engine.input(&operator_keypair.secret_bytes());
+        CommitmentSeed(Hmac::<sha256::Hash>::from_engine(engine).to_byte_array())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::collections::{BTreeMap, HashMap};
+
+    use ark_bn254::G1Affine;
+    use ark_ec::AffineRepr;
+    use bitcoin::{key::Keypair, secp256k1::Secp256k1};
+    use bitvm::{
+        chunk::api::{
+            num_assertions, num_hash, num_pubs, type_conversion_utils::RawProof, NUM_U256,
+        },
+        signatures::{signing_winternitz::WinternitzPublicKey, HASH_LEN},
+    };
 
-    use crate::serialization::{deserialize, serialize};
+    use crate::{
+        common::ZkProofVerifyingKey,
+        serialization::{deserialize, serialize},
+    };
 
-    use super::CommitmentMessageId;
+    use super::{
+        groth16_chunk_public_keys, groth16_chunk_secret_keys, CommitmentMessageId, CommitmentSeed,
+    };
 
     #[test]
     fn test_commitment_message_id_serialization() {
@@ -160,4 +297,82 @@ mod tests {
         let deserialized_messages = deserialize::<HashMap<CommitmentMessageId, &str>>(&json);
         assert_eq!(messages, deserialized_messages);
     }
+
+    #[test]
+    fn test_derive_commitment_secrets() {
+        let keypair = Keypair::from_seckey_slice(&Secp256k1::new(), &[7; 32]).unwrap();
+        let seed = CommitmentSeed::from_operator_keypair(&keypair);
+
+        let vk = RawProof::default().vk;
+        let secrets = CommitmentMessageId::derive_commitment_secrets(&seed, "graph", &vk);
+        assert_eq!(
+            secrets.len(),
+            CommitmentMessageId::commitment_message_ids(&vk).len()
+        );
+
+        // A restored seed derives the same secrets.
+        let restored_seed = CommitmentSeed::from_operator_keypair(&keypair);
+        assert!(
+            secrets == CommitmentMessageId::derive_commitment_secrets(&restored_seed, "graph", &vk)
+        );
+
+        let start_time = CommitmentMessageId::StartTime.derive_secret(&seed, "graph");
+        let superblock_hash = CommitmentMessageId::SuperblockHash.derive_secret(&seed, "graph");
+        let other_graph = CommitmentMessageId::StartTime.derive_secret(&seed, "other graph");
+        assert!(secrets[&CommitmentMessageId::StartTime] == start_time);
+        assert!(start_time.secret_key != superblock_hash.secret_key);
+        assert!(start_time.secret_key != other_graph.secret_key);
+        assert!(
+            WinternitzPublicKey::from(&start_time).parameters
+                != WinternitzPublicKey::from(&superblock_hash).parameters
+        );
+
+        // The seed is not the operator key itself.
+        assert!(CommitmentSeed::from_bytes(keypair.secret_bytes()).0 != seed.0);
+    }
+
+    // verifying key of a circuit with the given number of public inputs
+    fn verifying_key_with_public_inputs(num_pubs: usize) -> ZkProofVerifyingKey {
+        let mut vk = RawProof::default().vk;
+        vk.gamma_abc_g1.resize(num_pubs + 1, G1Affine::generator());
+        vk
+    }
+
+    #[test]
+    fn test_groth16_chunk_keys() {
+        for num_pubs_of_vk in [1, 3] {
+            let vk = verifying_key_with_public_inputs(num_pubs_of_vk);
+            let keypair = Keypair::from_seckey_slice(&Secp256k1::new(), &[7; 32]).unwrap();
+            let seed = CommitmentSeed::from_operator_keypair(&keypair);
+            let secrets = CommitmentMessageId::derive_commitment_secrets(&seed, "graph", &vk);
+            let public_keys: BTreeMap<CommitmentMessageId, WinternitzPublicKey> = secrets
+                .iter()
+                .map(|(message_id, secret)| (message_id.clone(), WinternitzPublicKey::from(secret)))
+                .collect();
+
+            // one commitment per assertion of the chunk api, plus the five peg-out messages
+            let num_pubs = num_pubs(&vk);
+            assert_eq!(num_pubs, num_pubs_of_vk);
+            assert_eq!(secrets.len(), num_assertions(num_pubs) + 5);
+
+            // the chunk api signs with the keys the commitment public keys are derived from
+            let chunk_secret_keys = groth16_chunk_secret_keys(&secrets, num_pubs);
+            assert_eq!(
+                groth16_chunk_public_keys(&public_keys, num_pubs),
+                chunk_secret_keys.public_keys()
+            );
+            assert_eq!(chunk_secret_keys.public_inputs().len(), num_pubs);
+            let first_hash_id = CommitmentMessageId::Groth16IntermediateValues((
+                (num_pubs + NUM_U256).to_string(),
+                HASH_LEN,
+            ));
+            assert!(
+                chunk_secret_keys.intermediate_hashes()[0] == secrets[&first_hash_id].secret_key
+            );
+            assert_eq!(
+                chunk_secret_keys.intermediate_hashes().len(),
+                num_hash(num_pubs)
+            );
+        }
+    }
 }
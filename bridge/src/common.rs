@@ -1 +1,28 @@
 pub type ZkProofVerifyingKey = ark_groth16::VerifyingKey<ark_bn254::Bn254>;
+
+// Serializes a verifying key as the hex of its compressed encoding.
+pub mod zkproof_verifying_key_hex {
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use bitcoin::hex::{DisplayHex, FromHex};
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    use super::ZkProofVerifyingKey;
+
+    pub fn serialize<S: Serializer>(
+        vk: &ZkProofVerifyingKey,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut bytes = Vec::new();
+        vk.serialize_compressed(&mut bytes)
+            .map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(&bytes.to_lower_hex_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<ZkProofVerifyingKey, D::Error> {
+        let bytes =
+            Vec::<u8>::from_hex(&String::deserialize(deserializer)?).map_err(Error::custom)?;
+        ZkProofVerifyingKey::deserialize_compressed(&*bytes).map_err(Error::custom)
+    }
+}
@@ -18,12 +18,7 @@ use bitvm::signatures::signing_winternitz::{
 };
 
 use super::{
-    super::{
-        constants::{NUM_BLOCKS_PER_2_WEEKS, NUM_BLOCKS_PER_6_HOURS, NUM_BLOCKS_PER_DAY},
-        scripts::*,
-        transactions::base::Input,
-        utils::num_blocks_per_network,
-    },
+    super::{scripts::*, timelock_policy::TimelockPolicy, transactions::base::Input},
     base::*,
 };
 
@@ -44,18 +39,16 @@ impl Connector1 {
         operator_taproot_public_key: &XOnlyPublicKey,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
         commitment_public_keys: &HashMap<CommitmentMessageId, WinternitzPublicKey>,
+        timelock_policy: &TimelockPolicy,
     ) -> Self {
         Connector1 {
             network,
             operator_taproot_public_key: *operator_taproot_public_key,
             n_of_n_taproot_public_key: *n_of_n_taproot_public_key,
             commitment_public_keys: commitment_public_keys.clone(),
-            num_blocks_timelock_leaf_0: num_blocks_per_network(network, NUM_BLOCKS_PER_2_WEEKS),
-            num_blocks_timelock_leaf_1: num_blocks_per_network(
-                network,
-                NUM_BLOCKS_PER_2_WEEKS + NUM_BLOCKS_PER_DAY,
-            ),
-            num_blocks_timelock_leaf_2: num_blocks_per_network(network, NUM_BLOCKS_PER_6_HOURS),
+            num_blocks_timelock_leaf_0: timelock_policy.kick_off_2,
+            num_blocks_timelock_leaf_1: timelock_policy.kick_off_timeout,
+            num_blocks_timelock_leaf_2: timelock_policy.start_time_timeout,
         }
     }
 
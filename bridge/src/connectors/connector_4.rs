@@ -2,11 +2,7 @@ use bitcoin::{Address, Network, PublicKey, ScriptBuf, TxIn};
 use serde::{Deserialize, Serialize};
 
 use super::{
-    super::{
-        scripts::*,
-        transactions::base::Input,
-        {constants::NUM_BLOCKS_PER_2_WEEKS, utils::num_blocks_per_network},
-    },
+    super::{scripts::*, timelock_policy::TimelockPolicy, transactions::base::Input},
     base::*,
 };
 
@@ -18,11 +14,15 @@ pub struct Connector4 {
 }
 
 impl Connector4 {
-    pub fn new(network: Network, operator_public_key: &PublicKey) -> Self {
+    pub fn new(
+        network: Network,
+        operator_public_key: &PublicKey,
+        timelock_policy: &TimelockPolicy,
+    ) -> Self {
         Connector4 {
             network,
             operator_public_key: *operator_public_key,
-            num_blocks_timelock: num_blocks_per_network(network, NUM_BLOCKS_PER_2_WEEKS),
+            num_blocks_timelock: timelock_policy.take_2,
         }
     }
 }
@@ -18,15 +18,15 @@ use serde::{Deserialize, Serialize};
 use crate::{
     commitments::CommitmentMessageId,
     constants::START_TIME_MESSAGE_LENGTH,
-    superblock::{extract_superblock_ts_from_header, SUPERBLOCK_MESSAGE_LENGTH},
+    superblock::{
+        extract_superblock_ts_from_header, SUPERBLOCK_MEASUREMENT_PERIOD_SECONDS,
+        SUPERBLOCK_MESSAGE_LENGTH,
+    },
     utils::{sb_hash_from_bytes, sb_hash_from_nibbles, H256},
 };
 
 use super::{
-    super::{
-        constants::NUM_BLOCKS_PER_3_DAYS, scripts::*, transactions::base::Input,
-        utils::num_blocks_per_network,
-    },
+    super::{scripts::*, timelock_policy::TimelockPolicy, transactions::base::Input},
     base::*,
 };
 
@@ -43,12 +43,13 @@ impl ConnectorB {
         network: Network,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
         commitment_public_keys: &HashMap<CommitmentMessageId, WinternitzPublicKey>,
+        timelock_policy: &TimelockPolicy,
     ) -> Self {
         ConnectorB {
             network,
             n_of_n_taproot_public_key: *n_of_n_taproot_public_key,
             commitment_public_keys: commitment_public_keys.clone(),
-            num_blocks_timelock_1: num_blocks_per_network(network, NUM_BLOCKS_PER_3_DAYS),
+            num_blocks_timelock_1: timelock_policy.take_1,
         }
     }
 
@@ -72,7 +73,6 @@ impl ConnectorB {
     }
 
     fn generate_taproot_leaf_2_script(&self) -> ScriptBuf {
-        const TWO_WEEKS_IN_SECONDS: u32 = 60 * 60 * 24 * 14;
         let superblock_hash_public_key =
             &self.commitment_public_keys[&CommitmentMessageId::SuperblockHash];
         let start_time_public_key = &self.commitment_public_keys[&CommitmentMessageId::StartTime];
@@ -105,7 +105,7 @@ impl ConnectorB {
             OP_GREATERTHAN OP_VERIFY        // Stack: SB' SB'.time start_time | Altstack: SB.hash
 
             // SB'.time < start_time + 2 weeks
-            { TWO_WEEKS_IN_SECONDS } OP_ADD // Stack: SB' SB'.time (start_time + 2 weeks) | Altstack: SB.hash
+            { SUPERBLOCK_MEASUREMENT_PERIOD_SECONDS } OP_ADD // Stack: SB' SB'.time (start_time + 2 weeks) | Altstack: SB.hash
             OP_LESSTHAN OP_VERIFY           // Stack: SB' | Altstack: SB.hash
 
             // Calculate SB' hash
@@ -175,9 +175,7 @@ mod tests {
 
     use bitcoin::{
         block::{Header, Version},
-        BlockHash, CompactTarget,
-        Network::Regtest,
-        TxMerkleNode,
+        BlockHash, CompactTarget, TxMerkleNode,
     };
     use bitcoin_script::script;
     use bitvm::{
@@ -195,8 +193,8 @@ mod tests {
     use crate::{
         constants::START_TIME_MESSAGE_LENGTH,
         superblock::{
-            extract_superblock_ts_from_header, get_start_time_block_number,
-            get_superblock_hash_message, SUPERBLOCK_HASH_MESSAGE_LENGTH, SUPERBLOCK_MESSAGE_LENGTH,
+            extract_superblock_ts_from_header, get_superblock_hash_message,
+            SUPERBLOCK_HASH_MESSAGE_LENGTH, SUPERBLOCK_MESSAGE_LENGTH,
         },
         utils::{sb_hash_from_bytes, sb_hash_from_nibbles, H256},
     };
@@ -224,7 +222,7 @@ mod tests {
         const TWO_WEEKS_IN_SECONDS: u32 = 60 * 60 * 24 * 14;
 
         // Setup test headers appropriately for the verification in the script to pass
-        let start_time = get_start_time_block_number(Regtest);
+        let start_time: u32 = 100;
 
         // Create a baseline header
         let base_header = get_superblock_header();
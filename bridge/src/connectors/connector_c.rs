@@ -5,11 +5,12 @@ use std::{
 
 use crate::{
     client::{
+        data_store::data_store::DataStore,
         files::BRIDGE_DATA_DIRECTORY_NAME,
         memory_cache::{TAPROOT_LOCK_SCRIPTS_CACHE, TAPROOT_SPEND_INFO_CACHE},
     },
-    commitments::CommitmentMessageId,
-    common::ZkProofVerifyingKey,
+    commitments::{groth16_chunk_public_keys, CommitmentMessageId},
+    common::{zkproof_verifying_key_hex, ZkProofVerifyingKey},
     connectors::base::*,
     error::{ChunkerError, Error},
     transactions::base::Input,
@@ -23,20 +24,22 @@ use bitcoin::{
     hex::DisplayHex,
     key::TweakedPublicKey,
     taproot::{ControlBlock, LeafVersion, TaprootBuilder, TaprootSpendInfo},
-    Address, Network, ScriptBuf, TapNodeHash, Transaction, TxIn, XOnlyPublicKey,
+    Address, Network, ScriptBuf, TapNodeHash, Transaction, TxIn, Weight, XOnlyPublicKey,
 };
 use num_traits::ToPrimitive;
 use secp256k1::SECP256K1;
 use serde::{Deserialize, Serialize};
 
 use bitvm::{
-    chunk::api::{
-        api_generate_full_tapscripts, api_generate_partial_script,
-        type_conversion_utils::{
-            script_to_witness, utils_signatures_from_raw_witnesses, utils_typed_pubkey_from_raw,
-            RawProof, RawWitness,
+    chunk::{
+        api::{
+            api_generate_full_tapscripts_with_options, num_pubs,
+            type_conversion_utils::{utils_signatures_from_raw_witnesses, RawProof, RawWitness},
+            validate_assertions_with_options, ChunkOptions, PublicKeys,
         },
-        validate_assertions, PublicKeys,
+        bundle::{verifying_key_hash, DisproveScriptBundle, DisproveScriptBundleError},
+        checkpoint::{ChunkCheckpoint, PassCheckpoint},
+        report::DisproveReport,
     },
     // chunker::{
     //     assigner::BridgeAssigner,
@@ -44,7 +47,7 @@ use bitvm::{
     //     common::RawWitness,
     //     disprove_execution::{disprove_exec, RawProof},
     // },
-    signatures::{signing_winternitz::WinternitzPublicKey, winternitz},
+    signatures::signing_winternitz::WinternitzPublicKey,
 };
 use zstd::DEFAULT_COMPRESSION_LEVEL;
 
@@ -59,7 +62,10 @@ pub struct DisproveLeaf {
 }
 
 const CACHE_DIRECTORY_NAME: &str = "cache";
+const CHUNK_CHECKPOINT_DIRECTORY_NAME: &str = "chunk_checkpoint";
+const DISPROVE_REPORT_DIRECTORY_NAME: &str = "disprove_reports";
 const LOCK_SCRIPTS_FILE_PREFIX: &str = "lock_scripts_";
+const DISPROVE_SCRIPT_BUNDLE_FILE_PREFIX: &str = "disprove_scripts_";
 const MAX_CACHE_FILES: u32 = 90; //~1GB in total, based on lock scripts cache being 11MB each
 
 fn get_lock_scripts_cache_path(cache_id: &str) -> PathBuf {
@@ -69,11 +75,74 @@ fn get_lock_scripts_cache_path(cache_id: &str) -> PathBuf {
         .join(lock_scripts_file_name)
 }
 
+pub fn get_disprove_script_bundle_file_name(vk: &ZkProofVerifyingKey) -> String {
+    format!(
+        "{DISPROVE_SCRIPT_BUNDLE_FILE_PREFIX}{}.bin",
+        verifying_key_hash(vk).to_lower_hex_string()
+    )
+}
+
+// The partial scripts only depend upon the verifying key, so they are kept apart from the lock
+// scripts of each operator and are not subject to the cache cleanup.
+pub fn get_disprove_script_bundle_path(vk: &ZkProofVerifyingKey) -> PathBuf {
+    Path::new(BRIDGE_DATA_DIRECTORY_NAME)
+        .join(CACHE_DIRECTORY_NAME)
+        .join(get_disprove_script_bundle_file_name(vk))
+}
+
+// Bundles are shared by every client using the verifying key, whatever the networks and n-of-n
+// of its graphs, so they are not stored under the remote path of the client data.
+pub fn get_disprove_script_bundle_remote_path() -> String {
+    format!("{BRIDGE_DATA_DIRECTORY_NAME}/{CACHE_DIRECTORY_NAME}")
+}
+
+// Partial results of the script generation and validation, so an interrupted run resumes
+// where it stopped instead of starting over
+fn get_chunk_checkpoint() -> ChunkCheckpoint {
+    ChunkCheckpoint::new(
+        Path::new(BRIDGE_DATA_DIRECTORY_NAME)
+            .join(CACHE_DIRECTORY_NAME)
+            .join(CHUNK_CHECKPOINT_DIRECTORY_NAME),
+    )
+}
+
+// Only the pass that is no longer needed is removed, as others may be resumed by another run
+// sharing the directory.
+fn clear_chunk_checkpoint_pass(pass: &PassCheckpoint) {
+    pass.clear()
+        .inspect_err(|e| eprintln!("Failed to clear chunk checkpoint: {}", e))
+        .ok();
+}
+
+// Keeps the report explaining why the assertions of a peg-out graph were disproven, along with
+// the weight of the disprove transaction spending the failing tapscript.
+pub fn save_disprove_report(
+    peg_out_graph_id: &str,
+    report: &DisproveReport,
+    disprove_tx_weight: Weight,
+) -> std::io::Result<PathBuf> {
+    let directory = Path::new(BRIDGE_DATA_DIRECTORY_NAME).join(DISPROVE_REPORT_DIRECTORY_NAME);
+    let file_path = directory.join(format!("{peg_out_graph_id}.json"));
+    let mut json = serde_json::to_value(report)?;
+    json["disprove_tx_weight"] = disprove_tx_weight.to_wu().into();
+    std::fs::create_dir_all(&directory)?;
+    std::fs::write(&file_path, serde_json::to_string_pretty(&json)?)?;
+    Ok(file_path)
+}
+
+// Graphs created before the verifying key was kept in the connector had their scripts generated
+// for the mock verifying key.
+fn default_verifying_key() -> ZkProofVerifyingKey {
+    RawProof::default().vk
+}
+
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct ConnectorC {
     pub network: Network,
     pub operator_taproot_public_key: XOnlyPublicKey,
     commitment_public_keys: BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
+    #[serde(with = "zkproof_verifying_key_hex", default = "default_verifying_key")]
+    verifying_key: ZkProofVerifyingKey,
 }
 
 impl ConnectorC {
@@ -81,6 +150,7 @@ impl ConnectorC {
         network: Network,
         operator_taproot_public_key: &XOnlyPublicKey,
         commitment_public_keys: &BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
+        verifying_key: &ZkProofVerifyingKey,
     ) -> Self {
         assert!(
             !commitment_public_keys.is_empty(),
@@ -90,50 +160,38 @@ impl ConnectorC {
             network,
             operator_taproot_public_key: *operator_taproot_public_key,
             commitment_public_keys: commitment_public_keys.clone(),
+            verifying_key: verifying_key.clone(),
         }
     }
 
+    pub fn verifying_key(&self) -> &ZkProofVerifyingKey {
+        &self.verifying_key
+    }
+
     pub fn generate_disprove_witness(
         &self,
         commit_1_witness: Vec<RawWitness>,
         commit_2_witness: Vec<RawWitness>,
-        vk: &ZkProofVerifyingKey,
-    ) -> Result<(usize, RawWitness), Error> {
+    ) -> Result<DisproveReport, Error> {
         println!("Generating disprove witness ...");
-        let mut sorted_pks: Vec<(u32, WinternitzPublicKey)> = vec![];
-        self.commitment_public_keys
-            .clone()
-            .into_iter()
-            .for_each(|(k, v)| {
-                if let CommitmentMessageId::Groth16IntermediateValues((name, _)) = k {
-                    let index = u32::from_str_radix(&name, 10).unwrap();
-                    sorted_pks.push((index, v));
-                }
-            });
-        sorted_pks.sort_by(|a, b| a.0.cmp(&b.0));
-        let sorted_pks = sorted_pks
-            .iter()
-            .map(|f| &f.1.public_key)
-            .collect::<Vec<&winternitz::PublicKey>>();
-
         let mut commit_witness = commit_1_witness.clone();
         commit_witness.extend_from_slice(&commit_2_witness);
 
-        let sigs = utils_signatures_from_raw_witnesses(&commit_witness);
-        let pubs = utils_typed_pubkey_from_raw(sorted_pks);
+        let vk = &self.verifying_key;
+        let num_pubs = num_pubs(vk);
+        let sigs = utils_signatures_from_raw_witnesses(&commit_witness, num_pubs);
+        let pubs = groth16_chunk_public_keys(&self.commitment_public_keys, num_pubs).into();
         let locs: Vec<ScriptBuf> = self
             .lock_scripts_bytes()
             .into_iter()
             .map(|f| ScriptBuf::from_bytes(f))
             .collect();
-        let locs = locs.try_into().unwrap();
-        let exec_res = validate_assertions(vk, sigs, pubs, &locs);
-        if exec_res.is_some() {
-            let exec_res = exec_res.unwrap();
-            let wit: RawWitness = script_to_witness(exec_res.1);
-            return Ok((exec_res.0, wit));
-        }
-        return Err(Error::Chunker(ChunkerError::ValidProof));
+        let checkpoint = get_chunk_checkpoint();
+        let validation_pass = checkpoint.validation_pass(&sigs, &locs);
+        let options = ChunkOptions::with_checkpoint(&checkpoint);
+        let exec_res = validate_assertions_with_options(vk, sigs, pubs, &locs, options);
+        clear_chunk_checkpoint_pass(&validation_pass);
+        exec_res.ok_or(Error::Chunker(ChunkerError::ValidProof))
     }
 
     pub fn taproot_merkle_root(&self) -> Option<TapNodeHash> {
@@ -149,7 +207,11 @@ impl ConnectorC {
     }
 
     pub fn taproot_script_and_control_block(&self, leaf_index: usize) -> (ScriptBuf, ControlBlock) {
-        let cache_id = lock_script_cache_id(&self.commitment_public_keys, leaf_index);
+        let cache_id = lock_script_cache_id(
+            &self.commitment_public_keys,
+            &self.verifying_key,
+            leaf_index,
+        );
         let cache = TAPROOT_LOCK_SCRIPTS_CACHE
             .write()
             .unwrap()
@@ -180,7 +242,7 @@ impl ConnectorC {
     }
 
     fn taproot_spend_info_cached(&self) -> TaprootSpendInfoCacheEntry {
-        let cache_id = spend_info_cache_id(&self.commitment_public_keys);
+        let cache_id = spend_info_cache_id(&self.commitment_public_keys, &self.verifying_key);
         TAPROOT_SPEND_INFO_CACHE
             .write()
             .unwrap()
@@ -196,7 +258,7 @@ impl ConnectorC {
     }
 
     fn lock_scripts_bytes(&self) -> Vec<Vec<u8>> {
-        let cache_id = spend_info_cache_id(&self.commitment_public_keys);
+        let cache_id = spend_info_cache_id(&self.commitment_public_keys, &self.verifying_key);
         let file_path = get_lock_scripts_cache_path(&cache_id);
         let lock_scripts_bytes = read_disk_cache(&file_path)
             .inspect_err(|e| {
@@ -207,12 +269,13 @@ impl ConnectorC {
                     );
                 }
             })
-            .unwrap_or_else(|_| generate_assert_leaves(&self.commitment_public_keys));
-        if !file_path.exists() {
-            write_disk_cache(&file_path, &lock_scripts_bytes)
-                .inspect_err(|e| eprintln!("Failed to write lock scripts cache to disk: {}", e))
-                .ok();
-        }
+            .unwrap_or_else(|_| {
+                generate_assert_leaves(
+                    &self.commitment_public_keys,
+                    &self.verifying_key,
+                    &file_path,
+                )
+            });
         cleanup_cache_files(
             LOCK_SCRIPTS_FILE_PREFIX,
             file_path.parent().unwrap(),
@@ -247,32 +310,39 @@ impl TaprootConnector for ConnectorC {
     }
 }
 
-fn first_winternitz_public_key_bytes(
+// The lock scripts depend upon the commitment public keys and the verifying key the disprove
+// scripts check.
+fn lock_scripts_cache_key_bytes(
     commitment_public_keys: &BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
+    vk: &ZkProofVerifyingKey,
 ) -> Vec<u8> {
     let (_, first_winternitz_public_key) = commitment_public_keys
         .iter()
         .next()
         .expect("commitment_public_keys should not be empty");
-    first_winternitz_public_key
+    let mut bytes = first_winternitz_public_key
         .public_key
         .as_flattened()
-        .to_vec()
+        .to_vec();
+    bytes.extend_from_slice(&verifying_key_hash(vk));
+    bytes
 }
 
 fn spend_info_cache_id(
     commitment_public_keys: &BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
+    vk: &ZkProofVerifyingKey,
 ) -> String {
-    let bytes = first_winternitz_public_key_bytes(commitment_public_keys);
+    let bytes = lock_scripts_cache_key_bytes(commitment_public_keys, vk);
     let hash = hash160::Hash::hash(&bytes);
     hash.as_byte_array().to_lower_hex_string()
 }
 
 fn lock_script_cache_id(
     commitment_public_keys: &BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
+    vk: &ZkProofVerifyingKey,
     leaf_index: usize,
 ) -> String {
-    let mut bytes = first_winternitz_public_key_bytes(commitment_public_keys);
+    let mut bytes = lock_scripts_cache_key_bytes(commitment_public_keys, vk);
     bytes.append(leaf_index.to_be_bytes().to_vec().as_mut());
     let hash = hash160::Hash::hash(&bytes);
     hash.as_byte_array().to_lower_hex_string()
@@ -306,33 +376,102 @@ fn generate_taproot_spend_info(
         .expect("Unable to finalize assert transaction connector c taproot")
 }
 
+// Writes the lock scripts to the cache at `file_path`, after which the results of the
+// tapscripts pass are no longer needed.
 fn generate_assert_leaves(
     commits_public_keys: &BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
+    vk: &ZkProofVerifyingKey,
+    file_path: &Path,
 ) -> Vec<Vec<u8>> {
     println!("Generating new lock scripts...");
-    // hash map to btree map
-    let mut sorted_pks: Vec<(u32, WinternitzPublicKey)> = vec![];
-    commits_public_keys.clone().into_iter().for_each(|(k, v)| {
-        if let CommitmentMessageId::Groth16IntermediateValues((name, _)) = k {
-            let index = u32::from_str_radix(&name, 10).unwrap();
-            sorted_pks.push((index, v));
-        }
-    });
-
-    sorted_pks.sort_by(|a, b| a.0.cmp(&b.0));
-    let sorted_pks = sorted_pks
-        .iter()
-        .map(|f| &f.1.public_key)
-        .collect::<Vec<&winternitz::PublicKey>>();
-
-    let default_proof = RawProof::default(); // mock a default proof to generate scripts
-    let partial_scripts = api_generate_partial_script(&default_proof.vk);
-    let pks: PublicKeys = utils_typed_pubkey_from_raw(sorted_pks);
-    let locks = api_generate_full_tapscripts(pks, &partial_scripts);
-    let locks = locks.into_iter().map(|f| f.into_bytes()).collect();
+    let checkpoint = get_chunk_checkpoint();
+    let partial_scripts = load_disprove_script_bundle(vk, &checkpoint);
+    let pks: PublicKeys = groth16_chunk_public_keys(commits_public_keys, num_pubs(vk)).into();
+    let tapscripts_pass = checkpoint.tapscripts_pass(&pks, &partial_scripts);
+    let options = ChunkOptions::with_checkpoint(&checkpoint);
+    let locks = api_generate_full_tapscripts_with_options(pks, &partial_scripts, options);
+    let locks: Vec<Vec<u8>> = locks.into_iter().map(|f| f.into_bytes()).collect();
+    write_disk_cache(file_path, &locks)
+        .inspect_err(|e| eprintln!("Failed to write lock scripts cache to disk: {}", e))
+        .map(|_| clear_chunk_checkpoint_pass(&tapscripts_pass))
+        .ok();
     locks
 }
 
+fn read_disprove_script_bundle(
+    file_path: &Path,
+    vk: &ZkProofVerifyingKey,
+) -> Option<DisproveScriptBundle> {
+    match DisproveScriptBundle::read_from_file(file_path, vk) {
+        Ok(bundle) => return Some(bundle),
+        Err(DisproveScriptBundleError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => eprintln!(
+            "Ignoring disprove script bundle {}: {}",
+            file_path.display(),
+            e
+        ),
+    }
+    None
+}
+
+/*
+  Loads the disprove script bundle of `vk` from the data store, falling back to the bundle on
+  disk. A bundle fetched from the data store is written to disk, where the lock scripts
+  generation picks it up instead of generating the partial scripts again.
+*/
+pub async fn fetch_disprove_script_bundle(
+    data_store: &DataStore,
+    vk: &ZkProofVerifyingKey,
+) -> Option<DisproveScriptBundle> {
+    let file_name = get_disprove_script_bundle_file_name(vk);
+    let file_path = get_disprove_script_bundle_path(vk);
+    match data_store
+        .fetch_compressed_data_by_key(&file_name, Some(&get_disprove_script_bundle_remote_path()))
+        .await
+    {
+        Ok((Some(bytes), _)) => match DisproveScriptBundle::from_bytes(&bytes, vk) {
+            Ok(bundle) => {
+                bundle
+                    .write_to_file(&file_path)
+                    .inspect_err(|e| {
+                        eprintln!("Failed to write disprove script bundle to disk: {}", e)
+                    })
+                    .ok();
+                return Some(bundle);
+            }
+            Err(e) => eprintln!(
+                "Ignoring disprove script bundle {} from the data store: {}",
+                file_name, e
+            ),
+        },
+        Ok((None, _)) => {}
+        Err(e) => eprintln!(
+            "Failed to fetch disprove script bundle from the data store: {}",
+            e
+        ),
+    }
+    read_disprove_script_bundle(&file_path, vk)
+}
+
+fn load_disprove_script_bundle(
+    vk: &ZkProofVerifyingKey,
+    checkpoint: &ChunkCheckpoint,
+) -> Vec<ScriptBuf> {
+    let file_path = get_disprove_script_bundle_path(vk);
+    if let Some(bundle) = read_disprove_script_bundle(&file_path, vk) {
+        return bundle.into_partial_scripts();
+    }
+    println!("Generating new disprove script bundle...");
+    let bundle =
+        DisproveScriptBundle::generate_with_options(vk, ChunkOptions::with_checkpoint(checkpoint));
+    bundle
+        .write_to_file(&file_path)
+        .inspect_err(|e| eprintln!("Failed to write disprove script bundle to disk: {}", e))
+        .map(|_| clear_chunk_checkpoint_pass(&checkpoint.partial_scripts_pass(vk)))
+        .ok();
+    bundle.into_partial_scripts()
+}
+
 pub fn get_commit_from_assert_commit_tx(assert_commit_tx: &Transaction) -> Vec<RawWitness> {
     let mut assert_commit_witness = Vec::new();
     for input in assert_commit_tx.input.iter() {
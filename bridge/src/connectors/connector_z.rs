@@ -1,4 +1,4 @@
-use crate::{constants::NUM_BLOCKS_PER_2_WEEKS, utils::num_blocks_per_network};
+use crate::timelock_policy::TimelockPolicy;
 use bitcoin::{
     taproot::{TaprootBuilder, TaprootSpendInfo},
     Address, Network, ScriptBuf, TxIn, XOnlyPublicKey,
@@ -27,13 +27,14 @@ impl ConnectorZ {
         evm_address: &str,
         depositor_taproot_public_key: &XOnlyPublicKey,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
+        timelock_policy: &TimelockPolicy,
     ) -> Self {
         ConnectorZ {
             network,
             depositor_taproot_public_key: *depositor_taproot_public_key,
             n_of_n_taproot_public_key: *n_of_n_taproot_public_key,
             evm_address: evm_address.to_string(),
-            num_blocks_timelock_0: num_blocks_per_network(network, NUM_BLOCKS_PER_2_WEEKS),
+            num_blocks_timelock_0: timelock_policy.peg_in_refund,
         }
     }
 
@@ -1,7 +1,7 @@
 use super::commitments::CommitmentMessageId;
 use super::graphs::base::GraphId;
 use super::transactions::{base::BaseTransaction, pre_signed::PreSignedTransaction};
-use bitcoin::{PublicKey, Txid};
+use bitcoin::{Amount, OutPoint, PublicKey, Txid};
 use std::fmt::{self, Display};
 
 #[derive(Debug)]
@@ -11,6 +11,8 @@ pub enum ClientError {
     ZkProofVerifyingKeyNotDefined,
     PegInGraphNotFound(GraphId),
     PegOutGraphNotFound(GraphId),
+    CommitmentSecretsMismatch(GraphId), // secrets derived for the graph do not match its public keys
+    VerifyingKeyMismatch(GraphId),      // disprove scripts of the graph check another verifying key
 }
 
 #[derive(Debug)]
@@ -36,6 +38,11 @@ pub enum GraphError {
     PrecedingTxNotConfirmed(Vec<NamedTx>),
     PrecedingTxTimelockNotMet(NamedTx),
     WitnessNotGenerated(CommitmentMessageId),
+    InvalidWitness(CommitmentMessageId),
+    SuperblockNotFound,
+    InvalidTimelockPolicy(String),
+    UnexpectedOperator(PublicKey), // operator of a peg-out graph not in the operator set of its peg-in
+    OperatorsMismatch(String),     // graph id of a peg-in whose copies list different operators
 }
 
 #[derive(Debug)]
@@ -61,14 +68,85 @@ pub enum ChunkerError {
 }
 
 #[derive(Debug)]
-pub enum Error {
+pub enum BitcoinBackendError {
     Esplora(esplora_client::Error),
+    Rpc(String),
+    TransactionRejected(Txid, String), // txid: the rejected transaction id, string: rejection reason
+    BlockNotFound(u32),                // height of the missing block
+}
+
+impl fmt::Display for BitcoinBackendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+// Txid and input index of the input the nonce is for.
+#[derive(Debug, PartialEq, Eq)]
+pub enum NonceError {
+    NonceNotPublished(Txid, usize),
+    SecretNonceNotFound(Txid, usize), // never generated, or consumed and deleted
+    NonceMismatch(Txid, usize), // the secret nonce is not the published one, e.g. it was regenerated
+    NonceAlreadyConsumed(Txid, usize),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum KeystoreError {
+    UnsupportedVersion(u32),
+    InvalidFormat(String),
+    KeyDerivationFailed(String),
+    EncryptionFailed,
+    DecryptionFailed, // wrong passphrase or tampered keystore
+    PassphraseNotSet, // KEYSTORE_PASSPHRASE_ENV_VAR is not set
+}
+
+impl fmt::Display for KeystoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProofError {
+    ProofNotFound(String), // path of the proof file
+    ProofProviderNotDefined,
+    InvalidFormat(String),
+    VerifyingKeyNotDefined,
+    VerifyingKeyMismatch,
+    InvalidProof,
+}
+
+impl fmt::Display for ProofError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum FeeBumpError {
+    UnspendableOutput(OutPoint), // neither an anchor nor paying to the key of the fee bumper
+    InsufficientFunds(Amount),   // amount missing to reach the target fee rate
+}
+
+impl fmt::Display for FeeBumpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    BitcoinBackend(BitcoinBackendError),
     Client(ClientError),
     Graph(GraphError),
     Transaction(TransactionError),
     L2(L2Error),
     Chunker(ChunkerError),
     Validation(ValidationError),
+    Nonce(NonceError),
+    Proof(ProofError),
+    FeeBump(FeeBumpError),
+    Keystore(KeystoreError),
     Other(String),
 }
 
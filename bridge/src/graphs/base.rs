@@ -4,23 +4,24 @@ use bitcoin::{
     policy::{DEFAULT_MIN_RELAY_TX_FEE, DUST_RELAY_TX_FEE},
     Network, Transaction, Txid,
 };
-use esplora_client::{AsyncClient, TxStatus};
 use futures::future::join_all;
 use musig2::SecNonce;
 
 use crate::{
+    client::bitcoin_backend::bitcoin_backend::{BitcoinBackend, TxStatus},
     contexts::verifier::VerifierContext,
-    error::{Error, TransactionError},
-    transactions::base::{
-        MIN_RELAY_FEE_ASSERT_COMMIT1, MIN_RELAY_FEE_ASSERT_COMMIT2, MIN_RELAY_FEE_ASSERT_FINAL,
-        MIN_RELAY_FEE_ASSERT_INITIAL, MIN_RELAY_FEE_DISPROVE, MIN_RELAY_FEE_KICK_OFF_1,
-        MIN_RELAY_FEE_KICK_OFF_2, MIN_RELAY_FEE_PEG_IN_CONFIRM, MIN_RELAY_FEE_PEG_IN_DEPOSIT,
-        MIN_RELAY_FEE_PEG_IN_REFUND, MIN_RELAY_FEE_PEG_OUT_CONFIRM, MIN_RELAY_FEE_START_TIME,
+    error::{BitcoinBackendError, Error, TransactionError},
+    transactions::{
+        base::{
+            MIN_RELAY_FEE_ASSERT_COMMIT1, MIN_RELAY_FEE_ASSERT_COMMIT2, MIN_RELAY_FEE_ASSERT_FINAL,
+            MIN_RELAY_FEE_ASSERT_INITIAL, MIN_RELAY_FEE_DISPROVE, MIN_RELAY_FEE_KICK_OFF_1,
+            MIN_RELAY_FEE_KICK_OFF_2, MIN_RELAY_FEE_PEG_IN_CONFIRM, MIN_RELAY_FEE_PEG_IN_DEPOSIT,
+            MIN_RELAY_FEE_PEG_IN_REFUND, MIN_RELAY_FEE_PEG_OUT_CONFIRM, MIN_RELAY_FEE_START_TIME,
+        },
+        pre_signed_musig2::PreSignedMusig2Transaction,
     },
 };
 
-pub const NUM_REQUIRED_OPERATORS: usize = 1;
-
 pub const GRAPH_VERSION: &str = "0.1";
 
 //1 btc
@@ -29,6 +30,10 @@ pub const CROWDFUNDING_AMOUNT: f64 = 1.0;
 // 67 = (32 + 4 + 1 + (107 / WITNESS_SCALE_FACTOR) + 4) for segwit TxOut
 // TODO: Use lower dust amount for other txout types
 pub const DUST_AMOUNT: u64 = (43 + 67) * DUST_RELAY_FEE_RATE;
+// 13 = pay-to-anchor TxOut, spent with an empty witness
+pub const ANCHOR_AMOUNT: u64 = (13 + 67) * DUST_RELAY_FEE_RATE;
+// kick off 1, kick off 2 and the four assert transactions carry an anchor output
+pub const NUM_ANCHOR_OUTPUTS: u64 = 6;
 pub const MIN_RELAY_FEE_RATE: u64 = (DEFAULT_MIN_RELAY_TX_FEE / 1000) as u64;
 pub const DUST_RELAY_FEE_RATE: u64 = (DUST_RELAY_TX_FEE / 1000) as u64;
 
@@ -48,7 +53,8 @@ pub const PEG_OUT_FEE: u64 = MIN_RELAY_FEE_PEG_OUT_CONFIRM // depth 0
     + MIN_RELAY_FEE_START_TIME // include START_TIME tx, spent in kickoff 1
     + MIN_RELAY_FEE_KICK_OFF_2 // depth 2
     + MIN_RELAY_FEE_ASSERT_SET // depth 3
-    + MIN_RELAY_FEE_DISPROVE; // depth 4
+    + MIN_RELAY_FEE_DISPROVE // depth 4
+    + ANCHOR_AMOUNT * NUM_ANCHOR_OUTPUTS;
 pub const PEG_IN_FEE: u64 =
     MIN_RELAY_FEE_PEG_IN_DEPOSIT + max(MIN_RELAY_FEE_PEG_IN_CONFIRM, MIN_RELAY_FEE_PEG_IN_REFUND);
 
@@ -66,34 +72,45 @@ pub trait BaseGraph {
         verifier_context: &VerifierContext,
         secret_nonces: &HashMap<Txid, HashMap<usize, SecNonce>>,
     );
+    fn musig2_transactions(&self) -> Vec<&dyn PreSignedMusig2Transaction>;
 }
 
 pub const fn max(a: u64, b: u64) -> u64 {
     [a, b][(a < b) as usize]
 }
 
-pub async fn get_block_height(client: &AsyncClient) -> Result<u32, Error> {
+// Relative timelocks are checked against the next block: a transaction spending an output
+// confirmed at `block_height` with a `num_blocks` timelock can be broadcast once the tip is at
+// `block_height + num_blocks - 1`.
+pub fn is_timelock_expired(block_height: u32, num_blocks: u32, tip_height: u32) -> bool {
+    block_height + num_blocks <= tip_height + 1
+}
+
+pub async fn get_block_height(client: &dyn BitcoinBackend) -> Result<u32, Error> {
     match client.get_height().await {
         Ok(height) => Ok(height),
-        Err(e) => Err(Error::Esplora(e)),
+        Err(e) => Err(Error::BitcoinBackend(e)),
     }
 }
 
-pub async fn verify_if_not_mined(client: &AsyncClient, txid: Txid) -> Result<(), Error> {
+pub async fn verify_if_not_mined(client: &dyn BitcoinBackend, txid: Txid) -> Result<(), Error> {
     match is_confirmed(client, txid).await {
         Ok(false) => Ok(()),
         Ok(true) => Err(Error::Transaction(TransactionError::AlreadyMined(txid))),
-        Err(e) => Err(Error::Esplora(e)),
+        Err(e) => Err(Error::BitcoinBackend(e)),
     }
 }
 
-pub async fn is_confirmed(client: &AsyncClient, txid: Txid) -> Result<bool, esplora_client::Error> {
+pub async fn is_confirmed(
+    client: &dyn BitcoinBackend,
+    txid: Txid,
+) -> Result<bool, BitcoinBackendError> {
     let tx_status = client.get_tx_status(&txid).await;
     tx_status.map(|x| x.confirmed)
 }
 
 pub async fn broadcast_and_verify(
-    client: &AsyncClient,
+    client: &dyn BitcoinBackend,
     transaction: &Transaction,
 ) -> Result<&'static str, Error> {
     let txid = transaction.compute_txid();
@@ -107,20 +124,20 @@ pub async fn broadcast_and_verify(
     match (tx_result, is_confirmed(client, txid).await) {
         (Ok(_), Ok(false)) | (Ok(_), Err(_)) => Ok("Tx broadcasted successfully."),
         (Ok(_), Ok(true)) | (Err(_), Ok(true)) => Ok("Tx mined successfully."),
-        (Err(e), _) => Err(Error::Esplora(e)),
+        (Err(e), _) => Err(Error::BitcoinBackend(e)),
     }
 }
 
 pub async fn get_tx_statuses(
-    client: &AsyncClient,
+    client: &dyn BitcoinBackend,
     txids: &[Txid],
-) -> Vec<Result<TxStatus, esplora_client::Error>> {
+) -> Vec<Result<TxStatus, BitcoinBackendError>> {
     join_all(txids.iter().map(|txid| client.get_tx_status(txid))).await
 }
 
 pub async fn get_onchain_txs(
-    client: &AsyncClient,
+    client: &dyn BitcoinBackend,
     txids: &[Txid],
-) -> Vec<Result<Option<Transaction>, esplora_client::Error>> {
+) -> Vec<Result<Option<Transaction>, BitcoinBackendError>> {
     join_all(txids.iter().map(|txid| client.get_tx(txid))).await
 }
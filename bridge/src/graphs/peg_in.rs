@@ -2,19 +2,19 @@ use bitcoin::{
     hex::{Case::Upper, DisplayHex},
     Network, OutPoint, PublicKey, Transaction, Txid, XOnlyPublicKey,
 };
-use esplora_client::{AsyncClient, TxStatus};
 use itertools::Itertools;
 use musig2::SecNonce;
 use num_traits::ToPrimitive;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 use crate::{
-    client::sdk::{
-        query::GraphCliQuery, query_contexts::depositor_signatures::DepositorSignatures,
+    client::{
+        bitcoin_backend::bitcoin_backend::{BitcoinBackend, TxStatus},
+        sdk::{query::GraphCliQuery, query_contexts::depositor_signatures::DepositorSignatures},
     },
-    error::{Error, GraphError, NamedTx},
+    error::{BitcoinBackendError, Error, GraphError, NamedTx},
     transactions::pre_signed_musig2::PreSignedMusig2Transaction,
 };
 
@@ -22,6 +22,7 @@ use super::{
     super::{
         connectors::{connector_0::Connector0, connector_z::ConnectorZ},
         contexts::{depositor::DepositorContext, verifier::VerifierContext},
+        timelock_policy::TimelockPolicy,
         transactions::{
             base::{validate_transaction, verify_public_nonces_for_tx, BaseTransaction, Input},
             peg_in_confirm::PegInConfirmTransaction,
@@ -31,8 +32,8 @@ use super::{
         },
     },
     base::{
-        get_tx_statuses, verify_if_not_mined, BaseGraph, GraphId, GRAPH_VERSION,
-        NUM_REQUIRED_OPERATORS,
+        get_tx_statuses, is_timelock_expired, verify_if_not_mined, BaseGraph, GraphId,
+        GRAPH_VERSION,
     },
     peg_out::{PegOutGraph, PegOutId},
 };
@@ -56,7 +57,7 @@ pub enum PegInVerifierStatus {
     #[display("Peg-in deposit transaction not confirmed yet. Wait...")]
     AwaitingDeposit, // no action required, wait
     #[display("No peg-out graph available yet. Wait...")]
-    AwaitingPegOutCreation, // need operator(s) to come online to create peg-out grah
+    AwaitingPegOutCreation, // need every operator of the peg-in to come online and create its peg-out graph
     #[display("Nonce required. Share nonce?")]
     PendingOurNonces(Vec<GraphId>), // the given verifier needs to submit nonces
     #[display("Awaiting nonces. Wait...")]
@@ -104,6 +105,17 @@ pub struct PegInGraph {
     depositor_taproot_public_key: XOnlyPublicKey,
     pub depositor_evm_address: String,
 
+    // Operators that each create a peg-out graph spending the peg-in confirm. The peg-in is only
+    // confirmed once all of them did. Empty for a peg-in any single operator can serve, as are
+    // graphs created before the operators were recorded.
+    #[serde(default)]
+    operator_public_keys: Vec<PublicKey>,
+
+    // None for graphs created before the policy was recorded, their timelocks are those of
+    // TimelockPolicy::for_network.
+    #[serde(default)]
+    timelock_policy: Option<TimelockPolicy>,
+
     connector_0: Connector0,
     connector_z: ConnectorZ,
 
@@ -142,15 +154,26 @@ impl BaseGraph for PegInGraph {
         )]
         .into()
     }
+
+    fn musig2_transactions(&self) -> Vec<&dyn PreSignedMusig2Transaction> {
+        vec![&self.peg_in_confirm_transaction]
+    }
 }
 
 impl PegInGraph {
-    pub fn new(context: &DepositorContext, deposit_input: Input, evm_address: &str) -> Self {
+    pub fn new(
+        context: &DepositorContext,
+        deposit_input: Input,
+        evm_address: &str,
+        operator_public_keys: &[PublicKey],
+        timelock_policy: &TimelockPolicy,
+    ) -> Self {
         let connectors = create_new_connectors(
             context.network,
             &context.n_of_n_taproot_public_key,
             &context.depositor_taproot_public_key,
             evm_address,
+            timelock_policy,
         );
 
         let peg_in_deposit_transaction =
@@ -184,6 +207,8 @@ impl PegInGraph {
             depositor_public_key: context.depositor_public_key,
             depositor_taproot_public_key: context.depositor_taproot_public_key,
             depositor_evm_address: evm_address.to_string(),
+            operator_public_keys: operator_public_keys.to_vec(),
+            timelock_policy: Some(*timelock_policy),
             connector_0: connectors.connector_0,
             connector_z: connectors.connector_z,
             peg_out_graphs: Vec::new(),
@@ -200,6 +225,8 @@ impl PegInGraph {
         n_of_n_taproot_public_key: &XOnlyPublicKey,
         depositor_evm_address: &str,
         deposit_input: Input,
+        operator_public_keys: &[PublicKey],
+        timelock_policy: &TimelockPolicy,
     ) -> Self {
         create_graph_without_signing(
             network,
@@ -210,6 +237,8 @@ impl PegInGraph {
             n_of_n_taproot_public_key,
             depositor_evm_address,
             deposit_input,
+            operator_public_keys,
+            timelock_policy,
         )
     }
 
@@ -224,12 +253,15 @@ impl PegInGraph {
         depositor_evm_address: &str,
         deposit_input: Input,
         signatures: &DepositorSignatures,
+        operator_public_keys: &[PublicKey],
+        timelock_policy: &TimelockPolicy,
     ) -> Self {
         let connectors = create_new_connectors(
             network,
             n_of_n_taproot_public_key,
             depositor_taproot_public_key,
             depositor_evm_address,
+            timelock_policy,
         );
 
         let peg_in_deposit_transaction = PegInDepositTransaction::new_with_signature(
@@ -271,6 +303,8 @@ impl PegInGraph {
             depositor_public_key: *depositor_public_key,
             depositor_taproot_public_key: *depositor_taproot_public_key,
             depositor_evm_address: depositor_evm_address.to_string(),
+            operator_public_keys: operator_public_keys.to_vec(),
+            timelock_policy: Some(*timelock_policy),
             connector_0: connectors.connector_0,
             connector_z: connectors.connector_z,
             peg_out_graphs: Vec::new(),
@@ -290,16 +324,45 @@ impl PegInGraph {
                 outpoint: self.peg_in_deposit_transaction.tx().input[0].previous_output, // Self-referencing
                 amount: self.peg_in_deposit_transaction.prev_outs()[0].value, // Self-referencing
             },
+            &self.operator_public_keys,
+            &self.timelock_policy(),
         )
     }
 
+    pub fn timelock_policy(&self) -> TimelockPolicy {
+        self.timelock_policy
+            .unwrap_or_else(|| TimelockPolicy::for_network(self.network))
+    }
+
+    pub fn operator_public_keys(&self) -> &[PublicKey] {
+        &self.operator_public_keys
+    }
+
+    pub fn accepts_operator(&self, operator_public_key: &PublicKey) -> bool {
+        self.operator_public_keys.is_empty()
+            || self.operator_public_keys.contains(operator_public_key)
+    }
+
+    // Operators of the peg-in that have not created their peg-out graph among `peg_outs` yet.
+    pub fn missing_operators(&self, peg_outs: &[&PegOutGraph]) -> Vec<PublicKey> {
+        self.operator_public_keys
+            .iter()
+            .filter(|operator_public_key| {
+                !peg_outs
+                    .iter()
+                    .any(|peg_out| peg_out.operator_public_key() == *operator_public_key)
+            })
+            .cloned()
+            .collect()
+    }
+
     pub fn peg_in_confirm_transaction_ref(&self) -> &PegInConfirmTransaction {
         &self.peg_in_confirm_transaction
     }
 
     pub async fn verifier_status(
         &self,
-        client: &AsyncClient,
+        client: &dyn BitcoinBackend,
         verifier_context: &VerifierContext,
         peg_outs: &[&PegOutGraph],
     ) -> PegInVerifierStatus {
@@ -318,7 +381,7 @@ impl PegInGraph {
             return PegInVerifierStatus::AwaitingDeposit;
         }
 
-        if peg_outs.len() < NUM_REQUIRED_OPERATORS {
+        if peg_outs.is_empty() || !self.missing_operators(peg_outs).is_empty() {
             return PegInVerifierStatus::AwaitingPegOutCreation;
         }
 
@@ -355,7 +418,7 @@ impl PegInGraph {
         PegInVerifierStatus::ReadyToSubmit
     }
 
-    pub async fn operator_status(&self, client: &AsyncClient) -> PegInOperatorStatus {
+    pub async fn operator_status(&self, client: &dyn BitcoinBackend) -> PegInOperatorStatus {
         let (peg_in_deposit_status, peg_in_confirm_status, _) =
             Self::get_peg_in_statuses(self, client).await;
 
@@ -378,10 +441,10 @@ impl PegInGraph {
 
     pub fn interpret_depositor_status(
         &self,
-        peg_in_deposit_status: &Result<TxStatus, esplora_client::Error>,
-        peg_in_confirm_status: &Result<TxStatus, esplora_client::Error>,
-        peg_in_refund_status: &Result<TxStatus, esplora_client::Error>,
-        blockchain_height: Result<u32, esplora_client::Error>,
+        peg_in_deposit_status: &Result<TxStatus, BitcoinBackendError>,
+        peg_in_confirm_status: &Result<TxStatus, BitcoinBackendError>,
+        peg_in_refund_status: &Result<TxStatus, BitcoinBackendError>,
+        blockchain_height: Result<u32, BitcoinBackendError>,
     ) -> PegInDepositorStatus {
         if peg_in_deposit_status
             .as_ref()
@@ -399,7 +462,11 @@ impl PegInGraph {
                 .block_height
                 .is_some_and(|block_height| {
                     blockchain_height.is_ok_and(|height| {
-                        block_height + self.connector_z.num_blocks_timelock_0 <= height
+                        is_timelock_expired(
+                            block_height,
+                            self.connector_z.num_blocks_timelock_0,
+                            height,
+                        )
                     })
                 })
             {
@@ -423,7 +490,7 @@ impl PegInGraph {
         }
     }
 
-    pub async fn depositor_status(&self, client: &AsyncClient) -> PegInDepositorStatus {
+    pub async fn depositor_status(&self, client: &dyn BitcoinBackend) -> PegInDepositorStatus {
         let tx_statuses = get_tx_statuses(
             client,
             &[
@@ -448,13 +515,13 @@ impl PegInGraph {
         )
     }
 
-    pub async fn deposit(&mut self, client: &AsyncClient) -> Result<Transaction, Error> {
+    pub async fn deposit(&mut self, client: &dyn BitcoinBackend) -> Result<Transaction, Error> {
         let txid = self.peg_in_deposit_transaction.tx().compute_txid();
         verify_if_not_mined(client, txid).await?;
         Ok(self.peg_in_deposit_transaction.finalize())
     }
 
-    pub async fn confirm(&mut self, client: &AsyncClient) -> Result<Transaction, Error> {
+    pub async fn confirm(&mut self, client: &dyn BitcoinBackend) -> Result<Transaction, Error> {
         let txid = self.peg_in_confirm_transaction.tx().compute_txid();
         verify_if_not_mined(client, txid).await?;
 
@@ -468,11 +535,11 @@ impl PegInGraph {
                     NamedTx::for_tx(&self.peg_in_deposit_transaction, status.confirmed),
                 ]))),
             },
-            Err(e) => Err(Error::Esplora(e)),
+            Err(e) => Err(Error::BitcoinBackend(e)),
         }
     }
 
-    pub async fn refund(&mut self, client: &AsyncClient) -> Result<Transaction, Error> {
+    pub async fn refund(&mut self, client: &dyn BitcoinBackend) -> Result<Transaction, Error> {
         let txid = self.peg_in_refund_transaction.tx().compute_txid();
         verify_if_not_mined(client, txid).await?;
 
@@ -486,17 +553,17 @@ impl PegInGraph {
                     NamedTx::for_tx(&self.peg_in_deposit_transaction, status.confirmed),
                 ]))),
             },
-            Err(e) => Err(Error::Esplora(e)),
+            Err(e) => Err(Error::BitcoinBackend(e)),
         }
     }
 
     async fn get_peg_in_statuses(
         &self,
-        client: &AsyncClient,
+        client: &dyn BitcoinBackend,
     ) -> (
-        Result<TxStatus, esplora_client::Error>,
-        Result<TxStatus, esplora_client::Error>,
-        Result<TxStatus, esplora_client::Error>,
+        Result<TxStatus, BitcoinBackendError>,
+        Result<TxStatus, BitcoinBackendError>,
+        Result<TxStatus, BitcoinBackendError>,
     ) {
         let peg_in_deposit_status = client
             .get_tx_status(&self.peg_in_deposit_transaction.tx().compute_txid())
@@ -518,7 +585,14 @@ impl PegInGraph {
     }
 
     pub fn validate(&self) -> Result<(), Error> {
+        self.timelock_policy().validate().map_err(Error::Graph)?;
         let peg_in_graph = self.new_for_validation();
+        // The refund timelock drives the graph status, so it must match the recorded policy.
+        if self.connector_z != peg_in_graph.connector_z {
+            return Err(Error::Graph(GraphError::InvalidTimelockPolicy(
+                "Connector timelocks do not match the timelock policy".to_string(),
+            )));
+        }
         validate_transaction(
             self.peg_in_deposit_transaction.tx(),
             peg_in_graph.peg_in_deposit_transaction.tx(),
@@ -540,7 +614,16 @@ impl PegInGraph {
         Ok(())
     }
 
-    pub fn merge(&mut self, source_peg_in_graph: &PegInGraph) {
+    // The operators are not part of the graph id, so a copy of the graph listing other operators
+    // is refused rather than merged.
+    pub fn merge(&mut self, source_peg_in_graph: &PegInGraph) -> Result<(), Error> {
+        let operators: BTreeSet<&PublicKey> = self.operator_public_keys.iter().collect();
+        let source_operators: BTreeSet<&PublicKey> =
+            source_peg_in_graph.operator_public_keys.iter().collect();
+        if operators != source_operators {
+            return Err(Error::Graph(GraphError::OperatorsMismatch(self.id.clone())));
+        }
+
         self.peg_in_confirm_transaction
             .merge(&source_peg_in_graph.peg_in_confirm_transaction);
 
@@ -548,11 +631,13 @@ impl PegInGraph {
             .extend(source_peg_in_graph.peg_out_graphs.clone());
         self.peg_out_graphs.sort();
         self.peg_out_graphs.dedup();
+
+        Ok(())
     }
 }
 
 impl GraphCliQuery for PegInGraph {
-    async fn broadcast_deposit(&self, client: &AsyncClient) -> Result<(), String> {
+    async fn broadcast_deposit(&self, client: &dyn BitcoinBackend) -> Result<(), String> {
         let txid = self.peg_in_deposit_transaction.tx().compute_txid();
         let tx_status = client.get_tx_status(&txid).await;
         match tx_status {
@@ -589,6 +674,7 @@ fn create_new_connectors(
     n_of_n_taproot_public_key: &XOnlyPublicKey,
     depositor_taproot_public_key: &XOnlyPublicKey,
     evm_address: &str,
+    timelock_policy: &TimelockPolicy,
 ) -> PegInConnectors {
     let connector_0 = Connector0::new(network, n_of_n_taproot_public_key);
     let connector_z = ConnectorZ::new(
@@ -596,6 +682,7 @@ fn create_new_connectors(
         evm_address,
         depositor_taproot_public_key,
         n_of_n_taproot_public_key,
+        timelock_policy,
     );
 
     PegInConnectors {
@@ -614,12 +701,15 @@ fn create_graph_without_signing(
     n_of_n_taproot_public_key: &XOnlyPublicKey,
     depositor_evm_address: &str,
     deposit_input: Input,
+    operator_public_keys: &[PublicKey],
+    timelock_policy: &TimelockPolicy,
 ) -> PegInGraph {
     let connectors = create_new_connectors(
         network,
         n_of_n_taproot_public_key,
         depositor_taproot_public_key,
         depositor_evm_address,
+        timelock_policy,
     );
     let peg_in_deposit_transaction = PegInDepositTransaction::new_for_validation(
         network,
@@ -657,6 +747,8 @@ fn create_graph_without_signing(
         depositor_public_key: *depositor_public_key,
         depositor_taproot_public_key: *depositor_taproot_public_key,
         depositor_evm_address: depositor_evm_address.to_string(),
+        operator_public_keys: operator_public_keys.to_vec(),
+        timelock_policy: Some(*timelock_policy),
         connector_0: connectors.connector_0,
         connector_z: connectors.connector_z,
         peg_out_graphs: Vec::new(),
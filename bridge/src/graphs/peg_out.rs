@@ -4,7 +4,6 @@ use bitcoin::{
     key::Keypair,
     Amount, Network, OutPoint, PublicKey, ScriptBuf, Transaction, Txid, XOnlyPublicKey,
 };
-use esplora_client::{AsyncClient, TxStatus};
 use musig2::SecNonce;
 use num_traits::ToPrimitive;
 use serde::{Deserialize, Serialize};
@@ -12,15 +11,20 @@ use sha2::{Digest, Sha256};
 use std::collections::{BTreeMap, HashMap};
 
 use crate::{
+    client::bitcoin_backend::bitcoin_backend::{BitcoinBackend, TxStatus},
     commitments::CommitmentMessageId,
     common::ZkProofVerifyingKey,
     connectors::{
-        connector_c::get_commit_from_assert_commit_tx, connector_d::ConnectorD,
-        connector_e::ConnectorE, connector_f_1::ConnectorF1, connector_f_2::ConnectorF2,
+        connector_c::{get_commit_from_assert_commit_tx, save_disprove_report},
+        connector_d::ConnectorD,
+        connector_e::ConnectorE,
+        connector_f_1::ConnectorF1,
+        connector_f_2::ConnectorF2,
     },
-    error::{Error, GraphError, L2Error, NamedTx},
+    error::{BitcoinBackendError, Error, GraphError, L2Error, NamedTx},
     superblock::{
-        find_superblock, get_start_time_block_number, get_superblock_hash_message,
+        find_disprove_superblock, find_superblock, get_committed_start_time,
+        get_committed_superblock_hash, get_start_time, get_superblock_hash_message,
         get_superblock_message,
     },
     transactions::{
@@ -58,6 +62,7 @@ use super::{
             connector_c::ConnectorC,
         },
         contexts::{operator::OperatorContext, verifier::VerifierContext},
+        timelock_policy::TimelockPolicy,
         transactions::{
             base::{
                 validate_transaction, verify_public_nonces_for_tx, BaseTransaction, Input,
@@ -79,8 +84,8 @@ use super::{
         },
     },
     base::{
-        get_onchain_txs, get_tx_statuses, verify_if_not_mined, BaseGraph, GraphId,
-        CROWDFUNDING_AMOUNT, GRAPH_VERSION,
+        get_onchain_txs, get_tx_statuses, is_timelock_expired, verify_if_not_mined, BaseGraph,
+        GraphId, CROWDFUNDING_AMOUNT, GRAPH_VERSION,
     },
     peg_in::PegInGraph,
 };
@@ -160,6 +165,16 @@ pub enum PegOutOperatorStatus {
     PegOutTake2Available,
 }
 
+// Transactions that become valid a fixed number of blocks after a preceding transaction of the
+// graph is confirmed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub enum PegOutTimelockedTx {
+    StartTimeTimeout,
+    KickOffTimeout,
+    Take1,
+    Take2,
+}
+
 struct PegOutConnectors {
     connector_0: Connector0,
     connector_1: Connector1,
@@ -192,6 +207,11 @@ pub struct PegOutGraph {
     pub peg_in_graph_id: String,
     peg_in_confirm_txid: Txid,
 
+    // None for graphs created before the policy was recorded, their timelocks are those of
+    // TimelockPolicy::for_network.
+    #[serde(default)]
+    timelock_policy: Option<TimelockPolicy>,
+
     // Note that only the connectors that are used with message commitments are
     // required to be here. They carry the Winternitz public keys, which need
     // to be pushed to remote data store. The remaining connectors can be
@@ -309,6 +329,10 @@ impl BaseGraph for PegOutGraph {
             })
             .collect()
     }
+
+    fn musig2_transactions(&self) -> Vec<&dyn PreSignedMusig2Transaction> {
+        self.all_presigned_txs().collect()
+    }
 }
 
 impl PegOutGraph {
@@ -317,6 +341,8 @@ impl PegOutGraph {
         peg_in_graph: &PegInGraph,
         peg_out_confirm_input: Input,
         commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
+        timelock_policy: &TimelockPolicy,
+        verifying_key: &ZkProofVerifyingKey,
     ) -> Self {
         let peg_in_confirm_transaction = peg_in_graph.peg_in_confirm_transaction_ref();
         let peg_in_confirm_txid = peg_in_confirm_transaction.tx().compute_txid();
@@ -378,6 +404,8 @@ impl PegOutGraph {
             &connector_b_commitment_public_keys,
             &connector_e1_commitment_public_keys,
             &connector_e2_commitment_public_keys,
+            timelock_policy,
+            verifying_key,
         );
 
         let peg_out_confirm_transaction =
@@ -437,8 +465,8 @@ impl PegOutGraph {
 
         let kick_off_2_vout_0 = 1;
         let kick_off_2_transaction = KickOff2Transaction::new(
-            context,
             &connectors.connector_1,
+            &connectors.connector_3,
             &connectors.connector_b,
             Input {
                 outpoint: OutPoint {
@@ -686,6 +714,7 @@ impl PegOutGraph {
             n_of_n_taproot_public_key: context.n_of_n_taproot_public_key,
             peg_in_graph_id: peg_in_graph.id().clone(),
             peg_in_confirm_txid,
+            timelock_policy: Some(*timelock_policy),
             connector_0: connectors.connector_0,
             connector_1: connectors.connector_1,
             connector_2: connectors.connector_2,
@@ -737,6 +766,8 @@ impl PegOutGraph {
             &self.connector_b.commitment_public_keys,
             &self.connector_e_1.commitment_public_keys(),
             &self.connector_e_2.commitment_public_keys(),
+            &self.timelock_policy(),
+            self.connector_c.verifying_key(),
         );
 
         let peg_out_confirm_vout_0 = 0;
@@ -804,9 +835,8 @@ impl PegOutGraph {
 
         let kick_off_2_vout_0 = 1;
         let kick_off_2_transaction = KickOff2Transaction::new_for_validation(
-            self.network,
-            &self.operator_public_key,
             &connectors.connector_1,
+            &connectors.connector_3,
             &connectors.connector_b,
             Input {
                 outpoint: OutPoint {
@@ -1056,6 +1086,7 @@ impl PegOutGraph {
             n_of_n_taproot_public_key: self.n_of_n_taproot_public_key,
             peg_in_graph_id: self.peg_in_graph_id.clone(),
             peg_in_confirm_txid,
+            timelock_policy: self.timelock_policy,
             connector_0: connectors.connector_0,
             connector_1: connectors.connector_1,
             connector_2: connectors.connector_2,
@@ -1095,7 +1126,7 @@ impl PegOutGraph {
 
     pub async fn verifier_status(
         &self,
-        client: &AsyncClient,
+        client: &dyn BitcoinBackend,
         verifier_context: &VerifierContext,
     ) -> PegOutVerifierStatus {
         if self.n_of_n_presigned {
@@ -1165,8 +1196,11 @@ impl PegOutGraph {
                         .block_height
                         .is_some_and(|block_height| {
                             blockchain_height.is_ok_and(|blockchain_height| {
-                                block_height + self.connector_1.num_blocks_timelock_leaf_2
-                                    <= blockchain_height
+                                is_timelock_expired(
+                                    block_height,
+                                    self.connector_1.num_blocks_timelock_leaf_2,
+                                    blockchain_height,
+                                )
                             })
                         })
                     {
@@ -1180,8 +1214,11 @@ impl PegOutGraph {
                     .block_height
                     .is_some_and(|block_height| {
                         blockchain_height.is_ok_and(|blockchain_height| {
-                            block_height + self.connector_1.num_blocks_timelock_leaf_1
-                                <= blockchain_height
+                            is_timelock_expired(
+                                block_height,
+                                self.connector_1.num_blocks_timelock_leaf_1,
+                                blockchain_height,
+                            )
                         })
                     })
                 {
@@ -1212,7 +1249,7 @@ impl PegOutGraph {
         }
     }
 
-    pub async fn operator_status(&self, client: &AsyncClient) -> PegOutOperatorStatus {
+    pub async fn operator_status(&self, client: &dyn BitcoinBackend) -> PegOutOperatorStatus {
         if self.n_of_n_presigned && self.is_peg_out_initiated() {
             let (
                 assert_initial_status,
@@ -1262,8 +1299,11 @@ impl PegOutGraph {
                                 .block_height
                                 .is_some_and(|block_height| {
                                     blockchain_height.is_ok_and(|blockchain_height| {
-                                        block_height + self.connector_4.num_blocks_timelock
-                                            <= blockchain_height
+                                        is_timelock_expired(
+                                            block_height,
+                                            self.connector_4.num_blocks_timelock,
+                                            blockchain_height,
+                                        )
                                     })
                                 })
                             {
@@ -1277,8 +1317,11 @@ impl PegOutGraph {
                             .block_height
                             .is_some_and(|block_height| {
                                 blockchain_height.is_ok_and(|blockchain_height| {
-                                    block_height + self.connector_b.num_blocks_timelock_1
-                                        <= blockchain_height
+                                    is_timelock_expired(
+                                        block_height,
+                                        self.connector_b.num_blocks_timelock_1,
+                                        blockchain_height,
+                                    )
                                 })
                             })
                         {
@@ -1314,8 +1357,11 @@ impl PegOutGraph {
                         .block_height
                         .is_some_and(|block_height| {
                             blockchain_height.is_ok_and(|blockchain_height| {
-                                block_height + self.connector_3.num_blocks_timelock
-                                    <= blockchain_height
+                                is_timelock_expired(
+                                    block_height,
+                                    self.connector_3.num_blocks_timelock,
+                                    blockchain_height,
+                                )
                             })
                         })
                     {
@@ -1345,8 +1391,11 @@ impl PegOutGraph {
                             .block_height
                             .is_some_and(|block_height| {
                                 blockchain_height.is_ok_and(|blockchain_height| {
-                                    block_height + self.connector_1.num_blocks_timelock_leaf_0
-                                        <= blockchain_height
+                                    is_timelock_expired(
+                                        block_height,
+                                        self.connector_1.num_blocks_timelock_leaf_0,
+                                        blockchain_height,
+                                    )
                                 })
                             })
                         {
@@ -1375,7 +1424,7 @@ impl PegOutGraph {
 
     pub fn interpret_withdrawer_status(
         &self,
-        peg_out_status: Option<&Result<TxStatus, esplora_client::Error>>,
+        peg_out_status: Option<&Result<TxStatus, BitcoinBackendError>>,
     ) -> PegOutWithdrawerStatus {
         if let Some(peg_out_status) = peg_out_status {
             if peg_out_status.as_ref().is_ok_and(|status| status.confirmed) {
@@ -1388,7 +1437,7 @@ impl PegOutGraph {
         }
     }
 
-    pub async fn withdrawer_status(&self, client: &AsyncClient) -> PegOutWithdrawerStatus {
+    pub async fn withdrawer_status(&self, client: &dyn BitcoinBackend) -> PegOutWithdrawerStatus {
         let peg_out_status = match self.peg_out_transaction {
             Some(_) => {
                 let peg_out_txid = self
@@ -1407,7 +1456,7 @@ impl PegOutGraph {
 
     pub async fn peg_out(
         &mut self,
-        client: &AsyncClient,
+        client: &dyn BitcoinBackend,
         context: &OperatorContext,
         input: Input,
     ) -> Result<Transaction, Error> {
@@ -1432,7 +1481,10 @@ impl PegOutGraph {
         Ok(self.peg_out_transaction.as_mut().unwrap().finalize())
     }
 
-    pub async fn peg_out_confirm(&mut self, client: &AsyncClient) -> Result<Transaction, Error> {
+    pub async fn peg_out_confirm(
+        &mut self,
+        client: &dyn BitcoinBackend,
+    ) -> Result<Transaction, Error> {
         verify_if_not_mined(client, self.peg_out_confirm_transaction.tx().compute_txid()).await?;
 
         if self.peg_out_transaction.as_ref().is_some() {
@@ -1454,7 +1506,7 @@ impl PegOutGraph {
                         ),
                     ]))),
                 },
-                Err(e) => Err(Error::Esplora(e)),
+                Err(e) => Err(Error::BitcoinBackend(e)),
             }
         } else {
             Err(Error::Graph(GraphError::PrecedingTxNotCreated("peg-out")))
@@ -1463,7 +1515,7 @@ impl PegOutGraph {
 
     pub async fn kick_off_1(
         &mut self,
-        client: &AsyncClient,
+        client: &dyn BitcoinBackend,
         context: &OperatorContext,
         source_network_txid_commitment_secret: &WinternitzSecret,
         destination_network_txid_commitment_secret: &WinternitzSecret,
@@ -1509,13 +1561,13 @@ impl PegOutGraph {
                     NamedTx::for_tx(&self.peg_out_confirm_transaction, status.confirmed),
                 ]))),
             },
-            Err(e) => Err(Error::Esplora(e)),
+            Err(e) => Err(Error::BitcoinBackend(e)),
         }
     }
 
     pub async fn challenge(
         &mut self,
-        client: &AsyncClient,
+        client: &dyn BitcoinBackend,
         crowdfundng_inputs: &Vec<InputWithScript<'_>>,
         keypair: &Keypair,
         output_script_pubkey: ScriptBuf,
@@ -1539,13 +1591,13 @@ impl PegOutGraph {
                     NamedTx::for_tx(&self.kick_off_1_transaction, status.confirmed),
                 ]))),
             },
-            Err(e) => Err(Error::Esplora(e)),
+            Err(e) => Err(Error::BitcoinBackend(e)),
         }
     }
 
     pub async fn start_time(
         &mut self,
-        client: &AsyncClient,
+        client: &dyn BitcoinBackend,
         context: &OperatorContext,
         start_time_commitment_secret: &WinternitzSecret,
     ) -> Result<Transaction, Error> {
@@ -1555,27 +1607,30 @@ impl PegOutGraph {
         let kick_off_1_status = client.get_tx_status(&kick_off_1_txid).await;
 
         match kick_off_1_status {
-            Ok(status) => match status.confirmed {
-                true => {
+            Ok(status) => match (status.confirmed, status.block_height) {
+                (true, Some(block_height)) => {
+                    let start_time = get_start_time(client, block_height)
+                        .await
+                        .map_err(Error::BitcoinBackend)?;
                     self.start_time_transaction.sign(
                         context,
                         &self.connector_2,
-                        get_start_time_block_number(context.network),
+                        start_time,
                         start_time_commitment_secret,
                     );
                     Ok(self.start_time_transaction.finalize())
                 }
-                false => Err(Error::Graph(GraphError::PrecedingTxNotConfirmed(vec![
+                _ => Err(Error::Graph(GraphError::PrecedingTxNotConfirmed(vec![
                     NamedTx::for_tx(&self.kick_off_1_transaction, status.confirmed),
                 ]))),
             },
-            Err(e) => Err(Error::Esplora(e)),
+            Err(e) => Err(Error::BitcoinBackend(e)),
         }
     }
 
     pub async fn start_time_timeout(
         &mut self,
-        client: &AsyncClient,
+        client: &dyn BitcoinBackend,
         output_script_pubkey: ScriptBuf,
     ) -> Result<Transaction, Error> {
         verify_if_not_mined(
@@ -1594,7 +1649,11 @@ impl PegOutGraph {
                 true => match status.block_height {
                     Some(block_height)
                         if blockchain_height.is_ok_and(|height| {
-                            block_height + self.connector_1.num_blocks_timelock_leaf_2 <= height
+                            is_timelock_expired(
+                                block_height,
+                                self.connector_1.num_blocks_timelock_leaf_2,
+                                height,
+                            )
                         }) =>
                     {
                         self.start_time_timeout_transaction
@@ -1609,13 +1668,13 @@ impl PegOutGraph {
                     NamedTx::for_tx(&self.kick_off_1_transaction, status.confirmed),
                 ]))),
             },
-            Err(e) => Err(Error::Esplora(e)),
+            Err(e) => Err(Error::BitcoinBackend(e)),
         }
     }
 
     pub async fn kick_off_2(
         &mut self,
-        client: &AsyncClient,
+        client: &dyn BitcoinBackend,
         context: &OperatorContext,
         superblock_commitment_secret: &WinternitzSecret,
         superblock_hash_commitment_secret: &WinternitzSecret,
@@ -1632,10 +1691,29 @@ impl PegOutGraph {
                 true => match status.block_height {
                     Some(block_height)
                         if blockchain_height.is_ok_and(|height| {
-                            block_height + self.connector_1.num_blocks_timelock_leaf_0 <= height
+                            is_timelock_expired(
+                                block_height,
+                                self.connector_1.num_blocks_timelock_leaf_0,
+                                height,
+                            )
                         }) =>
                     {
-                        let superblock_header = find_superblock();
+                        let start_time = self
+                            .start_time_transaction
+                            .start_time_witness
+                            .as_ref()
+                            .ok_or(Error::Graph(GraphError::WitnessNotGenerated(
+                                CommitmentMessageId::StartTime,
+                            )))?;
+                        let start_time =
+                            get_committed_start_time(start_time).ok_or(Error::Graph(
+                                GraphError::InvalidWitness(CommitmentMessageId::StartTime),
+                            ))?;
+                        let superblock_header = find_superblock(client, start_time, block_height)
+                            .await
+                            .map_err(Error::BitcoinBackend)?
+                            .ok_or(Error::Graph(GraphError::SuperblockNotFound))?
+                            .header;
                         self.kick_off_2_transaction.sign(
                             context,
                             &self.connector_1,
@@ -1658,13 +1736,13 @@ impl PegOutGraph {
                     NamedTx::for_tx(&self.kick_off_1_transaction, status.confirmed),
                 ]))),
             },
-            Err(e) => Err(Error::Esplora(e)),
+            Err(e) => Err(Error::BitcoinBackend(e)),
         }
     }
 
     pub async fn kick_off_timeout(
         &mut self,
-        client: &AsyncClient,
+        client: &dyn BitcoinBackend,
         output_script_pubkey: ScriptBuf,
     ) -> Result<Transaction, Error> {
         verify_if_not_mined(
@@ -1683,7 +1761,11 @@ impl PegOutGraph {
                 true => match status.block_height {
                     Some(block_height)
                         if blockchain_height.is_ok_and(|height| {
-                            block_height + self.connector_1.num_blocks_timelock_leaf_1 <= height
+                            is_timelock_expired(
+                                block_height,
+                                self.connector_1.num_blocks_timelock_leaf_1,
+                                height,
+                            )
                         }) =>
                     {
                         self.kick_off_timeout_transaction
@@ -1698,11 +1780,14 @@ impl PegOutGraph {
                     NamedTx::for_tx(&self.kick_off_1_transaction, status.confirmed),
                 ]))),
             },
-            Err(e) => Err(Error::Esplora(e)),
+            Err(e) => Err(Error::BitcoinBackend(e)),
         }
     }
 
-    pub async fn assert_initial(&mut self, client: &AsyncClient) -> Result<Transaction, Error> {
+    pub async fn assert_initial(
+        &mut self,
+        client: &dyn BitcoinBackend,
+    ) -> Result<Transaction, Error> {
         verify_if_not_mined(client, self.assert_initial_transaction.tx().compute_txid()).await?;
 
         let kick_off_2_txid = self.kick_off_2_transaction.tx().compute_txid();
@@ -1715,7 +1800,11 @@ impl PegOutGraph {
                 true => match status.block_height {
                     Some(block_height)
                         if blockchain_height.is_ok_and(|height| {
-                            block_height + self.connector_b.num_blocks_timelock_1 <= height
+                            is_timelock_expired(
+                                block_height,
+                                self.connector_b.num_blocks_timelock_1,
+                                height,
+                            )
                         }) =>
                     {
                         Ok(self.assert_initial_transaction.finalize())
@@ -1728,13 +1817,13 @@ impl PegOutGraph {
                     NamedTx::for_tx(&self.kick_off_2_transaction, status.confirmed),
                 ]))),
             },
-            Err(e) => Err(Error::Esplora(e)),
+            Err(e) => Err(Error::BitcoinBackend(e)),
         }
     }
 
     pub async fn assert_commit_1(
         &mut self,
-        client: &AsyncClient,
+        client: &dyn BitcoinBackend,
         commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
         proof: &RawProof,
     ) -> Result<Transaction, Error> {
@@ -1756,13 +1845,13 @@ impl PegOutGraph {
                     NamedTx::for_tx(&self.assert_initial_transaction, status.confirmed),
                 ]))),
             },
-            Err(e) => Err(Error::Esplora(e)),
+            Err(e) => Err(Error::BitcoinBackend(e)),
         }
     }
 
     pub async fn assert_commit_2(
         &mut self,
-        client: &AsyncClient,
+        client: &dyn BitcoinBackend,
         commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
         proof: &RawProof,
     ) -> Result<Transaction, Error> {
@@ -1784,7 +1873,7 @@ impl PegOutGraph {
                     NamedTx::for_tx(&self.assert_initial_transaction, status.confirmed),
                 ]))),
             },
-            Err(e) => Err(Error::Esplora(e)),
+            Err(e) => Err(Error::BitcoinBackend(e)),
         }
     }
 
@@ -1792,7 +1881,7 @@ impl PegOutGraph {
     // return both commit1 and commit2, will save time for verifying groth16
     pub async fn assert_commits(
         &mut self,
-        client: &AsyncClient,
+        client: &dyn BitcoinBackend,
         commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
         proof: &RawProof,
     ) -> Result<(Transaction, Transaction), Error> {
@@ -1820,11 +1909,14 @@ impl PegOutGraph {
                     NamedTx::for_tx(&self.assert_initial_transaction, status.confirmed),
                 ]))),
             },
-            Err(e) => Err(Error::Esplora(e)),
+            Err(e) => Err(Error::BitcoinBackend(e)),
         }
     }
 
-    pub async fn assert_final(&mut self, client: &AsyncClient) -> Result<Transaction, Error> {
+    pub async fn assert_final(
+        &mut self,
+        client: &dyn BitcoinBackend,
+    ) -> Result<Transaction, Error> {
         verify_if_not_mined(client, self.assert_final_transaction.tx().compute_txid()).await?;
 
         let assert_initial_txid = self.assert_initial_transaction.tx().compute_txid();
@@ -1837,15 +1929,14 @@ impl PegOutGraph {
                     NamedTx::for_tx(&self.assert_initial_transaction, status.confirmed),
                 ]))),
             },
-            Err(e) => Err(Error::Esplora(e)),
+            Err(e) => Err(Error::BitcoinBackend(e)),
         }
     }
 
     pub async fn disprove(
         &mut self,
-        client: &AsyncClient,
+        client: &dyn BitcoinBackend,
         output_script_pubkey: ScriptBuf,
-        verifying_key: &ZkProofVerifyingKey,
     ) -> Result<Transaction, Error> {
         verify_if_not_mined(client, self.disprove_transaction.tx().compute_txid()).await?;
 
@@ -1854,7 +1945,7 @@ impl PegOutGraph {
         let Some(onchain_assert_commit_1_tx) = client
             .get_tx(&assert_commit_1_txid)
             .await
-            .map_err(Error::Esplora)?
+            .map_err(Error::BitcoinBackend)?
         else {
             return Err(Error::Other(format!(
                 "Esplora failed to retrieve a tx {} with id: {}",
@@ -1865,7 +1956,7 @@ impl PegOutGraph {
         let Some(onchain_assert_commit_2_tx) = client
             .get_tx(&assert_commit_2_txid)
             .await
-            .map_err(Error::Esplora)?
+            .map_err(Error::BitcoinBackend)?
         else {
             return Err(Error::Other(format!(
                 "Esplora failed to retrieve a tx {} with id: {}",
@@ -1886,61 +1977,102 @@ impl PegOutGraph {
                     let assert_commit_2_witness =
                         get_commit_from_assert_commit_tx(&onchain_assert_commit_2_tx);
 
-                    let (input_script_index, disprove_witness) =
-                        self.connector_c.generate_disprove_witness(
-                            assert_commit_1_witness,
-                            assert_commit_2_witness,
-                            verifying_key,
-                        )?;
+                    let report = self.connector_c.generate_disprove_witness(
+                        assert_commit_1_witness,
+                        assert_commit_2_witness,
+                    )?;
                     self.disprove_transaction.add_input_output(
                         &self.connector_c,
-                        input_script_index,
-                        disprove_witness,
+                        report.tapscript_index,
+                        report.witness.clone(),
                         output_script_pubkey,
                     );
-                    Ok(self.disprove_transaction.finalize())
+                    let disprove_tx = self.disprove_transaction.finalize();
+                    println!(
+                        "Disproving the assertions at tapscript {} ({:?}), disprove tx weight {}",
+                        report.tapscript_index,
+                        report.reason,
+                        disprove_tx.weight()
+                    );
+                    save_disprove_report(self.id(), &report, disprove_tx.weight())
+                        .inspect(|path| println!("Disprove report saved to {}", path.display()))
+                        .inspect_err(|e| eprintln!("Failed to save the disprove report: {}", e))
+                        .ok();
+                    Ok(disprove_tx)
                 }
                 false => Err(Error::Graph(GraphError::PrecedingTxNotConfirmed(vec![
                     NamedTx::for_tx(&self.assert_final_transaction, status.confirmed),
                 ]))),
             },
-            Err(e) => Err(Error::Esplora(e)),
+            Err(e) => Err(Error::BitcoinBackend(e)),
         }
     }
 
     pub async fn disprove_chain(
         &mut self,
-        client: &AsyncClient,
+        client: &dyn BitcoinBackend,
         output_script_pubkey: ScriptBuf,
     ) -> Result<Transaction, Error> {
         verify_if_not_mined(client, self.disprove_chain_transaction.tx().compute_txid()).await?;
 
+        let kick_off_1_txid = self.kick_off_1_transaction.tx().compute_txid();
+        let kick_off_1_status = client.get_tx_status(&kick_off_1_txid).await;
+
         let kick_off_2_txid = self.kick_off_2_transaction.tx().compute_txid();
         let kick_off_2_status = client.get_tx_status(&kick_off_2_txid).await;
 
         match kick_off_2_status {
             Ok(status) => match status.confirmed {
                 true => {
+                    let kick_off_1_height = kick_off_1_status
+                        .map_err(Error::BitcoinBackend)?
+                        .block_height
+                        .ok_or(Error::Graph(GraphError::PrecedingTxNotConfirmed(vec![
+                            NamedTx::for_tx(&self.kick_off_1_transaction, false),
+                        ])))?;
+                    let start_time_witness = self
+                        .start_time_transaction
+                        .start_time_witness
+                        .as_ref()
+                        .ok_or(Error::Graph(GraphError::WitnessNotGenerated(
+                            CommitmentMessageId::StartTime,
+                        )))?;
+                    let superblock_hash_witness = self
+                        .kick_off_2_transaction
+                        .superblock_hash_witness
+                        .as_ref()
+                        .ok_or(Error::Graph(GraphError::WitnessNotGenerated(
+                            CommitmentMessageId::SuperblockHash,
+                        )))?;
+                    let start_time = get_committed_start_time(start_time_witness).ok_or(
+                        Error::Graph(GraphError::InvalidWitness(CommitmentMessageId::StartTime)),
+                    )?;
+                    let committed_superblock_hash = get_committed_superblock_hash(
+                        superblock_hash_witness,
+                    )
+                    .ok_or(Error::Graph(GraphError::InvalidWitness(
+                        CommitmentMessageId::SuperblockHash,
+                    )))?;
+
+                    // The disprove superblock must be heavier than the one the operator committed
+                    // in the Kick Off 2 tx.
+                    let disprove_sb = find_disprove_superblock(
+                        client,
+                        start_time,
+                        kick_off_1_height,
+                        &committed_superblock_hash,
+                    )
+                    .await
+                    .map_err(Error::BitcoinBackend)?
+                    .ok_or(Error::Graph(GraphError::SuperblockNotFound))?
+                    .header;
+
                     self.disprove_chain_transaction
                         .add_output(output_script_pubkey);
-
-                    // TODO: This must be a heavier superblock than the one the Operator committed in the KickOff2 tx.
-                    let disprove_sb = find_superblock();
-
                     self.disprove_chain_transaction.sign(
                         &disprove_sb,
-                        self.start_time_transaction
-                            .start_time_witness
-                            .as_ref()
-                            .ok_or(Error::Graph(GraphError::WitnessNotGenerated(
-                                CommitmentMessageId::StartTime,
-                            )))?,
-                        self.kick_off_2_transaction
-                            .superblock_hash_witness
-                            .as_ref()
-                            .ok_or(Error::Graph(GraphError::WitnessNotGenerated(
-                                CommitmentMessageId::SuperblockHash,
-                            )))?,
+                        start_time_witness,
+                        superblock_hash_witness,
                     );
 
                     Ok(self.disprove_chain_transaction.finalize())
@@ -1949,11 +2081,11 @@ impl PegOutGraph {
                     NamedTx::for_tx(&self.kick_off_2_transaction, status.confirmed),
                 ]))),
             },
-            Err(e) => Err(Error::Esplora(e)),
+            Err(e) => Err(Error::BitcoinBackend(e)),
         }
     }
 
-    pub async fn take_1(&mut self, client: &AsyncClient) -> Result<Transaction, Error> {
+    pub async fn take_1(&mut self, client: &dyn BitcoinBackend) -> Result<Transaction, Error> {
         verify_if_not_mined(client, self.take_1_transaction.tx().compute_txid()).await?;
         verify_if_not_mined(client, self.challenge_transaction.tx().compute_txid()).await?;
         verify_if_not_mined(client, self.assert_initial_transaction.tx().compute_txid()).await?;
@@ -1975,7 +2107,11 @@ impl PegOutGraph {
                     (true, true, true) => match ko2_stat.block_height {
                         Some(block_height)
                             if blockchain_height.is_ok_and(|height| {
-                                block_height + self.connector_3.num_blocks_timelock <= height
+                                is_timelock_expired(
+                                    block_height,
+                                    self.connector_3.num_blocks_timelock,
+                                    height,
+                                )
                             }) =>
                         {
                             Ok(self.take_1_transaction.finalize())
@@ -1995,13 +2131,13 @@ impl PegOutGraph {
                     ]))),
                 }
             }
-            (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => Err(Error::Esplora(e)),
+            (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => Err(Error::BitcoinBackend(e)),
         }
     }
 
     pub async fn take_2(
         &mut self,
-        client: &AsyncClient,
+        client: &dyn BitcoinBackend,
         context: &OperatorContext,
     ) -> Result<Transaction, Error> {
         verify_if_not_mined(client, self.take_2_transaction.tx().compute_txid()).await?;
@@ -2020,7 +2156,11 @@ impl PegOutGraph {
                 (true, true) => match assert_stat.block_height {
                     Some(block_height)
                         if blockchain_height.is_ok_and(|height| {
-                            block_height + self.connector_4.num_blocks_timelock <= height
+                            is_timelock_expired(
+                                block_height,
+                                self.connector_4.num_blocks_timelock,
+                                height,
+                            )
                         }) =>
                     {
                         self.take_2_transaction.sign(context, &self.connector_c);
@@ -2039,7 +2179,73 @@ impl PegOutGraph {
                     NamedTx::for_tx(&self.assert_final_transaction, assert_stat.confirmed),
                 ]))),
             },
-            (Err(e), _) | (_, Err(e)) => Err(Error::Esplora(e)),
+            (Err(e), _) | (_, Err(e)) => Err(Error::BitcoinBackend(e)),
+        }
+    }
+
+    // All transactions of the graph that can end up on chain, the peg-out transaction included
+    // once it has been created. Transactions come before the ones spending their outputs.
+    pub fn transactions(&self) -> Vec<&Transaction> {
+        let mut txs = vec![];
+        if let Some(peg_out_transaction) = &self.peg_out_transaction {
+            txs.push(peg_out_transaction.tx());
+        }
+        txs.extend([
+            self.peg_out_confirm_transaction.tx(),
+            self.kick_off_1_transaction.tx(),
+            self.start_time_transaction.tx(),
+            self.start_time_timeout_transaction.tx(),
+            self.kick_off_2_transaction.tx(),
+            self.kick_off_timeout_transaction.tx(),
+            self.challenge_transaction.tx(),
+            self.assert_initial_transaction.tx(),
+            self.assert_commit_1_transaction.tx(),
+            self.assert_commit_2_transaction.tx(),
+            self.assert_final_transaction.tx(),
+            self.disprove_chain_transaction.tx(),
+            self.disprove_transaction.tx(),
+            self.take_1_transaction.tx(),
+            self.take_2_transaction.tx(),
+        ]);
+
+        txs
+    }
+
+    // Returns each timelocked transaction with the txid of the transaction whose confirmation
+    // starts its timelock and the number of blocks to wait after that confirmation. These are
+    // the same conditions the corresponding broadcast methods check.
+    pub fn timelocked_txs(&self) -> [(PegOutTimelockedTx, Txid, u32); 4] {
+        let kick_off_1_txid = self.kick_off_1_transaction.tx().compute_txid();
+        [
+            (
+                PegOutTimelockedTx::StartTimeTimeout,
+                kick_off_1_txid,
+                self.connector_1.num_blocks_timelock_leaf_2,
+            ),
+            (
+                PegOutTimelockedTx::KickOffTimeout,
+                kick_off_1_txid,
+                self.connector_1.num_blocks_timelock_leaf_1,
+            ),
+            (
+                PegOutTimelockedTx::Take1,
+                self.kick_off_2_transaction.tx().compute_txid(),
+                self.connector_3.num_blocks_timelock,
+            ),
+            (
+                PegOutTimelockedTx::Take2,
+                self.assert_final_transaction.tx().compute_txid(),
+                self.connector_4.num_blocks_timelock,
+            ),
+        ]
+    }
+
+    pub fn timelocked_tx(&self, tx: PegOutTimelockedTx) -> &Transaction {
+        match tx {
+            PegOutTimelockedTx::StartTimeTimeout => self.start_time_timeout_transaction.tx(),
+            PegOutTimelockedTx::KickOffTimeout => self.kick_off_timeout_transaction.tx(),
+            PegOutTimelockedTx::Take1 => self.take_1_transaction.tx(),
+            PegOutTimelockedTx::Take2 => self.take_2_transaction.tx(),
         }
     }
 
@@ -2081,24 +2287,24 @@ impl PegOutGraph {
 
     async fn get_peg_out_statuses(
         &self,
-        client: &AsyncClient,
+        client: &dyn BitcoinBackend,
     ) -> (
-        Result<TxStatus, esplora_client::Error>,
-        Result<TxStatus, esplora_client::Error>,
-        Result<TxStatus, esplora_client::Error>,
-        Result<TxStatus, esplora_client::Error>,
-        Result<TxStatus, esplora_client::Error>,
-        Result<TxStatus, esplora_client::Error>,
-        Result<TxStatus, esplora_client::Error>,
-        Result<TxStatus, esplora_client::Error>,
-        Result<TxStatus, esplora_client::Error>,
-        Result<TxStatus, esplora_client::Error>,
-        Result<TxStatus, esplora_client::Error>,
-        Option<Result<TxStatus, esplora_client::Error>>,
-        Result<TxStatus, esplora_client::Error>,
-        Result<TxStatus, esplora_client::Error>,
-        Result<TxStatus, esplora_client::Error>,
-        Result<TxStatus, esplora_client::Error>,
+        Result<TxStatus, BitcoinBackendError>,
+        Result<TxStatus, BitcoinBackendError>,
+        Result<TxStatus, BitcoinBackendError>,
+        Result<TxStatus, BitcoinBackendError>,
+        Result<TxStatus, BitcoinBackendError>,
+        Result<TxStatus, BitcoinBackendError>,
+        Result<TxStatus, BitcoinBackendError>,
+        Result<TxStatus, BitcoinBackendError>,
+        Result<TxStatus, BitcoinBackendError>,
+        Result<TxStatus, BitcoinBackendError>,
+        Result<TxStatus, BitcoinBackendError>,
+        Option<Result<TxStatus, BitcoinBackendError>>,
+        Result<TxStatus, BitcoinBackendError>,
+        Result<TxStatus, BitcoinBackendError>,
+        Result<TxStatus, BitcoinBackendError>,
+        Result<TxStatus, BitcoinBackendError>,
     ) {
         let assert_initial_status = client
             .get_tx_status(&self.assert_initial_transaction.tx().compute_txid())
@@ -2144,7 +2350,7 @@ impl PegOutGraph {
             .get_tx_status(&self.kick_off_timeout_transaction.tx().compute_txid())
             .await;
 
-        let mut peg_out_status: Option<Result<TxStatus, esplora_client::Error>> = None;
+        let mut peg_out_status: Option<Result<TxStatus, BitcoinBackendError>> = None;
         if self.peg_out_transaction.is_some() {
             peg_out_status = Some(
                 client
@@ -2196,8 +2402,10 @@ impl PegOutGraph {
         )
     }
 
-    pub async fn validate(&self, client: &AsyncClient) -> Result<(), Error> {
+    pub async fn validate(&self, client: &dyn BitcoinBackend) -> Result<(), Error> {
+        self.timelock_policy().validate().map_err(Error::Graph)?;
         let peg_out_graph = self.new_for_validation();
+        self.validate_timelocks(&peg_out_graph)?;
 
         validate_transaction(
             self.assert_initial_transaction.tx(),
@@ -2325,6 +2533,49 @@ impl PegOutGraph {
         Ok(())
     }
 
+    pub fn timelock_policy(&self) -> TimelockPolicy {
+        self.timelock_policy
+            .unwrap_or_else(|| TimelockPolicy::for_network(self.network))
+    }
+
+    pub fn operator_public_key(&self) -> &PublicKey {
+        &self.operator_public_key
+    }
+
+    // The verifying key the disprove scripts of connector C check the proof against.
+    pub fn verifying_key(&self) -> &ZkProofVerifyingKey {
+        self.connector_c.verifying_key()
+    }
+
+    // The Winternitz public keys of all the commitments of the operator in the graph.
+    pub fn commitment_public_keys(&self) -> HashMap<CommitmentMessageId, WinternitzPublicKey> {
+        let mut commitment_public_keys = HashMap::new();
+        commitment_public_keys.extend(self.connector_1.commitment_public_keys.clone());
+        commitment_public_keys.extend(self.connector_2.commitment_public_keys.clone());
+        commitment_public_keys.extend(self.connector_6.commitment_public_keys.clone());
+        commitment_public_keys.extend(self.connector_b.commitment_public_keys.clone());
+        commitment_public_keys.extend(merge_to_connector_c_commits_public_key(
+            &self.connector_e_1.commitment_public_keys(),
+            &self.connector_e_2.commitment_public_keys(),
+        ));
+        commitment_public_keys
+    }
+
+    // The timelocks of the connectors drive the graph status, so they must match the recorded policy.
+    fn validate_timelocks(&self, peg_out_graph: &PegOutGraph) -> Result<(), Error> {
+        if self.connector_1 != peg_out_graph.connector_1
+            || self.connector_3 != peg_out_graph.connector_3
+            || self.connector_4 != peg_out_graph.connector_4
+            || self.connector_b != peg_out_graph.connector_b
+        {
+            return Err(Error::Graph(GraphError::InvalidTimelockPolicy(
+                "Connector timelocks do not match the timelock policy".to_string(),
+            )));
+        }
+
+        Ok(())
+    }
+
     pub fn merge(&mut self, source_peg_out_graph: &PegOutGraph) {
         self.assert_initial_transaction
             .merge(&source_peg_out_graph.assert_initial_transaction);
@@ -2381,6 +2632,8 @@ impl PegOutGraph {
             CommitmentMessageId,
             WinternitzPublicKey,
         >],
+        timelock_policy: &TimelockPolicy,
+        verifying_key: &ZkProofVerifyingKey,
     ) -> PegOutConnectors {
         let connector_0 = Connector0::new(network, n_of_n_taproot_public_key);
         let connector_1 = Connector1::new(
@@ -2388,6 +2641,7 @@ impl PegOutGraph {
             operator_taproot_public_key,
             n_of_n_taproot_public_key,
             connector_1_commitment_public_keys,
+            timelock_policy,
         );
         let connector_2 = Connector2::new(
             network,
@@ -2395,8 +2649,8 @@ impl PegOutGraph {
             n_of_n_taproot_public_key,
             connector_2_commitment_public_keys,
         );
-        let connector_3 = Connector3::new(network, operator_public_key);
-        let connector_4 = Connector4::new(network, operator_public_key);
+        let connector_3 = Connector3::new(network, operator_public_key, timelock_policy);
+        let connector_4 = Connector4::new(network, operator_public_key, timelock_policy);
         let connector_5 = Connector5::new(network, n_of_n_taproot_public_key);
         let connector_6 = Connector6::new(
             network,
@@ -2412,6 +2666,7 @@ impl PegOutGraph {
             network,
             n_of_n_taproot_public_key,
             connector_b_commitment_public_keys,
+            timelock_policy,
         );
 
         // connector c pks = connector e1 pks + connector e2 pks
@@ -2419,8 +2674,12 @@ impl PegOutGraph {
             connector_e1_commitment_public_keys,
             connector_e2_commitment_public_keys,
         );
-        let connector_c =
-            ConnectorC::new(network, operator_taproot_public_key, commitment_public_keys);
+        let connector_c = ConnectorC::new(
+            network,
+            operator_taproot_public_key,
+            commitment_public_keys,
+            verifying_key,
+        );
         let connector_d = ConnectorD::new(network, n_of_n_taproot_public_key);
 
         let assert_commit_connectors_e_1 = AssertCommit1ConnectorsE {
@@ -7,8 +7,10 @@ pub mod contexts;
 pub mod error;
 pub mod graphs;
 pub mod proof;
+pub mod proof_provider;
 pub mod scripts;
 pub mod serialization;
 pub mod superblock;
+pub mod timelock_policy;
 pub mod transactions;
 pub mod utils;
@@ -0,0 +1,173 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use ark_bn254::Bn254;
+use ark_crypto_primitives::snark::SNARK;
+use ark_groth16::Groth16;
+use ark_serialize::CanonicalDeserialize;
+use bitcoin::{hex::FromHex, Network};
+use serde::{Deserialize, Serialize};
+
+use bitvm::{
+    chunk::api::type_conversion_utils::RawProof,
+    groth16::risc0::{decode_risc0_seal, risc0_public_input},
+};
+
+use super::{common::ZkProofVerifyingKey, error::ProofError, graphs::base::GraphId, proof};
+
+// Source of the proof the operator asserts for a peg-out graph.
+pub trait ProofProvider: Send + Sync {
+    fn get_proof(&self, peg_out_graph_id: &GraphId) -> Result<RawProof, ProofError>;
+}
+
+/*
+  Fails if the proof is not for the given verifying key, since the disprove scripts of the graph are
+  generated for that key, or if the proof does not verify.
+*/
+pub fn validate_proof(
+    proof: &RawProof,
+    verifying_key: &ZkProofVerifyingKey,
+) -> Result<(), ProofError> {
+    if proof.vk != *verifying_key {
+        return Err(ProofError::VerifyingKeyMismatch);
+    }
+
+    match Groth16::<Bn254>::verify(verifying_key, &proof.public, &proof.proof) {
+        Ok(true) => Ok(()),
+        _ => Err(ProofError::InvalidProof),
+    }
+}
+
+// DO NOT USE IN PRODUCTION! Proves a dummy circuit with a fresh setup on every call.
+pub struct DummyProofProvider;
+
+impl ProofProvider for DummyProofProvider {
+    fn get_proof(&self, _peg_out_graph_id: &GraphId) -> Result<RawProof, ProofError> {
+        Ok(proof::get_proof())
+    }
+}
+
+// Reads `<directory>/<peg-out graph id>.bin`, a `RawProof` in arkworks compressed form.
+pub struct FileProofProvider {
+    directory: PathBuf,
+}
+
+impl FileProofProvider {
+    pub fn new(directory: PathBuf) -> Self {
+        FileProofProvider { directory }
+    }
+
+    pub fn proof_path(&self, peg_out_graph_id: &GraphId) -> PathBuf {
+        self.directory.join(format!("{peg_out_graph_id}.bin"))
+    }
+}
+
+impl ProofProvider for FileProofProvider {
+    fn get_proof(&self, peg_out_graph_id: &GraphId) -> Result<RawProof, ProofError> {
+        let bytes = read_proof_file(&self.proof_path(peg_out_graph_id))?;
+        RawProof::deserialize_compressed(&*bytes)
+            .map_err(|e| ProofError::InvalidFormat(format!("Invalid arkworks proof: {e}")))
+    }
+}
+
+// Seal of a risc0 Groth16 receipt and the journal of the proven guest, hex encoded.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Risc0Proof {
+    pub seal: String,
+    pub journal: String,
+}
+
+/*
+  Reads `<directory>/<peg-out graph id>.json`, a `Risc0Proof` of the risc0 to BitVM Groth16
+  circuit, and converts it to an arkworks proof for the given verifying key. The public input of
+  the circuit is the truncated BLAKE3 hash of the constants digest of the guest and its journal.
+*/
+pub struct Risc0ProofProvider {
+    directory: PathBuf,
+    verifying_key: ZkProofVerifyingKey,
+    constants_digest: [u8; 32],
+}
+
+impl Risc0ProofProvider {
+    pub fn new(
+        directory: PathBuf,
+        verifying_key: ZkProofVerifyingKey,
+        constants_digest: [u8; 32],
+    ) -> Self {
+        Risc0ProofProvider {
+            directory,
+            verifying_key,
+            constants_digest,
+        }
+    }
+
+    pub fn proof_path(&self, peg_out_graph_id: &GraphId) -> PathBuf {
+        self.directory.join(format!("{peg_out_graph_id}.json"))
+    }
+}
+
+impl ProofProvider for Risc0ProofProvider {
+    fn get_proof(&self, peg_out_graph_id: &GraphId) -> Result<RawProof, ProofError> {
+        let bytes = read_proof_file(&self.proof_path(peg_out_graph_id))?;
+        let risc0_proof: Risc0Proof = serde_json::from_slice(&bytes)
+            .map_err(|e| ProofError::InvalidFormat(format!("Invalid risc0 proof: {e}")))?;
+
+        Ok(RawProof {
+            proof: decode_risc0_seal(&decode_hex(&risc0_proof.seal, "seal")?)
+                .map_err(ProofError::InvalidFormat)?,
+            public: vec![risc0_public_input(
+                &self.constants_digest,
+                &decode_hex(&risc0_proof.journal, "journal")?,
+            )],
+            vk: self.verifying_key.clone(),
+        })
+    }
+}
+
+fn decode_hex(value: &str, name: &str) -> Result<Vec<u8>, ProofError> {
+    Vec::<u8>::from_hex(value)
+        .map_err(|e| ProofError::InvalidFormat(format!("Invalid {name} hex: {e}")))
+}
+
+fn read_proof_file(path: &Path) -> Result<Vec<u8>, ProofError> {
+    fs::read(path).map_err(|_| ProofError::ProofNotFound(path.display().to_string()))
+}
+
+/*
+  Proofs are read from BRIDGE_PROOF_DIRECTORY if it is set, as risc0 proofs if
+  BRIDGE_RISC0_CONSTANTS_DIGEST is set too, and as arkworks proofs otherwise.
+  Dummy proofs are only used on regtest with BRIDGE_DUMMY_PROOFS set, since they are only useful
+  for testing. Fails if no provider is configured.
+*/
+pub fn get_proof_provider(
+    network: Network,
+    verifying_key: Option<&ZkProofVerifyingKey>,
+) -> Result<Box<dyn ProofProvider>, ProofError> {
+    dotenv::dotenv().ok();
+    let Ok(directory) = dotenv::var("BRIDGE_PROOF_DIRECTORY") else {
+        let dummy_proofs = matches!(
+            dotenv::var("BRIDGE_DUMMY_PROOFS").as_deref(),
+            Ok("1" | "true")
+        );
+        return match dummy_proofs && network == Network::Regtest {
+            true => Ok(Box::new(DummyProofProvider)),
+            false => Err(ProofError::ProofProviderNotDefined),
+        };
+    };
+    let Ok(constants_digest) = dotenv::var("BRIDGE_RISC0_CONSTANTS_DIGEST") else {
+        return Ok(Box::new(FileProofProvider::new(PathBuf::from(directory))));
+    };
+
+    let constants_digest = <[u8; 32]>::from_hex(&constants_digest).map_err(|e| {
+        ProofError::InvalidFormat(format!("Invalid risc0 constants digest hex: {e}"))
+    })?;
+    let verifying_key = verifying_key.ok_or(ProofError::VerifyingKeyNotDefined)?;
+
+    Ok(Box::new(Risc0ProofProvider::new(
+        PathBuf::from(directory),
+        verifying_key.clone(),
+        constants_digest,
+    )))
+}
@@ -1,5 +1,6 @@
 use bitcoin::{
     hashes::{ripemd160::Hash as Ripemd160, sha256::Hash as Sha256, Hash},
+    opcodes::all::OP_PUSHNUM_1,
     Address, CompressedPublicKey, Network, PubkeyHash, PublicKey, ScriptBuf, XOnlyPublicKey,
 };
 use bitvm::treepp::script;
@@ -29,6 +30,15 @@ pub fn generate_burn_taproot_script() -> ScriptBuf {
     generate_pay_to_pubkey_taproot_script(&UNSPENDABLE_TAPROOT_PUBLIC_KEY)
 }
 
+// Pay-to-anchor (P2A): a witness v1 program anyone can spend with an empty witness. Standard since
+// bitcoind 28.0.
+pub fn generate_pay_to_anchor_script() -> ScriptBuf {
+    ScriptBuf::builder()
+        .push_opcode(OP_PUSHNUM_1)
+        .push_slice([0x4e, 0x73])
+        .into_script()
+}
+
 pub fn generate_pay_to_pubkey_script(public_key: &PublicKey) -> ScriptBuf {
     script! {
         { *public_key }
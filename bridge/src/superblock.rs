@@ -1,44 +1,148 @@
-use std::{mem::size_of, str::FromStr};
+use std::mem::size_of;
 
-use bitcoin::{
-    block::{Header, Version},
-    consensus::encode::serialize,
-    hashes::Hash,
-    BlockHash, CompactTarget, Network, TxMerkleNode,
-};
+use async_trait::async_trait;
+use bitcoin::{block::Header, consensus::encode::serialize, hashes::Hash, BlockHash, Witness};
 use bitcoin_script::{script, Script};
 
-use bitvm::pseudo::NMUL;
+use bitvm::{
+    pseudo::NMUL,
+    signatures::{signing_winternitz::LOG_D, winternitz::Parameters},
+};
+
+use crate::{
+    client::bitcoin_backend::bitcoin_backend::BitcoinBackend, constants::START_TIME_MESSAGE_LENGTH,
+    error::BitcoinBackendError,
+};
+
+// Length of the superblock measurement period ∆C that starts at the committed start time.
+// Blocks whose timestamp is strictly inside (start_time, start_time + period) are candidates.
+pub const SUPERBLOCK_MEASUREMENT_PERIOD_SECONDS: u32 = 60 * 60 * 24 * 14;
+
+// Number of blocks the median time past is computed over, see BIP113.
+const MEDIAN_TIME_SPAN: u32 = 11;
+
+// Source of the block headers of the best chain scanned for superblocks.
+#[async_trait]
+pub trait HeaderSource: Send + Sync {
+    async fn get_best_height(&self) -> Result<u32, BitcoinBackendError>;
+    async fn get_header(&self, height: u32) -> Result<Header, BitcoinBackendError>;
+}
+
+#[async_trait]
+impl<T: BitcoinBackend + ?Sized> HeaderSource for T {
+    async fn get_best_height(&self) -> Result<u32, BitcoinBackendError> {
+        self.get_height().await
+    }
+
+    async fn get_header(&self, height: u32) -> Result<Header, BitcoinBackendError> {
+        self.get_block_header(height).await
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Superblock {
+    pub height: u32,
+    pub header: Header,
+}
+
+// Median of the timestamps of the block at `height` and the 10 blocks before it.
+pub async fn get_median_time_past(
+    source: &(impl HeaderSource + ?Sized),
+    height: u32,
+) -> Result<u32, BitcoinBackendError> {
+    let mut times = vec![];
+    for height in height.saturating_sub(MEDIAN_TIME_SPAN - 1)..=height {
+        times.push(source.get_header(height).await?.time);
+    }
+    times.sort_unstable();
+
+    Ok(times[times.len() / 2])
+}
+
+// The start time an operator commits to when the measurement period begins at the block at
+// `height`: its median time past, which is also the earliest absolute lock time the start time
+// transaction can use.
+pub async fn get_start_time(
+    source: &(impl HeaderSource + ?Sized),
+    height: u32,
+) -> Result<u32, BitcoinBackendError> {
+    get_median_time_past(source, height).await
+}
+
+pub fn is_in_measurement_period(header: &Header, start_time: u32) -> bool {
+    header.time > start_time
+        && (header.time as u64) < start_time as u64 + SUPERBLOCK_MEASUREMENT_PERIOD_SECONDS as u64
+}
+
+// Block hashes compared as 256-bit numbers, the way the disprove chain script compares them.
+fn hash_as_number(block_hash: &BlockHash) -> [u8; 32] {
+    let mut number = block_hash.to_byte_array();
+    number.reverse();
+    number
+}
+
+// A superblock is heavier than another one if its hash is lower.
+pub fn is_heavier(block_hash: &BlockHash, than: &BlockHash) -> bool {
+    hash_as_number(block_hash) < hash_as_number(than)
+}
 
 /*
-  TODO: Implement selecting a block that marks the start of a superblock measurement period
-  that lasts for the period ∆C (e.g. 2000 blocks), during which the operator must observe
-  all blocks on the main chain and identify the heaviest superblock SB.
+  Scans the best chain from `start_height` for the heaviest block of the measurement period
+  that starts at `start_time`. Block timestamps are not monotonic, so blocks before
+  `start_height` (usually the block that confirmed Kick Off 1) are not considered even if their
+  timestamp is after `start_time`. The scan stops at the tip, or as soon as the median time past
+  reaches the end of the period, since no later block can have an earlier timestamp.
+  Returns `None` if no scanned block is in the measurement period.
 */
-pub fn get_start_time_block_number(network: Network) -> u32 {
-    match network {
-        Network::Bitcoin => 161249,
-        Network::Regtest => 100,
-        _ => 161249,
+pub async fn find_superblock(
+    source: &(impl HeaderSource + ?Sized),
+    start_time: u32,
+    start_height: u32,
+) -> Result<Option<Superblock>, BitcoinBackendError> {
+    let end_time = start_time as u64 + SUPERBLOCK_MEASUREMENT_PERIOD_SECONDS as u64;
+    let best_height = source.get_best_height().await?;
+
+    let mut superblock: Option<Superblock> = None;
+    let mut recent_times = vec![];
+    for height in start_height..=best_height {
+        let header = source.get_header(height).await?;
+        if is_in_measurement_period(&header, start_time)
+            && superblock.is_none_or(|superblock| {
+                is_heavier(&header.block_hash(), &superblock.header.block_hash())
+            })
+        {
+            superblock = Some(Superblock { height, header });
+        }
+
+        recent_times.push(header.time);
+        if recent_times.len() > MEDIAN_TIME_SPAN as usize {
+            recent_times.remove(0);
+        }
+        if recent_times.len() == MEDIAN_TIME_SPAN as usize {
+            let mut sorted_times = recent_times.clone();
+            sorted_times.sort_unstable();
+            if sorted_times[sorted_times.len() / 2] as u64 >= end_time {
+                break;
+            }
+        }
     }
+
+    Ok(superblock)
 }
 
-// TODO: Replace with a real superblock
-pub fn find_superblock() -> Header {
-    Header {
-        version: Version::from_consensus(0x200d2000),
-        prev_blockhash: BlockHash::from_str(
-            "000000000000000000027c9f5b07f21e39ba31aa4d900d519478bdac32f4a15d",
-        )
-        .unwrap(),
-        merkle_root: TxMerkleNode::from_str(
-            "0064b0d54f20412756ba7ce07b0594f3548b06f2dad5cfeaac2aca508634ed19",
-        )
-        .unwrap(),
-        time: 1729251961,
-        bits: CompactTarget::from_hex("0x17030ecd").unwrap(),
-        nonce: 0x400e345c,
-    }
+// Verifier side: returns the heaviest superblock of the measurement period if it is heavier
+// than the one the operator committed to, i.e. if the committed superblock can be disproven.
+pub async fn find_disprove_superblock(
+    source: &(impl HeaderSource + ?Sized),
+    start_time: u32,
+    start_height: u32,
+    committed_superblock_hash: &BlockHash,
+) -> Result<Option<Superblock>, BitcoinBackendError> {
+    Ok(find_superblock(source, start_time, start_height)
+        .await?
+        .filter(|superblock| {
+            is_heavier(&superblock.header.block_hash(), committed_superblock_hash)
+        }))
 }
 
 pub fn get_superblock_message(sb: &Header) -> Vec<u8> {
@@ -59,3 +163,64 @@ pub fn extract_superblock_ts_from_header() -> Script {
         for _ in 1..4 {  { NMUL(1 << 8) } OP_ADD }
     }
 }
+
+/*
+  Witness expected by `ConnectorB::generate_taproot_leaf_2_script` before the n-of-n signature:
+  - SB', the disprove superblock, one byte per element, first byte on top of the stack
+  - the committed start time (Winternitz witness of the Start Time tx)
+  - the committed SB hash (Winternitz witness of the Kick Off 2 tx)
+*/
+pub fn get_disprove_chain_witness(
+    disprove_sb: &Header,
+    start_time_witness: &Witness,
+    superblock_hash_witness: &Witness,
+) -> Vec<Vec<u8>> {
+    let mut disprove_sb_message = get_superblock_message(disprove_sb);
+    disprove_sb_message.reverse();
+
+    let mut unlock_data: Vec<Vec<u8>> = disprove_sb_message
+        .into_iter()
+        .map(|byte| vec![byte])
+        .collect();
+    unlock_data.extend(start_time_witness.to_vec());
+    unlock_data.extend(superblock_hash_witness.to_vec());
+    unlock_data
+}
+
+// Recovers the message signed by a Winternitz witness generated with
+// `generate_winternitz_witness`. The signature itself is not verified.
+fn get_winternitz_message(witness: &Witness, message_len: usize) -> Option<Vec<u8>> {
+    let parameters = Parameters::new_by_bit_length(message_len as u32 * 8, LOG_D);
+    if witness.len() != 2 * parameters.total_digit_len() as usize {
+        return None;
+    }
+
+    // The witness holds (signature, digit) pairs, message digits first and in reverse order.
+    let mut digits = vec![];
+    for i in 0..message_len * 2 {
+        let digit = witness.nth(2 * i + 1)?;
+        match digit {
+            [] => digits.push(0),
+            [digit] if *digit < 1 << LOG_D => digits.push(*digit),
+            _ => return None,
+        }
+    }
+    digits.reverse();
+
+    Some(
+        digits
+            .chunks(2)
+            .map(|digits| digits[0] | (digits[1] << LOG_D))
+            .collect(),
+    )
+}
+
+pub fn get_committed_start_time(start_time_witness: &Witness) -> Option<u32> {
+    get_winternitz_message(start_time_witness, START_TIME_MESSAGE_LENGTH)
+        .map(|message| u32::from_le_bytes(message.try_into().unwrap()))
+}
+
+pub fn get_committed_superblock_hash(superblock_hash_witness: &Witness) -> Option<BlockHash> {
+    get_winternitz_message(superblock_hash_witness, SUPERBLOCK_HASH_MESSAGE_LENGTH)
+        .map(|message| BlockHash::from_byte_array(message.try_into().unwrap()))
+}
@@ -0,0 +1,125 @@
+use bitcoin::Network;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    constants::{
+        NUM_BLOCKS_PER_2_WEEKS, NUM_BLOCKS_PER_3_DAYS, NUM_BLOCKS_PER_6_HOURS, NUM_BLOCKS_PER_DAY,
+    },
+    error::GraphError,
+};
+
+const NUM_BLOCKS_REGTEST: u32 = 2;
+const NUM_BLOCKS_TESTNET: u32 = 2;
+
+// Relative timelocks are enforced with nSequence, which holds at most 16 bits of blocks (BIP 68).
+pub const MAX_RELATIVE_TIMELOCK: u32 = 0xFFFF;
+
+/*
+  Number of blocks each relative timelock of the graphs lasts. The policy is recorded in the graphs,
+  so that verifiers rebuild their connectors with the timelocks the operator or depositor used.
+*/
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimelockPolicy {
+    // Connector 1 leaf 0: superblock measurement period after kick off 1, before kick off 2.
+    pub kick_off_2: u32,
+    // Connector 1 leaf 1: lets verifiers time out an operator that never broadcasts kick off 2.
+    pub kick_off_timeout: u32,
+    // Connector 1 leaf 2: lets verifiers time out an operator that never commits the start time.
+    pub start_time_timeout: u32,
+    // Connector 3 and connector B leaf 1: challenge and assert window after kick off 2, before take 1.
+    pub take_1: u32,
+    // Connector 4: disprove window after the assert final transaction, before take 2.
+    pub take_2: u32,
+    // Connector Z leaf 0: lets the depositor take back a peg-in the verifiers never confirm.
+    pub peg_in_refund: u32,
+}
+
+impl TimelockPolicy {
+    // Mainnet windows, and 2 blocks for every timelock on the test networks.
+    pub fn for_network(network: Network) -> Self {
+        match network {
+            Network::Bitcoin => Self::mainnet(),
+            Network::Regtest => Self::uniform(NUM_BLOCKS_REGTEST),
+            _ => Self::uniform(NUM_BLOCKS_TESTNET), // Testnet, Signet
+        }
+    }
+
+    pub fn mainnet() -> Self {
+        TimelockPolicy {
+            kick_off_2: NUM_BLOCKS_PER_2_WEEKS,
+            kick_off_timeout: NUM_BLOCKS_PER_2_WEEKS + NUM_BLOCKS_PER_DAY,
+            start_time_timeout: NUM_BLOCKS_PER_6_HOURS,
+            take_1: NUM_BLOCKS_PER_3_DAYS,
+            take_2: NUM_BLOCKS_PER_2_WEEKS,
+            peg_in_refund: NUM_BLOCKS_PER_2_WEEKS,
+        }
+    }
+
+    pub fn uniform(num_blocks: u32) -> Self {
+        TimelockPolicy {
+            kick_off_2: num_blocks,
+            kick_off_timeout: num_blocks,
+            start_time_timeout: num_blocks,
+            take_1: num_blocks,
+            take_2: num_blocks,
+            peg_in_refund: num_blocks,
+        }
+    }
+
+    pub fn max_num_blocks(&self) -> u32 {
+        self.timelocks()
+            .iter()
+            .map(|(_, num_blocks)| *num_blocks)
+            .max()
+            .unwrap()
+    }
+
+    pub fn validate(&self) -> Result<(), GraphError> {
+        for (name, num_blocks) in self.timelocks() {
+            if num_blocks == 0 || num_blocks > MAX_RELATIVE_TIMELOCK {
+                return Err(GraphError::InvalidTimelockPolicy(format!(
+                    "{name} timelock must be between 1 and {MAX_RELATIVE_TIMELOCK} blocks"
+                )));
+            }
+        }
+        // Verifiers must not be able to time out kick off 1 before the operator can broadcast kick off 2.
+        if self.kick_off_timeout < self.kick_off_2 {
+            return Err(GraphError::InvalidTimelockPolicy(
+                "kick_off_timeout timelock must not be shorter than kick_off_2 timelock"
+                    .to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn timelocks(&self) -> [(&'static str, u32); 6] {
+        [
+            ("kick_off_2", self.kick_off_2),
+            ("kick_off_timeout", self.kick_off_timeout),
+            ("start_time_timeout", self.start_time_timeout),
+            ("take_1", self.take_1),
+            ("take_2", self.take_2),
+            ("peg_in_refund", self.peg_in_refund),
+        ]
+    }
+}
+
+/*
+  BRIDGE_TIMELOCK_POLICY_FILE overrides the default policy of the network with a JSON file holding
+  every field of `TimelockPolicy`.
+*/
+pub fn get_timelock_policy(network: Network) -> Result<TimelockPolicy, GraphError> {
+    dotenv::dotenv().ok();
+    let Ok(path) = dotenv::var("BRIDGE_TIMELOCK_POLICY_FILE") else {
+        return Ok(TimelockPolicy::for_network(network));
+    };
+
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| GraphError::InvalidTimelockPolicy(format!("Could not read {path}: {e}")))?;
+    let timelock_policy: TimelockPolicy = serde_json::from_str(&contents)
+        .map_err(|e| GraphError::InvalidTimelockPolicy(format!("Could not parse {path}: {e}")))?;
+    timelock_policy.validate()?;
+
+    Ok(timelock_policy)
+}
@@ -79,8 +79,10 @@ impl AssertCommit1Transaction {
         }
         total_output_amount -= Amount::from_sat(MIN_RELAY_FEE_ASSERT_COMMIT1);
 
+        let _output_1 = generate_anchor_output();
+
         let _output_0 = TxOut {
-            value: total_output_amount,
+            value: total_output_amount - _output_1.value,
             script_pubkey: connector_f_1.generate_address().script_pubkey(),
         };
 
@@ -89,7 +91,7 @@ impl AssertCommit1Transaction {
                 version: bitcoin::transaction::Version(2),
                 lock_time: absolute::LockTime::ZERO,
                 input: inputs,
-                output: vec![_output_0],
+                output: vec![_output_0, _output_1],
             },
             prev_outs,
             prev_scripts,
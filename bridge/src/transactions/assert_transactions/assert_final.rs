@@ -143,9 +143,11 @@ impl AssertFinalTransaction {
             script_pubkey: connector_4.generate_address().script_pubkey(),
         };
 
+        let _output_3 = generate_anchor_output();
+
         // goes to take_2 tx or disprove tx
         let _output_1 = TxOut {
-            value: total_output_amount - Amount::from_sat(DUST_AMOUNT) * 2,
+            value: total_output_amount - Amount::from_sat(DUST_AMOUNT) * 2 - _output_3.value,
             script_pubkey: connector_5.generate_taproot_address().script_pubkey(),
         };
 
@@ -160,7 +162,7 @@ impl AssertFinalTransaction {
                 version: bitcoin::transaction::Version(2),
                 lock_time: absolute::LockTime::ZERO,
                 input: vec![_input_0, _input_1, _input_2],
-                output: vec![_output_0, _output_1, _output_2],
+                output: vec![_output_0, _output_1, _output_2, _output_3],
             },
             prev_outs: vec![
                 TxOut {
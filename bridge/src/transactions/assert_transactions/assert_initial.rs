@@ -10,7 +10,7 @@ use super::{
         super::{
             connectors::{base::*, connector_b::ConnectorB, connector_d::ConnectorD},
             contexts::{base::BaseContext, verifier::VerifierContext},
-            graphs::base::DUST_AMOUNT,
+            graphs::base::{ANCHOR_AMOUNT, DUST_AMOUNT},
         },
         base::*,
         pre_signed::*,
@@ -109,15 +109,22 @@ impl AssertInitialTransaction {
 
         let assert_commit1_expense = Amount::from_sat(
             MIN_RELAY_FEE_ASSERT_COMMIT1
+                + ANCHOR_AMOUNT
                 + assert_commit1_connectors_e.connectors_num() as u64 * DUST_AMOUNT,
         );
         let assert_commit2_expense = Amount::from_sat(
             MIN_RELAY_FEE_ASSERT_COMMIT2
+                + ANCHOR_AMOUNT
                 + assert_commit2_connectors_e.connectors_num() as u64 * DUST_AMOUNT,
         );
+        let anchor_output = generate_anchor_output();
+
         // goes to assert_final
         let _output_0 = TxOut {
-            value: total_output_amount - assert_commit1_expense - assert_commit2_expense,
+            value: total_output_amount
+                - assert_commit1_expense
+                - assert_commit2_expense
+                - anchor_output.value,
             script_pubkey: connector_d.generate_taproot_address().script_pubkey(),
         };
 
@@ -126,7 +133,7 @@ impl AssertInitialTransaction {
         // simple outputs for assert_x txs
         for i in 0..assert_commit1_connectors_e.connectors_num() {
             let amount = if i == 0 {
-                MIN_RELAY_FEE_ASSERT_COMMIT1 + DUST_AMOUNT
+                MIN_RELAY_FEE_ASSERT_COMMIT1 + ANCHOR_AMOUNT + DUST_AMOUNT
             } else {
                 DUST_AMOUNT
             };
@@ -142,7 +149,7 @@ impl AssertInitialTransaction {
         // simple outputs for assert_x txs
         for i in 0..assert_commit2_connectors_e.connectors_num() {
             let amount = if i == 0 {
-                MIN_RELAY_FEE_ASSERT_COMMIT2 + DUST_AMOUNT
+                MIN_RELAY_FEE_ASSERT_COMMIT2 + ANCHOR_AMOUNT + DUST_AMOUNT
             } else {
                 DUST_AMOUNT
             };
@@ -155,6 +162,8 @@ impl AssertInitialTransaction {
             });
         }
 
+        output.push(anchor_output);
+
         AssertInitialTransaction {
             tx: Transaction {
                 version: bitcoin::transaction::Version(2),
@@ -1,15 +1,14 @@
-use bitcoin::hex::DisplayHex;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
 
 use crate::{
-    commitments::CommitmentMessageId,
+    commitments::{groth16_chunk_secret_keys, CommitmentMessageId},
     connectors::{connector_e::ConnectorE, connector_f_1::ConnectorF1, connector_f_2::ConnectorF2},
 };
 
 use bitvm::{
     chunk::api::{
-        generate_signatures_for_any_proof,
+        generate_signatures_for_any_proof, num_pubs,
         type_conversion_utils::{utils_raw_witnesses_from_signatures, RawProof, RawWitness},
     },
     signatures::signing_winternitz::{WinternitzPublicKey, WinternitzSecret},
@@ -70,22 +69,13 @@ pub fn sign_assert_tx_with_groth16_proof(
     proof: &RawProof,
 ) -> (Vec<RawWitness>, Vec<RawWitness>) {
     println!("Signing assert tx with groth16 proof ...");
-    let mut sorted_secrets: Vec<(u32, String)> = vec![];
-    commitment_secrets.clone().into_iter().for_each(|(k, v)| {
-        if let CommitmentMessageId::Groth16IntermediateValues((name, _)) = k {
-            let index = u32::from_str_radix(&name, 10).unwrap();
-            sorted_secrets.push((index, v.secret_key.to_lower_hex_string()));
-        }
-    });
-
-    sorted_secrets.sort_by(|a, b| a.0.cmp(&b.0));
-    let secrets = sorted_secrets.iter().map(|f| f.1.clone()).collect();
+    let secrets = groth16_chunk_secret_keys(commitment_secrets, num_pubs(&proof.vk));
 
     let sigs = generate_signatures_for_any_proof(
         proof.proof.clone(),
         proof.public.clone(),
         &proof.vk,
-        secrets,
+        &secrets,
     );
 
     let raw = utils_raw_witnesses_from_signatures(&sigs);
@@ -1,34 +1,38 @@
 use super::pre_signed_musig2::{verify_public_nonce, PreSignedMusig2Transaction};
 use crate::{
-    error::{Error, ValidationError},
-    graphs::base::MIN_RELAY_FEE_RATE,
+    client::bitcoin_backend::bitcoin_backend::TxStatus,
+    error::{BitcoinBackendError, Error, ValidationError},
+    graphs::base::{ANCHOR_AMOUNT, MIN_RELAY_FEE_RATE},
+    scripts::generate_pay_to_anchor_script,
 };
-use bitcoin::{Amount, OutPoint, PublicKey, Script, Transaction, Txid, XOnlyPublicKey};
+use bitcoin::{Amount, OutPoint, PublicKey, Script, Transaction, TxOut, Txid, XOnlyPublicKey};
 use core::cmp;
-use esplora_client::TxStatus;
 use itertools::Itertools;
 use musig2::{secp256k1::schnorr::Signature, PubNonce};
 use std::collections::HashMap;
 
 // TODO: set to larger value to be compatible with future tx modifications
 pub const RELAY_FEE_BUFFER_MULTIPLIER: f32 = 1.0;
-pub const MIN_RELAY_FEE_KICK_OFF_1: u64 = relay_fee(6231);
+// value (8) + script length (1) + OP_1 OP_PUSHBYTES_2 4e73 (4)
+pub const ANCHOR_OUTPUT_VSIZE: usize = 13;
+pub const MIN_RELAY_FEE_KICK_OFF_1: u64 = relay_fee(6231 + ANCHOR_OUTPUT_VSIZE);
 pub const MIN_RELAY_FEE_START_TIME: u64 = relay_fee(407);
 pub const MIN_RELAY_FEE_START_TIME_TIMEOUT: u64 = relay_fee(265);
-pub const MIN_RELAY_FEE_KICK_OFF_2: u64 = relay_fee(5461);
+pub const MIN_RELAY_FEE_KICK_OFF_2: u64 = relay_fee(5461 + ANCHOR_OUTPUT_VSIZE);
 pub const MIN_RELAY_FEE_KICK_OFF_TIMEOUT: u64 = relay_fee(182);
 pub const MIN_RELAY_FEE_TAKE_1: u64 = relay_fee(380);
 pub const MIN_RELAY_FEE_TAKE_2: u64 = relay_fee(347);
 pub const MIN_RELAY_FEE_PEG_IN_DEPOSIT: u64 = relay_fee(122);
 pub const MIN_RELAY_FEE_PEG_IN_CONFIRM: u64 = relay_fee(173);
 pub const MIN_RELAY_FEE_PEG_IN_REFUND: u64 = relay_fee(138);
-pub const MIN_RELAY_FEE_PEG_OUT: u64 = relay_fee(122);
+pub const PEG_OUT_VSIZE: usize = 122;
+pub const MIN_RELAY_FEE_PEG_OUT: u64 = relay_fee(PEG_OUT_VSIZE);
 pub const MIN_RELAY_FEE_PEG_OUT_CONFIRM: u64 = relay_fee(122);
 pub const MIN_RELAY_FEE_ASSERT: u64 = relay_fee(232);
-pub const MIN_RELAY_FEE_ASSERT_INITIAL: u64 = relay_fee(48953);
-pub const MIN_RELAY_FEE_ASSERT_COMMIT1: u64 = relay_fee(739137);
-pub const MIN_RELAY_FEE_ASSERT_COMMIT2: u64 = relay_fee(470440);
-pub const MIN_RELAY_FEE_ASSERT_FINAL: u64 = relay_fee(352);
+pub const MIN_RELAY_FEE_ASSERT_INITIAL: u64 = relay_fee(48953 + ANCHOR_OUTPUT_VSIZE);
+pub const MIN_RELAY_FEE_ASSERT_COMMIT1: u64 = relay_fee(739137 + ANCHOR_OUTPUT_VSIZE);
+pub const MIN_RELAY_FEE_ASSERT_COMMIT2: u64 = relay_fee(470440 + ANCHOR_OUTPUT_VSIZE);
+pub const MIN_RELAY_FEE_ASSERT_FINAL: u64 = relay_fee(352 + ANCHOR_OUTPUT_VSIZE);
 pub const MIN_RELAY_FEE_CHALLENGE: u64 = relay_fee(317);
 pub const MIN_RELAY_FEE_DISPROVE: u64 = relay_fee(238785);
 pub const MIN_RELAY_FEE_DISPROVE_CHAIN: u64 = relay_fee(389370);
@@ -60,6 +64,24 @@ pub const fn relay_fee(vsize: usize) -> u64 {
     (vsize as f32 * RELAY_FEE_BUFFER_MULTIPLIER) as u64 * MIN_RELAY_FEE_RATE
 }
 
+// Keyless pay-to-anchor output appended to the operator's kick off and assert transactions. They
+// are pre-signed with SIGHASH_ALL at the minimum relay fee, so a CPFP child spending the anchor is
+// the only way to raise their fee rate once fees rise (see `transactions::cpfp`).
+pub fn generate_anchor_output() -> TxOut {
+    TxOut {
+        value: Amount::from_sat(ANCHOR_AMOUNT),
+        script_pubkey: generate_pay_to_anchor_script(),
+    }
+}
+
+pub fn anchor_vout(tx: &Transaction) -> Option<u32> {
+    let anchor_script = generate_pay_to_anchor_script();
+    tx.output
+        .iter()
+        .position(|output| output.script_pubkey == anchor_script)
+        .map(|vout| vout as u32)
+}
+
 pub fn merge_transactions(
     destination_transaction: &mut Transaction,
     source_transaction: &Transaction,
@@ -163,14 +185,14 @@ pub fn validate_transaction(
 pub fn validate_witness(
     tx: &Transaction,
     tx_name: &'static str,
-    tx_status_res: Result<TxStatus, esplora_client::Error>,
-    onchain_tx_res: Result<Option<Transaction>, esplora_client::Error>,
+    tx_status_res: Result<TxStatus, BitcoinBackendError>,
+    onchain_tx_res: Result<Option<Transaction>, BitcoinBackendError>,
 ) -> Result<(), Error> {
     let txid = tx.compute_txid();
-    let tx_status = tx_status_res.map_err(Error::Esplora)?;
+    let tx_status = tx_status_res.map_err(Error::BitcoinBackend)?;
 
     if tx_status.confirmed {
-        let result_tx = onchain_tx_res.map_err(Error::Esplora)?;
+        let result_tx = onchain_tx_res.map_err(Error::BitcoinBackend)?;
         match result_tx {
             Some(onchain_tx) => {
                 for i in 0..tx.input.len() {
@@ -240,10 +262,10 @@ mod tests {
         },
         Amount, OutPoint, PublicKey, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid, Witness,
     };
-    use esplora_client::TxStatus;
     use musig2::{secp256k1::schnorr::Signature, PubNonce};
 
     use crate::{
+        client::bitcoin_backend::bitcoin_backend::TxStatus,
         contexts::base::generate_keys_from_secret,
         error::{Error, ValidationError},
         transactions::{pre_signed_musig2::get_nonce_message, signing_musig2::generate_nonce},
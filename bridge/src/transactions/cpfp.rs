@@ -0,0 +1,136 @@
+use bitcoin::{
+    absolute, key::Keypair, transaction::Version, Amount, EcdsaSighashType, FeeRate, Network,
+    OutPoint, PublicKey, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Weight, Witness,
+};
+
+use super::{
+    super::{
+        client::bitcoin_backend::bitcoin_backend::min_relay_fee_rate,
+        error::FeeBumpError,
+        graphs::base::DUST_AMOUNT,
+        scripts::{
+            generate_pay_to_anchor_script, generate_pay_to_pubkey_script,
+            generate_pay_to_pubkey_script_address,
+        },
+    },
+    signing::populate_p2wsh_witness,
+};
+
+// Fee rate of unconfirmed transactions mined together, given with the fee each of them pays.
+pub fn package_fee_rate(package: &[(Transaction, Amount)]) -> FeeRate {
+    let weight: u64 = package.iter().map(|(tx, _)| tx.weight().to_wu()).sum();
+    let fee: Amount = package.iter().map(|(_, fee)| *fee).sum();
+    if weight == 0 {
+        return FeeRate::ZERO;
+    }
+
+    FeeRate::from_sat_per_kwu(fee.to_sat() * 1000 / weight)
+}
+
+/*
+  Builds a CPFP child raising the fee rate of `package` (a graph transaction and the children
+  already attached to it) to `fee_rate`. The child spends `spent_output` of the package, which
+  must be a keyless anchor or pay to the key of the fee bumper, e.g. the reward output of a
+  verifier or the output of take 1, and as many of `funding_utxos` as needed. The change goes
+  back to the fee bumper, so a later bump can chain another child onto it.
+
+  Returns the child and the fee it pays.
+*/
+pub fn generate_cpfp_tx(
+    network: Network,
+    keypair: &Keypair,
+    package: &[(Transaction, Amount)],
+    spent_output: (OutPoint, TxOut),
+    funding_utxos: &[(OutPoint, TxOut)],
+    fee_rate: FeeRate,
+) -> Result<(Transaction, Amount), FeeBumpError> {
+    let public_key = PublicKey::new(keypair.public_key());
+    let script = generate_pay_to_pubkey_script(&public_key);
+    let script_pubkey = generate_pay_to_pubkey_script_address(network, &public_key).script_pubkey();
+    if spent_output.1.script_pubkey != generate_pay_to_anchor_script()
+        && spent_output.1.script_pubkey != script_pubkey
+    {
+        return Err(FeeBumpError::UnspendableOutput(spent_output.0));
+    }
+
+    let package_weight = package
+        .iter()
+        .fold(Weight::ZERO, |weight, (tx, _)| weight + tx.weight());
+    let package_fee: Amount = package.iter().map(|(_, fee)| *fee).sum();
+
+    let mut inputs = vec![spent_output];
+    let mut funding_utxos = funding_utxos
+        .iter()
+        .filter(|(_, output)| output.script_pubkey == script_pubkey);
+    loop {
+        let input_amount: Amount = inputs.iter().map(|(_, output)| output.value).sum();
+        let mut tx = Transaction {
+            version: Version(2),
+            lock_time: absolute::LockTime::ZERO,
+            input: inputs
+                .iter()
+                .map(|(outpoint, _)| TxIn {
+                    previous_output: *outpoint,
+                    script_sig: ScriptBuf::new(),
+                    sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                    witness: Witness::default(),
+                })
+                .collect(),
+            output: vec![TxOut {
+                value: input_amount,
+                script_pubkey: script_pubkey.clone(),
+            }],
+        };
+        sign_inputs(&mut tx, &inputs, &script, keypair);
+
+        // ECDSA signatures vary in length by a byte, so allow one more byte per signed input
+        let child_weight = tx.weight() + Weight::from_vb_unchecked(inputs.len() as u64);
+        let child_fee = fee_rate
+            .fee_wu(package_weight + child_weight)
+            .unwrap_or(Amount::MAX_MONEY)
+            .checked_sub(package_fee)
+            .unwrap_or(Amount::ZERO)
+            .max(min_relay_fee_rate().fee_wu(child_weight).unwrap());
+
+        let required_amount = child_fee + Amount::from_sat(DUST_AMOUNT);
+        if input_amount >= required_amount {
+            tx.output[0].value = input_amount - child_fee;
+            tx.input
+                .iter_mut()
+                .for_each(|input| input.witness = Witness::default());
+            sign_inputs(&mut tx, &inputs, &script, keypair);
+
+            return Ok((tx, child_fee));
+        }
+        match funding_utxos.next() {
+            Some(funding_utxo) => inputs.push(funding_utxo.clone()),
+            None => {
+                return Err(FeeBumpError::InsufficientFunds(
+                    required_amount - input_amount,
+                ))
+            }
+        }
+    }
+}
+
+// Anchors are spent with an empty witness, every other input pays to the fee bumper.
+fn sign_inputs(
+    tx: &mut Transaction,
+    inputs: &[(OutPoint, TxOut)],
+    script: &ScriptBuf,
+    keypair: &Keypair,
+) {
+    for (input_index, (_, output)) in inputs.iter().enumerate() {
+        if output.script_pubkey == generate_pay_to_anchor_script() {
+            continue;
+        }
+        populate_p2wsh_witness(
+            tx,
+            input_index,
+            EcdsaSighashType::All,
+            script,
+            output.value,
+            &vec![keypair],
+        );
+    }
+}
@@ -6,7 +6,7 @@ use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use crate::{connectors::base::TaprootConnector, superblock::get_superblock_message};
+use crate::{connectors::base::TaprootConnector, superblock::get_disprove_chain_witness};
 
 use super::{
     super::{
@@ -174,18 +174,8 @@ impl DisproveChainTransaction {
         superblock_hash_witness: &Witness,
     ) {
         let input_index = 0;
-        let mut unlock_data: Vec<Vec<u8>> = Vec::new();
-
-        // Constructing the witness as follows:
-        // SB'
-        // Committed start time
-        // Committed SB hash
-
-        let mut disprove_sb_message = get_superblock_message(disprove_sb);
-        disprove_sb_message.reverse();
-        unlock_data.extend(disprove_sb_message.into_iter().map(|byte| vec![byte]));
-        unlock_data.extend(start_time_witness.to_vec());
-        unlock_data.extend(superblock_hash_witness.to_vec());
+        let unlock_data =
+            get_disprove_chain_witness(disprove_sb, start_time_witness, superblock_hash_witness);
 
         push_taproot_leaf_unlock_data_to_witness(self.tx_mut(), input_index, unlock_data);
     }
@@ -96,8 +96,10 @@ impl KickOff1Transaction {
             script_pubkey: connector_2.generate_taproot_address().script_pubkey(),
         };
 
+        let _output_3 = generate_anchor_output();
+
         let _output_1 = TxOut {
-            value: total_output_amount - _output_0.value - _output_2.value,
+            value: total_output_amount - _output_0.value - _output_2.value - _output_3.value,
             script_pubkey: connector_1.generate_taproot_address().script_pubkey(),
         };
 
@@ -106,7 +108,7 @@ impl KickOff1Transaction {
                 version: bitcoin::transaction::Version(2),
                 lock_time: absolute::LockTime::ZERO,
                 input: vec![_input_0],
-                output: vec![_output_0, _output_1, _output_2],
+                output: vec![_output_0, _output_1, _output_2, _output_3],
             },
             prev_outs: vec![TxOut {
                 value: input_0.amount,
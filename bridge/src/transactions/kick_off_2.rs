@@ -1,6 +1,5 @@
 use bitcoin::{
-    absolute, consensus, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
-    Witness,
+    absolute, consensus, Amount, ScriptBuf, TapSighashType, Transaction, TxOut, Witness,
 };
 use serde::{Deserialize, Serialize};
 
@@ -52,29 +51,20 @@ impl PreSignedTransaction for KickOff2Transaction {
 
 impl KickOff2Transaction {
     pub fn new(
-        context: &OperatorContext,
         connector_1: &Connector1,
+        connector_3: &Connector3,
         connector_b: &ConnectorB,
         input_0: Input,
     ) -> Self {
-        Self::new_for_validation(
-            context.network,
-            &context.operator_public_key,
-            connector_1,
-            connector_b,
-            input_0,
-        )
+        Self::new_for_validation(connector_1, connector_3, connector_b, input_0)
     }
 
     pub fn new_for_validation(
-        network: Network,
-        operator_public_key: &PublicKey,
         connector_1: &Connector1,
+        connector_3: &Connector3,
         connector_b: &ConnectorB,
         input_0: Input,
     ) -> Self {
-        let connector_3 = Connector3::new(network, operator_public_key);
-
         let input_0_leaf = 0;
         let _input_0 = connector_1.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
 
@@ -85,8 +75,10 @@ impl KickOff2Transaction {
             script_pubkey: connector_3.generate_address().script_pubkey(),
         };
 
+        let _output_2 = generate_anchor_output();
+
         let _output_1 = TxOut {
-            value: total_output_amount - _output_0.value,
+            value: total_output_amount - _output_0.value - _output_2.value,
             script_pubkey: connector_b.generate_taproot_address().script_pubkey(),
         };
 
@@ -95,7 +87,7 @@ impl KickOff2Transaction {
                 version: bitcoin::transaction::Version(2),
                 lock_time: absolute::LockTime::ZERO,
                 input: vec![_input_0],
-                output: vec![_output_0, _output_1],
+                output: vec![_output_0, _output_1, _output_2],
             },
             prev_outs: vec![TxOut {
                 value: input_0.amount,
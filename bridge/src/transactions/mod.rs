@@ -2,6 +2,7 @@ pub mod assert;
 pub mod assert_transactions;
 pub mod base;
 pub mod challenge;
+pub mod cpfp;
 pub mod disprove;
 pub mod disprove_chain;
 pub mod kick_off_1;
@@ -167,16 +167,16 @@ impl StartTimeTransaction {
         &mut self,
         context: &OperatorContext,
         connector_2: &Connector2,
-        start_time_block_number: u32,
+        start_time: u32,
         start_time_commitment_secret: &WinternitzSecret,
     ) {
-        self.tx_mut().lock_time = absolute::LockTime::from_height(start_time_block_number)
-            .expect("Failed to set lock time from block.");
+        // The transaction cannot be mined before the committed start time.
+        self.tx_mut().lock_time = absolute::LockTime::from_consensus(start_time);
         self.sign_input_0(
             context,
             connector_2,
             &WinternitzSigningInputs {
-                message: &start_time_block_number.to_le_bytes(),
+                message: &start_time.to_le_bytes(),
                 signing_key: start_time_commitment_secret,
             },
         );
@@ -78,7 +78,7 @@ async fn test_assert_tx_success() {
     );
     check_tx_output_sum(ONE_HUNDRED, &tx);
     wait_for_timelock_expiry(config.network, Some("kick off 2 connector b")).await;
-    let result = config.client_0.esplora.broadcast(&tx).await;
+    let result = config.client_0.bitcoin_backend.broadcast(&tx).await;
     println!("Txid: {:?}", tx.compute_txid());
     println!("Assert tx result: {:?}\n", result);
     assert!(result.is_ok());
@@ -134,12 +134,20 @@ async fn test_assert_commits_tx_success() {
 
     wait_for_timelock_expiry(config.network, Some("assert initial connector 4")).await;
 
-    let commit1_result = config.client_0.esplora.broadcast(&assert_commit1_tx).await;
+    let commit1_result = config
+        .client_0
+        .bitcoin_backend
+        .broadcast(&assert_commit1_tx)
+        .await;
     println!("Txid: {:?}", assert_commit1_tx.compute_txid());
     println!("Assert commit 1 tx result: {:?}\n", commit1_result);
     assert!(commit1_result.is_ok());
 
-    let commit2_result = config.client_0.esplora.broadcast(&assert_commit2_tx).await;
+    let commit2_result = config
+        .client_0
+        .bitcoin_backend
+        .broadcast(&assert_commit2_tx)
+        .await;
     println!("Txid: {:?}", assert_commit2_tx.compute_txid());
     println!("Assert commit 2 tx result: {:?}\n", commit2_result);
     assert!(commit2_result.is_ok());
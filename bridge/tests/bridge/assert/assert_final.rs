@@ -101,7 +101,7 @@ async fn test_assert_final_tx_success() {
             - MIN_RELAY_FEE_ASSERT_FINAL,
         &tx,
     );
-    let result = config.client_0.esplora.broadcast(&tx).await;
+    let result = config.client_0.bitcoin_backend.broadcast(&tx).await;
     println!("Txid: {:?}", tx.compute_txid());
     println!("Assert final tx result: {:?}\n", result);
     println!("Transaction hex: \n{}", serialize_hex(&tx));
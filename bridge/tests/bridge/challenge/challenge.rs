@@ -86,7 +86,7 @@ async fn test_challenge_tx() {
 
     let tx = challenge_tx.finalize();
     check_tx_output_sum(two_thirds_of_initial_amount * 2 + DUST_AMOUNT, &tx);
-    let result = config.client_0.esplora.broadcast(&tx).await;
+    let result = config.client_0.bitcoin_backend.broadcast(&tx).await;
     println!("Txid: {:?}", tx.compute_txid());
     println!("Challenge tx result: {:?}\n", result);
     assert!(result.is_ok());
@@ -95,7 +95,7 @@ async fn test_challenge_tx() {
     let challenge_txid = tx.compute_txid();
     let refund_utxos = config
         .client_0
-        .esplora
+        .bitcoin_backend
         .get_address_utxo(refund_address)
         .await
         .unwrap();
@@ -0,0 +1,229 @@
+use bitcoin::{
+    absolute, transaction::Version, Amount, Network, OutPoint, Sequence, Transaction, TxIn, TxOut,
+    Witness,
+};
+use bridge::{
+    client::bitcoin_backend::{bitcoin_backend::BitcoinBackend, memory_backend::MemoryBackend},
+    connectors::{base::TaprootConnector, connector_z::ConnectorZ},
+    contexts::{base::generate_keys_from_secret, depositor::DepositorContext},
+    error::BitcoinBackendError,
+    scripts::generate_pay_to_pubkey_script_address,
+    timelock_policy::TimelockPolicy,
+    transactions::{
+        base::{BaseTransaction, Input, MIN_RELAY_FEE_PEG_IN_REFUND},
+        peg_in_refund::PegInRefundTransaction,
+    },
+};
+
+const SECRET: &str = "d898098e09898a0980989b980809809809f09809884324874302975287524398";
+const DEPOSITOR_SECRET: &str = "b8f17ea979be24199e7c3fec71ee88914d92fd4ca508443f765d56ce024ef1d7";
+const VERIFIER_SECRET: &str = "ee0817eac0c13aa8ee2dd3256304041f09f0499d1089b56495310ae8093583e2";
+const EVM_ADDRESS: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+
+fn spending_tx(outpoint: OutPoint, output: TxOut) -> Transaction {
+    Transaction {
+        version: Version(2),
+        lock_time: absolute::LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: outpoint,
+            script_sig: bitcoin::ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::default(),
+        }],
+        output: vec![output],
+    }
+}
+
+fn assert_rejected(result: Result<(), BitcoinBackendError>, expected_reason: &str) {
+    match result {
+        Err(BitcoinBackendError::TransactionRejected(_, reason)) => assert!(
+            reason.starts_with(expected_reason),
+            "unexpected rejection reason: {reason}"
+        ),
+        result => panic!("expected rejection with {expected_reason}, got {result:?}"),
+    }
+}
+
+fn setup_peg_in_refund(backend: &MemoryBackend) -> (DepositorContext, ConnectorZ, Input) {
+    let (_, verifier_public_key) = generate_keys_from_secret(Network::Regtest, VERIFIER_SECRET);
+    let depositor_context =
+        DepositorContext::new(Network::Regtest, DEPOSITOR_SECRET, &[verifier_public_key]);
+    let connector_z = ConnectorZ::new(
+        Network::Regtest,
+        EVM_ADDRESS,
+        &depositor_context.depositor_taproot_public_key,
+        &depositor_context.n_of_n_taproot_public_key,
+        &TimelockPolicy::for_network(Network::Regtest),
+    );
+    let amount = Amount::from_sat(100_000 + MIN_RELAY_FEE_PEG_IN_REFUND);
+    let outpoint = backend.fund(
+        connector_z.generate_taproot_address().script_pubkey(),
+        amount,
+    );
+
+    (depositor_context, connector_z, Input { outpoint, amount })
+}
+
+#[tokio::test]
+async fn test_memory_backend_broadcast_and_mine() {
+    let backend = MemoryBackend::new();
+    let (_, public_key) = generate_keys_from_secret(Network::Regtest, SECRET);
+    let address = generate_pay_to_pubkey_script_address(Network::Regtest, &public_key);
+
+    let funding_outpoint = backend.fund(address.script_pubkey(), Amount::from_sat(100_000));
+    assert_eq!(backend.get_height().await.unwrap(), 1);
+
+    let utxos = backend.get_address_utxo(address.clone()).await.unwrap();
+    assert_eq!(utxos.len(), 1);
+    assert_eq!(utxos[0].txid, funding_outpoint.txid);
+    assert!(utxos[0].status.confirmed);
+
+    let tx = spending_tx(
+        funding_outpoint,
+        TxOut {
+            value: Amount::from_sat(99_000),
+            script_pubkey: address.script_pubkey(),
+        },
+    );
+    let txid = tx.compute_txid();
+    backend.broadcast(&tx).await.unwrap();
+    assert!(!backend.get_tx_status(&txid).await.unwrap().confirmed);
+    assert_eq!(backend.get_tx(&txid).await.unwrap(), Some(tx));
+
+    backend.mine_blocks(1);
+    let status = backend.get_tx_status(&txid).await.unwrap();
+    assert!(status.confirmed);
+    assert_eq!(status.block_height, Some(2));
+
+    let utxos = backend.get_address_utxo(address).await.unwrap();
+    assert_eq!(utxos.len(), 1);
+    assert_eq!(utxos[0].txid, txid);
+}
+
+#[tokio::test]
+async fn test_memory_backend_rejects_double_spend() {
+    let backend = MemoryBackend::new();
+    let (_, public_key) = generate_keys_from_secret(Network::Regtest, SECRET);
+    let address = generate_pay_to_pubkey_script_address(Network::Regtest, &public_key);
+    let funding_outpoint = backend.fund(address.script_pubkey(), Amount::from_sat(100_000));
+
+    let tx = spending_tx(
+        funding_outpoint,
+        TxOut {
+            value: Amount::from_sat(99_000),
+            script_pubkey: address.script_pubkey(),
+        },
+    );
+    let double_spend_tx = spending_tx(
+        funding_outpoint,
+        TxOut {
+            value: Amount::from_sat(98_000),
+            script_pubkey: address.script_pubkey(),
+        },
+    );
+
+    backend.broadcast(&tx).await.unwrap();
+    let result = backend.broadcast(&double_spend_tx).await;
+    assert!(matches!(
+        result,
+        Err(BitcoinBackendError::TransactionRejected(txid, _)) if txid == double_spend_tx.compute_txid()
+    ));
+}
+
+#[tokio::test]
+async fn test_memory_backend_rejects_missing_input() {
+    let backend = MemoryBackend::new();
+    let (_, public_key) = generate_keys_from_secret(Network::Regtest, SECRET);
+    let address = generate_pay_to_pubkey_script_address(Network::Regtest, &public_key);
+    let funding_outpoint = backend.fund(address.script_pubkey(), Amount::from_sat(100_000));
+
+    let tx = spending_tx(
+        OutPoint {
+            txid: funding_outpoint.txid,
+            vout: 1,
+        },
+        TxOut {
+            value: Amount::from_sat(99_000),
+            script_pubkey: address.script_pubkey(),
+        },
+    );
+    assert_rejected(
+        backend.broadcast(&tx).await,
+        "bad-txns-inputs-missingorspent",
+    );
+
+    let tx = spending_tx(
+        funding_outpoint,
+        TxOut {
+            value: Amount::from_sat(100_001),
+            script_pubkey: address.script_pubkey(),
+        },
+    );
+    assert_rejected(backend.broadcast(&tx).await, "bad-txns-in-belowout");
+}
+
+#[tokio::test]
+async fn test_memory_backend_enforces_absolute_timelock() {
+    let backend = MemoryBackend::new();
+    let (_, public_key) = generate_keys_from_secret(Network::Regtest, SECRET);
+    let address = generate_pay_to_pubkey_script_address(Network::Regtest, &public_key);
+    let funding_outpoint = backend.fund(address.script_pubkey(), Amount::from_sat(100_000));
+
+    let mut tx = spending_tx(
+        funding_outpoint,
+        TxOut {
+            value: Amount::from_sat(99_000),
+            script_pubkey: address.script_pubkey(),
+        },
+    );
+    tx.input[0].sequence = Sequence::ENABLE_LOCKTIME_NO_RBF;
+    tx.lock_time = absolute::LockTime::from_height(5).unwrap();
+
+    assert_rejected(backend.broadcast(&tx).await, "non-final");
+    backend.mine_blocks(3);
+    assert_rejected(backend.broadcast(&tx).await, "non-final");
+    backend.mine_blocks(1);
+    assert!(backend.broadcast(&tx).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_memory_backend_enforces_relative_timelock() {
+    let backend = MemoryBackend::new();
+    let (depositor_context, connector_z, input) = setup_peg_in_refund(&backend);
+
+    let tx = PegInRefundTransaction::new(&depositor_context, &connector_z, input).finalize();
+    assert_rejected(backend.broadcast(&tx).await, "non-BIP68-final");
+
+    // the funding block counts as the first confirmation
+    backend.mine_blocks(connector_z.num_blocks_timelock_0 - 2);
+    assert_rejected(backend.broadcast(&tx).await, "non-BIP68-final");
+
+    backend.mine_blocks(1);
+    assert!(backend.broadcast(&tx).await.is_ok());
+    backend.mine_blocks(1);
+    assert!(
+        backend
+            .get_tx_status(&tx.compute_txid())
+            .await
+            .unwrap()
+            .confirmed
+    );
+}
+
+#[tokio::test]
+async fn test_memory_backend_rejects_invalid_taproot_script() {
+    let backend = MemoryBackend::new();
+    let (depositor_context, connector_z, input) = setup_peg_in_refund(&backend);
+    backend.mine_blocks(connector_z.num_blocks_timelock_0);
+
+    let mut tx = PegInRefundTransaction::new(&depositor_context, &connector_z, input).finalize();
+    // corrupt the schnorr signature, the first witness element of the script path spend
+    let mut witness = tx.input[0].witness.to_vec();
+    witness[0][0] ^= 1;
+    tx.input[0].witness = Witness::from_slice(&witness);
+
+    assert_rejected(
+        backend.broadcast(&tx).await,
+        "mandatory-script-verify-flag-failed",
+    );
+}
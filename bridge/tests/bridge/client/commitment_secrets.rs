@@ -0,0 +1,126 @@
+use std::{fs, path::Path, sync::Arc};
+
+use bitcoin::{Amount, Network, PublicKey};
+use bridge::{
+    client::{
+        bitcoin_backend::memory_backend::MemoryBackend,
+        chain::chain_adaptor::get_chain_adaptor,
+        client::BitVMClient,
+        files::{get_private_data_file_path, BRIDGE_DATA_DIRECTORY_NAME},
+        keystore::KEYSTORE_PASSPHRASE_ENV_VAR,
+    },
+    commitments::CommitmentMessageId,
+    constants::DestinationNetwork,
+    contexts::base::{generate_keys_from_secret, generate_n_of_n_public_key},
+    error::{ClientError, Error},
+    graphs::base::PEG_OUT_FEE,
+    scripts::generate_pay_to_pubkey_script_address,
+    transactions::base::{Input, MIN_RELAY_FEE_PEG_IN_CONFIRM, MIN_RELAY_FEE_PEG_IN_DEPOSIT},
+};
+
+use crate::bridge::helper::{get_reward_amount, get_valid_proof};
+
+const DEPOSITOR_SECRET: &str = "b8f17ea979be24199e7c3fec71ee88914d92fd4ca508443f765d56ce024ef1d7";
+const VERIFIER_SECRET: &str = "ee0817eac0c13aa8ee2dd3256304041f09f0499d1089b56495310ae8093583e2";
+const OPERATOR_SECRET: &str = "3076ca1dfc1e383be26d5dd3c0c427340f96139fa8c2520862cf551ec2d670ac";
+const KEYSTORE_PASSPHRASE: &str = "test keystore passphrase";
+const EVM_ADDRESS: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+const DEPOSIT_AMOUNT: u64 = 2 << 20;
+const PATH_PREFIX: &str = "test_commitment_secrets";
+
+async fn new_client(backend: Arc<MemoryBackend>, n_of_n_public_keys: &[PublicKey]) -> BitVMClient {
+    BitVMClient::new(
+        Some(backend),
+        Network::Regtest,
+        DestinationNetwork::Local,
+        Some(get_chain_adaptor(DestinationNetwork::Local, None, None)),
+        n_of_n_public_keys,
+        Some(DEPOSITOR_SECRET),
+        Some(OPERATOR_SECRET),
+        Some(VERIFIER_SECRET),
+        None,
+        Some(PATH_PREFIX),
+        Some(get_valid_proof().vk),
+    )
+    .await
+}
+
+// Once the private data is lost, the secrets of a graph are re-derived from the operator key,
+// and only if they match the public keys of the graph.
+#[tokio::test]
+async fn test_commitment_secrets_rederived_after_private_data_loss() {
+    if std::env::var(KEYSTORE_PASSPHRASE_ENV_VAR).is_err() {
+        std::env::set_var(KEYSTORE_PASSPHRASE_ENV_VAR, KEYSTORE_PASSPHRASE);
+    }
+    let backend = Arc::new(MemoryBackend::new());
+    let (_, verifier_public_key) = generate_keys_from_secret(Network::Regtest, VERIFIER_SECRET);
+    let (_, operator_public_key) = generate_keys_from_secret(Network::Regtest, OPERATOR_SECRET);
+    let (_, depositor_public_key) = generate_keys_from_secret(Network::Regtest, DEPOSITOR_SECRET);
+    let n_of_n_public_keys: Vec<PublicKey> = vec![verifier_public_key];
+    let private_data_path = get_private_data_file_path(
+        &Path::new(BRIDGE_DATA_DIRECTORY_NAME)
+            .join(PATH_PREFIX)
+            .join(Network::Regtest.to_string())
+            .join(DestinationNetwork::Local.to_string())
+            .join(
+                generate_n_of_n_public_key(&n_of_n_public_keys)
+                    .0
+                    .to_string(),
+            ),
+    );
+    let _ = fs::remove_file(&private_data_path);
+
+    let mut client = new_client(backend.clone(), &n_of_n_public_keys).await;
+    let fund = |public_key: &PublicKey, amount: u64| Input {
+        outpoint: backend.fund(
+            generate_pay_to_pubkey_script_address(Network::Regtest, public_key).script_pubkey(),
+            Amount::from_sat(amount),
+        ),
+        amount: Amount::from_sat(amount),
+    };
+    let deposit_amount =
+        DEPOSIT_AMOUNT + MIN_RELAY_FEE_PEG_IN_DEPOSIT + MIN_RELAY_FEE_PEG_IN_CONFIRM;
+    let peg_out_confirm_amount = get_reward_amount(DEPOSIT_AMOUNT) + PEG_OUT_FEE;
+
+    // one graph with derived secrets, one with secrets generated randomly
+    let derived_peg_in_graph_id = client
+        .create_peg_in_graph(fund(&depositor_public_key, deposit_amount), EVM_ADDRESS)
+        .await;
+    let derived_secrets = client.derive_commitment_secrets(&derived_peg_in_graph_id);
+    let derived_peg_out_graph_id = client
+        .create_peg_out_graph(
+            &derived_peg_in_graph_id,
+            fund(&operator_public_key, peg_out_confirm_amount),
+            derived_secrets,
+        )
+        .unwrap();
+    let random_peg_in_graph_id = client
+        .create_peg_in_graph(fund(&depositor_public_key, deposit_amount), EVM_ADDRESS)
+        .await;
+    let random_peg_out_graph_id = client
+        .create_peg_out_graph(
+            &random_peg_in_graph_id,
+            fund(&operator_public_key, peg_out_confirm_amount),
+            CommitmentMessageId::generate_commitment_secrets(&get_valid_proof().vk),
+        )
+        .unwrap();
+    assert!(private_data_path.exists());
+
+    let data = client.data().clone();
+    drop(client);
+    fs::remove_file(&private_data_path).unwrap();
+    let mut client = new_client(backend.clone(), &n_of_n_public_keys).await;
+    *client.data_mut() = data;
+    assert!(client.private_data().unwrap().commitment_secrets.is_empty());
+
+    // the secrets are found, kick off 1 then fails on its preceding transactions
+    assert!(!matches!(
+        client.broadcast_kick_off_1(&derived_peg_out_graph_id).await,
+        Err(Error::Client(_))
+    ));
+    assert!(matches!(
+        client.broadcast_kick_off_1(&random_peg_out_graph_id).await,
+        Err(Error::Client(ClientError::CommitmentSecretsMismatch(graph_id)))
+            if graph_id == random_peg_out_graph_id
+    ));
+}
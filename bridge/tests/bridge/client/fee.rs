@@ -64,15 +64,15 @@ async fn test_peg_in_fees() {
         .create_peg_in_graph(peg_in_input, &config.depositor_evm_address)
         .await;
 
-    let esplora_client = config.client_0.esplora.clone();
+    let bitcoin_backend = config.client_0.bitcoin_backend.clone();
 
     let peg_in_graph = get_peg_in_graph_mut(&mut config.client_0, peg_in_graph_id.clone());
-    let peg_in_deposit_tx = peg_in_graph.deposit(&esplora_client).await.unwrap();
+    let peg_in_deposit_tx = peg_in_graph.deposit(&bitcoin_backend).await.unwrap();
     check_tx_output_sum(
         INITIAL_AMOUNT + max(MIN_RELAY_FEE_PEG_IN_CONFIRM, MIN_RELAY_FEE_PEG_IN_REFUND),
         &peg_in_deposit_tx,
     );
-    let deposit_result = esplora_client.broadcast(&peg_in_deposit_tx).await;
+    let deposit_result = bitcoin_backend.broadcast(&peg_in_deposit_tx).await;
     println!("Deposit result: {deposit_result:?}");
     assert!(deposit_result.is_ok());
     config
@@ -89,14 +89,14 @@ async fn test_peg_in_fees() {
 
     let peg_in_graph = get_peg_in_graph_mut(&mut config.client_0, peg_in_graph_id.clone());
     wait_for_timelock_expiry(config.network, Some("peg-in deposit connector z")).await;
-    let peg_in_confirm_tx = peg_in_graph.confirm(&esplora_client).await.unwrap();
+    let peg_in_confirm_tx = peg_in_graph.confirm(&bitcoin_backend).await.unwrap();
     check_tx_output_sum(
         INITIAL_AMOUNT + max(MIN_RELAY_FEE_PEG_IN_CONFIRM, MIN_RELAY_FEE_PEG_IN_REFUND)
             - MIN_RELAY_FEE_PEG_IN_CONFIRM,
         &peg_in_confirm_tx,
     );
 
-    let peg_in_refund_tx = peg_in_graph.refund(&esplora_client).await.unwrap();
+    let peg_in_refund_tx = peg_in_graph.refund(&bitcoin_backend).await.unwrap();
     check_tx_output_sum(
         INITIAL_AMOUNT + max(MIN_RELAY_FEE_PEG_IN_CONFIRM, MIN_RELAY_FEE_PEG_IN_REFUND)
             - MIN_RELAY_FEE_PEG_IN_REFUND,
@@ -155,16 +155,19 @@ async fn test_peg_out_fees() {
             &config.depositor_evm_address,
         )
         .await;
-    let peg_out_graph_id = config.client_0.create_peg_out_graph(
-        &peg_in_graph_id,
-        Input {
-            outpoint: peg_out_confirm_outpoint,
-            amount: peg_out_confirm_input_amount,
-        },
-        config.commitment_secrets.clone(),
-    );
+    let peg_out_graph_id = config
+        .client_0
+        .create_peg_out_graph(
+            &peg_in_graph_id,
+            Input {
+                outpoint: peg_out_confirm_outpoint,
+                amount: peg_out_confirm_input_amount,
+            },
+            config.commitment_secrets.clone(),
+        )
+        .unwrap();
 
-    let esplora_client = config.client_0.esplora.clone();
+    let bitcoin_backend = config.client_0.bitcoin_backend.clone();
     config
         .client_0
         .broadcast_peg_in_deposit(&peg_in_graph_id)
@@ -210,22 +213,34 @@ async fn test_peg_out_fees() {
         .await;
 
     println!("Verifier 0 push peg-out nonces");
-    config.client_0.push_verifier_nonces(&peg_out_graph_id);
+    config
+        .client_0
+        .push_verifier_nonces(&peg_out_graph_id)
+        .unwrap();
     config.client_0.flush().await;
 
     println!("Verifier 1 push peg-out nonces");
     config.client_1.sync().await;
-    config.client_1.push_verifier_nonces(&peg_out_graph_id);
+    config
+        .client_1
+        .push_verifier_nonces(&peg_out_graph_id)
+        .unwrap();
     config.client_1.flush().await;
 
     println!("Verifier 0 pre-sign peg-out");
     config.client_0.sync().await;
-    config.client_0.push_verifier_signature(&peg_out_graph_id);
+    config
+        .client_0
+        .push_verifier_signature(&peg_out_graph_id)
+        .expect("Failed to push verifier signature");
     config.client_0.flush().await;
 
     println!("Verifier 1 pre-sign peg-out");
     config.client_1.sync().await;
-    config.client_1.push_verifier_signature(&peg_out_graph_id);
+    config
+        .client_1
+        .push_verifier_signature(&peg_out_graph_id)
+        .expect("Failed to push verifier signature");
     config.client_1.flush().await;
     config.client_0.sync().await;
 
@@ -267,7 +282,7 @@ async fn test_peg_out_fees() {
     let peg_out_graph = get_peg_out_graph_mut(&mut config.client_0, peg_out_graph_id.clone());
     let peg_out_tx = peg_out_graph
         .peg_out(
-            &esplora_client,
+            &bitcoin_backend,
             &config.operator_context,
             Input {
                 outpoint: peg_out_outpoint,
@@ -277,32 +292,32 @@ async fn test_peg_out_fees() {
         .await
         .unwrap();
     check_tx_output_sum(ONE_HUNDRED, &peg_out_tx);
-    let peg_out_result = esplora_client.broadcast(&peg_out_tx).await;
+    let peg_out_result = bitcoin_backend.broadcast(&peg_out_tx).await;
     wait_for_confirmation(config.network).await;
     println!("peg out tx result: {:?}\n", peg_out_result);
     assert!(peg_out_result.is_ok());
 
     let peg_out_confirm_tx = peg_out_graph
-        .peg_out_confirm(&esplora_client)
+        .peg_out_confirm(&bitcoin_backend)
         .await
         .unwrap();
     check_tx_output_sum(
         reward_amount + PEG_OUT_FEE - MIN_RELAY_FEE_PEG_OUT_CONFIRM,
         &peg_out_confirm_tx,
     );
-    let peg_out_confirm_result = esplora_client.broadcast(&peg_out_confirm_tx).await;
+    let peg_out_confirm_result = bitcoin_backend.broadcast(&peg_out_confirm_tx).await;
     wait_for_confirmation(config.network).await;
     println!("peg out confirm tx result: {:?}\n", peg_out_confirm_result);
     assert!(peg_out_confirm_result.is_ok());
 
-    let private_data = config.client_0.private_data();
+    let private_data = config.client_0.private_data().unwrap();
     let secrets_map = private_data.commitment_secrets[&config.operator_context.operator_public_key]
         [&peg_out_graph_id]
         .clone();
     let peg_out_graph = get_peg_out_graph_mut(&mut config.client_0, peg_out_graph_id.clone());
     let kick_off_1_tx = peg_out_graph
         .kick_off_1(
-            &esplora_client,
+            &bitcoin_backend,
             &config.operator_context,
             &secrets_map[&CommitmentMessageId::PegOutTxIdSourceNetwork],
             &secrets_map[&CommitmentMessageId::PegOutTxIdDestinationNetwork],
@@ -313,7 +328,7 @@ async fn test_peg_out_fees() {
         reward_amount + PEG_OUT_FEE - MIN_RELAY_FEE_PEG_OUT_CONFIRM - MIN_RELAY_FEE_KICK_OFF_1,
         &kick_off_1_tx,
     );
-    let kick_off_1_result = esplora_client.broadcast(&kick_off_1_tx).await;
+    let kick_off_1_result = bitcoin_backend.broadcast(&kick_off_1_tx).await;
     wait_for_confirmation(config.network).await;
     println!(
         "kick off 1 tx result: {:?}, {:?}\n",
@@ -324,7 +339,7 @@ async fn test_peg_out_fees() {
 
     let start_time_tx = peg_out_graph
         .start_time(
-            &esplora_client,
+            &bitcoin_backend,
             &config.operator_context,
             &secrets_map[&CommitmentMessageId::StartTime],
         )
@@ -335,7 +350,7 @@ async fn test_peg_out_fees() {
     wait_for_timelock_expiry(config.network, Some("kick off 1 connector 1")).await;
     let start_time_timeout_tx = peg_out_graph
         .start_time_timeout(
-            &esplora_client,
+            &bitcoin_backend,
             generate_pay_to_pubkey_script(&config.depositor_context.depositor_public_key),
         )
         .await
@@ -378,7 +393,7 @@ async fn test_peg_out_fees() {
     let peg_out_graph = get_peg_out_graph_mut(&mut config.client_0, peg_out_graph_id.clone());
     let challenge_tx = peg_out_graph
         .challenge(
-            &esplora_client,
+            &bitcoin_backend,
             &challenge_crowdfunding_inputs,
             &config.depositor_context.depositor_keypair,
             depositor_pubkey_script.clone(),
@@ -396,7 +411,7 @@ async fn test_peg_out_fees() {
         &config.withdrawer_context.withdrawer_public_key,
     );
     let kick_off_timeout_tx = peg_out_graph
-        .kick_off_timeout(&esplora_client, reward_address.script_pubkey())
+        .kick_off_timeout(&bitcoin_backend, reward_address.script_pubkey())
         .await
         .unwrap();
     check_tx_output_sum(
@@ -411,7 +426,7 @@ async fn test_peg_out_fees() {
 
     let kick_off_2_tx = peg_out_graph
         .kick_off_2(
-            &esplora_client,
+            &bitcoin_backend,
             &config.operator_context,
             &config.commitment_secrets[&CommitmentMessageId::Superblock],
             &config.commitment_secrets[&CommitmentMessageId::SuperblockHash],
@@ -427,7 +442,7 @@ async fn test_peg_out_fees() {
             - MIN_RELAY_FEE_START_TIME,
         &kick_off_2_tx,
     );
-    let kick_off_2_result = esplora_client.broadcast(&kick_off_2_tx).await;
+    let kick_off_2_result = bitcoin_backend.broadcast(&kick_off_2_tx).await;
     println!(
         "kick off 2 tx result: {:?}, {:?}\n",
         kick_off_2_result,
@@ -436,7 +451,7 @@ async fn test_peg_out_fees() {
     wait_for_confirmation(config.network).await;
     wait_for_timelock_expiry(config.network, Some("kick off 2 connector 3")).await;
 
-    let take_1_tx = peg_out_graph.take_1(&esplora_client).await.unwrap();
+    let take_1_tx = peg_out_graph.take_1(&bitcoin_backend).await.unwrap();
     // minus 1 dust from kick off 1 connector 2
     check_tx_output_sum(
         INITIAL_AMOUNT + reward_amount + MIN_RELAY_FEE_ASSERT_SET - MIN_RELAY_FEE_TAKE_1
@@ -448,7 +463,7 @@ async fn test_peg_out_fees() {
     let verifier_pubkey_script =
         generate_pay_to_pubkey_script(&config.verifier_0_context.verifier_public_key);
     let disprove_chain_tx = peg_out_graph
-        .disprove_chain(&esplora_client, verifier_pubkey_script.clone())
+        .disprove_chain(&bitcoin_backend, verifier_pubkey_script.clone())
         .await
         .unwrap();
     // minus 2 dust from kick off 1, 1 dust from kick off 2
@@ -459,7 +474,10 @@ async fn test_peg_out_fees() {
         &disprove_chain_tx,
     );
 
-    let assert_initial_tx = peg_out_graph.assert_initial(&esplora_client).await.unwrap();
+    let assert_initial_tx = peg_out_graph
+        .assert_initial(&bitcoin_backend)
+        .await
+        .unwrap();
     // minus 2 dust from kick off 1, 1 dust from kick off 2
     check_tx_output_sum(
         reward_amount + MIN_RELAY_FEE_ASSERT_SET - MIN_RELAY_FEE_ASSERT_INITIAL
@@ -467,7 +485,7 @@ async fn test_peg_out_fees() {
             - DUST_AMOUNT * 3,
         &assert_initial_tx,
     );
-    let assert_initial_result = esplora_client.broadcast(&assert_initial_tx).await;
+    let assert_initial_result = bitcoin_backend.broadcast(&assert_initial_tx).await;
     println!(
         "assert initial tx result: {:?}, {:?}\n",
         assert_initial_result,
@@ -478,7 +496,7 @@ async fn test_peg_out_fees() {
 
     let (assert_commit1_tx, assert_commit2_tx) = peg_out_graph
         .assert_commits(
-            &esplora_client,
+            &bitcoin_backend,
             &config.commitment_secrets,
             &config.invalid_proof,
         )
@@ -486,7 +504,7 @@ async fn test_peg_out_fees() {
         .unwrap();
     // checked in assert_commit_1 single tx test
     // check_tx_output_sum(assert_commit1_dust_amount, &assert_commit1_tx);
-    let assert_commit1_result = esplora_client.broadcast(&assert_commit1_tx).await;
+    let assert_commit1_result = bitcoin_backend.broadcast(&assert_commit1_tx).await;
     println!(
         "assert commit 1 tx result: {:?}, {:?}\n",
         assert_commit1_result,
@@ -496,7 +514,7 @@ async fn test_peg_out_fees() {
 
     // checked in assert_commit_2 single tx test
     // check_tx_output_sum(assert_commit2_dust_amount, &assert_commit2_tx);
-    let assert_commit2_result = esplora_client.broadcast(&assert_commit2_tx).await;
+    let assert_commit2_result = bitcoin_backend.broadcast(&assert_commit2_tx).await;
     println!(
         "assert commit 2 tx result: {:?}, {:?}\n",
         assert_commit2_result,
@@ -504,13 +522,13 @@ async fn test_peg_out_fees() {
     );
     wait_for_confirmation(config.network).await;
 
-    let assert_final_tx = peg_out_graph.assert_final(&esplora_client).await.unwrap();
+    let assert_final_tx = peg_out_graph.assert_final(&bitcoin_backend).await.unwrap();
     // minus 2 dust from kick off 1, 1 dust from kick off 2
     check_tx_output_sum(
         reward_amount + MIN_RELAY_FEE_DISPROVE - DUST_AMOUNT * 3,
         &assert_final_tx,
     );
-    let assert_final_result = esplora_client.broadcast(&assert_final_tx).await;
+    let assert_final_result = bitcoin_backend.broadcast(&assert_final_tx).await;
     println!(
         "assert final tx result: {:?}, {:?}\n",
         assert_final_result,
@@ -520,7 +538,7 @@ async fn test_peg_out_fees() {
     wait_for_timelock_expiry(config.network, Some("assert final connector 4")).await;
 
     let take_2_tx = peg_out_graph
-        .take_2(&esplora_client, &config.operator_context)
+        .take_2(&bitcoin_backend, &config.operator_context)
         .await
         .unwrap();
     // minus 2 dust from kick off 1, 1 dust from kick off 2
@@ -531,13 +549,8 @@ async fn test_peg_out_fees() {
         &take_2_tx,
     );
 
-    let zk_verifying_key = config.invalid_proof.vk;
     let disprove_tx = peg_out_graph
-        .disprove(
-            &esplora_client,
-            verifier_pubkey_script.clone(),
-            &zk_verifying_key,
-        )
+        .disprove(&bitcoin_backend, verifier_pubkey_script.clone())
         .await
         .unwrap();
     // minus 2 dust from kick off 1, 1 dust from kick off 2, 1 dust from assert final
@@ -0,0 +1,196 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use bitcoin::{hashes::Hash, Network, Txid};
+use bitvm::signatures::signing_winternitz::WinternitzSecret;
+use bridge::{
+    client::{
+        cli::key_command::KeysCommand,
+        client::BitVMClientPrivateData,
+        files::get_private_data_from_file,
+        keystore::{Keystore, KEYSTORE_PASSPHRASE_ENV_VAR},
+    },
+    commitments::CommitmentMessageId,
+    contexts::base::generate_keys_from_secret,
+    error::KeystoreError,
+    serialization::{serialize, try_deserialize},
+    transactions::signing_musig2::generate_nonce,
+};
+use zeroize::Zeroizing;
+
+const PASSPHRASE: &str = "correct horse battery staple";
+const OPERATOR_SECRET: &str = "3076ca1dfc1e383be26d5dd3c0c427340f96139fa8c2520862cf551ec2d670ac";
+
+fn test_directory(name: &str) -> PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("bridge-keystore-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).unwrap();
+    path
+}
+
+fn passphrase(passphrase: &str) -> impl FnOnce() -> Result<Zeroizing<String>, KeystoreError> {
+    let passphrase = Zeroizing::new(passphrase.to_string());
+    move || Ok(passphrase)
+}
+
+fn test_private_data() -> BitVMClientPrivateData {
+    let (_, public_key) = generate_keys_from_secret(Network::Regtest, OPERATOR_SECRET);
+    BitVMClientPrivateData {
+        secret_nonces: HashMap::from([(
+            public_key,
+            HashMap::from([(
+                "graph".to_string(),
+                HashMap::from([(
+                    Txid::from_byte_array([1; 32]),
+                    HashMap::from([(0, generate_nonce())]),
+                )]),
+            )]),
+        )]),
+        commitment_secrets: HashMap::from([(
+            public_key,
+            HashMap::from([(
+                "graph".to_string(),
+                HashMap::from([(CommitmentMessageId::StartTime, WinternitzSecret::new(4))]),
+            )]),
+        )]),
+        consumed_nonces: HashMap::new(),
+    }
+}
+
+#[test]
+fn test_keystore_round_trip() {
+    let keystore = Keystore::encrypt(b"secret data", PASSPHRASE).unwrap();
+
+    assert_eq!(
+        keystore.decrypt(PASSPHRASE).unwrap().as_slice(),
+        b"secret data"
+    );
+    assert_eq!(
+        keystore.decrypt("wrong passphrase").unwrap_err(),
+        KeystoreError::DecryptionFailed
+    );
+}
+
+#[test]
+fn test_keystore_rejects_tampering() {
+    let keystore = Keystore::encrypt(b"secret data", PASSPHRASE).unwrap();
+
+    let mut tampered = keystore.clone();
+    let last = tampered.ciphertext.pop().unwrap();
+    tampered
+        .ciphertext
+        .push(if last == '0' { '1' } else { '0' });
+    assert_eq!(
+        tampered.decrypt(PASSPHRASE).unwrap_err(),
+        KeystoreError::DecryptionFailed
+    );
+
+    let mut tampered = keystore.clone();
+    tampered.kdf.time_cost += 1;
+    assert_eq!(
+        tampered.decrypt(PASSPHRASE).unwrap_err(),
+        KeystoreError::DecryptionFailed
+    );
+
+    let mut tampered = keystore;
+    tampered.version += 1;
+    assert_eq!(
+        tampered.decrypt(PASSPHRASE).unwrap_err(),
+        KeystoreError::UnsupportedVersion(tampered.version)
+    );
+}
+
+#[test]
+fn test_private_data_is_migrated_to_keystore() {
+    let path = test_directory("private-data").join("secret_data.json");
+    let private_data = test_private_data();
+    fs::write(&path, serialize(&private_data)).unwrap();
+
+    assert!(get_private_data_from_file(&path, passphrase(PASSPHRASE)).unwrap() == private_data);
+    let keystore = try_deserialize::<Keystore>(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert!(keystore.decrypt(PASSPHRASE).is_ok());
+
+    // Reading the migrated file decrypts it.
+    assert!(get_private_data_from_file(&path, passphrase(PASSPHRASE)).unwrap() == private_data);
+
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_private_data_with_wrong_passphrase() {
+    let path = test_directory("wrong-passphrase").join("secret_data.json");
+    let keystore =
+        Keystore::encrypt(serialize(&test_private_data()).as_bytes(), PASSPHRASE).unwrap();
+    fs::write(&path, serialize(&keystore)).unwrap();
+
+    assert!(matches!(
+        get_private_data_from_file(&path, passphrase("wrong passphrase")),
+        Err(KeystoreError::DecryptionFailed)
+    ));
+
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_private_data_passphrase_is_only_read_from_existing_files() {
+    let path = test_directory("no-passphrase").join("secret_data.json");
+    let no_passphrase = || Err(KeystoreError::PassphraseNotSet);
+
+    assert!(get_private_data_from_file(&path, no_passphrase)
+        .unwrap()
+        .commitment_secrets
+        .is_empty());
+
+    fs::write(&path, serialize(&test_private_data())).unwrap();
+    assert!(matches!(
+        get_private_data_from_file(&path, no_passphrase),
+        Err(KeystoreError::PassphraseNotSet)
+    ));
+    // Not migrated without a passphrase.
+    assert!(
+        get_private_data_from_file(&path, passphrase(PASSPHRASE)).unwrap() == test_private_data()
+    );
+
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_invalid_private_data_is_not_migrated() {
+    let path = test_directory("invalid-private-data").join("secret_data.json");
+    fs::write(&path, "{\"version\": 1}").unwrap();
+
+    assert!(matches!(
+        get_private_data_from_file(&path, passphrase(PASSPHRASE)),
+        Err(KeystoreError::InvalidFormat(_))
+    ));
+    assert_eq!(fs::read_to_string(&path).unwrap(), "{\"version\": 1}");
+
+    fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_keys_are_migrated_to_keystore() {
+    let key_dir = test_directory("keys");
+    fs::write(
+        key_dir.join("bridge.toml"),
+        format!("[keys]\noperator = \"{OPERATOR_SECRET}\"\n"),
+    )
+    .unwrap();
+
+    if std::env::var(KEYSTORE_PASSPHRASE_ENV_VAR).is_err() {
+        std::env::set_var(KEYSTORE_PASSPHRASE_ENV_VAR, PASSPHRASE);
+    }
+    let keys_command = KeysCommand::new(Some(key_dir.display().to_string()));
+
+    let config = keys_command.read_config().unwrap();
+    assert_eq!(config.keys.operator.as_deref(), Some(OPERATOR_SECRET));
+
+    let contents = fs::read_to_string(&keys_command.config_path).unwrap();
+    assert!(!contents.contains(OPERATOR_SECRET));
+    assert!(contents.contains("[keystore]"));
+
+    let config = keys_command.read_config().unwrap();
+    assert_eq!(config.keys.operator.as_deref(), Some(OPERATOR_SECRET));
+
+    fs::remove_dir_all(key_dir).unwrap();
+}
@@ -3,7 +3,13 @@ use std::str::FromStr;
 use bitcoin::{Amount, OutPoint, Txid};
 use bridge::{
     client::client::{BitVMClient, BitVMClientPublicData},
-    graphs::{base::PEG_OUT_FEE, peg_in::PegInGraph, peg_out::PegOutGraph},
+    error::{Error, GraphError},
+    graphs::{
+        base::{BaseGraph, PEG_OUT_FEE},
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+    },
+    serialization::{deserialize, serialize},
     transactions::base::Input,
 };
 
@@ -43,6 +49,21 @@ async fn test_merge_add_new_graph() {
     assert_eq!(merged_data.peg_out_graphs.len(), 2);
 }
 
+#[tokio::test]
+async fn test_merge_rejects_peg_in_graph_with_other_operators() {
+    let (_, mut peg_in_graph, _) = setup_and_create_graphs().await;
+
+    let mut json: serde_json::Value = serde_json::from_str(&serialize(&peg_in_graph)).unwrap();
+    json["operator_public_keys"] = serde_json::json!([peg_in_graph.depositor_public_key]);
+    let other_peg_in_graph = deserialize::<PegInGraph>(&json.to_string());
+
+    assert!(matches!(
+        peg_in_graph.merge(&other_peg_in_graph),
+        Err(Error::Graph(GraphError::OperatorsMismatch(graph_id))) if &graph_id == peg_in_graph.id()
+    ));
+    assert!(peg_in_graph.merge(&peg_in_graph.clone()).is_ok());
+}
+
 async fn setup_and_create_graphs() -> (BitVMClient, PegInGraph, PegOutGraph) {
     let mut config = setup_test().await;
 
@@ -67,14 +88,17 @@ async fn setup_and_create_graphs() -> (BitVMClient, PegInGraph, PegOutGraph) {
         .create_peg_in_graph(input, &config.depositor_evm_address)
         .await;
 
-    config.client_0.create_peg_out_graph(
-        &peg_in_graph_id,
-        Input {
-            outpoint: peg_out_outpoint,
-            amount,
-        },
-        config.commitment_secrets.clone(),
-    );
+    config
+        .client_0
+        .create_peg_out_graph(
+            &peg_in_graph_id,
+            Input {
+                outpoint: peg_out_outpoint,
+                amount,
+            },
+            config.commitment_secrets.clone(),
+        )
+        .unwrap();
 
     let new_peg_in_graph = PegInGraph::new(
         &config.depositor_context,
@@ -83,6 +107,8 @@ async fn setup_and_create_graphs() -> (BitVMClient, PegInGraph, PegOutGraph) {
             amount: Amount::from_sat(INITIAL_AMOUNT),
         },
         &config.depositor_evm_address,
+        &[config.operator_context.operator_public_key],
+        &config.timelock_policy,
     );
 
     let new_peg_out_graph = PegOutGraph::new(
@@ -93,6 +119,8 @@ async fn setup_and_create_graphs() -> (BitVMClient, PegInGraph, PegOutGraph) {
             amount,
         },
         &config.commitment_secrets,
+        &config.timelock_policy,
+        &config.valid_proof.vk,
     );
 
     (config.client_0, new_peg_in_graph, new_peg_out_graph)
@@ -1,7 +1,13 @@
+pub mod bitcoin_backend;
+pub mod commitment_secrets;
 pub mod fee;
+pub mod keystore;
 pub mod merge;
 pub mod musig2_keys;
+pub mod musig2_nonces;
 pub mod musig2_peg_in;
 pub mod musig2_peg_out;
+pub mod operators;
+pub mod peg_out_memory_backend;
 pub mod sync;
 pub mod validate;
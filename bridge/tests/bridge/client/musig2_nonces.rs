@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use bitcoin::{hashes::Hash, Amount, Network, OutPoint, Txid};
+use bridge::{
+    client::client::BitVMClientPrivateData,
+    contexts::{
+        base::generate_keys_from_secret, depositor::DepositorContext, verifier::VerifierContext,
+    },
+    error::NonceError,
+    graphs::{base::BaseGraph, peg_in::PegInGraph},
+    serialization::{deserialize, serialize},
+    timelock_policy::TimelockPolicy,
+    transactions::{base::Input, pre_signed::PreSignedTransaction},
+};
+
+const DEPOSITOR_SECRET: &str = "b8f17ea979be24199e7c3fec71ee88914d92fd4ca508443f765d56ce024ef1d7";
+const VERIFIER_0_SECRET: &str = "ee0817eac0c13aa8ee2dd3256304041f09f0499d1089b56495310ae8093583e2";
+const VERIFIER_1_SECRET: &str = "fc294c70faf210d4d0807ea7a3dba8f7e41700d90c119e1ae82a0687d89d297f";
+const EVM_ADDRESS: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+
+struct NonceTest {
+    verifier_0: VerifierContext,
+    verifier_1: VerifierContext,
+    graph: PegInGraph,
+}
+
+fn setup() -> NonceTest {
+    let (_, verifier_0_public_key) = generate_keys_from_secret(Network::Regtest, VERIFIER_0_SECRET);
+    let (_, verifier_1_public_key) = generate_keys_from_secret(Network::Regtest, VERIFIER_1_SECRET);
+    let n_of_n_public_keys = [verifier_0_public_key, verifier_1_public_key];
+
+    let depositor = DepositorContext::new(Network::Regtest, DEPOSITOR_SECRET, &n_of_n_public_keys);
+    let graph = PegInGraph::new(
+        &depositor,
+        Input {
+            outpoint: OutPoint {
+                txid: Txid::from_byte_array([1; 32]),
+                vout: 0,
+            },
+            amount: Amount::from_sat(2 << 20),
+        },
+        EVM_ADDRESS,
+        &[],
+        &TimelockPolicy::for_network(Network::Regtest),
+    );
+
+    NonceTest {
+        verifier_0: VerifierContext::new(Network::Regtest, VERIFIER_0_SECRET, &n_of_n_public_keys),
+        verifier_1: VerifierContext::new(Network::Regtest, VERIFIER_1_SECRET, &n_of_n_public_keys),
+        graph,
+    }
+}
+
+fn empty_private_data() -> BitVMClientPrivateData {
+    BitVMClientPrivateData {
+        secret_nonces: HashMap::new(),
+        commitment_secrets: HashMap::new(),
+        consumed_nonces: HashMap::new(),
+    }
+}
+
+// Generates and publishes the nonces of both verifiers, returning the private data of verifier 0.
+fn push_nonces(test: &mut NonceTest) -> BitVMClientPrivateData {
+    let mut private_data = empty_private_data();
+    let secret_nonces = test.graph.push_verifier_nonces(&test.verifier_0);
+    private_data
+        .secret_nonces
+        .entry(test.verifier_0.verifier_public_key)
+        .or_default()
+        .insert(test.graph.id().clone(), secret_nonces);
+    test.graph.push_verifier_nonces(&test.verifier_1);
+    private_data
+}
+
+fn take_secret_nonces(
+    private_data: &mut BitVMClientPrivateData,
+    test: &NonceTest,
+) -> Result<bool, NonceError> {
+    private_data
+        .take_secret_nonces(
+            &test.verifier_0.verifier_public_key,
+            test.graph.id(),
+            &test.graph.musig2_transactions(),
+        )
+        .map(|secret_nonces| secret_nonces.is_some())
+}
+
+fn graph_secret_nonce_count(private_data: &BitVMClientPrivateData, test: &NonceTest) -> usize {
+    private_data
+        .secret_nonces
+        .get(&test.verifier_0.verifier_public_key)
+        .and_then(|nonces| nonces.get(test.graph.id()))
+        .map_or(0, |nonces| nonces.len())
+}
+
+#[test]
+fn test_secret_nonces_are_consumed_once() {
+    let mut test = setup();
+    let mut private_data = push_nonces(&mut test);
+
+    let secret_nonces = private_data
+        .take_secret_nonces(
+            &test.verifier_0.verifier_public_key,
+            test.graph.id(),
+            &test.graph.musig2_transactions(),
+        )
+        .unwrap()
+        .unwrap();
+    assert_eq!(graph_secret_nonce_count(&private_data, &test), 0);
+    assert_eq!(
+        private_data.consumed_nonces[&test.verifier_0.verifier_public_key].len(),
+        1
+    );
+
+    test.graph.verifier_sign(&test.verifier_0, &secret_nonces);
+
+    // Signing again is a no-op.
+    assert_eq!(take_secret_nonces(&mut private_data, &test), Ok(false));
+}
+
+#[test]
+fn test_secret_nonces_restored_from_backup_after_signing() {
+    let mut test = setup();
+    let mut private_data = push_nonces(&mut test);
+    let backup = serialize(&private_data);
+
+    let secret_nonces = private_data
+        .take_secret_nonces(
+            &test.verifier_0.verifier_public_key,
+            test.graph.id(),
+            &test.graph.musig2_transactions(),
+        )
+        .unwrap()
+        .unwrap();
+    test.graph.verifier_sign(&test.verifier_0, &secret_nonces);
+
+    // The backup still holds the secret nonces, but the graph already has our signatures.
+    let mut restored_private_data: BitVMClientPrivateData = deserialize(&backup);
+    assert_eq!(graph_secret_nonce_count(&restored_private_data, &test), 1);
+    assert_eq!(
+        take_secret_nonces(&mut restored_private_data, &test),
+        Ok(false)
+    );
+    assert_eq!(graph_secret_nonce_count(&restored_private_data, &test), 0);
+    assert!(restored_private_data.consumed_nonces == private_data.consumed_nonces);
+}
+
+#[test]
+fn test_secret_nonces_restored_from_backup_before_signature_is_published() {
+    let mut test = setup();
+    let mut private_data = push_nonces(&mut test);
+    let unsigned_graph = test.graph.clone();
+    let backup = serialize(&private_data);
+
+    let secret_nonces = private_data
+        .take_secret_nonces(
+            &test.verifier_0.verifier_public_key,
+            test.graph.id(),
+            &test.graph.musig2_transactions(),
+        )
+        .unwrap()
+        .unwrap();
+    test.graph.verifier_sign(&test.verifier_0, &secret_nonces);
+
+    // The signed graph was lost, and the secret nonces are restored into the current private data.
+    test.graph = unsigned_graph;
+    let restored_private_data: BitVMClientPrivateData = deserialize(&backup);
+    private_data.secret_nonces = restored_private_data.secret_nonces;
+
+    let txid = test.graph.musig2_transactions()[0].tx().compute_txid();
+    assert_eq!(
+        take_secret_nonces(&mut private_data, &test),
+        Err(NonceError::NonceAlreadyConsumed(txid, 0))
+    );
+}
+
+#[test]
+fn test_regenerated_nonce_is_refused() {
+    let mut test = setup();
+    let backup = serialize(&push_nonces(&mut test));
+
+    // The nonces are regenerated, e.g. the graph is re-signed after a merge conflict, but the
+    // private data is restored from a backup holding the previous secret nonces.
+    push_nonces(&mut test);
+    let mut restored_private_data: BitVMClientPrivateData = deserialize(&backup);
+
+    let txid = test.graph.musig2_transactions()[0].tx().compute_txid();
+    assert_eq!(
+        take_secret_nonces(&mut restored_private_data, &test),
+        Err(NonceError::NonceMismatch(txid, 0))
+    );
+    assert_eq!(graph_secret_nonce_count(&restored_private_data, &test), 1);
+}
+
+#[test]
+fn test_missing_nonces_are_refused() {
+    let mut test = setup();
+    let txid = test.graph.musig2_transactions()[0].tx().compute_txid();
+
+    assert_eq!(
+        take_secret_nonces(&mut empty_private_data(), &test),
+        Err(NonceError::NonceNotPublished(txid, 0))
+    );
+
+    push_nonces(&mut test);
+    assert_eq!(
+        take_secret_nonces(&mut empty_private_data(), &test),
+        Err(NonceError::SecretNonceNotFound(txid, 0))
+    );
+}
@@ -58,7 +58,9 @@ async fn test_musig2_peg_in() {
     depositor_operator_verifier_0_client.sync().await;
 
     println!("Verifier 0: Generating nonces...");
-    depositor_operator_verifier_0_client.push_verifier_nonces(&graph_id);
+    depositor_operator_verifier_0_client
+        .push_verifier_nonces(&graph_id)
+        .unwrap();
 
     println!("Verifier 0: Saving state changes to remote...");
     depositor_operator_verifier_0_client.flush().await;
@@ -68,7 +70,7 @@ async fn test_musig2_peg_in() {
     verifier_1_client.sync().await;
 
     println!("Verifier 1: Generating nonces...");
-    verifier_1_client.push_verifier_nonces(&graph_id);
+    verifier_1_client.push_verifier_nonces(&graph_id).unwrap();
 
     println!("Verifier 1: Saving state changes to remote...");
     verifier_1_client.flush().await;
@@ -78,7 +80,9 @@ async fn test_musig2_peg_in() {
     depositor_operator_verifier_0_client.sync().await;
 
     println!("Verifier 0: Pre-signing...");
-    depositor_operator_verifier_0_client.push_verifier_signature(&graph_id);
+    depositor_operator_verifier_0_client
+        .push_verifier_signature(&graph_id)
+        .expect("Failed to push verifier signature");
 
     println!("Verifier 0: Saving state changes to remote...");
     depositor_operator_verifier_0_client.flush().await;
@@ -88,7 +92,9 @@ async fn test_musig2_peg_in() {
     verifier_1_client.sync().await;
 
     println!("Verifier 1: Pre-signing...");
-    verifier_1_client.push_verifier_signature(&graph_id);
+    verifier_1_client
+        .push_verifier_signature(&graph_id)
+        .expect("Failed to push verifier signature");
 
     println!("Verifier 1: Saving state changes to remote...");
     verifier_1_client.flush().await;
@@ -0,0 +1,370 @@
+use std::sync::Arc;
+
+use bitcoin::{Amount, Network, PublicKey};
+use bridge::{
+    client::{
+        bitcoin_backend::memory_backend::MemoryBackend, chain::chain_adaptor::get_chain_adaptor,
+        client::BitVMClient, keystore::KEYSTORE_PASSPHRASE_ENV_VAR,
+    },
+    constants::DestinationNetwork,
+    contexts::{
+        base::generate_keys_from_secret, operator::OperatorContext, verifier::VerifierContext,
+    },
+    error::{ClientError, Error, GraphError},
+    graphs::{
+        base::{BaseGraph, PEG_OUT_FEE},
+        peg_in::{PegInGraph, PegInVerifierStatus},
+        peg_out::PegOutGraph,
+    },
+    scripts::generate_pay_to_pubkey_script_address,
+    transactions::base::{Input, MIN_RELAY_FEE_PEG_IN_CONFIRM, MIN_RELAY_FEE_PEG_IN_DEPOSIT},
+};
+
+use crate::bridge::helper::{get_reward_amount, get_valid_proof};
+
+const DEPOSITOR_SECRET: &str = "b8f17ea979be24199e7c3fec71ee88914d92fd4ca508443f765d56ce024ef1d7";
+const VERIFIER_0_SECRET: &str = "ee0817eac0c13aa8ee2dd3256304041f09f0499d1089b56495310ae8093583e2";
+const VERIFIER_1_SECRET: &str = "fc294c70faf210d4d0807ea7a3dba8f7e41700d90c119e1ae82a0687d89d297f";
+const OPERATOR_0_SECRET: &str = "3076ca1dfc1e383be26d5dd3c0c427340f96139fa8c2520862cf551ec2d670ac";
+const OPERATOR_1_SECRET: &str = "1f9b6a3e8c7d2f4a5b0c9e8d7f6a5b4c3d2e1f0a9b8c7d6e5f4a3b2c1d0e9f8a";
+const OPERATOR_2_SECRET: &str = "7c4e2a9d1b8f3e6a0c5d9b2e7f1a4c8d3b6e9a2f5c8d1e4b7a0f3c6e9d2b5a8f";
+const KEYSTORE_PASSPHRASE: &str = "test keystore passphrase";
+const EVM_ADDRESS: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+const DEPOSIT_AMOUNT: u64 = 2 << 20;
+
+async fn create_client(
+    backend: &Arc<MemoryBackend>,
+    n_of_n_public_keys: &[PublicKey],
+    operator_secret: &str,
+    verifier_secret: &str,
+    user_profile: &str,
+) -> BitVMClient {
+    BitVMClient::new(
+        Some(backend.clone()),
+        Network::Regtest,
+        DestinationNetwork::Local,
+        Some(get_chain_adaptor(DestinationNetwork::Local, None, None)),
+        n_of_n_public_keys,
+        Some(DEPOSITOR_SECRET),
+        Some(operator_secret),
+        Some(verifier_secret),
+        None,
+        Some(user_profile),
+        Some(get_valid_proof().vk),
+    )
+    .await
+}
+
+fn fund_operator(backend: &MemoryBackend, operator_public_key: &PublicKey) {
+    backend.fund(
+        generate_pay_to_pubkey_script_address(Network::Regtest, operator_public_key)
+            .script_pubkey(),
+        Amount::from_sat(get_reward_amount(DEPOSIT_AMOUNT) + PEG_OUT_FEE),
+    );
+}
+
+fn peg_outs_of<'a>(client: &'a BitVMClient, peg_in_graph: &PegInGraph) -> Vec<&'a PegOutGraph> {
+    client
+        .data()
+        .peg_out_graphs
+        .iter()
+        .filter(|peg_out| peg_in_graph.peg_out_graphs.contains(peg_out.id()))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_peg_in_awaits_peg_out_graphs_of_all_operators() {
+    if std::env::var(KEYSTORE_PASSPHRASE_ENV_VAR).is_err() {
+        std::env::set_var(KEYSTORE_PASSPHRASE_ENV_VAR, KEYSTORE_PASSPHRASE);
+    }
+    let backend = Arc::new(MemoryBackend::new());
+    let (_, verifier_0_public_key) = generate_keys_from_secret(Network::Regtest, VERIFIER_0_SECRET);
+    let (_, verifier_1_public_key) = generate_keys_from_secret(Network::Regtest, VERIFIER_1_SECRET);
+    let n_of_n_public_keys = vec![verifier_0_public_key, verifier_1_public_key];
+    let verifier_0_context =
+        VerifierContext::new(Network::Regtest, VERIFIER_0_SECRET, &n_of_n_public_keys);
+    let operator_0_context =
+        OperatorContext::new(Network::Regtest, OPERATOR_0_SECRET, &n_of_n_public_keys);
+    let operator_1_context =
+        OperatorContext::new(Network::Regtest, OPERATOR_1_SECRET, &n_of_n_public_keys);
+    let operator_2_context =
+        OperatorContext::new(Network::Regtest, OPERATOR_2_SECRET, &n_of_n_public_keys);
+    let operator_public_keys = [
+        operator_0_context.operator_public_key,
+        operator_1_context.operator_public_key,
+    ];
+
+    let mut client_0 = create_client(
+        &backend,
+        &n_of_n_public_keys,
+        OPERATOR_0_SECRET,
+        VERIFIER_0_SECRET,
+        "test_operators_0",
+    )
+    .await;
+    let mut client_1 = create_client(
+        &backend,
+        &n_of_n_public_keys,
+        OPERATOR_1_SECRET,
+        VERIFIER_1_SECRET,
+        "test_operators_1",
+    )
+    .await;
+    let mut client_2 = create_client(
+        &backend,
+        &n_of_n_public_keys,
+        OPERATOR_2_SECRET,
+        VERIFIER_1_SECRET,
+        "test_operators_2",
+    )
+    .await;
+    client_0.set_operator_public_keys(&operator_public_keys);
+
+    // peg-in deposit
+    let (_, depositor_public_key) = generate_keys_from_secret(Network::Regtest, DEPOSITOR_SECRET);
+    let deposit_amount = Amount::from_sat(
+        DEPOSIT_AMOUNT + MIN_RELAY_FEE_PEG_IN_DEPOSIT + MIN_RELAY_FEE_PEG_IN_CONFIRM,
+    );
+    let outpoint = backend.fund(
+        generate_pay_to_pubkey_script_address(Network::Regtest, &depositor_public_key)
+            .script_pubkey(),
+        deposit_amount,
+    );
+    let peg_in_graph_id = client_0
+        .create_peg_in_graph(
+            Input {
+                outpoint,
+                amount: deposit_amount,
+            },
+            EVM_ADDRESS,
+        )
+        .await;
+    client_0
+        .broadcast_peg_in_deposit(&peg_in_graph_id)
+        .await
+        .unwrap();
+    backend.mine_blocks(1);
+
+    let peg_in_graph = client_0.data().peg_in_graphs[0].clone();
+    assert_eq!(peg_in_graph.operator_public_keys(), &operator_public_keys);
+    assert!(!peg_in_graph.accepts_operator(&operator_2_context.operator_public_key));
+    assert_eq!(
+        peg_in_graph
+            .verifier_status(backend.as_ref(), &verifier_0_context, &[])
+            .await,
+        PegInVerifierStatus::AwaitingPegOutCreation
+    );
+
+    // operator 0 creates its peg-out graph, operator 1 is still missing
+    fund_operator(&backend, &operator_0_context.operator_public_key);
+    client_0.process_peg_in_as_operator(&peg_in_graph_id).await;
+    let peg_in_graph = client_0.data().peg_in_graphs[0].clone();
+    let peg_outs = peg_outs_of(&client_0, &peg_in_graph);
+    assert_eq!(peg_outs.len(), 1);
+    assert_eq!(
+        peg_in_graph.missing_operators(&peg_outs),
+        vec![operator_1_context.operator_public_key]
+    );
+    assert_eq!(
+        peg_in_graph
+            .verifier_status(backend.as_ref(), &verifier_0_context, &peg_outs)
+            .await,
+        PegInVerifierStatus::AwaitingPegOutCreation
+    );
+
+    // operator 2 is not part of the peg-in
+    fund_operator(&backend, &operator_2_context.operator_public_key);
+    client_2.merge_data(client_0.data().clone());
+    client_2.process_peg_in_as_operator(&peg_in_graph_id).await;
+    assert_eq!(client_2.data().peg_out_graphs.len(), 1);
+
+    // operator 1 creates its peg-out graph, spending the same peg-in confirm
+    fund_operator(&backend, &operator_1_context.operator_public_key);
+    client_1.merge_data(client_0.data().clone());
+    client_1.process_peg_in_as_operator(&peg_in_graph_id).await;
+    client_0.merge_data(client_1.data().clone());
+    let peg_in_graph = client_0.data().peg_in_graphs[0].clone();
+    let peg_outs = peg_outs_of(&client_0, &peg_in_graph);
+    assert_eq!(peg_outs.len(), 2);
+    assert!(peg_outs
+        .iter()
+        .all(|peg_out| peg_out.peg_in_graph_id == peg_in_graph_id));
+    for operator_public_key in operator_public_keys.iter() {
+        assert!(peg_outs
+            .iter()
+            .any(|peg_out| peg_out.operator_public_key() == operator_public_key));
+    }
+    assert!(peg_in_graph.missing_operators(&peg_outs).is_empty());
+    assert!(matches!(
+        peg_in_graph
+            .verifier_status(backend.as_ref(), &verifier_0_context, &peg_outs)
+            .await,
+        PegInVerifierStatus::PendingOurNonces(_)
+    ));
+}
+
+#[tokio::test]
+async fn test_validate_rejects_peg_out_graph_of_unexpected_operator() {
+    if std::env::var(KEYSTORE_PASSPHRASE_ENV_VAR).is_err() {
+        std::env::set_var(KEYSTORE_PASSPHRASE_ENV_VAR, KEYSTORE_PASSPHRASE);
+    }
+    let backend = Arc::new(MemoryBackend::new());
+    let (_, verifier_0_public_key) = generate_keys_from_secret(Network::Regtest, VERIFIER_0_SECRET);
+    let n_of_n_public_keys = vec![verifier_0_public_key];
+    let operator_0_context =
+        OperatorContext::new(Network::Regtest, OPERATOR_0_SECRET, &n_of_n_public_keys);
+    let operator_2_context =
+        OperatorContext::new(Network::Regtest, OPERATOR_2_SECRET, &n_of_n_public_keys);
+
+    let mut client = create_client(
+        &backend,
+        &n_of_n_public_keys,
+        OPERATOR_2_SECRET,
+        VERIFIER_0_SECRET,
+        "test_operators_validate",
+    )
+    .await;
+    client.set_operator_public_keys(&[operator_0_context.operator_public_key]);
+    let (_, depositor_public_key) = generate_keys_from_secret(Network::Regtest, DEPOSITOR_SECRET);
+    let deposit_amount = Amount::from_sat(
+        DEPOSIT_AMOUNT + MIN_RELAY_FEE_PEG_IN_DEPOSIT + MIN_RELAY_FEE_PEG_IN_CONFIRM,
+    );
+    let peg_in_graph_id = client
+        .create_peg_in_graph(
+            Input {
+                outpoint: backend.fund(
+                    generate_pay_to_pubkey_script_address(Network::Regtest, &depositor_public_key)
+                        .script_pubkey(),
+                    deposit_amount,
+                ),
+                amount: deposit_amount,
+            },
+            EVM_ADDRESS,
+        )
+        .await;
+    assert!(BitVMClient::validate_data(backend.as_ref(), client.data()).await);
+
+    // the client refuses to create it, but a peer could still publish one
+    let peg_out_confirm_input = Input {
+        outpoint: backend.fund(
+            generate_pay_to_pubkey_script_address(
+                Network::Regtest,
+                &operator_2_context.operator_public_key,
+            )
+            .script_pubkey(),
+            Amount::from_sat(get_reward_amount(DEPOSIT_AMOUNT) + PEG_OUT_FEE),
+        ),
+        amount: Amount::from_sat(get_reward_amount(DEPOSIT_AMOUNT) + PEG_OUT_FEE),
+    };
+    let commitment_secrets = client.derive_commitment_secrets(&peg_in_graph_id);
+    let result = client.create_peg_out_graph(
+        &peg_in_graph_id,
+        Input {
+            outpoint: peg_out_confirm_input.outpoint,
+            amount: peg_out_confirm_input.amount,
+        },
+        commitment_secrets.clone(),
+    );
+    assert!(matches!(
+        result,
+        Err(Error::Graph(GraphError::UnexpectedOperator(operator_public_key)))
+            if operator_public_key == operator_2_context.operator_public_key
+    ));
+    assert!(client.data().peg_out_graphs.is_empty());
+
+    let peg_in_graph = client.data().peg_in_graphs[0].clone();
+    let peg_out_graph = PegOutGraph::new(
+        &operator_2_context,
+        &peg_in_graph,
+        peg_out_confirm_input,
+        &commitment_secrets,
+        &peg_in_graph.timelock_policy(),
+        &get_valid_proof().vk,
+    );
+    let mut data = client.data().clone();
+    data.peg_out_graphs.push(peg_out_graph);
+    assert!(!BitVMClient::validate_data(backend.as_ref(), &data).await);
+}
+
+#[tokio::test]
+async fn test_push_verifier_nonces_rejects_peg_out_graph_of_another_verifying_key() {
+    if std::env::var(KEYSTORE_PASSPHRASE_ENV_VAR).is_err() {
+        std::env::set_var(KEYSTORE_PASSPHRASE_ENV_VAR, KEYSTORE_PASSPHRASE);
+    }
+    let backend = Arc::new(MemoryBackend::new());
+    let (_, verifier_0_public_key) = generate_keys_from_secret(Network::Regtest, VERIFIER_0_SECRET);
+    let n_of_n_public_keys = vec![verifier_0_public_key];
+    let operator_0_context =
+        OperatorContext::new(Network::Regtest, OPERATOR_0_SECRET, &n_of_n_public_keys);
+
+    let mut client = create_client(
+        &backend,
+        &n_of_n_public_keys,
+        OPERATOR_0_SECRET,
+        VERIFIER_0_SECRET,
+        "test_operators_verifying_key",
+    )
+    .await;
+    let (_, depositor_public_key) = generate_keys_from_secret(Network::Regtest, DEPOSITOR_SECRET);
+    let deposit_amount = Amount::from_sat(
+        DEPOSIT_AMOUNT + MIN_RELAY_FEE_PEG_IN_DEPOSIT + MIN_RELAY_FEE_PEG_IN_CONFIRM,
+    );
+    let peg_in_graph_id = client
+        .create_peg_in_graph(
+            Input {
+                outpoint: backend.fund(
+                    generate_pay_to_pubkey_script_address(Network::Regtest, &depositor_public_key)
+                        .script_pubkey(),
+                    deposit_amount,
+                ),
+                amount: deposit_amount,
+            },
+            EVM_ADDRESS,
+        )
+        .await;
+    let peg_out_confirm_amount = Amount::from_sat(get_reward_amount(DEPOSIT_AMOUNT) + PEG_OUT_FEE);
+    let commitment_secrets = client.derive_commitment_secrets(&peg_in_graph_id);
+    let peg_out_graph_id = client
+        .create_peg_out_graph(
+            &peg_in_graph_id,
+            Input {
+                outpoint: backend.fund(
+                    generate_pay_to_pubkey_script_address(
+                        Network::Regtest,
+                        &operator_0_context.operator_public_key,
+                    )
+                    .script_pubkey(),
+                    peg_out_confirm_amount,
+                ),
+                amount: peg_out_confirm_amount,
+            },
+            commitment_secrets,
+        )
+        .unwrap();
+
+    // a verifier expecting proofs of another circuit does not sign the graph
+    let mut vk = get_valid_proof().vk;
+    vk.beta_g2 = vk.gamma_g2;
+    let mut other_client = BitVMClient::new(
+        Some(backend.clone()),
+        Network::Regtest,
+        DestinationNetwork::Local,
+        Some(get_chain_adaptor(DestinationNetwork::Local, None, None)),
+        &n_of_n_public_keys,
+        Some(DEPOSITOR_SECRET),
+        Some(OPERATOR_0_SECRET),
+        Some(VERIFIER_0_SECRET),
+        None,
+        Some("test_operators_other_verifying_key"),
+        Some(vk),
+    )
+    .await;
+    other_client.merge_data(client.data().clone());
+    assert!(matches!(
+        other_client.push_verifier_nonces(&peg_out_graph_id),
+        Err(Error::Client(ClientError::VerifyingKeyMismatch(graph_id)))
+            if graph_id == peg_out_graph_id
+    ));
+
+    client.push_verifier_nonces(&peg_out_graph_id).unwrap();
+}
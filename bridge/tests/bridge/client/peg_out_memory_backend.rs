@@ -0,0 +1,171 @@
+use std::sync::Arc;
+
+use bitcoin::{Amount, Network, PublicKey, Txid};
+use bridge::{
+    client::{
+        bitcoin_backend::{bitcoin_backend::BitcoinBackend, memory_backend::MemoryBackend},
+        chain::{chain::PegOutEvent, chain_adaptor::get_chain_adaptor},
+        client::BitVMClient,
+        keystore::KEYSTORE_PASSPHRASE_ENV_VAR,
+    },
+    constants::{DestinationNetwork, DESTINATION_NETWORK_TXID_LENGTH},
+    contexts::base::generate_keys_from_secret,
+    graphs::base::{BaseGraph, PEG_OUT_FEE},
+    scripts::generate_pay_to_pubkey_script_address,
+    timelock_policy::TimelockPolicy,
+    transactions::base::{
+        Input, MIN_RELAY_FEE_PEG_IN_CONFIRM, MIN_RELAY_FEE_PEG_IN_DEPOSIT, MIN_RELAY_FEE_PEG_OUT,
+    },
+};
+
+use crate::bridge::helper::{get_default_peg_out_event, get_reward_amount, get_valid_proof};
+
+const DEPOSITOR_SECRET: &str = "b8f17ea979be24199e7c3fec71ee88914d92fd4ca508443f765d56ce024ef1d7";
+const VERIFIER_SECRET: &str = "ee0817eac0c13aa8ee2dd3256304041f09f0499d1089b56495310ae8093583e2";
+const OPERATOR_SECRET: &str = "3076ca1dfc1e383be26d5dd3c0c427340f96139fa8c2520862cf551ec2d670ac";
+const KEYSTORE_PASSPHRASE: &str = "test keystore passphrase";
+const EVM_ADDRESS: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+const DEPOSIT_AMOUNT: u64 = 2 << 20;
+
+async fn assert_confirmed(backend: &MemoryBackend, txid: &Txid) {
+    assert!(backend.get_tx_status(txid).await.unwrap().confirmed);
+}
+
+// Runs the happy path of a peg-in and its peg-out, up to the operator taking the deposit with
+// take 1, against the in-memory chain, so no esplora or bitcoind node is needed.
+#[tokio::test]
+async fn test_memory_backend_peg_in_and_peg_out_take_1() {
+    if std::env::var(KEYSTORE_PASSPHRASE_ENV_VAR).is_err() {
+        std::env::set_var(KEYSTORE_PASSPHRASE_ENV_VAR, KEYSTORE_PASSPHRASE);
+    }
+    let backend = Arc::new(MemoryBackend::new());
+    let (_, verifier_public_key) = generate_keys_from_secret(Network::Regtest, VERIFIER_SECRET);
+    let (_, operator_public_key) = generate_keys_from_secret(Network::Regtest, OPERATOR_SECRET);
+    let (_, depositor_public_key) = generate_keys_from_secret(Network::Regtest, DEPOSITOR_SECRET);
+    let n_of_n_public_keys: Vec<PublicKey> = vec![verifier_public_key];
+    let timelock_policy = TimelockPolicy::for_network(Network::Regtest);
+
+    // a single client plays the depositor, the operator and the only verifier
+    let mut client = BitVMClient::new(
+        Some(backend.clone()),
+        Network::Regtest,
+        DestinationNetwork::Local,
+        Some(get_chain_adaptor(DestinationNetwork::Local, None, None)),
+        &n_of_n_public_keys,
+        Some(DEPOSITOR_SECRET),
+        Some(OPERATOR_SECRET),
+        Some(VERIFIER_SECRET),
+        None,
+        Some("test_memory_backend_peg_out"),
+        Some(get_valid_proof().vk),
+    )
+    .await;
+    client.set_timelock_policy(timelock_policy);
+
+    // peg-in
+    let deposit_amount = Amount::from_sat(
+        DEPOSIT_AMOUNT + MIN_RELAY_FEE_PEG_IN_DEPOSIT + MIN_RELAY_FEE_PEG_IN_CONFIRM,
+    );
+    let peg_in_graph_id = client
+        .create_peg_in_graph(
+            Input {
+                outpoint: backend.fund(
+                    generate_pay_to_pubkey_script_address(Network::Regtest, &depositor_public_key)
+                        .script_pubkey(),
+                    deposit_amount,
+                ),
+                amount: deposit_amount,
+            },
+            EVM_ADDRESS,
+        )
+        .await;
+    let peg_in_deposit_txid = client
+        .broadcast_peg_in_deposit(&peg_in_graph_id)
+        .await
+        .unwrap();
+    backend.mine_blocks(1);
+    assert_confirmed(&backend, &peg_in_deposit_txid).await;
+
+    client.push_verifier_nonces(&peg_in_graph_id).unwrap();
+    client.push_verifier_signature(&peg_in_graph_id).unwrap();
+    let peg_in_confirm_txid = client
+        .broadcast_peg_in_confirm(&peg_in_graph_id)
+        .await
+        .unwrap();
+    backend.mine_blocks(1);
+    assert_confirmed(&backend, &peg_in_confirm_txid).await;
+
+    // peg-out graph
+    let operator_script_pubkey =
+        generate_pay_to_pubkey_script_address(Network::Regtest, &operator_public_key)
+            .script_pubkey();
+    backend.fund(
+        operator_script_pubkey.clone(),
+        Amount::from_sat(get_reward_amount(DEPOSIT_AMOUNT) + PEG_OUT_FEE),
+    );
+    client.process_peg_in_as_operator(&peg_in_graph_id).await;
+    assert_eq!(client.data().peg_out_graphs.len(), 1);
+    let peg_out_graph_id = client.data().peg_out_graphs[0].id().clone();
+
+    client.push_verifier_nonces(&peg_out_graph_id).unwrap();
+    client.push_verifier_signature(&peg_out_graph_id).unwrap();
+
+    // the operator pays the withdrawer, then claims the deposit
+    client
+        .get_peg_out_graph_mut(&peg_out_graph_id)
+        .unwrap()
+        .peg_out_chain_event = Some(PegOutEvent {
+        tx_hash: vec![1; DESTINATION_NETWORK_TXID_LENGTH],
+        ..get_default_peg_out_event()
+    });
+    let peg_out_amount = Amount::from_sat(DEPOSIT_AMOUNT + MIN_RELAY_FEE_PEG_OUT);
+    let peg_out_input = Input {
+        outpoint: backend.fund(operator_script_pubkey, peg_out_amount),
+        amount: peg_out_amount,
+    };
+    let peg_out_txid = client
+        .broadcast_peg_out(&peg_out_graph_id, peg_out_input)
+        .await
+        .unwrap();
+    backend.mine_blocks(1);
+    assert_confirmed(&backend, &peg_out_txid).await;
+
+    let peg_out_confirm_txid = client
+        .broadcast_peg_out_confirm(&peg_out_graph_id)
+        .await
+        .unwrap();
+    backend.mine_blocks(1);
+    assert_confirmed(&backend, &peg_out_confirm_txid).await;
+
+    let kick_off_1_txid = client
+        .broadcast_kick_off_1(&peg_out_graph_id)
+        .await
+        .unwrap();
+    backend.mine_blocks(1);
+    assert_confirmed(&backend, &kick_off_1_txid).await;
+
+    let start_time_txid = client
+        .broadcast_start_time(&peg_out_graph_id)
+        .await
+        .unwrap();
+    backend.mine_blocks(1);
+    assert_confirmed(&backend, &start_time_txid).await;
+
+    // kick off 2 spends connector 1 and is broadcast one block before the timelock from kick off
+    // 1 expires, so it is mined in the first block satisfying it
+    backend.mine_blocks(timelock_policy.kick_off_2 - 2);
+    let kick_off_2_txid = client
+        .broadcast_kick_off_2(&peg_out_graph_id)
+        .await
+        .unwrap();
+    backend.mine_blocks(1);
+    assert_confirmed(&backend, &kick_off_2_txid).await;
+
+    assert!(client.broadcast_take_1(&peg_out_graph_id).await.is_err());
+    backend.mine_blocks(timelock_policy.take_1 - 2);
+    assert!(client.broadcast_take_1(&peg_out_graph_id).await.is_err());
+    backend.mine_blocks(1);
+    let take_1_txid = client.broadcast_take_1(&peg_out_graph_id).await.unwrap();
+    backend.mine_blocks(1);
+    assert_confirmed(&backend, &take_1_txid).await;
+}
@@ -1,29 +1,31 @@
-use std::str::FromStr;
+use std::{str::FromStr, sync::Arc};
 
 use bitcoin::{Amount, OutPoint, Txid};
 
 use bridge::{
-    client::client::{BitVMClient, BitVMClientPublicData},
+    client::{
+        bitcoin_backend::bitcoin_backend::BitcoinBackend,
+        client::{BitVMClient, BitVMClientPublicData},
+    },
     graphs::{base::PEG_OUT_FEE, peg_in::PegInGraph, peg_out::PegOutGraph},
     scripts::generate_burn_script,
     transactions::{base::Input, pre_signed::PreSignedTransaction},
 };
-use esplora_client::AsyncClient;
 
 use crate::bridge::setup::{setup_test, INITIAL_AMOUNT};
 
 #[tokio::test]
 async fn test_validate_success() {
-    let (esplora, data, _) = setup_and_create_graphs().await;
+    let (bitcoin_backend, data, _) = setup_and_create_graphs().await;
 
-    let result = BitVMClient::validate_data(&esplora, &data).await;
+    let result = BitVMClient::validate_data(bitcoin_backend.as_ref(), &data).await;
 
     assert!(result);
 }
 
 #[tokio::test]
 async fn test_validate_invalid_previous_output() {
-    let (esplora, mut data, peg_in_outpoint) = setup_and_create_graphs().await;
+    let (bitcoin_backend, mut data, peg_in_outpoint) = setup_and_create_graphs().await;
 
     let changed_outpoint = OutPoint {
         txid: peg_in_outpoint.txid,
@@ -33,60 +35,60 @@ async fn test_validate_invalid_previous_output() {
     let deposit_tx = data.peg_in_graphs[1].peg_in_deposit_transaction.tx_mut();
     deposit_tx.input[0].previous_output = changed_outpoint;
 
-    let result = BitVMClient::validate_data(&esplora, &data).await;
+    let result = BitVMClient::validate_data(bitcoin_backend.as_ref(), &data).await;
 
     assert!(!result);
 }
 
 #[tokio::test]
 async fn test_validate_invalid_script_sig() {
-    let (esplora, mut data, _) = setup_and_create_graphs().await;
+    let (bitcoin_backend, mut data, _) = setup_and_create_graphs().await;
 
     let deposit_tx = data.peg_in_graphs[1].peg_in_deposit_transaction.tx_mut();
     deposit_tx.input[0].script_sig = generate_burn_script();
 
-    let result = BitVMClient::validate_data(&esplora, &data).await;
+    let result = BitVMClient::validate_data(bitcoin_backend.as_ref(), &data).await;
 
     assert!(!result);
 }
 
 #[tokio::test]
 async fn test_validate_invalid_sequence() {
-    let (esplora, mut data, _) = setup_and_create_graphs().await;
+    let (bitcoin_backend, mut data, _) = setup_and_create_graphs().await;
 
     let deposit_tx = data.peg_in_graphs[1].peg_in_deposit_transaction.tx_mut();
     deposit_tx.input[0].sequence = bitcoin::Sequence(100);
 
-    let result = BitVMClient::validate_data(&esplora, &data).await;
+    let result = BitVMClient::validate_data(bitcoin_backend.as_ref(), &data).await;
 
     assert!(!result);
 }
 
 #[tokio::test]
 async fn test_validate_invalid_value() {
-    let (esplora, mut data, _) = setup_and_create_graphs().await;
+    let (bitcoin_backend, mut data, _) = setup_and_create_graphs().await;
 
     let deposit_tx = data.peg_in_graphs[1].peg_in_deposit_transaction.tx_mut();
     deposit_tx.output[0].value = Amount::from_sat(1);
 
-    let result = BitVMClient::validate_data(&esplora, &data).await;
+    let result = BitVMClient::validate_data(bitcoin_backend.as_ref(), &data).await;
 
     assert!(!result);
 }
 
 #[tokio::test]
 async fn test_validate_invalid_script_pubkey() {
-    let (esplora, mut data, _) = setup_and_create_graphs().await;
+    let (bitcoin_backend, mut data, _) = setup_and_create_graphs().await;
 
     let deposit_tx = data.peg_in_graphs[1].peg_in_deposit_transaction.tx_mut();
     deposit_tx.output[0].script_pubkey = generate_burn_script();
 
-    let result = BitVMClient::validate_data(&esplora, &data).await;
+    let result = BitVMClient::validate_data(bitcoin_backend.as_ref(), &data).await;
 
     assert!(!result);
 }
 
-async fn setup_and_create_graphs() -> (AsyncClient, BitVMClientPublicData, OutPoint) {
+async fn setup_and_create_graphs() -> (Arc<dyn BitcoinBackend>, BitVMClientPublicData, OutPoint) {
     let config = setup_test().await;
 
     let amount_0 = Amount::from_sat(INITIAL_AMOUNT + PEG_OUT_FEE + 1);
@@ -109,6 +111,8 @@ async fn setup_and_create_graphs() -> (AsyncClient, BitVMClientPublicData, OutPo
             amount: amount_0,
         },
         &config.depositor_evm_address,
+        &[config.operator_context.operator_public_key],
+        &config.timelock_policy,
     );
 
     let peg_in_graph_1 = PegInGraph::new(
@@ -118,6 +122,8 @@ async fn setup_and_create_graphs() -> (AsyncClient, BitVMClientPublicData, OutPo
             amount: amount_1,
         },
         &config.depositor_evm_address,
+        &[config.operator_context.operator_public_key],
+        &config.timelock_policy,
     );
 
     let peg_out_graph = PegOutGraph::new(
@@ -128,6 +134,8 @@ async fn setup_and_create_graphs() -> (AsyncClient, BitVMClientPublicData, OutPo
             amount: amount_0,
         },
         &config.commitment_secrets,
+        &config.timelock_policy,
+        &config.valid_proof.vk,
     );
 
     let data = BitVMClientPublicData {
@@ -136,5 +144,5 @@ async fn setup_and_create_graphs() -> (AsyncClient, BitVMClientPublicData, OutPo
         peg_out_graphs: vec![peg_out_graph],
     };
 
-    (config.client_0.esplora, data, peg_in_outpoint)
+    (config.client_0.bitcoin_backend, data, peg_in_outpoint)
 }
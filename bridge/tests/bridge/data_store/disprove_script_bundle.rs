@@ -0,0 +1,68 @@
+use bitcoin::ScriptBuf;
+use bitvm::chunk::{
+    api::{num_pubs, num_taps},
+    bundle::DisproveScriptBundle,
+};
+use bridge::{
+    client::data_store::{
+        base::DataStoreDriver,
+        data_store::DataStore,
+        local_file::{LocalFile, TEST_DATA_DIRECTORY_NAME},
+    },
+    connectors::connector_c::{
+        fetch_disprove_script_bundle, get_disprove_script_bundle_file_name,
+        get_disprove_script_bundle_path, get_disprove_script_bundle_remote_path,
+    },
+};
+
+use crate::bridge::helper::get_valid_proof;
+
+const LOCAL_FILE_DATA_STORE_ENV_VAR: &str = "BRIDGE_USE_LOCAL_FILE_DATA_STORE";
+
+#[tokio::test]
+async fn test_fetch_disprove_script_bundle_from_data_store_then_disk() {
+    if std::env::var(LOCAL_FILE_DATA_STORE_ENV_VAR).is_err() {
+        std::env::set_var(LOCAL_FILE_DATA_STORE_ENV_VAR, "true");
+    }
+    let data_store = DataStore::new().await;
+    let local_file = LocalFile::new().unwrap();
+
+    // another verifying key than the one of the other tests, whose lock scripts would otherwise be
+    // generated from the placeholder scripts below
+    let mut vk = get_valid_proof().vk;
+    vk.beta_g2 = vk.gamma_g2;
+    let bundle = DisproveScriptBundle::from_partial_scripts(
+        &vk,
+        vec![ScriptBuf::new(); num_taps(num_pubs(&vk))],
+    );
+    let file_name = get_disprove_script_bundle_file_name(&vk);
+    let remote_path = get_disprove_script_bundle_remote_path();
+    let disk_path = get_disprove_script_bundle_path(&vk);
+    let store_path = std::path::Path::new(TEST_DATA_DIRECTORY_NAME)
+        .join("shared_file_store")
+        .join(&remote_path)
+        .join(&file_name);
+    std::fs::remove_file(&disk_path).ok();
+    std::fs::remove_file(&store_path).ok();
+
+    assert_eq!(fetch_disprove_script_bundle(&data_store, &vk).await, None);
+
+    local_file
+        .upload_compressed_object(&file_name, &bundle.to_bytes(), Some(&remote_path))
+        .await
+        .unwrap();
+    assert_eq!(
+        fetch_disprove_script_bundle(&data_store, &vk).await,
+        Some(bundle.clone())
+    );
+    assert!(disk_path.exists());
+
+    // the bundle written to disk is used once the data store no longer has it
+    std::fs::remove_file(&store_path).unwrap();
+    assert_eq!(
+        fetch_disprove_script_bundle(&data_store, &vk).await,
+        Some(bundle)
+    );
+
+    std::fs::remove_file(&disk_path).unwrap();
+}
@@ -1,3 +1,4 @@
+pub mod disprove_script_bundle;
 pub mod ftp;
 pub mod ftps;
 pub mod sftp;
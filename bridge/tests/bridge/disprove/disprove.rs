@@ -78,7 +78,7 @@ async fn test_disprove_tx_success() {
 
     let tx = disprove_tx.finalize();
     check_tx_output_sum(INITIAL_AMOUNT, &tx);
-    let result = config.client_0.esplora.broadcast(&tx).await;
+    let result = config.client_0.bitcoin_backend.broadcast(&tx).await;
     println!("Txid: {:?}", tx.compute_txid());
     println!("Disprove tx result: {:?}\n", result);
     assert!(result.is_ok());
@@ -166,7 +166,7 @@ async fn test_disprove_tx_with_verifier_added_to_output_success() {
     tx.output.push(verifier_output);
     check_tx_output_sum(INITIAL_AMOUNT, &tx);
 
-    let result = config.client_0.esplora.broadcast(&tx).await;
+    let result = config.client_0.bitcoin_backend.broadcast(&tx).await;
     println!("Txid: {:?}", tx.compute_txid());
     println!("Disprove tx result: {:?}\n", result);
     assert!(result.is_ok());
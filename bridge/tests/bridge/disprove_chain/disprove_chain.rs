@@ -1,11 +1,11 @@
-use bitcoin::{Amount, Network::Regtest};
+use bitcoin::Amount;
 
 use bitvm::signatures::signing_winternitz::{generate_winternitz_witness, WinternitzSigningInputs};
 use bridge::{
     commitments::CommitmentMessageId,
     connectors::base::TaprootConnector,
     scripts::generate_pay_to_pubkey_script_address,
-    superblock::{get_start_time_block_number, get_superblock_hash_message},
+    superblock::get_superblock_hash_message,
     transactions::{
         base::{BaseTransaction, Input, MIN_RELAY_FEE_DISPROVE_CHAIN},
         disprove_chain::DisproveChainTransaction,
@@ -70,7 +70,7 @@ async fn test_disprove_chain_tx_success() {
     let disprove_sb = get_superblock_header();
 
     let start_time_witness = generate_winternitz_witness(&WinternitzSigningInputs {
-        message: &get_start_time_block_number(Regtest).to_le_bytes(),
+        message: &(disprove_sb.time - 1).to_le_bytes(),
         signing_key: &config.commitment_secrets[&CommitmentMessageId::StartTime],
     });
 
@@ -83,7 +83,7 @@ async fn test_disprove_chain_tx_success() {
     let tx = disprove_chain_tx.finalize();
     check_tx_output_sum(INITIAL_AMOUNT, &tx);
 
-    let result = config.client_0.esplora.broadcast(&tx).await;
+    let result = config.client_0.bitcoin_backend.broadcast(&tx).await;
     println!("Txid: {:?}", tx.compute_txid());
     println!("Disprove Chain tx result: {:?}\n", result);
     assert!(result.is_ok());
@@ -82,7 +82,7 @@ async fn test_peg_out_for_chain() {
     let peg_out_tx_id = peg_out_tx.compute_txid();
 
     // mine peg-out
-    let peg_out_result = config.client_0.esplora.broadcast(&peg_out_tx).await;
+    let peg_out_result = config.client_0.bitcoin_backend.broadcast(&peg_out_tx).await;
     println!("Peg Out Tx result: {:?}", peg_out_result);
     assert!(peg_out_result.is_ok());
     println!("Peg Out Txid: {:?}", peg_out_tx_id);
@@ -10,13 +10,12 @@ use bridge::{
     transactions::pre_signed::PreSignedTransaction,
 };
 use bridge::{
-    client::client::BitVMClient,
+    client::{bitcoin_backend::esplora_backend::EsploraBackend, client::BitVMClient},
     contexts::{depositor::DepositorContext, operator::OperatorContext},
     graphs::peg_out::PegOutOperatorStatus,
     scripts::generate_pay_to_pubkey_script_address,
     transactions::base::Input,
 };
-use esplora_client::Builder;
 use futures::StreamExt;
 use serial_test::serial;
 
@@ -31,11 +30,9 @@ async fn test_e2e_0_simulate_complete_peg_in() {
     operator_client.sync().await;
     operator_client.sync_l2().await;
 
-    let esplora = Builder::new(REGTEST_ESPLORA_URL)
-        .build_async()
-        .expect("Could not build esplora client");
+    let bitcoin_backend = EsploraBackend::new(REGTEST_ESPLORA_URL);
     let peg_out_graph = find_peg_out_graph(&operator_client, peg_out_graph_id.as_str()).unwrap();
-    let status = peg_out_graph.operator_status(&esplora).await;
+    let status = peg_out_graph.operator_status(&bitcoin_backend).await;
     println!("Graph id: {} status: {}", peg_out_graph.id(), status);
     println!("Peg in completed, please proceed to initate peg out in UI.");
 }
@@ -45,9 +42,7 @@ async fn test_e2e_0_simulate_complete_peg_in() {
 #[serial]
 async fn test_e2e_1_simulate_peg_out() {
     let config = setup_test().await;
-    let esplora = Builder::new(REGTEST_ESPLORA_URL)
-        .build_async()
-        .expect("Could not build esplora client");
+    let bitcoin_backend = EsploraBackend::new(REGTEST_ESPLORA_URL);
 
     let mut operator_client = config.client_0;
     operator_client.sync().await;
@@ -58,7 +53,7 @@ async fn test_e2e_1_simulate_peg_out() {
     let peg_out_graph_result = futures::stream::iter(peg_out_graphs)
         .filter(|g| {
             Box::pin(async {
-                let status = g.operator_status(&esplora).await;
+                let status = g.operator_status(&bitcoin_backend).await;
                 println!("Graph id: {} status: {}", g.id(), status);
                 match status {
                     PegOutOperatorStatus::PegOutStartPegOut => true,
@@ -208,32 +203,42 @@ async fn create_graph() -> (
 
     depositor_operator_verifier_0_client.sync().await;
     println!("Creating peg-out graph...");
-    let peg_out_graph_id = depositor_operator_verifier_0_client.create_peg_out_graph(
-        &peg_in_graph_id,
-        Input {
-            outpoint: kick_off_outpoint,
-            amount: kick_off_input_amount,
-        },
-        config.commitment_secrets,
-    );
+    let peg_out_graph_id = depositor_operator_verifier_0_client
+        .create_peg_out_graph(
+            &peg_in_graph_id,
+            Input {
+                outpoint: kick_off_outpoint,
+                amount: kick_off_input_amount,
+            },
+            config.commitment_secrets,
+        )
+        .unwrap();
 
     println!("Verifier 0 push peg-out nonces");
-    depositor_operator_verifier_0_client.push_verifier_nonces(&peg_out_graph_id);
+    depositor_operator_verifier_0_client
+        .push_verifier_nonces(&peg_out_graph_id)
+        .unwrap();
     depositor_operator_verifier_0_client.flush().await;
 
     println!("Verifier 1 push peg-out nonces");
     verifier_1_client.sync().await;
-    verifier_1_client.push_verifier_nonces(&peg_out_graph_id);
+    verifier_1_client
+        .push_verifier_nonces(&peg_out_graph_id)
+        .unwrap();
     verifier_1_client.flush().await;
 
     println!("Verifier 0 pre-sign peg-out");
     depositor_operator_verifier_0_client.sync().await;
-    depositor_operator_verifier_0_client.push_verifier_signature(&peg_out_graph_id);
+    depositor_operator_verifier_0_client
+        .push_verifier_signature(&peg_out_graph_id)
+        .expect("Failed to push verifier signature");
     depositor_operator_verifier_0_client.flush().await;
 
     println!("Verifier 1 pre-sign peg-out");
     verifier_1_client.sync().await;
-    verifier_1_client.push_verifier_signature(&peg_out_graph_id);
+    verifier_1_client
+        .push_verifier_signature(&peg_out_graph_id)
+        .expect("Failed to push verifier signature");
     verifier_1_client.flush().await;
 
     (
@@ -268,19 +273,23 @@ async fn create_peg_in_graph(
         .broadcast_peg_in_deposit(&graph_id)
         .await
         .expect("Failed to broadcast peg-in deposit");
-    client_0.push_verifier_nonces(&graph_id);
+    client_0.push_verifier_nonces(&graph_id).unwrap();
     client_0.flush().await;
 
     client_1.sync().await;
-    client_1.push_verifier_nonces(&graph_id);
+    client_1.push_verifier_nonces(&graph_id).unwrap();
     client_1.flush().await;
 
     client_0.sync().await;
-    client_0.push_verifier_signature(&graph_id);
+    client_0
+        .push_verifier_signature(&graph_id)
+        .expect("Failed to push verifier signature");
     client_0.flush().await;
 
     client_1.sync().await;
-    client_1.push_verifier_signature(&graph_id);
+    client_1
+        .push_verifier_signature(&graph_id)
+        .expect("Failed to push verifier signature");
     client_1.flush().await;
 
     // Wait for peg-in deposit transaction to be mined
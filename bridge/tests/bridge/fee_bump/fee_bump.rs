@@ -0,0 +1,298 @@
+use bitcoin::{
+    absolute, key::Keypair, transaction::Version, Amount, FeeRate, Network, OutPoint, PublicKey,
+    ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness,
+};
+use bridge::{
+    client::{
+        bitcoin_backend::{bitcoin_backend::BitcoinBackend, memory_backend::MemoryBackend},
+        fee_bumper::{FeeBumpPolicy, FeeBumper},
+        watchtower::Watchtower,
+    },
+    contexts::base::generate_keys_from_secret,
+    error::FeeBumpError,
+    graphs::base::ANCHOR_AMOUNT,
+    scripts::{generate_pay_to_anchor_script, generate_pay_to_pubkey_script_address},
+    transactions::{
+        base::{anchor_vout, generate_anchor_output},
+        cpfp::{generate_cpfp_tx, package_fee_rate},
+    },
+};
+
+const OPERATOR_SECRET: &str = "3076ca1dfc1e383be26d5dd3c0c427340f96139fa8c2520862cf551ec2d670ac";
+const FUNDING_AMOUNT: u64 = 100_000;
+// a few sat/vB, below the fee rate of the simulated fee spike
+const PARENT_FEE: u64 = 300;
+const SPIKE_FEE_RATE: u64 = 20;
+
+struct Setup {
+    backend: MemoryBackend,
+    keypair: Keypair,
+    funding_script_pubkey: ScriptBuf,
+    // pre-signed transaction paying the minimum relay fee, with an anchor output
+    parent: Transaction,
+    // confirmed output paying to the operator, left to fund the children
+    funding_utxo: (OutPoint, TxOut),
+}
+
+fn setup() -> Setup {
+    let backend = MemoryBackend::new();
+    let (keypair, public_key) = generate_keys_from_secret(Network::Regtest, OPERATOR_SECRET);
+    let funding_script_pubkey =
+        generate_pay_to_pubkey_script_address(Network::Regtest, &public_key).script_pubkey();
+    let parent_input = backend.fund(
+        funding_script_pubkey.clone(),
+        Amount::from_sat(FUNDING_AMOUNT),
+    );
+    let funding_outpoint = backend.fund(
+        funding_script_pubkey.clone(),
+        Amount::from_sat(FUNDING_AMOUNT),
+    );
+
+    let parent = Transaction {
+        version: Version(2),
+        lock_time: absolute::LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: parent_input,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::default(),
+        }],
+        output: vec![
+            TxOut {
+                value: Amount::from_sat(FUNDING_AMOUNT - PARENT_FEE - ANCHOR_AMOUNT),
+                script_pubkey: ScriptBuf::new(),
+            },
+            generate_anchor_output(),
+        ],
+    };
+    let funding_utxo = (
+        funding_outpoint,
+        TxOut {
+            value: Amount::from_sat(FUNDING_AMOUNT),
+            script_pubkey: funding_script_pubkey.clone(),
+        },
+    );
+
+    Setup {
+        backend,
+        keypair,
+        funding_script_pubkey,
+        parent,
+        funding_utxo,
+    }
+}
+
+fn anchor(parent: &Transaction) -> (OutPoint, TxOut) {
+    let vout = anchor_vout(parent).unwrap();
+    (
+        OutPoint::new(parent.compute_txid(), vout),
+        parent.output[vout as usize].clone(),
+    )
+}
+
+#[test]
+fn test_anchor_output() {
+    let anchor_output = generate_anchor_output();
+    assert_eq!(anchor_output.value, Amount::from_sat(ANCHOR_AMOUNT));
+    assert_eq!(
+        anchor_output.script_pubkey.to_hex_string(),
+        "51024e73" // OP_1 OP_PUSHBYTES_2 4e73
+    );
+    assert!(anchor_output.script_pubkey.is_witness_program());
+
+    let setup = setup();
+    assert_eq!(anchor_vout(&setup.parent), Some(1));
+    assert_eq!(
+        setup.parent.output[1].script_pubkey,
+        generate_pay_to_anchor_script()
+    );
+}
+
+#[tokio::test]
+async fn test_cpfp_child_pulls_parent_into_block() {
+    let setup = setup();
+    let fee_rate = FeeRate::from_sat_per_vb_unchecked(SPIKE_FEE_RATE);
+    setup.backend.set_fee_rate(fee_rate);
+    assert_eq!(setup.backend.estimate_fee_rate(1).await.unwrap(), fee_rate);
+
+    let parent_txid = setup.parent.compute_txid();
+    setup.backend.broadcast(&setup.parent).await.unwrap();
+    setup.backend.mine_blocks(1);
+    assert!(
+        !setup
+            .backend
+            .get_tx_status(&parent_txid)
+            .await
+            .unwrap()
+            .confirmed
+    );
+
+    let package = vec![(setup.parent.clone(), Amount::from_sat(PARENT_FEE))];
+    assert!(package_fee_rate(&package) < fee_rate);
+    let (child, child_fee) = generate_cpfp_tx(
+        Network::Regtest,
+        &setup.keypair,
+        &package,
+        anchor(&setup.parent),
+        &[setup.funding_utxo.clone()],
+        fee_rate,
+    )
+    .unwrap();
+    assert_eq!(child.input.len(), 2);
+    assert_eq!(child.output[0].script_pubkey, setup.funding_script_pubkey);
+    assert_eq!(
+        child.output[0].value,
+        Amount::from_sat(FUNDING_AMOUNT + ANCHOR_AMOUNT) - child_fee
+    );
+    assert!(package_fee_rate(&[package[0].clone(), (child.clone(), child_fee)]) >= fee_rate);
+
+    setup.backend.broadcast(&child).await.unwrap();
+    setup.backend.mine_blocks(1);
+    for txid in [parent_txid, child.compute_txid()] {
+        assert!(setup.backend.get_tx_status(&txid).await.unwrap().confirmed);
+    }
+}
+
+#[test]
+fn test_cpfp_child_errors() {
+    let setup = setup();
+    let fee_rate = FeeRate::from_sat_per_vb_unchecked(SPIKE_FEE_RATE);
+    let package = vec![(setup.parent.clone(), Amount::from_sat(PARENT_FEE))];
+
+    let unspendable_output = (
+        OutPoint::new(setup.parent.compute_txid(), 0),
+        setup.parent.output[0].clone(),
+    );
+    assert_eq!(
+        generate_cpfp_tx(
+            Network::Regtest,
+            &setup.keypair,
+            &package,
+            unspendable_output.clone(),
+            &[setup.funding_utxo.clone()],
+            fee_rate,
+        )
+        .err(),
+        Some(FeeBumpError::UnspendableOutput(unspendable_output.0))
+    );
+
+    // the anchor alone cannot pay for the package
+    assert!(matches!(
+        generate_cpfp_tx(
+            Network::Regtest,
+            &setup.keypair,
+            &package,
+            anchor(&setup.parent),
+            &[],
+            fee_rate,
+        ),
+        Err(FeeBumpError::InsufficientFunds(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_fee_bumper_bumps_stuck_transaction() {
+    let setup = setup();
+    setup
+        .backend
+        .set_fee_rate(FeeRate::from_sat_per_vb_unchecked(SPIKE_FEE_RATE));
+    let parent_txid = setup.parent.compute_txid();
+    setup.backend.broadcast(&setup.parent).await.unwrap();
+
+    let watchtower = Watchtower::new(setup.backend.get_height().await.unwrap());
+    let mut fee_bumper = FeeBumper::new(
+        Network::Regtest,
+        FeeBumpPolicy {
+            stuck_blocks: 1,
+            ..FeeBumpPolicy::default()
+        },
+    );
+    fee_bumper.track(
+        &"graph".to_string(),
+        setup.parent.clone(),
+        anchor_vout(&setup.parent).unwrap(),
+        &setup.keypair,
+        None,
+    );
+
+    // not stuck yet
+    assert!(fee_bumper
+        .poll(&setup.backend, &watchtower)
+        .await
+        .unwrap()
+        .is_empty());
+
+    setup.backend.mine_blocks(1);
+    let children = fee_bumper.poll(&setup.backend, &watchtower).await.unwrap();
+    assert_eq!(children.len(), 1);
+    let child = setup.backend.get_tx(&children[0]).await.unwrap().unwrap();
+    assert_eq!(child.input[0].previous_output, anchor(&setup.parent).0);
+
+    setup.backend.mine_blocks(1);
+    assert!(
+        setup
+            .backend
+            .get_tx_status(&parent_txid)
+            .await
+            .unwrap()
+            .confirmed
+    );
+    assert!(fee_bumper.is_tracking(&parent_txid));
+    assert!(fee_bumper
+        .poll(&setup.backend, &watchtower)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(!fee_bumper.is_tracking(&parent_txid));
+}
+
+#[tokio::test]
+async fn test_fee_bumper_ignores_confirming_transaction() {
+    let setup = setup();
+    let parent_txid = setup.parent.compute_txid();
+    setup.backend.broadcast(&setup.parent).await.unwrap();
+
+    let watchtower = Watchtower::new(setup.backend.get_height().await.unwrap());
+    let mut fee_bumper = FeeBumper::new(
+        Network::Regtest,
+        FeeBumpPolicy {
+            stuck_blocks: 0,
+            ..FeeBumpPolicy::default()
+        },
+    );
+    fee_bumper.track(
+        &"graph".to_string(),
+        setup.parent.clone(),
+        anchor_vout(&setup.parent).unwrap(),
+        &setup.keypair,
+        None,
+    );
+
+    // the parent pays more than the estimate, the minimum relay fee rate
+    assert!(fee_bumper
+        .poll(&setup.backend, &watchtower)
+        .await
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        setup
+            .backend
+            .get_address_utxo(generate_pay_to_pubkey_script_address(
+                Network::Regtest,
+                &PublicKey::new(setup.keypair.public_key())
+            ))
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+    setup.backend.mine_blocks(1);
+    assert!(
+        setup
+            .backend
+            .get_tx_status(&parent_txid)
+            .await
+            .unwrap()
+            .confirmed
+    );
+}
@@ -12,19 +12,21 @@ use bitcoin::{
 use bitcoin::{PubkeyHash, PublicKey, Txid};
 
 use bitvm::chunk::api::type_conversion_utils::RawProof;
-use bitvm::chunk::api::{NUM_HASH, NUM_PUBS, NUM_U256};
+use bitvm::chunk::api::{num_hash, num_pubs, NUM_U256};
 use bitvm::signatures::HASH_LEN;
 use bridge::client::chain::chain::PegOutEvent;
 use bridge::client::data_store::local_file::TEST_DATA_DIRECTORY_NAME;
 use bridge::proof::get_proof;
 use bridge::{
-    client::client::BitVMClient,
+    client::{client::BitVMClient, esplora::get_esplora_url},
+    common::ZkProofVerifyingKey,
     graphs::{
         base::{BaseGraph, REWARD_MULTIPLIER, REWARD_PRECISION},
         peg_in::PegInGraph,
         peg_out::PegOutGraph,
     },
-    utils::{num_blocks_per_network, read_disk_cache, write_disk_cache},
+    timelock_policy::TimelockPolicy,
+    utils::{read_disk_cache, write_disk_cache},
 };
 
 use colored::Colorize;
@@ -102,7 +104,7 @@ pub async fn wait_for_timelock_expiry(network: Network, timelock_name: Option<&s
     // wait for an exact number of seconds, consider using a simple sleep (or adding a sister helper function).
     let tx_wait_time = tx_wait_time(network);
     let timeout = Duration::from_secs(
-        tx_wait_time * num_blocks_per_network(network, 0) as u64 + tx_wait_time,
+        tx_wait_time * TimelockPolicy::for_network(network).max_num_blocks() as u64 + tx_wait_time,
     );
     let message = format!(
         " for{} timelock to expire",
@@ -128,7 +130,7 @@ pub async fn generate_stub_outpoint(
                 "Fund {:?} with {} sats at {}",
                 funding_utxo_address,
                 input_value.to_sat(),
-                client.esplora.url(),
+                get_esplora_url(client.source_network),
             );
         });
     OutPoint {
@@ -150,7 +152,7 @@ pub async fn generate_stub_outpoints(
                 "Fund {:?} with {} sats at {}",
                 funding_utxo_address,
                 input_value.to_sat(),
-                client.esplora.url(),
+                get_esplora_url(client.source_network),
             );
         });
     funding_utxos
@@ -180,7 +182,7 @@ pub async fn verify_funding_inputs(client: &BitVMClient, funding_inputs: &Vec<(&
             "Fund {:?} with {} sats at {}",
             input_to_fund.0,
             input_to_fund.1.to_sat(),
-            client.esplora.url(),
+            get_esplora_url(client.source_network),
         );
     }
     if !inputs_to_fund.is_empty() {
@@ -267,13 +269,17 @@ pub fn random_hex<'a>(size: usize) -> Cow<'a, str> {
     Cow::Owned(buffer.to_hex_string(Lower))
 }
 
-const INTERMEDIATE_VARIABLES_FILE_NAME: &str = "intermediates.bin";
+const INTERMEDIATE_VARIABLES_FILE_PREFIX: &str = "intermediates_";
 const TEST_CACHE_DIRECTORY_NAME: &str = "test_cache";
 
-pub fn get_intermediate_variables_cached() -> BTreeMap<String, usize> {
+// The intermediate variables only depend upon the number of public inputs of the verifying key.
+pub fn get_intermediate_variables_cached(vk: &ZkProofVerifyingKey) -> BTreeMap<String, usize> {
+    let num_pubs = num_pubs(vk);
     let intermediate_variables_cache_path = Path::new(TEST_DATA_DIRECTORY_NAME)
         .join(TEST_CACHE_DIRECTORY_NAME)
-        .join(INTERMEDIATE_VARIABLES_FILE_NAME);
+        .join(format!(
+            "{INTERMEDIATE_VARIABLES_FILE_PREFIX}{num_pubs}.bin"
+        ));
     let intermediate_variables = if intermediate_variables_cache_path.exists() {
         read_disk_cache(&intermediate_variables_cache_path)
             .inspect_err(|e| {
@@ -290,14 +296,14 @@ pub fn get_intermediate_variables_cached() -> BTreeMap<String, usize> {
     intermediate_variables.unwrap_or_else(|| {
         println!("Generating new intermediate variables...");
         let mut intermediate_variables: BTreeMap<String, usize> = BTreeMap::new();
-        for i in 0..NUM_PUBS {
+        for i in 0..num_pubs {
             intermediate_variables.insert(format!("{}", i), 32);
         }
         for i in 0..NUM_U256 {
-            intermediate_variables.insert(format!("{}", i + NUM_PUBS), 32);
+            intermediate_variables.insert(format!("{}", i + num_pubs), 32);
         }
-        for i in 0..NUM_HASH {
-            intermediate_variables.insert(format!("{}", i + NUM_PUBS + NUM_U256), HASH_LEN);
+        for i in 0..num_hash(num_pubs) {
+            intermediate_variables.insert(format!("{}", i + num_pubs + NUM_U256), HASH_LEN);
         }
 
         write_disk_cache(&intermediate_variables_cache_path, &intermediate_variables).unwrap();
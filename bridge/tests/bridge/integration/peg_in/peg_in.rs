@@ -7,6 +7,7 @@ use crate::bridge::{
     helper::{generate_stub_outpoint, get_reward_amount, wait_for_timelock_expiry},
     setup::{setup_test, SetupConfig, INITIAL_AMOUNT, ONE_HUNDRED},
 };
+use bridge::error::BitcoinBackendError;
 use bridge::{
     client::client::BitVMClient,
     connectors::{base::TaprootConnector, connector_0::Connector0},
@@ -45,7 +46,11 @@ async fn test_peg_in_success() {
     let deposit_txid = peg_in_deposit_tx.compute_txid();
 
     // mine peg-in deposit
-    let deposit_result = config.client_0.esplora.broadcast(&peg_in_deposit_tx).await;
+    let deposit_result = config
+        .client_0
+        .bitcoin_backend
+        .broadcast(&peg_in_deposit_tx)
+        .await;
     println!("Peg-in Deposit tx result: {:?}\n", deposit_result);
     assert!(deposit_result.is_ok());
     println!("Deposit Txid: {:?}", deposit_txid);
@@ -85,7 +90,11 @@ async fn test_peg_in_success() {
     let confirm_txid = peg_in_confirm_tx.compute_txid();
 
     // mine peg-in confirm
-    let confirm_result = config.client_0.esplora.broadcast(&peg_in_confirm_tx).await;
+    let confirm_result = config
+        .client_0
+        .bitcoin_backend
+        .broadcast(&peg_in_confirm_tx)
+        .await;
     println!("Peg-in Confirm tx result: {:?}\n", confirm_result);
     assert!(confirm_result.is_ok());
     println!("Confirm Txid: {:?}", confirm_txid);
@@ -98,7 +107,7 @@ async fn test_peg_in_success() {
     let multi_sig_address = connector_0.generate_taproot_address();
     let multi_sig_utxos = config
         .client_0
-        .esplora
+        .bitcoin_backend
         .get_address_utxo(multi_sig_address.clone())
         .await
         .unwrap();
@@ -137,7 +146,11 @@ async fn test_peg_in_time_lock_not_surpassed() {
     let deposit_txid = peg_in_deposit_tx.compute_txid();
 
     // mine peg-in deposit
-    let deposit_result = config.client_0.esplora.broadcast(&peg_in_deposit_tx).await;
+    let deposit_result = config
+        .client_0
+        .bitcoin_backend
+        .broadcast(&peg_in_deposit_tx)
+        .await;
     println!("Peg-in Deposit tx result: {:?}\n", deposit_result);
     assert!(deposit_result.is_ok());
 
@@ -156,7 +169,11 @@ async fn test_peg_in_time_lock_not_surpassed() {
     let peg_in_refund_tx = peg_in_refund.finalize();
 
     // mine peg-in refund
-    let refund_result = config.client_0.esplora.broadcast(&peg_in_refund_tx).await;
+    let refund_result = config
+        .client_0
+        .bitcoin_backend
+        .broadcast(&peg_in_refund_tx)
+        .await;
     println!("Peg-in Refund tx result: {:?}\n", refund_result);
     assert!(refund_result.is_err());
     let error = refund_result.unwrap_err();
@@ -166,7 +183,10 @@ async fn test_peg_in_time_lock_not_surpassed() {
             "sendrawtransaction RPC error: {\"code\":-26,\"message\":\"non-BIP68-final\"}",
         ), // indicates that relative timelock based on sequence numbers has not elapsed
     };
-    assert_eq!(error.to_string(), expected_error.to_string());
+    assert_eq!(
+        error.to_string(),
+        BitcoinBackendError::Esplora(expected_error).to_string()
+    );
 }
 
 #[tokio::test]
@@ -187,7 +207,11 @@ async fn test_peg_in_time_lock_surpassed() {
     let deposit_txid = peg_in_deposit_tx.compute_txid();
 
     // mine peg-in deposit
-    let deposit_result = config.client_0.esplora.broadcast(&peg_in_deposit_tx).await;
+    let deposit_result = config
+        .client_0
+        .bitcoin_backend
+        .broadcast(&peg_in_deposit_tx)
+        .await;
     println!("Peg-in Deposit tx result: {:?}\n", deposit_result);
     assert!(deposit_result.is_ok());
 
@@ -208,7 +232,11 @@ async fn test_peg_in_time_lock_surpassed() {
 
     // mine peg-in refund
     wait_for_timelock_expiry(config.network, Some("peg-in deposit connector_z")).await;
-    let refund_result = config.client_0.esplora.broadcast(&peg_in_refund_tx).await;
+    let refund_result = config
+        .client_0
+        .bitcoin_backend
+        .broadcast(&peg_in_refund_tx)
+        .await;
     println!("Peg-in Refund tx result: {:?}\n", refund_result);
     assert!(refund_result.is_ok());
 
@@ -219,7 +247,7 @@ async fn test_peg_in_time_lock_surpassed() {
     );
     let depositor_utxos = config
         .client_0
-        .esplora
+        .bitcoin_backend
         .get_address_utxo(depositor_address.clone())
         .await
         .unwrap();
@@ -308,7 +336,7 @@ async fn test_peg_in_graph_automatic_verifier() {
     .await;
     let client_0 = &mut config.client_0;
     let client_1 = &mut config.client_1;
-    let esplora = client_0.esplora.clone();
+    let bitcoin_backend = client_0.bitcoin_backend.clone();
 
     // create the actual graph & check that status changes to PegInWait
     client_0
@@ -316,7 +344,7 @@ async fn test_peg_in_graph_automatic_verifier() {
         .await;
     assert_eq!(
         graph(client_0)
-            .verifier_status(&esplora, &config.verifier_0_context, &[])
+            .verifier_status(&bitcoin_backend, &config.verifier_0_context, &[])
             .await,
         PegInVerifierStatus::AwaitingDeposit
     );
@@ -328,7 +356,7 @@ async fn test_peg_in_graph_automatic_verifier() {
     loop {
         if !matches!(
             graph(client_0)
-                .verifier_status(&esplora, &config.verifier_0_context, &[])
+                .verifier_status(&bitcoin_backend, &config.verifier_0_context, &[])
                 .await,
             PegInVerifierStatus::AwaitingDeposit
         ) {
@@ -340,7 +368,7 @@ async fn test_peg_in_graph_automatic_verifier() {
 
     assert_eq!(
         graph(client_0)
-            .verifier_status(&esplora, &config.verifier_0_context, &[])
+            .verifier_status(&bitcoin_backend, &config.verifier_0_context, &[])
             .await,
         PegInVerifierStatus::AwaitingPegOutCreation
     );
@@ -370,7 +398,7 @@ async fn test_peg_in_graph_automatic_verifier() {
     assert_eq!(
         graph(client_0)
             .verifier_status(
-                &esplora,
+                &bitcoin_backend,
                 &config.verifier_0_context,
                 &pegouts_of(client_0).iter().collect::<Vec<_>>()
             )
@@ -386,7 +414,7 @@ async fn test_peg_in_graph_automatic_verifier() {
     assert_eq!(
         graph(client_0)
             .verifier_status(
-                &esplora,
+                &bitcoin_backend,
                 &config.verifier_0_context,
                 &pegouts_of(client_0).iter().collect::<Vec<_>>()
             )
@@ -402,7 +430,7 @@ async fn test_peg_in_graph_automatic_verifier() {
     assert!(matches!(
         graph(client_0)
             .verifier_status(
-                &esplora,
+                &bitcoin_backend,
                 &config.verifier_0_context,
                 &pegouts_of(client_0).iter().collect::<Vec<_>>()
             )
@@ -418,7 +446,7 @@ async fn test_peg_in_graph_automatic_verifier() {
     assert_eq!(
         graph(client_0)
             .verifier_status(
-                &esplora,
+                &bitcoin_backend,
                 &config.verifier_0_context,
                 &pegouts_of(client_0).iter().collect::<Vec<_>>()
             )
@@ -434,7 +462,7 @@ async fn test_peg_in_graph_automatic_verifier() {
     assert_eq!(
         graph(client_0)
             .verifier_status(
-                &esplora,
+                &bitcoin_backend,
                 &config.verifier_0_context,
                 &pegouts_of(client_0).iter().collect::<Vec<_>>()
             )
@@ -449,7 +477,7 @@ async fn test_peg_in_graph_automatic_verifier() {
     loop {
         if graph(client_0)
             .verifier_status(
-                &esplora,
+                &bitcoin_backend,
                 &config.verifier_0_context,
                 &pegouts_of(client_0).iter().collect::<Vec<_>>(),
             )
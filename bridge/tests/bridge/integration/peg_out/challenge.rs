@@ -100,7 +100,11 @@ async fn test_challenge_success() {
 
     // mine challenge tx
     check_tx_output_sum(INITIAL_AMOUNT + DUST_AMOUNT, &challenge_tx);
-    let challenge_result = config.client_0.esplora.broadcast(&challenge_tx).await;
+    let challenge_result = config
+        .client_0
+        .bitcoin_backend
+        .broadcast(&challenge_tx)
+        .await;
     assert!(challenge_result.is_ok());
 
     // operator balance
@@ -110,7 +114,7 @@ async fn test_challenge_success() {
     );
     let operator_utxos = config
         .client_0
-        .esplora
+        .bitcoin_backend
         .get_address_utxo(operator_address)
         .await
         .unwrap();
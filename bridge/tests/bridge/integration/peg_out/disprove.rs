@@ -67,6 +67,7 @@ async fn test_disprove_success() {
         &config.client_0,
         &config.operator_context,
         &config.connector_1,
+        &config.connector_3,
         &config.connector_b,
         &kick_off_2_funding_utxo_address,
         kick_off_2_input_amount,
@@ -85,7 +86,7 @@ async fn test_disprove_success() {
     };
 
     let (assert_initial_tx, assert_initial_txid) = create_and_mine_assert_initial_tx(
-        &config.client_0.esplora,
+        &config.client_0.bitcoin_backend,
         config.network,
         &config.verifier_0_context,
         &config.verifier_1_context,
@@ -128,7 +129,11 @@ async fn test_disprove_success() {
         assert_commit_1.tx().input.len(),
         assert_commit_1.tx().output.len()
     );
-    let assert_commit_1_result = config.client_0.esplora.broadcast(&assert_commit_1_tx).await;
+    let assert_commit_1_result = config
+        .client_0
+        .bitcoin_backend
+        .broadcast(&assert_commit_1_tx)
+        .await;
     assert!(
         assert_commit_1_result.is_ok(),
         "error: {:?}",
@@ -163,7 +168,11 @@ async fn test_disprove_success() {
         assert_commit_2.tx().input.len(),
         assert_commit_2.tx().output.len()
     );
-    let assert_commit_2_result = config.client_0.esplora.broadcast(&assert_commit_2_tx).await;
+    let assert_commit_2_result = config
+        .client_0
+        .bitcoin_backend
+        .broadcast(&assert_commit_2_tx)
+        .await;
     assert!(
         assert_commit_2_result.is_ok(),
         "error: {:?}",
@@ -224,7 +233,11 @@ async fn test_disprove_success() {
 
     let assert_final_tx = assert_final.finalize();
     let assert_final_txid = assert_final_tx.compute_txid();
-    let assert_final_result = config.client_0.esplora.broadcast(&assert_final_tx).await;
+    let assert_final_result = config
+        .client_0
+        .bitcoin_backend
+        .broadcast(&assert_final_tx)
+        .await;
     assert!(
         assert_final_result.is_ok(),
         "error: {:?}",
@@ -238,14 +251,11 @@ async fn test_disprove_success() {
     let assert_commit_1_witness = get_commit_from_assert_commit_tx(&assert_commit_1_tx);
     let assert_commit_2_witness = get_commit_from_assert_commit_tx(&assert_commit_2_tx);
 
-    let (script_index, disprove_witness) = config
+    let report = config
         .connector_c
-        .generate_disprove_witness(
-            assert_commit_1_witness,
-            assert_commit_2_witness,
-            &config.invalid_proof.vk,
-        )
+        .generate_disprove_witness(assert_commit_1_witness, assert_commit_2_witness)
         .unwrap();
+    let (script_index, disprove_witness) = (report.tapscript_index, report.witness);
     // let script_index = 1;
 
     let disprove_input_0 = Input {
@@ -305,14 +315,18 @@ async fn test_disprove_success() {
     // mine disprove
     check_tx_output_sum(INITIAL_AMOUNT, &disprove_tx);
     wait_for_timelock_expiry(config.network, Some("Assert connector 4")).await;
-    let disprove_result = config.client_0.esplora.broadcast(&disprove_tx).await;
+    let disprove_result = config
+        .client_0
+        .bitcoin_backend
+        .broadcast(&disprove_tx)
+        .await;
     println!("Disprove tx result: {disprove_result:?}");
     assert!(disprove_result.is_ok());
 
     // reward balance
     let reward_utxos = config
         .client_0
-        .esplora
+        .bitcoin_backend
         .get_address_utxo(reward_address)
         .await
         .unwrap();
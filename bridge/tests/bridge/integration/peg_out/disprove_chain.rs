@@ -43,6 +43,7 @@ async fn test_disprove_chain_success() {
         &config.client_0,
         &config.operator_context,
         &config.connector_1,
+        &config.connector_3,
         &config.connector_b,
         &kick_off_2_funding_utxo_address,
         kick_off_2_input_amount,
@@ -92,14 +93,18 @@ async fn test_disprove_chain_success() {
     check_tx_output_sum(INITIAL_AMOUNT, &disprove_chain_tx);
     // mine disprove chain
     wait_for_timelock_expiry(config.network, Some("kick off 2 connector 3")).await;
-    let disprove_chain_result = config.client_0.esplora.broadcast(&disprove_chain_tx).await;
+    let disprove_chain_result = config
+        .client_0
+        .bitcoin_backend
+        .broadcast(&disprove_chain_tx)
+        .await;
     println!("disprove chain result: {:?}", disprove_chain_result);
     assert!(disprove_chain_result.is_ok());
 
     // reward balance
     let reward_utxos = config
         .client_0
-        .esplora
+        .bitcoin_backend
         .get_address_utxo(reward_address)
         .await
         .unwrap();
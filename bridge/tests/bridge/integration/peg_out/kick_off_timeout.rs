@@ -76,6 +76,7 @@ async fn test_kick_off_timeout_success() {
         &config.operator_context.operator_taproot_public_key, // Verifiers get this via remote storage.
         &config.verifier_0_context.n_of_n_taproot_public_key,
         &config.connector_1.commitment_public_keys, // Verifiers get this via remote storage.
+        &config.timelock_policy,                    // Verifiers get this via remote storage.
     );
     kick_off_timeout.pre_sign(
         &config.verifier_0_context,
@@ -87,6 +88,7 @@ async fn test_kick_off_timeout_success() {
         &config.operator_context.operator_taproot_public_key,
         &config.verifier_0_context.n_of_n_taproot_public_key,
         &config.connector_1.commitment_public_keys,
+        &config.timelock_policy,
     );
     kick_off_timeout.pre_sign(
         &config.verifier_1_context,
@@ -108,7 +110,7 @@ async fn test_kick_off_timeout_success() {
     wait_for_timelock_expiry(config.network, Some("kick off 1 connector 1")).await;
     let kick_off_timeout_result = config
         .client_0
-        .esplora
+        .bitcoin_backend
         .broadcast(&kick_off_timeout_tx)
         .await;
     println!("Kick-off timeout result: {kick_off_timeout_result:?}");
@@ -117,7 +119,7 @@ async fn test_kick_off_timeout_success() {
     // reward balance
     let reward_utxos = config
         .client_0
-        .esplora
+        .bitcoin_backend
         .get_address_utxo(reward_address)
         .await
         .unwrap();
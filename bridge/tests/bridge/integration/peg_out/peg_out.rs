@@ -78,7 +78,7 @@ async fn test_peg_out_success() {
 
     check_tx_output_sum(INITIAL_AMOUNT, &peg_out_tx);
     // mine peg-out
-    let peg_out_result = config.client_0.esplora.broadcast(&peg_out_tx).await;
+    let peg_out_result = config.client_0.bitcoin_backend.broadcast(&peg_out_tx).await;
     println!("Peg Out Tx result: {:?}", peg_out_result);
     assert!(peg_out_result.is_ok());
     println!("Peg Out Txid: {:?}", peg_out_txid);
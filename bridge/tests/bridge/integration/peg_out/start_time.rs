@@ -3,7 +3,7 @@ use bridge::{
     commitments::CommitmentMessageId,
     connectors::base::TaprootConnector,
     graphs::base::DUST_AMOUNT,
-    superblock::get_start_time_block_number,
+    superblock::get_start_time,
     transactions::{
         base::{BaseTransaction, Input, MIN_RELAY_FEE_KICK_OFF_1, MIN_RELAY_FEE_START_TIME},
         start_time::StartTimeTransaction,
@@ -66,10 +66,14 @@ async fn test_start_time_success() {
         start_time_input_0,
     );
 
+    let height = config.client_0.bitcoin_backend.get_height().await.unwrap();
+    let committed_start_time = get_start_time(&*config.client_0.bitcoin_backend, height)
+        .await
+        .unwrap();
     start_time.sign(
         &config.operator_context,
         &config.connector_2,
-        get_start_time_block_number(config.network),
+        committed_start_time,
         &config.commitment_secrets[&CommitmentMessageId::StartTime],
     );
 
@@ -78,7 +82,11 @@ async fn test_start_time_success() {
     check_tx_output_sum(DUST_AMOUNT, &start_time_tx);
     // mine start time timeout
     wait_for_timelock_expiry(config.network, Some("kick off 1 connector 1")).await;
-    let start_time_result = config.client_0.esplora.broadcast(&start_time_tx).await;
+    let start_time_result = config
+        .client_0
+        .bitcoin_backend
+        .broadcast(&start_time_tx)
+        .await;
     println!("Start time tx result: {:?}\n", start_time_result);
     assert!(start_time_result.is_ok());
 }
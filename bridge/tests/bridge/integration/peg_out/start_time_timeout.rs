@@ -115,7 +115,7 @@ async fn test_start_time_timeout_success() {
     wait_for_timelock_expiry(config.network, Some("kick off 1 connector 1")).await;
     let start_time_timeout_result = config
         .client_0
-        .esplora
+        .bitcoin_backend
         .broadcast(&start_time_timeout_tx)
         .await;
     println!("Start time timeout result: {:?}", start_time_timeout_result);
@@ -124,7 +124,7 @@ async fn test_start_time_timeout_success() {
     // reward balance
     let reward_utxos = config
         .client_0
-        .esplora
+        .bitcoin_backend
         .get_address_utxo(reward_address)
         .await
         .unwrap();
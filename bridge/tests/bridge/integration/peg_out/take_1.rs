@@ -89,8 +89,8 @@ async fn test_take_1_success() {
         amount: kick_off_1_tx.output[vout as usize].value,
     };
     let mut kick_off_2 = KickOff2Transaction::new(
-        &config.operator_context,
         &config.connector_1,
+        &config.connector_3,
         &config.connector_b,
         kick_off_2_input_0,
     );
@@ -112,7 +112,11 @@ async fn test_take_1_success() {
 
     // mine kick-off 2
     wait_for_timelock_expiry(config.network, Some("kick off 1 connector 1")).await;
-    let kick_off_2_result = config.client_0.esplora.broadcast(&kick_off_2_tx).await;
+    let kick_off_2_result = config
+        .client_0
+        .bitcoin_backend
+        .broadcast(&kick_off_2_tx)
+        .await;
     println!("Kick-off 2 result: {:?}\n", kick_off_2_result);
     assert!(kick_off_2_result.is_ok());
 
@@ -194,7 +198,7 @@ async fn test_take_1_success() {
     );
     // mine take 1
     wait_for_timelock_expiry(config.network, Some("kick off 2 connector 3")).await;
-    let take_1_result = config.client_0.esplora.broadcast(&take_1_tx).await;
+    let take_1_result = config.client_0.bitcoin_backend.broadcast(&take_1_tx).await;
     println!("TAKE 1 result: {:?}\n", take_1_result);
     assert!(take_1_result.is_ok());
 
@@ -205,7 +209,7 @@ async fn test_take_1_success() {
     );
     let operator_utxos = config
         .client_0
-        .esplora
+        .bitcoin_backend
         .get_address_utxo(operator_address.clone())
         .await
         .unwrap();
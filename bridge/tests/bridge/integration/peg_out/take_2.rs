@@ -145,7 +145,7 @@ async fn test_take_2_success() {
     // mine take 2
     check_tx_output_sum(INITIAL_AMOUNT + reward_amount + DUST_AMOUNT * 2, &take_2_tx);
     wait_for_timelock_expiry(config.network, Some("assert connector 4")).await;
-    let take_2_result = config.client_0.esplora.broadcast(&take_2_tx).await;
+    let take_2_result = config.client_0.bitcoin_backend.broadcast(&take_2_tx).await;
     println!("Take 2 result: {:?}\n", take_2_result);
     assert!(take_2_result.is_ok());
 
@@ -156,7 +156,7 @@ async fn test_take_2_success() {
     );
     let operator_utxos = config
         .client_0
-        .esplora
+        .bitcoin_backend
         .get_address_utxo(operator_address.clone())
         .await
         .unwrap();
@@ -7,8 +7,9 @@ use bridge::{
     commitments::CommitmentMessageId,
     connectors::{
         connector_0::Connector0, connector_1::Connector1, connector_2::Connector2,
-        connector_4::Connector4, connector_5::Connector5, connector_6::Connector6,
-        connector_b::ConnectorB, connector_c::ConnectorC, connector_z::ConnectorZ,
+        connector_3::Connector3, connector_4::Connector4, connector_5::Connector5,
+        connector_6::Connector6, connector_b::ConnectorB, connector_c::ConnectorC,
+        connector_z::ConnectorZ,
     },
     contexts::{depositor::DepositorContext, operator::OperatorContext, verifier::VerifierContext},
     superblock::{get_superblock_hash_message, get_superblock_message},
@@ -71,7 +72,7 @@ pub async fn create_and_mine_kick_off_1_tx(
     let kick_off_1_txid = kick_off_1_tx.compute_txid();
 
     // mine kick-off 1 tx
-    let kick_off_1_result = client.esplora.broadcast(&kick_off_1_tx).await;
+    let kick_off_1_result = client.bitcoin_backend.broadcast(&kick_off_1_tx).await;
     println!("Kick-off 1 result: {kick_off_1_result:?}");
     assert!(kick_off_1_result.is_ok());
 
@@ -82,6 +83,7 @@ pub async fn create_and_mine_kick_off_2_tx(
     client: &BitVMClient,
     operator_context: &OperatorContext,
     connector_1: &Connector1,
+    connector_3: &Connector3,
     connector_b: &ConnectorB,
     kick_off_2_funding_utxo_address: &Address,
     input_amount: Amount,
@@ -93,12 +95,8 @@ pub async fn create_and_mine_kick_off_2_tx(
         outpoint: kick_off_2_funding_outpoint,
         amount: input_amount,
     };
-    let mut kick_off_2 = KickOff2Transaction::new(
-        &operator_context,
-        &connector_1,
-        &connector_b,
-        kick_off_2_input,
-    );
+    let mut kick_off_2 =
+        KickOff2Transaction::new(&connector_1, &connector_3, &connector_b, kick_off_2_input);
     let superblock_header = get_superblock_header();
     kick_off_2.sign(
         operator_context,
@@ -117,7 +115,7 @@ pub async fn create_and_mine_kick_off_2_tx(
 
     // mine kick-off 2 tx
     wait_for_timelock_expiry(operator_context.network, Some("kick off 1 connector 1")).await;
-    let kick_off_2_result = client.esplora.broadcast(&kick_off_2_tx).await;
+    let kick_off_2_result = client.bitcoin_backend.broadcast(&kick_off_2_tx).await;
     println!("Kick off 2 tx result: {kick_off_2_result:?}");
     assert!(kick_off_2_result.is_ok());
 
@@ -161,7 +159,7 @@ pub async fn create_and_mine_assert_tx(
 
     // mine assert tx
     wait_for_timelock_expiry(verifier_0_context.network, Some("kick off 2 connector b")).await;
-    let assert_result = client.esplora.broadcast(&assert_tx).await;
+    let assert_result = client.bitcoin_backend.broadcast(&assert_tx).await;
     assert!(assert_result.is_ok());
 
     (assert_tx, assert_txid)
@@ -197,7 +195,7 @@ pub async fn create_and_mine_peg_in_confirm_tx(
     let peg_in_confirm_txid = peg_in_confirm_tx.compute_txid();
 
     // mine peg-in confirm
-    let confirm_result = client.esplora.broadcast(&peg_in_confirm_tx).await;
+    let confirm_result = client.bitcoin_backend.broadcast(&peg_in_confirm_tx).await;
     assert!(confirm_result.is_ok());
 
     (peg_in_confirm_tx, peg_in_confirm_txid)
@@ -3,6 +3,7 @@ use bitcoin::Amount;
 use bridge::{
     commitments::CommitmentMessageId,
     connectors::base::TaprootConnector,
+    error::BitcoinBackendError,
     graphs::base::DUST_AMOUNT,
     superblock::{get_superblock_hash_message, get_superblock_message},
     transactions::{
@@ -42,8 +43,8 @@ async fn test_kick_off_2_tx_success() {
         generate_stub_outpoint(&config.client_0, &funding_utxo_address0, input_value0).await;
 
     let mut kick_off_2_tx = KickOff2Transaction::new(
-        &config.operator_context,
         &config.connector_1,
+        &config.connector_3,
         &config.connector_b,
         Input {
             outpoint: funding_outpoint0,
@@ -68,7 +69,8 @@ async fn test_kick_off_2_tx_success() {
     let tx = kick_off_2_tx.finalize();
     check_tx_output_sum(reward_amount + DUST_AMOUNT, &tx);
     wait_for_timelock_expiry(config.network, Some("kick off 2 connector 3")).await;
-    let result: Result<(), esplora_client::Error> = config.client_0.esplora.broadcast(&tx).await;
+    let result: Result<(), BitcoinBackendError> =
+        config.client_0.bitcoin_backend.broadcast(&tx).await;
     println!("Txid: {:?}", tx.compute_txid());
     println!("Kick Off 2 tx result: {:?}\n", result);
     assert!(result.is_ok());
@@ -69,7 +69,7 @@ async fn test_kick_off_timeout_tx_success() {
     let tx = kick_off_timeout_tx.finalize();
     check_tx_output_sum(reward_amount, &tx);
     wait_for_timelock_expiry(config.network, Some("kick off 1 connector 1")).await;
-    let result = config.client_0.esplora.broadcast(&tx).await;
+    let result = config.client_0.bitcoin_backend.broadcast(&tx).await;
     println!("Txid: {:?}", tx.compute_txid());
     println!("Kick off timeout result: {:?}\n", result);
     assert!(result.is_ok());
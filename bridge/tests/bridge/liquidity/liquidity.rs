@@ -0,0 +1,278 @@
+use std::sync::Arc;
+
+use bitcoin::{hashes::Hash, Amount, FeeRate, Network, OutPoint, PublicKey, Txid};
+use bridge::{
+    client::{
+        bitcoin_backend::{
+            bitcoin_backend::{Utxo, UtxoStatus},
+            memory_backend::MemoryBackend,
+        },
+        chain::{
+            chain::{Chain, PegOutEvent},
+            chain_adaptor::get_chain_adaptor,
+            mock_adaptor::{MockAdaptor, MockAdaptorConfig},
+        },
+        client::BitVMClient,
+        keystore::KEYSTORE_PASSPHRASE_ENV_VAR,
+        liquidity::{
+            peg_out_fee, CapitalState, LiquidityDeadline, LiquidityReport, PegOutLiquidity,
+        },
+    },
+    constants::DestinationNetwork,
+    contexts::base::generate_keys_from_secret,
+    graphs::{
+        base::{BaseGraph, PEG_OUT_FEE},
+        peg_out::{PegOutOperatorStatus, PegOutTimelockedTx},
+    },
+    scripts::generate_pay_to_pubkey_script_address,
+    transactions::base::{
+        Input, MIN_RELAY_FEE_PEG_IN_CONFIRM, MIN_RELAY_FEE_PEG_IN_DEPOSIT, MIN_RELAY_FEE_PEG_OUT,
+    },
+};
+
+use crate::bridge::helper::{get_reward_amount, get_valid_proof};
+
+const DEPOSITOR_SECRET: &str = "b8f17ea979be24199e7c3fec71ee88914d92fd4ca508443f765d56ce024ef1d7";
+const VERIFIER_SECRET: &str = "ee0817eac0c13aa8ee2dd3256304041f09f0499d1089b56495310ae8093583e2";
+const OPERATOR_SECRET: &str = "3076ca1dfc1e383be26d5dd3c0c427340f96139fa8c2520862cf551ec2d670ac";
+const KEYSTORE_PASSPHRASE: &str = "test keystore passphrase";
+const EVM_ADDRESS: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+const DEPOSIT_AMOUNT: u64 = 2 << 20;
+
+fn utxo(vout: u32, amount: u64) -> Utxo {
+    Utxo {
+        txid: Txid::all_zeros(),
+        vout,
+        status: UtxoStatus {
+            confirmed: true,
+            block_height: Some(1),
+            block_hash: None,
+            block_time: None,
+        },
+        value: Amount::from_sat(amount),
+    }
+}
+
+fn peg_out_event(operator_public_key: PublicKey, vout: u32, amount: u64) -> PegOutEvent {
+    PegOutEvent {
+        withdrawer_chain_address: EVM_ADDRESS.to_string(),
+        withdrawer_destination_address: String::new(),
+        withdrawer_public_key_hash: operator_public_key.pubkey_hash(),
+        source_outpoint: OutPoint::new(Txid::all_zeros(), vout),
+        amount: Amount::from_sat(amount),
+        operator_public_key,
+        timestamp: 1722328130 + vout,
+        tx_hash: [0u8; 32].into(),
+    }
+}
+
+fn peg_out(id: &str, status: PegOutOperatorStatus, has_fronted: bool) -> PegOutLiquidity {
+    PegOutLiquidity {
+        graph_id: id.to_string(),
+        status: status.to_string(),
+        state: CapitalState::from_status(&status, has_fronted),
+        withdrawal_amount: if matches!(status, PegOutOperatorStatus::PegOutWait) && !has_fronted {
+            0
+        } else {
+            DEPOSIT_AMOUNT
+        },
+        reimbursement_amount: DEPOSIT_AMOUNT + 1000,
+    }
+}
+
+#[test]
+fn test_liquidity_report_totals_and_coverage() {
+    let (_, operator_public_key) = generate_keys_from_secret(Network::Regtest, OPERATOR_SECRET);
+    let peg_outs = vec![
+        peg_out("idle", PegOutOperatorStatus::PegOutWait, false),
+        peg_out("requested", PegOutOperatorStatus::PegOutStartPegOut, false),
+        peg_out("fronted", PegOutOperatorStatus::PegOutWait, true),
+        peg_out(
+            "kicked_off",
+            PegOutOperatorStatus::PegOutKickOff2Available,
+            true,
+        ),
+        peg_out("take_1", PegOutOperatorStatus::PegOutTake1Available, true),
+        peg_out("complete", PegOutOperatorStatus::PegOutComplete, true),
+    ];
+    let deadlines = vec![
+        LiquidityDeadline {
+            graph_id: "take_1".to_string(),
+            tx: PegOutTimelockedTx::Take1,
+            height: 120,
+            blocks_remaining: 0,
+        },
+        LiquidityDeadline {
+            graph_id: "kicked_off".to_string(),
+            tx: PegOutTimelockedTx::StartTimeTimeout,
+            height: 110,
+            blocks_remaining: 0,
+        },
+    ];
+    let required_amount = DEPOSIT_AMOUNT + MIN_RELAY_FEE_PEG_OUT;
+    // the large UTXO is enough in total, but only covers one withdrawal
+    let utxos = vec![
+        utxo(0, required_amount - 1),
+        utxo(1, 3 * required_amount),
+        utxo(2, required_amount),
+    ];
+    let events = vec![
+        peg_out_event(operator_public_key, 2, DEPOSIT_AMOUNT),
+        peg_out_event(operator_public_key, 1, DEPOSIT_AMOUNT),
+        peg_out_event(operator_public_key, 3, DEPOSIT_AMOUNT),
+    ];
+
+    let report = LiquidityReport::new(
+        operator_public_key,
+        120,
+        peg_outs,
+        deadlines,
+        &utxos,
+        &events,
+        MIN_RELAY_FEE_PEG_OUT,
+    );
+
+    assert_eq!(report.requested_amount, DEPOSIT_AMOUNT);
+    assert_eq!(report.in_flight_amount, 2 * DEPOSIT_AMOUNT);
+    assert_eq!(report.reclaimable_amount, DEPOSIT_AMOUNT + 1000);
+    assert_eq!(
+        report.totals_by_status[&PegOutOperatorStatus::PegOutWait.to_string()].count,
+        2
+    );
+    assert_eq!(
+        report.totals_by_status[&PegOutOperatorStatus::PegOutWait.to_string()].withdrawal_amount,
+        DEPOSIT_AMOUNT
+    );
+    assert_eq!(
+        report
+            .deadlines
+            .iter()
+            .map(|deadline| deadline.height)
+            .collect::<Vec<_>>(),
+        vec![110, 120]
+    );
+
+    // oldest withdrawal first, each one takes the smallest UTXO large enough
+    assert_eq!(report.available_amount, 5 * required_amount - 1);
+    assert_eq!(
+        report
+            .pending_withdrawals
+            .iter()
+            .map(|withdrawal| (withdrawal.source_outpoint.vout, withdrawal.funding_utxo))
+            .collect::<Vec<_>>(),
+        vec![
+            (1, Some(OutPoint::new(Txid::all_zeros(), 2))),
+            (2, Some(OutPoint::new(Txid::all_zeros(), 1))),
+            (3, None),
+        ]
+    );
+    assert_eq!(report.warnings.len(), 1);
+    assert!(report.warnings[0].contains(&events[2].source_outpoint.to_string()));
+
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["peg_outs"][0]["state"], "idle");
+    assert_eq!(json["deadlines"][0]["tx"], "StartTimeTimeout");
+}
+
+#[tokio::test]
+async fn test_client_liquidity_report() {
+    if std::env::var(KEYSTORE_PASSPHRASE_ENV_VAR).is_err() {
+        std::env::set_var(KEYSTORE_PASSPHRASE_ENV_VAR, KEYSTORE_PASSPHRASE);
+    }
+    let backend = Arc::new(MemoryBackend::new());
+    let (_, verifier_public_key) = generate_keys_from_secret(Network::Regtest, VERIFIER_SECRET);
+    let (_, operator_public_key) = generate_keys_from_secret(Network::Regtest, OPERATOR_SECRET);
+    let (_, depositor_public_key) = generate_keys_from_secret(Network::Regtest, DEPOSITOR_SECRET);
+    let mut client = BitVMClient::new(
+        Some(backend.clone()),
+        Network::Regtest,
+        DestinationNetwork::Local,
+        Some(get_chain_adaptor(DestinationNetwork::Local, None, None)),
+        &[verifier_public_key],
+        Some(DEPOSITOR_SECRET),
+        Some(OPERATOR_SECRET),
+        Some(VERIFIER_SECRET),
+        None,
+        Some("test_liquidity_report"),
+        Some(get_valid_proof().vk),
+    )
+    .await;
+
+    let deposit_amount = Amount::from_sat(
+        DEPOSIT_AMOUNT + MIN_RELAY_FEE_PEG_IN_DEPOSIT + MIN_RELAY_FEE_PEG_IN_CONFIRM,
+    );
+    let peg_in_graph_id = client
+        .create_peg_in_graph(
+            Input {
+                outpoint: backend.fund(
+                    generate_pay_to_pubkey_script_address(Network::Regtest, &depositor_public_key)
+                        .script_pubkey(),
+                    deposit_amount,
+                ),
+                amount: deposit_amount,
+            },
+            EVM_ADDRESS,
+        )
+        .await;
+    client
+        .broadcast_peg_in_deposit(&peg_in_graph_id)
+        .await
+        .unwrap();
+    backend.mine_blocks(1);
+    let operator_script_pubkey =
+        generate_pay_to_pubkey_script_address(Network::Regtest, &operator_public_key)
+            .script_pubkey();
+    backend.fund(
+        operator_script_pubkey.clone(),
+        Amount::from_sat(get_reward_amount(DEPOSIT_AMOUNT) + PEG_OUT_FEE),
+    );
+    client.process_peg_in_as_operator(&peg_in_graph_id).await;
+
+    let report = client.liquidity_report().await.unwrap();
+    assert_eq!(report.operator_public_key, operator_public_key);
+    assert_eq!(report.peg_outs.len(), 1);
+    assert_eq!(
+        &report.peg_outs[0].graph_id,
+        client.data().peg_out_graphs[0].id()
+    );
+    assert_eq!(report.peg_outs[0].state, CapitalState::Idle);
+    assert!(report.deadlines.is_empty());
+    assert!(report.pending_withdrawals.is_empty());
+    assert!(report.warnings.is_empty());
+    // without a fee estimate the backend falls back to the minimum relay fee rate
+    assert_eq!(report.peg_out_fee, MIN_RELAY_FEE_PEG_OUT);
+
+    // a withdrawal larger than the operator UTXOs is assigned to the operator on L2
+    let peg_in_graph = client.data().peg_in_graphs[0].clone();
+    let source_outpoint = OutPoint::new(
+        peg_in_graph.peg_in_confirm_transaction.tx().compute_txid(),
+        0,
+    );
+    client.set_chain_service(Chain::new(Box::new(MockAdaptor::new(Some(
+        MockAdaptorConfig {
+            peg_out_init_events: Some(vec![PegOutEvent {
+                source_outpoint,
+                ..peg_out_event(operator_public_key, 0, DEPOSIT_AMOUNT)
+            }]),
+            peg_out_burnt_events: None,
+            peg_out_minted_events: None,
+        },
+    )))));
+    let report = client.liquidity_report().await.unwrap();
+    assert_eq!(report.pending_withdrawals.len(), 1);
+    assert_eq!(
+        report.pending_withdrawals[0].source_outpoint,
+        source_outpoint
+    );
+    assert_eq!(report.pending_withdrawals[0].funding_utxo, None);
+    assert!(!report.warnings.is_empty());
+
+    // pending withdrawals are priced at the fee estimate of the backend
+    let fee_rate = FeeRate::from_sat_per_vb_unchecked(20);
+    backend.set_fee_rate(fee_rate);
+    let report = client.liquidity_report().await.unwrap();
+    assert_eq!(report.peg_out_fee, peg_out_fee(fee_rate));
+    assert!(report.peg_out_fee > MIN_RELAY_FEE_PEG_OUT);
+    assert!(report.warnings[0]
+        .contains(&Amount::from_sat(DEPOSIT_AMOUNT + report.peg_out_fee).to_string()));
+}
@@ -7,16 +7,21 @@ pub mod disprove;
 pub mod disprove_chain;
 pub mod e2e;
 pub mod faucet;
+pub mod fee_bump;
 pub mod helper;
 pub mod integration;
 pub mod kick_off_1;
 pub mod kick_off_2;
 pub mod kick_off_timeout;
+pub mod liquidity;
 pub mod peg_in;
+pub mod proof_provider;
 pub mod serialization;
 pub mod setup;
 pub mod start_time;
 pub mod start_time_timeout;
+pub mod superblock;
 pub mod take_1;
 pub mod take_2;
+pub mod timelock_policy;
 pub mod validate;
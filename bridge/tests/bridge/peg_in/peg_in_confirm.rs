@@ -56,7 +56,7 @@ async fn test_peg_in_confirm_tx_success() {
 
     let tx = peg_in_confirm_tx.finalize();
     check_tx_output_sum(INITIAL_AMOUNT, &tx);
-    let result = config.client_0.esplora.broadcast(&tx).await;
+    let result = config.client_0.bitcoin_backend.broadcast(&tx).await;
     println!("Txid: {:?}", tx.compute_txid());
     println!("Peg in confirm tx result: {:?}\n", result);
     println!("Transaction hex: \n{}", serialize_hex(&tx));
@@ -40,7 +40,7 @@ async fn test_peg_in_deposit_tx_success() {
 
     let tx = peg_in_deposit_tx.finalize();
     check_tx_output_sum(INITIAL_AMOUNT, &tx);
-    let result = config.client_0.esplora.broadcast(&tx).await;
+    let result = config.client_0.bitcoin_backend.broadcast(&tx).await;
     println!("Txid: {:?}", tx.compute_txid());
     println!("Peg in deposit tx result: {:?}\n", result);
     assert!(result.is_ok());
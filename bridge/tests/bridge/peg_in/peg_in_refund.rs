@@ -41,7 +41,7 @@ async fn test_peg_in_refund_tx_success() {
     let tx = peg_in_refund_tx.finalize();
     check_tx_output_sum(INITIAL_AMOUNT, &tx);
     wait_for_timelock_expiry(config.network, Some("peg in deposit connector z")).await;
-    let result = config.client_0.esplora.broadcast(&tx).await;
+    let result = config.client_0.bitcoin_backend.broadcast(&tx).await;
     println!("Txid: {:?}", tx.compute_txid());
     println!("Peg in refund tx result: {:?}\n", result);
     assert!(result.is_ok());
@@ -0,0 +1 @@
+pub mod proof_provider;
@@ -0,0 +1,149 @@
+use std::{fs, path::PathBuf};
+
+use ark_bn254::{Bn254, Fr};
+use ark_crypto_primitives::snark::{CircuitSpecificSetupSNARK, SNARK};
+use ark_ff::One;
+use ark_groth16::Groth16;
+use ark_serialize::CanonicalSerialize;
+use ark_std::test_rng;
+use bitcoin::{hex::DisplayHex, Network};
+use bitvm::groth16::risc0::{decode_risc0_seal, encode_risc0_seal, risc0_public_input};
+use rand::{RngCore, SeedableRng};
+
+use bridge::{
+    error::ProofError,
+    proof::{get_proof, DummyCircuit},
+    proof_provider::{
+        get_proof_provider, validate_proof, FileProofProvider, ProofProvider, Risc0Proof,
+        Risc0ProofProvider,
+    },
+    serialization::serialize,
+};
+
+use crate::bridge::helper::invalidate_proof;
+
+const GRAPH_ID: &str = "GRAPH";
+const CONSTANTS_DIGEST: [u8; 32] = [7; 32];
+
+fn test_directory(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "bridge-proof-provider-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).unwrap();
+    path
+}
+
+#[test]
+fn test_file_proof_provider() {
+    let directory = test_directory("file");
+    let provider = FileProofProvider::new(directory.clone());
+    assert_eq!(
+        provider.get_proof(&GRAPH_ID.to_string()).unwrap_err(),
+        ProofError::ProofNotFound(
+            provider
+                .proof_path(&GRAPH_ID.to_string())
+                .display()
+                .to_string()
+        )
+    );
+
+    let proof = get_proof();
+    let mut bytes = vec![];
+    proof.serialize_compressed(&mut bytes).unwrap();
+    fs::write(provider.proof_path(&GRAPH_ID.to_string()), bytes).unwrap();
+
+    let loaded_proof = provider.get_proof(&GRAPH_ID.to_string()).unwrap();
+    assert_eq!(loaded_proof, proof);
+    assert_eq!(validate_proof(&loaded_proof, &proof.vk), Ok(()));
+
+    fs::remove_dir_all(directory).unwrap();
+}
+
+#[test]
+fn test_validate_proof() {
+    let proof = get_proof();
+    let other_proof = get_proof();
+
+    assert_eq!(validate_proof(&proof, &proof.vk), Ok(()));
+    assert_eq!(
+        validate_proof(&proof, &other_proof.vk),
+        Err(ProofError::VerifyingKeyMismatch)
+    );
+    assert_eq!(
+        validate_proof(&invalidate_proof(&proof), &proof.vk),
+        Err(ProofError::InvalidProof)
+    );
+}
+
+#[test]
+fn test_risc0_proof_provider() {
+    let journal = b"final spv journal".to_vec();
+    let public_input = risc0_public_input(&CONSTANTS_DIGEST, &journal);
+
+    // A circuit whose public input is a * b stands in for the risc0 to BitVM Groth16 circuit.
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+    let circuit = DummyCircuit::<Fr> {
+        a: Some(public_input),
+        b: Some(Fr::one()),
+        num_variables: 10,
+        num_constraints: 1 << 6,
+    };
+    let (pk, vk) = Groth16::<Bn254>::setup(circuit, &mut rng).unwrap();
+    let proof = Groth16::<Bn254>::prove(&pk, circuit, &mut rng).unwrap();
+
+    let seal = encode_risc0_seal(&proof);
+    assert_eq!(decode_risc0_seal(&seal).unwrap(), proof);
+    assert!(decode_risc0_seal(&seal[1..]).is_err());
+
+    let directory = test_directory("risc0");
+    let provider = Risc0ProofProvider::new(directory.clone(), vk.clone(), CONSTANTS_DIGEST);
+    fs::write(
+        provider.proof_path(&GRAPH_ID.to_string()),
+        serialize(&Risc0Proof {
+            seal: seal.to_lower_hex_string(),
+            journal: journal.to_lower_hex_string(),
+        }),
+    )
+    .unwrap();
+
+    let raw_proof = provider.get_proof(&GRAPH_ID.to_string()).unwrap();
+    assert_eq!(raw_proof.public, vec![public_input]);
+    assert_eq!(validate_proof(&raw_proof, &vk), Ok(()));
+    assert_eq!(
+        validate_proof(&raw_proof, &get_proof().vk),
+        Err(ProofError::VerifyingKeyMismatch)
+    );
+
+    // A journal that was not proven does not verify.
+    let provider = Risc0ProofProvider::new(directory.clone(), vk.clone(), [8; 32]);
+    let raw_proof = provider.get_proof(&GRAPH_ID.to_string()).unwrap();
+    assert_eq!(
+        validate_proof(&raw_proof, &vk),
+        Err(ProofError::InvalidProof)
+    );
+
+    fs::remove_dir_all(directory).unwrap();
+}
+
+#[test]
+fn test_get_proof_provider_requires_a_provider() {
+    std::env::remove_var("BRIDGE_PROOF_DIRECTORY");
+    std::env::remove_var("BRIDGE_DUMMY_PROOFS");
+    assert!(matches!(
+        get_proof_provider(Network::Regtest, None),
+        Err(ProofError::ProofProviderNotDefined)
+    ));
+
+    // dummy proofs are an explicit opt-in, and only on regtest
+    std::env::set_var("BRIDGE_DUMMY_PROOFS", "1");
+    assert!(matches!(
+        get_proof_provider(Network::Testnet, None),
+        Err(ProofError::ProofProviderNotDefined)
+    ));
+    let provider = get_proof_provider(Network::Regtest, None).unwrap();
+    assert!(provider.get_proof(&GRAPH_ID.to_string()).is_ok());
+    std::env::remove_var("BRIDGE_DUMMY_PROOFS");
+}
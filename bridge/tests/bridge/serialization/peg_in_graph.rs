@@ -4,6 +4,7 @@ use bridge::{
     graphs::peg_in::PegInGraph,
     scripts::generate_pay_to_pubkey_script_address,
     serialization::{deserialize, serialize},
+    timelock_policy::TimelockPolicy,
     transactions::base::{Input, MIN_RELAY_FEE_PEG_IN_CONFIRM, MIN_RELAY_FEE_PEG_IN_DEPOSIT},
 };
 
@@ -33,10 +34,25 @@ async fn test_peg_in_graph_serialization() {
         &config.depositor_context,
         Input { outpoint, amount },
         &config.depositor_evm_address,
+        &[config.operator_context.operator_public_key],
+        &config.timelock_policy,
     );
 
     let json = serialize(&peg_in_graph);
     assert!(!json.is_empty());
     let deserialized_peg_in_graph = deserialize::<PegInGraph>(&json);
     assert!(peg_in_graph == deserialized_peg_in_graph);
+
+    // graphs serialized before the timelock policy and the operators were recorded use the
+    // timelocks of the network and accept any operator
+    let mut legacy_json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let legacy_fields = legacy_json.as_object_mut().unwrap();
+    legacy_fields.remove("timelock_policy");
+    legacy_fields.remove("operator_public_keys");
+    let legacy_peg_in_graph = deserialize::<PegInGraph>(&legacy_json.to_string());
+    assert_eq!(
+        legacy_peg_in_graph.timelock_policy(),
+        TimelockPolicy::for_network(config.depositor_context.network)
+    );
+    assert!(legacy_peg_in_graph.operator_public_keys().is_empty());
 }
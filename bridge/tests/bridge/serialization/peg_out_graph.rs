@@ -4,6 +4,7 @@ use bridge::{
     graphs::{base::PEG_OUT_FEE, peg_in::PegInGraph, peg_out::PegOutGraph},
     scripts::generate_pay_to_pubkey_script_address,
     serialization::{deserialize, serialize},
+    timelock_policy::TimelockPolicy,
     transactions::base::{Input, MIN_RELAY_FEE_PEG_IN_CONFIRM},
 };
 
@@ -44,6 +45,8 @@ async fn test_peg_out_graph_serialization() {
         &config.depositor_context,
         Input { outpoint, amount },
         &config.depositor_evm_address,
+        &[config.operator_context.operator_public_key],
+        &config.timelock_policy,
     );
 
     let kick_off_outpoint =
@@ -57,10 +60,24 @@ async fn test_peg_out_graph_serialization() {
             amount: kick_off_amount,
         },
         &config.commitment_secrets,
+        &config.timelock_policy,
+        &config.valid_proof.vk,
     );
 
     let json = serialize(&peg_out_graph);
     assert!(!json.is_empty());
     let deserialized_peg_out_graph = deserialize::<PegOutGraph>(&json);
     assert!(peg_out_graph == deserialized_peg_out_graph);
+
+    // graphs serialized before the timelock policy was recorded use the timelocks of the network
+    let mut legacy_json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    legacy_json
+        .as_object_mut()
+        .unwrap()
+        .remove("timelock_policy");
+    let legacy_peg_out_graph = deserialize::<PegOutGraph>(&legacy_json.to_string());
+    assert_eq!(
+        legacy_peg_out_graph.timelock_policy(),
+        TimelockPolicy::for_network(config.depositor_context.network)
+    );
 }
@@ -5,9 +5,11 @@ use bitcoin::{Network, PublicKey};
 use super::helper::{get_intermediate_variables_cached, get_valid_proof, invalidate_proof};
 use bridge::{
     client::{
-        chain::chain_adaptor::get_chain_adaptor, client::BitVMClient, esplora::get_esplora_url,
+        chain::chain_adaptor::get_chain_adaptor, client::BitVMClient,
+        keystore::KEYSTORE_PASSPHRASE_ENV_VAR,
     },
     commitments::CommitmentMessageId,
+    common::ZkProofVerifyingKey,
     connectors::{
         connector_0::Connector0, connector_1::Connector1, connector_2::Connector2,
         connector_3::Connector3, connector_4::Connector4, connector_5::Connector5,
@@ -25,6 +27,7 @@ use bridge::{
     },
     serialization::serialize,
     superblock::{SUPERBLOCK_HASH_MESSAGE_LENGTH, SUPERBLOCK_MESSAGE_LENGTH},
+    timelock_policy::TimelockPolicy,
     transactions::assert_transactions::utils::{
         groth16_commitment_secrets_to_public_keys, merge_to_connector_c_commits_public_key,
         AssertCommit1ConnectorsE, AssertCommit2ConnectorsE, AssertCommitConnectorsF,
@@ -44,6 +47,7 @@ const VERIFIER_0_SECRET: &str = "ee0817eac0c13aa8ee2dd3256304041f09f0499d1089b56
 const VERIFIER_1_SECRET: &str = "fc294c70faf210d4d0807ea7a3dba8f7e41700d90c119e1ae82a0687d89d297f";
 const DEPOSITOR_SECRET: &str = "b8f17ea979be24199e7c3fec71ee88914d92fd4ca508443f765d56ce024ef1d7";
 const WITHDRAWER_SECRET: &str = "fffd54f6d8f8ad470cb507fd4b6e9b3ea26b4221a4900cc5ad5916ce67c02f1e";
+const KEYSTORE_PASSPHRASE: &str = "test keystore passphrase";
 
 const DEPOSITOR_EVM_ADDRESS: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8"; // l2 local test network account 1
 const WITHDRAWER_EVM_ADDRESS: &str = "0x3C44CdDdB6a900fa2b585dd299e03d12FA4293BC"; // l2 local test network account 2
@@ -74,6 +78,7 @@ pub struct SetupConfig {
     pub connector_6: Connector6,
     pub depositor_evm_address: String,
     pub withdrawer_evm_address: String,
+    pub timelock_policy: TimelockPolicy,
     pub commitment_secrets: HashMap<CommitmentMessageId, WinternitzSecret>,
     pub valid_proof: RawProof,
     pub invalid_proof: RawProof,
@@ -105,6 +110,7 @@ pub struct SetupConfigFull {
     pub connector_6: Connector6,
     pub depositor_evm_address: String,
     pub withdrawer_evm_address: String,
+    pub timelock_policy: TimelockPolicy,
     pub commitment_secrets: HashMap<CommitmentMessageId, WinternitzSecret>,
     pub valid_proof: RawProof,
     pub invalid_proof: RawProof,
@@ -150,6 +156,7 @@ pub async fn setup_test_full() -> SetupConfigFull {
         config.network,
         &config.operator_context.operator_taproot_public_key,
         &commitment_public_keys,
+        &config.valid_proof.vk,
     );
     serialize(&connector_c); // Caches the lock scripts
 
@@ -179,6 +186,7 @@ pub async fn setup_test_full() -> SetupConfigFull {
         connector_6: config.connector_6,
         depositor_evm_address: config.depositor_evm_address,
         withdrawer_evm_address: config.withdrawer_evm_address,
+        timelock_policy: config.timelock_policy,
         commitment_secrets: config.commitment_secrets,
         valid_proof: config.valid_proof,
         invalid_proof: config.invalid_proof,
@@ -189,8 +197,6 @@ pub async fn setup_test() -> SetupConfig {
     let source_network = Network::Regtest;
     let destination_network = DestinationNetwork::Local;
 
-    let commitment_secrets = get_test_commitment_secrets();
-
     let (_, verifier_0_public_key) = generate_keys_from_secret(source_network, VERIFIER_0_SECRET);
     let (_, verifier_1_public_key) = generate_keys_from_secret(source_network, VERIFIER_1_SECRET);
     let mut n_of_n_public_keys: Vec<PublicKey> = Vec::new();
@@ -211,8 +217,13 @@ pub async fn setup_test() -> SetupConfig {
     let valid_proof = get_valid_proof();
     let invalid_proof = invalidate_proof(&valid_proof);
 
+    let commitment_secrets = get_test_commitment_secrets(&valid_proof.vk);
+
+    if std::env::var(KEYSTORE_PASSPHRASE_ENV_VAR).is_err() {
+        std::env::set_var(KEYSTORE_PASSPHRASE_ENV_VAR, KEYSTORE_PASSPHRASE);
+    }
     let client_0 = BitVMClient::new(
-        Some(get_esplora_url(source_network)),
+        None,
         source_network,
         destination_network,
         Some(get_chain_adaptor(destination_network, None, None)),
@@ -227,7 +238,7 @@ pub async fn setup_test() -> SetupConfig {
     .await;
 
     let client_1 = BitVMClient::new(
-        Some(get_esplora_url(source_network)),
+        None,
         source_network,
         destination_network,
         Some(get_chain_adaptor(destination_network, None, None)),
@@ -241,6 +252,7 @@ pub async fn setup_test() -> SetupConfig {
     )
     .await;
 
+    let timelock_policy = TimelockPolicy::for_network(source_network);
     let connector_a = ConnectorA::new(
         source_network,
         &operator_context.operator_taproot_public_key,
@@ -261,6 +273,7 @@ pub async fn setup_test() -> SetupConfig {
                 ),
             ),
         ]),
+        &timelock_policy,
     );
     let connector_d = ConnectorD::new(source_network, &operator_context.n_of_n_taproot_public_key);
 
@@ -277,6 +290,7 @@ pub async fn setup_test() -> SetupConfig {
         DEPOSITOR_EVM_ADDRESS,
         &depositor_context.depositor_taproot_public_key,
         &operator_context.n_of_n_taproot_public_key,
+        &timelock_policy,
     );
     let connector_0 = Connector0::new(source_network, &operator_context.n_of_n_taproot_public_key);
 
@@ -296,6 +310,7 @@ pub async fn setup_test() -> SetupConfig {
                 ),
             ),
         ]),
+        &timelock_policy,
     );
     let connector_2 = Connector2::new(
         source_network,
@@ -306,8 +321,16 @@ pub async fn setup_test() -> SetupConfig {
             WinternitzPublicKey::from(&commitment_secrets[&CommitmentMessageId::StartTime]),
         )]),
     );
-    let connector_3 = Connector3::new(source_network, &operator_context.operator_public_key);
-    let connector_4 = Connector4::new(source_network, &operator_context.operator_public_key);
+    let connector_3 = Connector3::new(
+        source_network,
+        &operator_context.operator_public_key,
+        &timelock_policy,
+    );
+    let connector_4 = Connector4::new(
+        source_network,
+        &operator_context.operator_public_key,
+        &timelock_policy,
+    );
     let connector_5 = Connector5::new(source_network, &operator_context.n_of_n_taproot_public_key);
     let connector_6 = Connector6::new(
         source_network,
@@ -351,6 +374,7 @@ pub async fn setup_test() -> SetupConfig {
         connector_6,
         depositor_evm_address: DEPOSITOR_EVM_ADDRESS.to_string(),
         withdrawer_evm_address: WITHDRAWER_EVM_ADDRESS.to_string(),
+        timelock_policy,
         commitment_secrets,
         valid_proof,
         invalid_proof,
@@ -358,7 +382,9 @@ pub async fn setup_test() -> SetupConfig {
 }
 
 // Use fixed secrets for testing to ensure repeatable spending addresses.
-fn get_test_commitment_secrets() -> HashMap<CommitmentMessageId, WinternitzSecret> {
+fn get_test_commitment_secrets(
+    vk: &ZkProofVerifyingKey,
+) -> HashMap<CommitmentMessageId, WinternitzSecret> {
     let mut commitment_map = HashMap::from([
         (
             CommitmentMessageId::PegOutTxIdSourceNetwork,
@@ -382,7 +408,7 @@ fn get_test_commitment_secrets() -> HashMap<CommitmentMessageId, WinternitzSecre
         ),
     ]);
 
-    let all_variables = get_intermediate_variables_cached();
+    let all_variables = get_intermediate_variables_cached(vk);
     // split variable to different connectors
     for (v, size) in all_variables {
         commitment_map.insert(
@@ -4,7 +4,7 @@ use bridge::{
     commitments::CommitmentMessageId,
     connectors::base::TaprootConnector,
     graphs::base::DUST_AMOUNT,
-    superblock::get_start_time_block_number,
+    superblock::get_start_time,
     transactions::{
         base::{BaseTransaction, Input, MIN_RELAY_FEE_START_TIME},
         start_time::StartTimeTransaction,
@@ -42,10 +42,14 @@ async fn test_start_time_tx_success() {
         },
     );
 
+    let height = config.client_0.bitcoin_backend.get_height().await.unwrap();
+    let committed_start_time = get_start_time(&*config.client_0.bitcoin_backend, height)
+        .await
+        .unwrap();
     start_time_tx.sign(
         &config.operator_context,
         &config.connector_2,
-        get_start_time_block_number(config.network),
+        committed_start_time,
         &config.commitment_secrets[&CommitmentMessageId::StartTime],
     );
 
@@ -53,7 +57,7 @@ async fn test_start_time_tx_success() {
     check_tx_output_sum(DUST_AMOUNT, &tx);
     // TODO: revisit here after superblock time lock is implemented
     // wait_timelock_expiry(config.network, Some("start time absolute lock time")).await;
-    let result = config.client_0.esplora.broadcast(&tx).await;
+    let result = config.client_0.bitcoin_backend.broadcast(&tx).await;
     println!("Txid: {:?}", tx.compute_txid());
     println!("Start time tx result: {:?}\n", result);
     assert!(result.is_ok());
@@ -87,7 +87,7 @@ async fn test_start_time_timeout_tx_success() {
     let tx = start_time_timeout_tx.finalize();
     check_tx_output_sum(reward_amount + DUST_AMOUNT, &tx);
     wait_for_timelock_expiry(config.network, Some("kick off 1 connector 1")).await;
-    let result = config.client_0.esplora.broadcast(&tx).await;
+    let result = config.client_0.bitcoin_backend.broadcast(&tx).await;
     println!("Txid: {:?}", tx.compute_txid());
     println!("Start time timeout tx result: {:?}\n", result);
     assert!(result.is_ok());
@@ -0,0 +1,196 @@
+use async_trait::async_trait;
+use bitcoin::{
+    block::{Header, Version},
+    hashes::Hash,
+    BlockHash, CompactTarget, TxMerkleNode,
+};
+
+use bitvm::signatures::signing_winternitz::{
+    generate_winternitz_witness, WinternitzSecret, WinternitzSigningInputs,
+};
+use bridge::{
+    client::bitcoin_backend::{bitcoin_backend::BitcoinBackend, memory_backend::MemoryBackend},
+    constants::START_TIME_MESSAGE_LENGTH,
+    error::BitcoinBackendError,
+    superblock::{
+        find_disprove_superblock, find_superblock, get_committed_start_time,
+        get_committed_superblock_hash, get_start_time, get_superblock_hash_message, is_heavier,
+        is_in_measurement_period, HeaderSource, Superblock, SUPERBLOCK_HASH_MESSAGE_LENGTH,
+        SUPERBLOCK_MEASUREMENT_PERIOD_SECONDS,
+    },
+};
+
+const START_TIME: u32 = 1_700_000_000;
+const BLOCK_INTERVAL_SECONDS: u32 = 600;
+
+struct TestHeaders(Vec<Header>);
+
+#[async_trait]
+impl HeaderSource for TestHeaders {
+    async fn get_best_height(&self) -> Result<u32, BitcoinBackendError> {
+        Ok(self.0.len() as u32 - 1)
+    }
+
+    async fn get_header(&self, height: u32) -> Result<Header, BitcoinBackendError> {
+        Ok(self.0[height as usize])
+    }
+}
+
+// Headers are not mined, their hashes only need to differ for the superblock search.
+fn test_headers(count: u32, time: impl Fn(u32) -> u32) -> TestHeaders {
+    let mut headers: Vec<Header> = vec![];
+    for height in 0..count {
+        headers.push(Header {
+            version: Version::TWO,
+            prev_blockhash: headers
+                .last()
+                .map_or(BlockHash::all_zeros(), |header| header.block_hash()),
+            merkle_root: TxMerkleNode::all_zeros(),
+            time: time(height),
+            bits: CompactTarget::from_consensus(0x207fffff),
+            nonce: height,
+        });
+    }
+    TestHeaders(headers)
+}
+
+fn find_superblock_by_scan(
+    headers: &TestHeaders,
+    start_time: u32,
+    start_height: u32,
+) -> Option<Superblock> {
+    let mut superblock: Option<Superblock> = None;
+    for (height, header) in headers.0.iter().enumerate().skip(start_height as usize) {
+        if is_in_measurement_period(header, start_time)
+            && superblock.is_none_or(|superblock| {
+                is_heavier(&header.block_hash(), &superblock.header.block_hash())
+            })
+        {
+            superblock = Some(Superblock {
+                height: height as u32,
+                header: *header,
+            });
+        }
+    }
+    superblock
+}
+
+#[test]
+fn test_is_in_measurement_period() {
+    let header = |time| test_headers(1, |_| time).0[0];
+
+    assert!(!is_in_measurement_period(&header(START_TIME), START_TIME));
+    assert!(is_in_measurement_period(
+        &header(START_TIME + 1),
+        START_TIME
+    ));
+    assert!(is_in_measurement_period(
+        &header(START_TIME + SUPERBLOCK_MEASUREMENT_PERIOD_SECONDS - 1),
+        START_TIME
+    ));
+    assert!(!is_in_measurement_period(
+        &header(START_TIME + SUPERBLOCK_MEASUREMENT_PERIOD_SECONDS),
+        START_TIME
+    ));
+}
+
+#[tokio::test]
+async fn test_find_superblock_returns_heaviest_block_of_period() {
+    // Three measurement periods worth of blocks, with some timestamps out of order.
+    let headers = test_headers(3 * 2016, |height| match height % 7 {
+        3 => START_TIME - 3600 + height * BLOCK_INTERVAL_SECONDS,
+        _ => START_TIME - 7200 + height * BLOCK_INTERVAL_SECONDS,
+    });
+
+    for start_height in [0, 5, 100, 2000] {
+        let superblock = find_superblock(&headers, START_TIME, start_height)
+            .await
+            .unwrap();
+        assert!(superblock.is_some());
+        assert_eq!(
+            superblock,
+            find_superblock_by_scan(&headers, START_TIME, start_height)
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_find_superblock_without_block_in_period() {
+    let headers = test_headers(100, |height| START_TIME - 100_000 + height);
+
+    assert_eq!(
+        find_superblock(&headers, START_TIME, 0).await.unwrap(),
+        None
+    );
+}
+
+#[tokio::test]
+async fn test_find_disprove_superblock() {
+    let headers = test_headers(2 * 2016, |height| {
+        START_TIME - 1200 + height * BLOCK_INTERVAL_SECONDS
+    });
+    let superblock = find_superblock(&headers, START_TIME, 0)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let committed_hash = superblock.header.block_hash();
+    assert_eq!(
+        find_disprove_superblock(&headers, START_TIME, 0, &committed_hash)
+            .await
+            .unwrap(),
+        None
+    );
+
+    let committed_hash = BlockHash::from_byte_array([0xff; 32]);
+    assert_eq!(
+        find_disprove_superblock(&headers, START_TIME, 0, &committed_hash)
+            .await
+            .unwrap(),
+        Some(superblock)
+    );
+}
+
+#[tokio::test]
+async fn test_get_start_time_from_backend() {
+    let backend = MemoryBackend::new();
+    backend.mine_blocks(20);
+
+    for height in 1..=20 {
+        let header = backend.get_block_header(height).await.unwrap();
+        let prev_header = backend.get_block_header(height - 1).await.unwrap();
+        assert_eq!(header.prev_blockhash, prev_header.block_hash());
+    }
+    assert!(backend.get_block_header(21).await.is_err());
+
+    // Timestamps of the memory backend are increasing, so the median time past is the time of
+    // the block 5 blocks before.
+    let start_time = get_start_time(&backend, 15).await.unwrap();
+    assert_eq!(start_time, backend.get_block_header(10).await.unwrap().time);
+}
+
+#[test]
+fn test_get_committed_values() {
+    let start_time_secret = WinternitzSecret::new(START_TIME_MESSAGE_LENGTH);
+    let superblock_hash_secret = WinternitzSecret::new(SUPERBLOCK_HASH_MESSAGE_LENGTH);
+    let superblock = test_headers(5, |height| START_TIME + height).0[4];
+
+    let start_time_witness = generate_winternitz_witness(&WinternitzSigningInputs {
+        message: &START_TIME.to_le_bytes(),
+        signing_key: &start_time_secret,
+    });
+    let superblock_hash_witness = generate_winternitz_witness(&WinternitzSigningInputs {
+        message: &get_superblock_hash_message(&superblock),
+        signing_key: &superblock_hash_secret,
+    });
+
+    assert_eq!(
+        get_committed_start_time(&start_time_witness),
+        Some(START_TIME)
+    );
+    assert_eq!(
+        get_committed_superblock_hash(&superblock_hash_witness),
+        Some(superblock.block_hash())
+    );
+    assert_eq!(get_committed_start_time(&superblock_hash_witness), None);
+}
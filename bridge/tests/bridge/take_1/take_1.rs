@@ -95,7 +95,7 @@ async fn test_take_1_tx_success() {
     let tx = take_1_tx.finalize();
     check_tx_output_sum(ONE_HUNDRED + reward_amount + DUST_AMOUNT * 2, &tx);
     wait_for_timelock_expiry(config.network, Some("kick off 2 connector 3")).await;
-    let result = config.client_0.esplora.broadcast(&tx).await;
+    let result = config.client_0.bitcoin_backend.broadcast(&tx).await;
     println!("Txid: {:?}", tx.compute_txid());
     println!("Take 1 tx result: {:?}\n", result);
     assert!(result.is_ok());
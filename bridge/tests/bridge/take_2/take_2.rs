@@ -100,7 +100,7 @@ async fn test_take_2_tx_success() {
     let tx = take_2_tx.finalize();
     check_tx_output_sum(ONE_HUNDRED + reward_amount + DUST_AMOUNT * 2, &tx);
     wait_for_timelock_expiry(config.network, Some("assert connector 4")).await;
-    let result = config.client_0.esplora.broadcast(&tx).await;
+    let result = config.client_0.bitcoin_backend.broadcast(&tx).await;
     println!("Txid: {:?}", tx.compute_txid());
     println!("Take 2 tx result: {:?}\n", result);
     assert!(result.is_ok());
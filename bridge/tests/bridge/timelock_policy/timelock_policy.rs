@@ -0,0 +1,127 @@
+use bitcoin::{hashes::Hash, Amount, Network, OutPoint, Sequence, Txid};
+use bridge::{
+    connectors::{
+        base::{P2wshConnector, TaprootConnector},
+        connector_3::Connector3,
+        connector_4::Connector4,
+        connector_z::ConnectorZ,
+    },
+    constants::{NUM_BLOCKS_PER_2_WEEKS, NUM_BLOCKS_PER_3_DAYS, NUM_BLOCKS_PER_6_HOURS},
+    contexts::{base::generate_keys_from_secret, depositor::DepositorContext},
+    error::GraphError,
+    timelock_policy::{TimelockPolicy, MAX_RELATIVE_TIMELOCK},
+    transactions::base::Input,
+};
+
+const DEPOSITOR_SECRET: &str = "b8f17ea979be24199e7c3fec71ee88914d92fd4ca508443f765d56ce024ef1d7";
+const OPERATOR_SECRET: &str = "3076ca1dfc1e383be26d5dd3c0c427340f96139fa8c2520862cf551ec2d670ac";
+const VERIFIER_SECRET: &str = "ee0817eac0c13aa8ee2dd3256304041f09f0499d1089b56495310ae8093583e2";
+const EVM_ADDRESS: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+
+// Distinct windows, e.g. for a signet rehearsal of the mainnet schedule.
+fn signet_policy() -> TimelockPolicy {
+    TimelockPolicy {
+        kick_off_2: 144,
+        kick_off_timeout: 150,
+        start_time_timeout: 6,
+        take_1: 18,
+        take_2: 72,
+        peg_in_refund: 288,
+    }
+}
+
+fn input() -> Input {
+    Input {
+        outpoint: OutPoint {
+            txid: Txid::from_byte_array([1; 32]),
+            vout: 0,
+        },
+        amount: Amount::from_sat(100_000),
+    }
+}
+
+#[test]
+fn test_timelock_policy_for_network() {
+    let mainnet = TimelockPolicy::for_network(Network::Bitcoin);
+    assert_eq!(mainnet.kick_off_2, NUM_BLOCKS_PER_2_WEEKS);
+    assert_eq!(mainnet.start_time_timeout, NUM_BLOCKS_PER_6_HOURS);
+    assert_eq!(mainnet.take_1, NUM_BLOCKS_PER_3_DAYS);
+    assert!(mainnet.validate().is_ok());
+
+    for network in [Network::Regtest, Network::Testnet, Network::Signet] {
+        assert_eq!(
+            TimelockPolicy::for_network(network),
+            TimelockPolicy::uniform(2)
+        );
+    }
+}
+
+#[test]
+fn test_timelock_policy_validate() {
+    assert!(signet_policy().validate().is_ok());
+
+    let invalid_policies = [
+        TimelockPolicy {
+            take_1: 0,
+            ..signet_policy()
+        },
+        TimelockPolicy {
+            take_2: MAX_RELATIVE_TIMELOCK + 1,
+            ..signet_policy()
+        },
+        TimelockPolicy {
+            kick_off_timeout: 143,
+            ..signet_policy()
+        },
+    ];
+    for policy in invalid_policies {
+        assert!(matches!(
+            policy.validate(),
+            Err(GraphError::InvalidTimelockPolicy(_))
+        ));
+    }
+}
+
+#[test]
+fn test_connectors_use_timelock_policy() {
+    let policy = signet_policy();
+    let (_, operator_public_key) = generate_keys_from_secret(Network::Signet, OPERATOR_SECRET);
+    let (_, verifier_public_key) = generate_keys_from_secret(Network::Signet, VERIFIER_SECRET);
+    let depositor_context =
+        DepositorContext::new(Network::Signet, DEPOSITOR_SECRET, &[verifier_public_key]);
+
+    let connector_3 = Connector3::new(Network::Signet, &operator_public_key, &policy);
+    assert_eq!(
+        connector_3.generate_tx_in(&input()).sequence,
+        Sequence(policy.take_1)
+    );
+
+    let connector_4 = Connector4::new(Network::Signet, &operator_public_key, &policy);
+    assert_eq!(
+        connector_4.generate_tx_in(&input()).sequence,
+        Sequence(policy.take_2)
+    );
+    assert_ne!(
+        connector_4.generate_address(),
+        Connector4::new(
+            Network::Signet,
+            &operator_public_key,
+            &TimelockPolicy::for_network(Network::Signet)
+        )
+        .generate_address()
+    );
+
+    let connector_z = ConnectorZ::new(
+        Network::Signet,
+        EVM_ADDRESS,
+        &depositor_context.depositor_taproot_public_key,
+        &depositor_context.n_of_n_taproot_public_key,
+        &policy,
+    );
+    assert_eq!(
+        connector_z
+            .generate_taproot_leaf_tx_in(0, &input())
+            .sequence,
+        Sequence(policy.peg_in_refund)
+    );
+}
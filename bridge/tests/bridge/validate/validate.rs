@@ -1,23 +1,24 @@
-use std::str::FromStr;
+use std::{str::FromStr, sync::Arc};
 
 use bitcoin::{Amount, OutPoint, Txid};
 
 use bridge::{
-    error::{Error, ValidationError},
+    client::bitcoin_backend::bitcoin_backend::BitcoinBackend,
+    error::{Error, GraphError, ValidationError},
     graphs::{base::PEG_IN_FEE, peg_in::PegInGraph, peg_out::PegOutGraph},
     scripts::generate_burn_script,
+    serialization::{deserialize, serialize},
     transactions::{base::Input, pre_signed::PreSignedTransaction},
 };
-use esplora_client::AsyncClient;
 
 use crate::bridge::setup::{setup_test, INITIAL_AMOUNT};
 
 #[tokio::test]
 async fn test_validate_success() {
-    let (peg_in_graph, peg_out_graph, _, esplora) = setup_and_create_graphs().await;
+    let (peg_in_graph, peg_out_graph, _, bitcoin_backend) = setup_and_create_graphs().await;
 
     let is_peg_in_data_valid = peg_in_graph.validate();
-    let is_peg_out_data_valid = peg_out_graph.validate(&esplora).await;
+    let is_peg_out_data_valid = peg_out_graph.validate(bitcoin_backend.as_ref()).await;
 
     assert!(is_peg_in_data_valid.is_ok());
     assert!(is_peg_out_data_valid.is_ok());
@@ -167,7 +168,31 @@ async fn test_validate_invalid_script_pubkey() {
     }
 }
 
-async fn setup_and_create_graphs() -> (PegInGraph, PegOutGraph, OutPoint, AsyncClient) {
+#[tokio::test]
+async fn test_validate_timelock_policy() {
+    let (peg_in_graph, peg_out_graph, _, bitcoin_backend) = setup_and_create_graphs().await;
+
+    // A policy the connectors were not built with.
+    let peg_in_graph: PegInGraph = deserialize(&serialize(&peg_in_graph).replacen(
+        "\"peg_in_refund\":2",
+        "\"peg_in_refund\":3",
+        1,
+    ));
+    assert!(matches!(
+        peg_in_graph.validate(),
+        Err(Error::Graph(GraphError::InvalidTimelockPolicy(_)))
+    ));
+
+    // A policy that fails validation.
+    let peg_out_graph: PegOutGraph =
+        deserialize(&serialize(&peg_out_graph).replacen("\"take_1\":2", "\"take_1\":0", 1));
+    assert!(matches!(
+        peg_out_graph.validate(bitcoin_backend.as_ref()).await,
+        Err(Error::Graph(GraphError::InvalidTimelockPolicy(_)))
+    ));
+}
+
+async fn setup_and_create_graphs() -> (PegInGraph, PegOutGraph, OutPoint, Arc<dyn BitcoinBackend>) {
     let config = setup_test().await;
 
     let amount = Amount::from_sat(INITIAL_AMOUNT + PEG_IN_FEE);
@@ -189,6 +214,8 @@ async fn setup_and_create_graphs() -> (PegInGraph, PegOutGraph, OutPoint, AsyncC
             amount,
         },
         &config.depositor_evm_address,
+        &[config.operator_context.operator_public_key],
+        &config.timelock_policy,
     );
 
     let peg_out_graph = PegOutGraph::new(
@@ -199,12 +226,14 @@ async fn setup_and_create_graphs() -> (PegInGraph, PegOutGraph, OutPoint, AsyncC
             amount,
         },
         &config.commitment_secrets,
+        &config.timelock_policy,
+        &config.valid_proof.vk,
     );
 
     (
         peg_in_graph,
         peg_out_graph,
         peg_in_outpoint,
-        config.client_0.esplora,
+        config.client_0.bitcoin_backend,
     )
 }
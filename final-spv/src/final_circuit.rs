@@ -1,7 +1,7 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use header_chain::header_chain::BlockHeaderCircuitOutput;
 
-use crate::spv::SPV;
+use crate::spv::{BatchSPV, SPV};
 
 #[derive(Eq, PartialEq, Clone, Debug, BorshDeserialize, BorshSerialize)]
 
@@ -9,3 +9,13 @@ pub struct FinalCircuitInput {
     pub block_header_circuit_output: BlockHeaderCircuitOutput,
     pub spv: SPV,
 }
+
+/// Input of [`crate::batch_final_circuit`].
+#[derive(Eq, PartialEq, Clone, Debug, BorshDeserialize, BorshSerialize)]
+pub struct BatchFinalCircuitInput {
+    pub block_header_circuit_output: BlockHeaderCircuitOutput,
+    pub spv: BatchSPV,
+    /// Minimum number of blocks on top of every block of the SPV proof, i.e.
+    /// `chain_state.block_height - tx_block_height >= min_confirmation_depth`.
+    pub min_confirmation_depth: u32,
+}
@@ -1,4 +1,4 @@
-use final_circuit::FinalCircuitInput;
+use final_circuit::{BatchFinalCircuitInput, FinalCircuitInput};
 use risc0_zkvm::guest::env;
 use zkvm::ZkvmGuest;
 
@@ -63,3 +63,41 @@ pub fn final_circuit(guest: &impl ZkvmGuest) {
     let end = env::cycle_count();
     println!("Final circuit took {:?} cycles", end - start);
 }
+
+/// The final circuit proving a batch of transactions at a minimum confirmation depth. The guest
+/// still runs [`final_circuit`], the ELFs in `prover/elfs` being built from it.
+pub fn batch_final_circuit(guest: &impl ZkvmGuest) {
+    let start = env::cycle_count();
+    let input: BatchFinalCircuitInput = guest.read_from_host::<BatchFinalCircuitInput>();
+    guest.verify(HEADER_CHAIN_GUEST_ID, &input.block_header_circuit_output);
+    guest.commit(&batch_final_circuit_output(&input));
+    let end = env::cycle_count();
+    println!("Batch final circuit took {:?} cycles", end - start);
+}
+
+/// Checks the batch against the header chain output and returns what [`batch_final_circuit`]
+/// commits to. Panics if the batch is not valid or not deep enough.
+pub fn batch_final_circuit_output(input: &BatchFinalCircuitInput) -> [u8; 32] {
+    let chain_state = &input.block_header_circuit_output.chain_state;
+    assert!(
+        input.spv.verify(&chain_state.block_hashes_mmr),
+        "SPV proof is not valid"
+    );
+    let confirmation_depth = input
+        .spv
+        .confirmation_depth(&chain_state.block_hashes_mmr, chain_state.block_height)
+        .expect("SPV blocks are not in the header chain");
+    assert!(
+        confirmation_depth >= input.min_confirmation_depth,
+        "SPV blocks are {} blocks deep, {} required",
+        confirmation_depth,
+        input.min_confirmation_depth
+    );
+    let mut hasher = blake3::Hasher::new();
+
+    hasher.update(&input.spv.outputs_digest());
+    hasher.update(&chain_state.best_block_hash);
+    hasher.update(&chain_state.total_work);
+    hasher.update(&input.min_confirmation_depth.to_le_bytes());
+    *hasher.finalize().as_bytes()
+}
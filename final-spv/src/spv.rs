@@ -1,4 +1,5 @@
 use crate::{merkle_tree::BlockInclusionProof, transaction::CircuitTransaction};
+use bitcoin::TxOut;
 use borsh::{BorshDeserialize, BorshSerialize};
 use header_chain::{
     header_chain::CircuitBlockHeader, mmr_guest::MMRGuest, mmr_native::MMRInclusionProof,
@@ -38,17 +39,185 @@ impl SPV {
     }
 }
 
+/// A transaction of a [`BlockSPV`] with the indices of the outputs to commit to.
+#[derive(Eq, PartialEq, Clone, Debug, BorshDeserialize, BorshSerialize)]
+pub struct SPVTransaction {
+    pub transaction: CircuitTransaction,
+    pub block_inclusion_proof: BlockInclusionProof,
+    pub vouts: Vec<u32>,
+}
+
+impl SPVTransaction {
+    pub fn new(
+        transaction: CircuitTransaction,
+        block_inclusion_proof: BlockInclusionProof,
+        vouts: Vec<u32>,
+    ) -> Self {
+        SPVTransaction {
+            transaction,
+            block_inclusion_proof,
+            vouts,
+        }
+    }
+}
+
+/// Transactions of a single block, sharing the block header and its MMR inclusion proof.
+#[derive(Eq, PartialEq, Clone, Debug, BorshDeserialize, BorshSerialize)]
+pub struct BlockSPV {
+    pub block_header: CircuitBlockHeader,
+    pub mmr_inclusion_proof: MMRInclusionProof,
+    pub transactions: Vec<SPVTransaction>,
+}
+
+impl BlockSPV {
+    pub fn new(
+        block_header: CircuitBlockHeader,
+        mmr_inclusion_proof: MMRInclusionProof,
+        transactions: Vec<SPVTransaction>,
+    ) -> Self {
+        BlockSPV {
+            block_header,
+            mmr_inclusion_proof,
+            transactions,
+        }
+    }
+}
+
+/// A transaction output proven by a [`BatchSPV`].
+#[derive(Eq, PartialEq, Clone, Debug, BorshDeserialize, BorshSerialize)]
+pub struct SPVOutput {
+    pub txid: [u8; 32],
+    pub vout: u32,
+    pub script_pubkey: Vec<u8>,
+    pub value: u64,
+}
+
+impl SPVOutput {
+    pub fn new(txid: [u8; 32], vout: u32, output: &TxOut) -> Self {
+        SPVOutput {
+            txid,
+            vout,
+            script_pubkey: output.script_pubkey.to_bytes(),
+            value: output.value.to_sat(),
+        }
+    }
+}
+
+/// Proves the inclusion of several transactions, possibly in different blocks, and selects
+/// some of their outputs. Transactions of the same block share one block header and MMR proof.
+#[derive(Eq, PartialEq, Clone, Debug, BorshDeserialize, BorshSerialize)]
+pub struct BatchSPV {
+    pub blocks: Vec<BlockSPV>,
+}
+
+impl BatchSPV {
+    pub fn new(blocks: Vec<BlockSPV>) -> Self {
+        BatchSPV { blocks }
+    }
+
+    /// Checks that every transaction is in its block, that every block is in the MMR, and that
+    /// every selected output exists. A batch has to select at least one output of each
+    /// transaction, otherwise the transaction would not be committed to.
+    pub fn verify(&self, mmr_guest: &MMRGuest) -> bool {
+        if self.blocks.is_empty() {
+            return false;
+        }
+        for block in self.blocks.iter() {
+            if block.transactions.is_empty() {
+                return false;
+            }
+            let block_hash = block.block_header.compute_block_hash();
+            if !mmr_guest.verify_proof(block_hash, &block.mmr_inclusion_proof) {
+                return false;
+            }
+            for spv_transaction in block.transactions.iter() {
+                let txid = spv_transaction.transaction.txid();
+                if spv_transaction.block_inclusion_proof.get_root(txid)
+                    != block.block_header.merkle_root
+                {
+                    return false;
+                }
+                if spv_transaction.vouts.is_empty()
+                    || spv_transaction
+                        .vouts
+                        .iter()
+                        .any(|vout| *vout as usize >= spv_transaction.transaction.output.len())
+                {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Returns the number of blocks on top of the shallowest block of the batch, for a chain whose
+    /// tip is at `block_height` and whose block hashes, starting at genesis, are in the MMR.
+    /// Returns `None` if an MMR inclusion proof does not fit the MMR or a block is above the tip.
+    pub fn confirmation_depth(&self, mmr_guest: &MMRGuest, block_height: u32) -> Option<u32> {
+        let mut depth = None;
+        for block in self.blocks.iter() {
+            let tx_block_height = mmr_guest.leaf_index(&block.mmr_inclusion_proof)?;
+            let block_depth = block_height.checked_sub(tx_block_height)?;
+            depth = Some(depth.map_or(block_depth, |depth: u32| depth.min(block_depth)));
+        }
+        depth
+    }
+
+    /// Returns the selected outputs, in the order of the blocks, transactions and vouts.
+    pub fn outputs(&self) -> Vec<SPVOutput> {
+        let mut outputs = vec![];
+        for block in self.blocks.iter() {
+            for spv_transaction in block.transactions.iter() {
+                let txid = spv_transaction.transaction.txid();
+                for vout in spv_transaction.vouts.iter() {
+                    outputs.push(SPVOutput::new(
+                        txid,
+                        *vout,
+                        &spv_transaction.transaction.output[*vout as usize],
+                    ));
+                }
+            }
+        }
+        outputs
+    }
+
+    /// Returns the digest of the selected outputs committed by the final circuit.
+    pub fn outputs_digest(&self) -> [u8; 32] {
+        outputs_digest(&self.outputs())
+    }
+}
+
+/// Blake3 digest of the outputs, hashing for each of them the txid, the vout and the value
+/// (little-endian), the script length (little-endian u32) and the script, after the number of
+/// outputs (little-endian u32).
+pub fn outputs_digest(outputs: &[SPVOutput]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&(outputs.len() as u32).to_le_bytes());
+    for output in outputs {
+        hasher.update(&output.txid);
+        hasher.update(&output.vout.to_le_bytes());
+        hasher.update(&output.value.to_le_bytes());
+        hasher.update(&(output.script_pubkey.len() as u32).to_le_bytes());
+        hasher.update(&output.script_pubkey);
+    }
+    *hasher.finalize().as_bytes()
+}
+
 #[cfg(test)]
 mod tests {
     use borsh::BorshDeserialize;
     use header_chain::{
-        header_chain::CircuitBlockHeader, mmr_guest::MMRGuest, mmr_native::MMRNative,
+        header_chain::{BlockHeaderCircuitOutput, ChainState, CircuitBlockHeader},
+        mmr_guest::MMRGuest,
+        mmr_native::MMRNative,
     };
     use hex_literal::hex;
 
     use crate::{
+        batch_final_circuit_output,
+        final_circuit::BatchFinalCircuitInput,
         merkle_tree::{verify_merkle_proof, BitcoinMerkleTree, BlockInclusionProof},
-        spv::SPV,
+        spv::{outputs_digest, BatchSPV, BlockSPV, SPVOutput, SPVTransaction, SPV},
         transaction::CircuitTransaction,
     };
 
@@ -137,4 +306,200 @@ mod tests {
             }
         }
     }
+
+    // Builds a block out of each group of transactions, since the mainnet blocks above only have
+    // one each, and appends them to the MMRs. The MMR proofs are generated once all the blocks
+    // are appended, so they verify against the final MMR.
+    fn batch_blocks(
+        blocks: &[(&[CircuitTransaction], Vec<Vec<u32>>)],
+        mmr_native: &mut MMRNative,
+        mmr_guest: &mut MMRGuest,
+    ) -> Vec<BlockSPV> {
+        let mut blocks_spv = vec![];
+        for (txs, vouts) in blocks {
+            let txids: Vec<[u8; 32]> = txs.iter().map(|tx| tx.txid()).collect();
+            let mut block_header =
+                CircuitBlockHeader::try_from_slice(&MAINNET_BLOCK_HEADERS[0]).unwrap();
+            block_header.merkle_root = BitcoinMerkleTree::new(txids).root();
+            block_header.time += mmr_native.leaf_count();
+            mmr_native.append(block_header.compute_block_hash());
+            mmr_guest.append(block_header.compute_block_hash());
+
+            let mid_state_merkle_tree = BitcoinMerkleTree::new_mid_state(txs);
+            let transactions: Vec<SPVTransaction> = txs
+                .iter()
+                .zip(vouts.iter())
+                .enumerate()
+                .map(|(i, (tx, vouts))| {
+                    SPVTransaction::new(
+                        tx.clone(),
+                        mid_state_merkle_tree.generate_proof(i as u32),
+                        vouts.clone(),
+                    )
+                })
+                .collect();
+            blocks_spv.push((block_header, mmr_native.leaf_count() - 1, transactions));
+        }
+
+        blocks_spv
+            .into_iter()
+            .map(|(block_header, index, transactions)| {
+                let (_, mmr_inclusion_proof) = mmr_native.generate_proof(index);
+                BlockSPV::new(block_header, mmr_inclusion_proof, transactions)
+            })
+            .collect()
+    }
+
+    fn mainnet_txs() -> Vec<CircuitTransaction> {
+        MAINNET_BLOCK_TRANSACTIONS
+            .iter()
+            .map(|tx| CircuitTransaction(bitcoin::consensus::deserialize(*tx).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_batch_spv() {
+        let mut mmr_native = MMRNative::new();
+        let mut mmr_guest = MMRGuest::new();
+        let txs = mainnet_txs();
+        let mut blocks = batch_blocks(
+            &[
+                (&txs[0..3], vec![vec![0], vec![0], vec![0]]),
+                (
+                    &txs[3..8],
+                    vec![vec![0], vec![0], vec![0], vec![0], vec![0]],
+                ),
+            ],
+            &mut mmr_native,
+            &mut mmr_guest,
+        );
+        let block_1 = blocks.pop().unwrap();
+        let block_0 = blocks.pop().unwrap();
+        // only the second and fourth transactions of the second block are proven
+        let mut block_1_selection = block_1.clone();
+        block_1_selection.transactions = vec![
+            block_1.transactions[1].clone(),
+            block_1.transactions[3].clone(),
+        ];
+        let batch_spv = BatchSPV::new(vec![block_0, block_1_selection]);
+
+        assert!(batch_spv.verify(&mmr_guest));
+
+        let outputs = batch_spv.outputs();
+        let expected_txs = [&txs[0], &txs[1], &txs[2], &txs[4], &txs[6]];
+        assert_eq!(outputs.len(), expected_txs.len());
+        for (output, tx) in outputs.iter().zip(expected_txs) {
+            assert_eq!(output, &SPVOutput::new(tx.txid(), 0, &tx.output[0]));
+            assert_eq!(output.value, 50 * 100_000_000);
+        }
+        assert_eq!(batch_spv.outputs_digest(), outputs_digest(&outputs));
+        // the blocks are at heights 0 and 1
+        assert_eq!(batch_spv.confirmation_depth(&mmr_guest, 1), Some(0));
+        assert_eq!(batch_spv.confirmation_depth(&mmr_guest, 7), Some(6));
+        assert_eq!(batch_spv.confirmation_depth(&mmr_guest, 0), None);
+        assert_ne!(batch_spv.outputs_digest(), outputs_digest(&outputs[1..]));
+    }
+
+    // Batches two transactions of one block and one of the next, below `depth` more blocks, and
+    // returns the final circuit input with the chain tip on top of them.
+    fn batch_final_circuit_input(
+        depth: u32,
+        min_confirmation_depth: u32,
+    ) -> BatchFinalCircuitInput {
+        let mut mmr_native = MMRNative::new();
+        let mut mmr_guest = MMRGuest::new();
+        let txs = mainnet_txs();
+        let mut blocks = batch_blocks(
+            &[
+                (&txs[0..2], vec![vec![0], vec![0]]),
+                (&txs[2..3], vec![vec![0]]),
+            ],
+            &mut mmr_native,
+            &mut mmr_guest,
+        );
+        for i in 0..depth {
+            mmr_native.append([i as u8; 32]);
+            mmr_guest.append([i as u8; 32]);
+        }
+        for (index, block) in blocks.iter_mut().enumerate() {
+            block.mmr_inclusion_proof = mmr_native.generate_proof(index as u32).1;
+        }
+
+        let mut chain_state = ChainState::new();
+        chain_state.block_height = mmr_native.leaf_count() - 1;
+        chain_state.best_block_hash = [1; 32];
+        chain_state.total_work = [2; 32];
+        chain_state.block_hashes_mmr = mmr_guest;
+        BatchFinalCircuitInput {
+            block_header_circuit_output: BlockHeaderCircuitOutput {
+                method_id: [0; 8],
+                chain_state,
+            },
+            spv: BatchSPV::new(blocks),
+            min_confirmation_depth,
+        }
+    }
+
+    #[test]
+    fn test_batch_final_circuit_output() {
+        let input = batch_final_circuit_input(6, 6);
+        assert_eq!(input.spv.outputs().len(), 3);
+
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&input.spv.outputs_digest());
+        hasher.update(&[1; 32]);
+        hasher.update(&[2; 32]);
+        hasher.update(&6u32.to_le_bytes());
+        assert_eq!(
+            batch_final_circuit_output(&input),
+            *hasher.finalize().as_bytes()
+        );
+        // the journal tells the verifier how deep the blocks are required to be
+        assert_ne!(
+            batch_final_circuit_output(&batch_final_circuit_input(6, 5)),
+            batch_final_circuit_output(&input)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "SPV blocks are 5 blocks deep, 6 required")]
+    fn test_batch_final_circuit_rejects_shallow_blocks() {
+        batch_final_circuit_output(&batch_final_circuit_input(5, 6));
+    }
+
+    #[test]
+    fn test_batch_spv_rejects_invalid_proofs() {
+        let mut mmr_native = MMRNative::new();
+        let mut mmr_guest = MMRGuest::new();
+        let txs = mainnet_txs();
+        let block = batch_blocks(
+            &[(&txs[0..4], vec![vec![0], vec![0], vec![0], vec![0]])],
+            &mut mmr_native,
+            &mut mmr_guest,
+        )
+        .remove(0);
+        let batch_spv = BatchSPV::new(vec![block.clone()]);
+        assert!(batch_spv.verify(&mmr_guest));
+
+        // empty batches and transactions without selected outputs commit to nothing
+        assert!(!BatchSPV::new(vec![]).verify(&mmr_guest));
+        let mut invalid_batch_spv = batch_spv.clone();
+        invalid_batch_spv.blocks[0].transactions[0].vouts = vec![];
+        assert!(!invalid_batch_spv.verify(&mmr_guest));
+
+        let mut invalid_batch_spv = batch_spv.clone();
+        invalid_batch_spv.blocks[0].transactions[0].vouts = vec![1];
+        assert!(!invalid_batch_spv.verify(&mmr_guest));
+
+        // a transaction with the inclusion proof of another one
+        let mut invalid_batch_spv = batch_spv.clone();
+        invalid_batch_spv.blocks[0].transactions[1].block_inclusion_proof =
+            block.transactions[2].block_inclusion_proof.clone();
+        assert!(!invalid_batch_spv.verify(&mmr_guest));
+
+        // a block that is not in the MMR
+        let mut invalid_batch_spv = batch_spv.clone();
+        invalid_batch_spv.blocks[0].block_header.nonce += 1;
+        assert!(!invalid_batch_spv.verify(&mmr_guest));
+    }
 }
@@ -14,6 +14,7 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use crypto_bigint::{Encoding, U256};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::fmt;
 
 /// Network configuration holder for Bitcoin-specific constants
 #[derive(Debug)]
@@ -23,80 +24,101 @@ pub struct NetworkConstants {
     pub max_target_bytes: [u8; 32],
 }
 
-pub const NETWORK_TYPE: &str = {
-    match option_env!("BITCOIN_NETWORK") {
-        Some(network) if matches!(network.as_bytes(), b"mainnet") => "mainnet",
-        Some(network) if matches!(network.as_bytes(), b"testnet4") => "testnet4",
-        Some(network) if matches!(network.as_bytes(), b"signet") => "signet",
-        Some(network) if matches!(network.as_bytes(), b"regtest") => "regtest",
-        None => "mainnet",
-        _ => panic!("Invalid network type"),
+/// Bitcoin networks supported by the header chain verification.
+#[derive(
+    Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug, BorshDeserialize, BorshSerialize,
+)]
+pub enum BitcoinNetwork {
+    Mainnet,
+    Testnet4,
+    Signet,
+    Regtest,
+}
+
+impl BitcoinNetwork {
+    /// Parses the network names accepted in `BITCOIN_NETWORK`.
+    pub const fn from_name(name: &str) -> Option<Self> {
+        match name.as_bytes() {
+            b"mainnet" => Some(BitcoinNetwork::Mainnet),
+            b"testnet4" => Some(BitcoinNetwork::Testnet4),
+            b"signet" => Some(BitcoinNetwork::Signet),
+            b"regtest" => Some(BitcoinNetwork::Regtest),
+            _ => None,
+        }
     }
-};
 
-// Const evaluation of network type from environment
-const IS_REGTEST: bool = matches!(NETWORK_TYPE.as_bytes(), b"regtest");
-const IS_TESTNET4: bool = matches!(NETWORK_TYPE.as_bytes(), b"testnet4");
-const MINIMUM_WORK_TESTNET: U256 =
-    U256::from_be_hex("0000000000000000000000000000000000000000000000000000000100010001");
+    pub const fn name(self) -> &'static str {
+        match self {
+            BitcoinNetwork::Mainnet => "mainnet",
+            BitcoinNetwork::Testnet4 => "testnet4",
+            BitcoinNetwork::Signet => "signet",
+            BitcoinNetwork::Regtest => "regtest",
+        }
+    }
 
-pub const NETWORK_CONSTANTS: NetworkConstants = {
+    pub const fn constants(self) -> NetworkConstants {
+        match self {
+            BitcoinNetwork::Signet => NetworkConstants {
+                max_bits: 0x1E0377AE,
+                max_target: U256::from_be_hex(
+                    "00000377AE000000000000000000000000000000000000000000000000000000",
+                ),
+                max_target_bytes: [
+                    0, 0, 3, 119, 174, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                    0, 0, 0, 0, 0, 0, 0,
+                ],
+            },
+            BitcoinNetwork::Regtest => NetworkConstants {
+                max_bits: 0x207FFFFF,
+                max_target: U256::from_be_hex(
+                    "7FFFFF0000000000000000000000000000000000000000000000000000000000",
+                ),
+                max_target_bytes: [
+                    127, 255, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                    0, 0, 0, 0, 0, 0, 0, 0,
+                ],
+            },
+            BitcoinNetwork::Mainnet | BitcoinNetwork::Testnet4 => NetworkConstants {
+                max_bits: 0x1D00FFFF,
+                max_target: U256::from_be_hex(
+                    "00000000FFFF0000000000000000000000000000000000000000000000000000",
+                ),
+                max_target_bytes: [
+                    0, 0, 0, 0, 255, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                    0, 0, 0, 0, 0, 0, 0,
+                ],
+            },
+        }
+    }
+
+    const fn is_regtest(self) -> bool {
+        matches!(self, BitcoinNetwork::Regtest)
+    }
+
+    const fn is_testnet4(self) -> bool {
+        matches!(self, BitcoinNetwork::Testnet4)
+    }
+}
+
+/// The network pinned at compile time through `BITCOIN_NETWORK`, defaulting to mainnet. This is
+/// the network the guest verifies; native code can pick any network at runtime instead.
+pub const NETWORK: BitcoinNetwork = {
     match option_env!("BITCOIN_NETWORK") {
-        Some(n) if matches!(n.as_bytes(), b"signet") => NetworkConstants {
-            max_bits: 0x1E0377AE,
-            max_target: U256::from_be_hex(
-                "00000377AE000000000000000000000000000000000000000000000000000000",
-            ),
-            max_target_bytes: [
-                0, 0, 3, 119, 174, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-                0, 0, 0, 0, 0, 0,
-            ],
+        Some(network) => match BitcoinNetwork::from_name(network) {
+            Some(network) => network,
+            None => panic!("Invalid network type"),
         },
-        Some(n) if matches!(n.as_bytes(), b"regtest") => NetworkConstants {
-            max_bits: 0x207FFFFF,
-            max_target: U256::from_be_hex(
-                "7FFFFF0000000000000000000000000000000000000000000000000000000000",
-            ),
-            max_target_bytes: [
-                127, 255, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-                0, 0, 0, 0, 0, 0,
-            ],
-        },
-        Some(n) if matches!(n.as_bytes(), b"testnet4") => NetworkConstants {
-            max_bits: 0x1D00FFFF,
-            max_target: U256::from_be_hex(
-                "00000000FFFF0000000000000000000000000000000000000000000000000000",
-            ),
-            max_target_bytes: [
-                0, 0, 0, 0, 255, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-                0, 0, 0, 0, 0, 0,
-            ],
-        },
-        Some(n) if matches!(n.as_bytes(), b"mainnet") => NetworkConstants {
-            max_bits: 0x1D00FFFF,
-            max_target: U256::from_be_hex(
-                "00000000FFFF0000000000000000000000000000000000000000000000000000",
-            ),
-            max_target_bytes: [
-                0, 0, 0, 0, 255, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-                0, 0, 0, 0, 0, 0,
-            ],
-        },
-        // Default to mainnet for None
-        None => NetworkConstants {
-            max_bits: 0x1D00FFFF,
-            max_target: U256::from_be_hex(
-                "00000000FFFF0000000000000000000000000000000000000000000000000000",
-            ),
-            max_target_bytes: [
-                0, 0, 0, 0, 255, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-                0, 0, 0, 0, 0, 0,
-            ],
-        },
-        _ => panic!("Unsupported network"),
+        None => BitcoinNetwork::Mainnet,
     }
 };
 
+pub const NETWORK_TYPE: &str = NETWORK.name();
+
+const MINIMUM_WORK_TESTNET: U256 =
+    U256::from_be_hex("0000000000000000000000000000000000000000000000000000000100010001");
+
+pub const NETWORK_CONSTANTS: NetworkConstants = NETWORK.constants();
+
 /// An epoch should be two weeks (represented as number of seconds)
 /// seconds/minute * minutes/hour * hours/day * 14 days
 const EXPECTED_EPOCH_TIMESPAN: u32 = 60 * 60 * 24 * 14;
@@ -175,27 +197,69 @@ pub struct ChainState {
 }
 
 impl ChainState {
+    /// Creates the state preceding the genesis block of the compile-time [`NETWORK`].
     pub fn new() -> Self {
+        Self::new_for_network(NETWORK)
+    }
+
+    /// Creates the state preceding the genesis block of `network`.
+    pub fn new_for_network(network: BitcoinNetwork) -> Self {
         ChainState {
             block_height: u32::MAX,
             total_work: [0u8; 32],
             best_block_hash: [0u8; 32],
-            current_target_bits: NETWORK_CONSTANTS.max_bits,
+            current_target_bits: network.constants().max_bits,
             epoch_start_time: 0,
             prev_11_timestamps: [0u32; 11],
             block_hashes_mmr: MMRGuest::new(),
         }
     }
 
+    /// Applies the block headers following the rules of the compile-time [`NETWORK`].
+    /// Panics on the first invalid header.
     pub fn apply_blocks(&mut self, block_headers: Vec<CircuitBlockHeader>) {
-        let mut current_target_bytes = if IS_REGTEST {
-            NETWORK_CONSTANTS.max_target.to_be_bytes()
+        self.apply_blocks_for_network(block_headers, NETWORK);
+    }
+
+    /// Applies the block headers following the rules of `network`, which has to be the network
+    /// the chain state was created for. Panics on the first invalid header.
+    pub fn apply_blocks_for_network(
+        &mut self,
+        block_headers: Vec<CircuitBlockHeader>,
+        network: BitcoinNetwork,
+    ) {
+        if let Err(error) = self.try_apply_blocks_for_network(block_headers, network) {
+            panic!("{}", error);
+        }
+    }
+
+    /// Fallible version of [`ChainState::apply_blocks`].
+    pub fn try_apply_blocks(
+        &mut self,
+        block_headers: Vec<CircuitBlockHeader>,
+    ) -> Result<(), HeaderChainError> {
+        self.try_apply_blocks_for_network(block_headers, NETWORK)
+    }
+
+    /// Fallible version of [`ChainState::apply_blocks_for_network`]. On error, the headers
+    /// preceding the invalid one remain applied.
+    pub fn try_apply_blocks_for_network(
+        &mut self,
+        block_headers: Vec<CircuitBlockHeader>,
+        network: BitcoinNetwork,
+    ) -> Result<(), HeaderChainError> {
+        let network_constants = network.constants();
+        let is_regtest = network.is_regtest();
+        let is_testnet4 = network.is_testnet4();
+
+        let mut current_target_bytes = if is_regtest {
+            network_constants.max_target.to_be_bytes()
         } else {
             bits_to_target(self.current_target_bits)
         };
         let mut current_work: U256 = U256::from_be_bytes(self.total_work);
 
-        let mut last_block_time = if IS_TESTNET4 {
+        let mut last_block_time = if is_testnet4 {
             if self.block_height == u32::MAX {
                 0
             } else {
@@ -208,7 +272,7 @@ impl ChainState {
         for block_header in block_headers {
             self.block_height = self.block_height.wrapping_add(1);
 
-            let (target_to_use, expected_bits, work_to_add) = if IS_TESTNET4 {
+            let (target_to_use, expected_bits, work_to_add) = if is_testnet4 {
                 if block_header.time > last_block_time + 1200 {
                     // If the block is an epoch block, then it still has to have the real target.
                     if self.block_height % BLOCKS_PER_EPOCH == 0 {
@@ -219,8 +283,8 @@ impl ChainState {
                         )
                     } else {
                         (
-                            NETWORK_CONSTANTS.max_target_bytes,
-                            NETWORK_CONSTANTS.max_bits,
+                            network_constants.max_target_bytes,
+                            network_constants.max_bits,
                             MINIMUM_WORK_TESTNET,
                         )
                     }
@@ -241,48 +305,176 @@ impl ChainState {
 
             let new_block_hash = block_header.compute_block_hash();
 
-            assert_eq!(block_header.prev_block_hash, self.best_block_hash);
-
-            if IS_REGTEST {
-                assert_eq!(block_header.bits, NETWORK_CONSTANTS.max_bits);
+            let expected_bits = if is_regtest {
+                network_constants.max_bits
             } else {
-                assert_eq!(block_header.bits, expected_bits);
-            }
-
-            check_hash_valid(&new_block_hash, &target_to_use);
-
-            if !validate_timestamp(block_header.time, self.prev_11_timestamps) {
-                panic!("Timestamp is not valid");
+                expected_bits
+            };
+            if let Err(error) = check_block_header(
+                self.block_height,
+                &block_header,
+                &new_block_hash,
+                &self.best_block_hash,
+                expected_bits,
+                &target_to_use,
+                self.prev_11_timestamps,
+            ) {
+                self.block_height = self.block_height.wrapping_sub(1);
+                self.total_work = current_work.to_be_bytes();
+                return Err(error);
             }
 
             self.block_hashes_mmr.append(new_block_hash);
             self.best_block_hash = new_block_hash;
             current_work = current_work.wrapping_add(&work_to_add);
 
-            if !IS_REGTEST && self.block_height % BLOCKS_PER_EPOCH == 0 {
+            if !is_regtest && self.block_height % BLOCKS_PER_EPOCH == 0 {
                 self.epoch_start_time = block_header.time;
             }
 
             self.prev_11_timestamps[self.block_height as usize % 11] = block_header.time;
 
-            if IS_TESTNET4 {
+            if is_testnet4 {
                 last_block_time = block_header.time;
             }
 
-            if !IS_REGTEST && self.block_height % BLOCKS_PER_EPOCH == BLOCKS_PER_EPOCH - 1 {
+            if !is_regtest && self.block_height % BLOCKS_PER_EPOCH == BLOCKS_PER_EPOCH - 1 {
                 current_target_bytes = calculate_new_difficulty(
                     self.epoch_start_time,
                     block_header.time,
                     self.current_target_bits,
+                    &network_constants,
                 );
                 self.current_target_bits = target_to_bits(&current_target_bytes);
             }
         }
 
         self.total_work = current_work.to_be_bytes();
+
+        Ok(())
     }
 }
 
+/// Reasons for rejecting a block header, with the height the header would have had.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub enum HeaderChainError {
+    /// The header does not build on the current best block.
+    InvalidPrevBlockHash {
+        height: u32,
+        expected: [u8; 32],
+        actual: [u8; 32],
+    },
+    /// The header's bits differ from the required difficulty target.
+    InvalidBits {
+        height: u32,
+        expected: u32,
+        actual: u32,
+    },
+    /// The block hash is above the difficulty target.
+    InsufficientProofOfWork { height: u32, block_hash: [u8; 32] },
+    /// The header's timestamp is not after the median time of the previous 11 blocks.
+    InvalidTimestamp {
+        height: u32,
+        time: u32,
+        median_time_past: u32,
+    },
+}
+
+impl HeaderChainError {
+    pub fn height(&self) -> u32 {
+        match self {
+            HeaderChainError::InvalidPrevBlockHash { height, .. }
+            | HeaderChainError::InvalidBits { height, .. }
+            | HeaderChainError::InsufficientProofOfWork { height, .. }
+            | HeaderChainError::InvalidTimestamp { height, .. } => *height,
+        }
+    }
+}
+
+impl fmt::Display for HeaderChainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeaderChainError::InvalidPrevBlockHash {
+                height,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Previous block hash is not valid at height {}: expected {:?}, got {:?}",
+                height, expected, actual
+            ),
+            HeaderChainError::InvalidBits {
+                height,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Bits are not valid at height {}: expected {:#x}, got {:#x}",
+                height, expected, actual
+            ),
+            HeaderChainError::InsufficientProofOfWork { height, block_hash } => write!(
+                f,
+                "Hash is not valid at height {}: {:?}",
+                height, block_hash
+            ),
+            HeaderChainError::InvalidTimestamp {
+                height,
+                time,
+                median_time_past,
+            } => write!(
+                f,
+                "Timestamp is not valid at height {}: {} is not after median time past {}",
+                height, time, median_time_past
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HeaderChainError {}
+
+fn check_block_header(
+    height: u32,
+    block_header: &CircuitBlockHeader,
+    block_hash: &[u8; 32],
+    best_block_hash: &[u8; 32],
+    expected_bits: u32,
+    target: &[u8; 32],
+    prev_11_timestamps: [u32; 11],
+) -> Result<(), HeaderChainError> {
+    if block_header.prev_block_hash != *best_block_hash {
+        return Err(HeaderChainError::InvalidPrevBlockHash {
+            height,
+            expected: *best_block_hash,
+            actual: block_header.prev_block_hash,
+        });
+    }
+
+    if block_header.bits != expected_bits {
+        return Err(HeaderChainError::InvalidBits {
+            height,
+            expected: expected_bits,
+            actual: block_header.bits,
+        });
+    }
+
+    if !check_hash_valid(block_hash, target) {
+        return Err(HeaderChainError::InsufficientProofOfWork {
+            height,
+            block_hash: *block_hash,
+        });
+    }
+
+    if !validate_timestamp(block_header.time, prev_11_timestamps) {
+        return Err(HeaderChainError::InvalidTimestamp {
+            height,
+            time: block_header.time,
+            median_time_past: median(prev_11_timestamps),
+        });
+    }
+
+    Ok(())
+}
+
 fn median(arr: [u32; 11]) -> u32 {
     let mut sorted_arr = arr;
     sorted_arr.sort_unstable();
@@ -323,6 +515,7 @@ fn calculate_new_difficulty(
     epoch_start_time: u32,
     last_timestamp: u32,
     current_target: u32,
+    network_constants: &NetworkConstants,
 ) -> [u8; 32] {
     let mut actual_timespan = last_timestamp - epoch_start_time;
     if actual_timespan < EXPECTED_EPOCH_TIMESPAN / 4 {
@@ -336,20 +529,21 @@ fn calculate_new_difficulty(
         .wrapping_mul(&U256::from(actual_timespan))
         .wrapping_div(&U256::from(EXPECTED_EPOCH_TIMESPAN));
 
-    if new_target > NETWORK_CONSTANTS.max_target {
-        new_target = NETWORK_CONSTANTS.max_target;
+    if new_target > network_constants.max_target {
+        new_target = network_constants.max_target;
     }
     new_target.to_be_bytes()
 }
 
-fn check_hash_valid(hash: &[u8; 32], target_bytes: &[u8; 32]) {
+pub(crate) fn check_hash_valid(hash: &[u8; 32], target_bytes: &[u8; 32]) -> bool {
     for i in 0..32 {
         if hash[31 - i] < target_bytes[i] {
-            return;
+            return true;
         } else if hash[31 - i] > target_bytes[i] {
-            panic!("Hash is not valid");
+            return false;
         }
     }
+    true
 }
 
 fn calculate_work(target: &[u8; 32]) -> U256 {
@@ -922,7 +1116,6 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Hash is not valid")]
     fn test_hash_check_fail() {
         let block_headers = BLOCK_HEADERS
             .iter()
@@ -934,13 +1127,13 @@ mod tests {
             .map(|header| header.compute_block_hash())
             .collect::<Vec<[u8; 32]>>();
 
-        // The validation is expected to panic
-        check_hash_valid(
+        // The validation is expected to fail
+        assert!(!check_hash_valid(
             &first_15_hashes[0],
             &U256::from_be_hex("00000000FFFF0000000000000000000000000000000000000000000000000000")
                 .wrapping_div(&(U256::ONE << 157))
                 .to_be_bytes(),
-        );
+        ));
     }
 
     #[test]
@@ -956,7 +1149,10 @@ mod tests {
             .collect::<Vec<[u8; 32]>>();
 
         for (i, hash) in first_15_hashes.into_iter().enumerate() {
-            check_hash_valid(&hash, &bits_to_target(block_headers[i].bits));
+            assert!(check_hash_valid(
+                &hash,
+                &bits_to_target(block_headers[i].bits)
+            ));
         }
     }
 
@@ -986,7 +1182,12 @@ mod tests {
     #[test]
     fn test_difficulty_adjustments() {
         for (start_time, end_time, start_target, end_target) in DIFFICULTY_ADJUSTMENTS {
-            let new_target_bytes = calculate_new_difficulty(start_time, end_time, start_target);
+            let new_target_bytes = calculate_new_difficulty(
+                start_time,
+                end_time,
+                start_target,
+                &BitcoinNetwork::Mainnet.constants(),
+            );
             let bits = target_to_bits(&new_target_bytes);
             assert_eq!(bits, end_target);
         }
@@ -1071,4 +1272,243 @@ mod tests {
         assert_eq!(original_header, converted_header);
         assert_eq!(original_header.block_hash(), converted_header.block_hash());
     }
+
+    fn genesis_header(network: BitcoinNetwork) -> CircuitBlockHeader {
+        // Merkle root of the mainnet, signet and regtest genesis coinbase
+        let merkle_root = hex!("3ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4a");
+        let (merkle_root, time, bits, nonce) = match network {
+            BitcoinNetwork::Mainnet => (merkle_root, 1231006505, 0x1d00ffff, 2083236893),
+            BitcoinNetwork::Testnet4 => (
+                hex!("4e7b2b9128fe0291db0693af2ae418b767e657cd407e80cb1434221eaea7a07a"),
+                1714777860,
+                0x1d00ffff,
+                393743547,
+            ),
+            BitcoinNetwork::Signet => (merkle_root, 1598918400, 0x1e0377ae, 52613770),
+            BitcoinNetwork::Regtest => (merkle_root, 1296688602, 0x207fffff, 2),
+        };
+        CircuitBlockHeader {
+            version: 1,
+            prev_block_hash: [0u8; 32],
+            merkle_root,
+            time,
+            bits,
+            nonce,
+        }
+    }
+
+    #[test]
+    fn test_network_names() {
+        for network in [
+            BitcoinNetwork::Mainnet,
+            BitcoinNetwork::Testnet4,
+            BitcoinNetwork::Signet,
+            BitcoinNetwork::Regtest,
+        ] {
+            assert_eq!(BitcoinNetwork::from_name(network.name()), Some(network));
+        }
+        assert_eq!(BitcoinNetwork::from_name("testnet3"), None);
+    }
+
+    #[test]
+    fn test_apply_genesis_block_for_each_network() {
+        for network in [
+            BitcoinNetwork::Mainnet,
+            BitcoinNetwork::Testnet4,
+            BitcoinNetwork::Signet,
+            BitcoinNetwork::Regtest,
+        ] {
+            let genesis_header = genesis_header(network);
+            let mut chain_state = ChainState::new_for_network(network);
+            chain_state.apply_blocks_for_network(vec![genesis_header.clone()], network);
+
+            assert_eq!(chain_state.block_height, 0);
+            assert_eq!(
+                chain_state.best_block_hash,
+                genesis_header.compute_block_hash()
+            );
+            assert_eq!(
+                U256::from_be_bytes(chain_state.total_work),
+                calculate_work(&bits_to_target(genesis_header.bits))
+            );
+        }
+    }
+
+    fn mine_regtest_header(prev_block_hash: [u8; 32], time: u32) -> CircuitBlockHeader {
+        let mut block_header = CircuitBlockHeader {
+            version: 4,
+            prev_block_hash,
+            merkle_root: [0u8; 32],
+            time,
+            bits: BitcoinNetwork::Regtest.constants().max_bits,
+            nonce: 0,
+        };
+        while !check_hash_valid(
+            &block_header.compute_block_hash(),
+            &BitcoinNetwork::Regtest.constants().max_target_bytes,
+        ) {
+            block_header.nonce += 1;
+        }
+        block_header
+    }
+
+    fn mine_regtest_headers(
+        prev_header: &CircuitBlockHeader,
+        count: u32,
+    ) -> Vec<CircuitBlockHeader> {
+        let mut block_headers: Vec<CircuitBlockHeader> = vec![];
+        for i in 1..=count {
+            let prev_block_hash = block_headers
+                .last()
+                .unwrap_or(prev_header)
+                .compute_block_hash();
+            block_headers.push(mine_regtest_header(
+                prev_block_hash,
+                prev_header.time + i * 600,
+            ));
+        }
+        block_headers
+    }
+
+    fn regtest_chain_state() -> (ChainState, CircuitBlockHeader) {
+        let genesis_header = genesis_header(BitcoinNetwork::Regtest);
+        let mut chain_state = ChainState::new_for_network(BitcoinNetwork::Regtest);
+        chain_state
+            .try_apply_blocks_for_network(vec![genesis_header.clone()], BitcoinNetwork::Regtest)
+            .unwrap();
+        (chain_state, genesis_header)
+    }
+
+    #[test]
+    fn test_apply_regtest_blocks() {
+        let (mut chain_state, genesis_header) = regtest_chain_state();
+        let block_headers = mine_regtest_headers(&genesis_header, 20);
+        let best_block_hash = block_headers.last().unwrap().compute_block_hash();
+        chain_state.apply_blocks_for_network(block_headers, BitcoinNetwork::Regtest);
+
+        assert_eq!(chain_state.block_height, 20);
+        assert_eq!(chain_state.best_block_hash, best_block_hash);
+    }
+
+    #[test]
+    fn test_try_apply_blocks_invalid_prev_block_hash() {
+        let (mut chain_state, genesis_header) = regtest_chain_state();
+        let block_headers = mine_regtest_headers(&genesis_header, 2);
+        let expected_state = chain_state.clone();
+
+        let result = chain_state
+            .try_apply_blocks_for_network(block_headers[1..].to_vec(), BitcoinNetwork::Regtest);
+
+        assert_eq!(
+            result,
+            Err(HeaderChainError::InvalidPrevBlockHash {
+                height: 1,
+                expected: genesis_header.compute_block_hash(),
+                actual: block_headers[0].compute_block_hash(),
+            })
+        );
+        assert_eq!(chain_state, expected_state);
+    }
+
+    #[test]
+    fn test_try_apply_blocks_invalid_bits() {
+        let (mut chain_state, genesis_header) = regtest_chain_state();
+        let mut block_headers = mine_regtest_headers(&genesis_header, 3);
+        block_headers[2].bits = 0x1d00ffff;
+
+        let result = chain_state
+            .try_apply_blocks_for_network(block_headers.clone(), BitcoinNetwork::Regtest);
+
+        assert_eq!(
+            result,
+            Err(HeaderChainError::InvalidBits {
+                height: 3,
+                expected: 0x207fffff,
+                actual: 0x1d00ffff,
+            })
+        );
+        // the valid headers preceding the invalid one stay applied
+        assert_eq!(chain_state.block_height, 2);
+        assert_eq!(
+            chain_state.best_block_hash,
+            block_headers[1].compute_block_hash()
+        );
+        assert_eq!(
+            U256::from_be_bytes(chain_state.total_work),
+            calculate_work(&BitcoinNetwork::Regtest.constants().max_target_bytes)
+                .wrapping_mul(&U256::from(3u32))
+        );
+    }
+
+    #[test]
+    fn test_try_apply_blocks_insufficient_proof_of_work() {
+        let (mut chain_state, genesis_header) = regtest_chain_state();
+        let mut block_header = mine_regtest_headers(&genesis_header, 1).remove(0);
+        while check_hash_valid(
+            &block_header.compute_block_hash(),
+            &BitcoinNetwork::Regtest.constants().max_target_bytes,
+        ) {
+            block_header.nonce += 1;
+        }
+
+        let result = chain_state
+            .try_apply_blocks_for_network(vec![block_header.clone()], BitcoinNetwork::Regtest);
+
+        assert_eq!(
+            result,
+            Err(HeaderChainError::InsufficientProofOfWork {
+                height: 1,
+                block_hash: block_header.compute_block_hash(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_try_apply_blocks_invalid_timestamp() {
+        let (mut chain_state, genesis_header) = regtest_chain_state();
+        let block_headers = mine_regtest_headers(&genesis_header, 10);
+        let best_block_hash = block_headers.last().unwrap().compute_block_hash();
+        chain_state
+            .try_apply_blocks_for_network(block_headers, BitcoinNetwork::Regtest)
+            .unwrap();
+        let median_time_past = median(chain_state.prev_11_timestamps);
+
+        let block_header = mine_regtest_header(best_block_hash, median_time_past);
+        let result =
+            chain_state.try_apply_blocks_for_network(vec![block_header], BitcoinNetwork::Regtest);
+
+        assert_eq!(
+            result,
+            Err(HeaderChainError::InvalidTimestamp {
+                height: 11,
+                time: median_time_past,
+                median_time_past,
+            })
+        );
+        assert_eq!(result.unwrap_err().height(), 11);
+    }
+
+    #[test]
+    #[should_panic(expected = "Timestamp is not valid at height 11")]
+    fn test_apply_blocks_panics_on_invalid_header() {
+        let (mut chain_state, genesis_header) = regtest_chain_state();
+        let mut block_headers = mine_regtest_headers(&genesis_header, 11);
+        let last_block_header = block_headers.pop().unwrap();
+        block_headers.push(mine_regtest_header(
+            last_block_header.prev_block_hash,
+            genesis_header.time,
+        ));
+
+        chain_state.apply_blocks_for_network(block_headers, BitcoinNetwork::Regtest);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_apply_regtest_genesis_block_on_mainnet_fails() {
+        let mut chain_state = ChainState::new_for_network(BitcoinNetwork::Mainnet);
+        chain_state.apply_blocks_for_network(
+            vec![genesis_header(BitcoinNetwork::Regtest)],
+            BitcoinNetwork::Mainnet,
+        );
+    }
 }
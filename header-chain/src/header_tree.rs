@@ -0,0 +1,420 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    header_chain::{BitcoinNetwork, ChainState, CircuitBlockHeader, HeaderChainError},
+    mmr_native::MMRNative,
+};
+
+/// A header known to the [`HeaderTree`], with the chain state right after applying it.
+#[derive(Clone, Debug)]
+struct HeaderNode {
+    header: CircuitBlockHeader,
+    parent: Option<usize>,
+    chain_state: ChainState,
+}
+
+/// Reasons for rejecting a header inserted into a [`HeaderTree`].
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub enum HeaderTreeError {
+    /// The header builds on a block that is neither the base of the tree nor in the tree.
+    UnknownParent {
+        block_hash: [u8; 32],
+        prev_block_hash: [u8; 32],
+    },
+    /// The header is not valid on top of its parent.
+    InvalidHeader(HeaderChainError),
+    /// The chain state does not end at the base of the tree or at a header in the tree.
+    UnknownChainState { best_block_hash: [u8; 32] },
+}
+
+impl fmt::Display for HeaderTreeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeaderTreeError::UnknownParent {
+                block_hash,
+                prev_block_hash,
+            } => write!(
+                f,
+                "Parent {:?} of block {:?} is not in the header tree",
+                prev_block_hash, block_hash
+            ),
+            HeaderTreeError::InvalidHeader(error) => write!(f, "{}", error),
+            HeaderTreeError::UnknownChainState { best_block_hash } => write!(
+                f,
+                "Chain state tip {:?} is not in the header tree",
+                best_block_hash
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HeaderTreeError {}
+
+/// The result of moving a chain state to the best tip of a [`HeaderTree`].
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct Reorg {
+    /// Hash of the last block shared by the old and the new chain.
+    pub common_ancestor: [u8; 32],
+    /// Hashes of the blocks removed from the old chain, from the tip down to the common ancestor.
+    pub disconnected: Vec<[u8; 32]>,
+    /// Headers of the new chain following the common ancestor, in chain order. These are the
+    /// headers to prove on top of a proof ending at the common ancestor.
+    pub connected: Vec<CircuitBlockHeader>,
+}
+
+/// Native tracker of competing branches on top of a base chain state.
+///
+/// Every header is validated against its own parent, so a branch can fork off at any header in
+/// the tree. The best tip is the one with the most cumulative work; among tips with equal work,
+/// the first one seen wins, like in Bitcoin Core. [`HeaderTree::reorg`] moves a [`ChainState`]
+/// and its [`MMRNative`] from any header in the tree to the best tip.
+#[derive(Clone, Debug)]
+pub struct HeaderTree {
+    network: BitcoinNetwork,
+    base: ChainState,
+    nodes: Vec<HeaderNode>,
+    indices: HashMap<[u8; 32], usize>,
+    best_tip: Option<usize>,
+}
+
+impl HeaderTree {
+    /// Creates a tree whose branches all extend `base`, which follows the rules of `network`.
+    pub fn new(base: ChainState, network: BitcoinNetwork) -> Self {
+        HeaderTree {
+            network,
+            base,
+            nodes: vec![],
+            indices: HashMap::new(),
+            best_tip: None,
+        }
+    }
+
+    /// Returns the chain state all branches extend.
+    pub fn base(&self) -> &ChainState {
+        &self.base
+    }
+
+    /// Returns the number of headers in the tree.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn contains(&self, block_hash: &[u8; 32]) -> bool {
+        self.indices.contains_key(block_hash)
+    }
+
+    /// Returns the chain state after applying the block with the given hash.
+    pub fn chain_state(&self, block_hash: &[u8; 32]) -> Option<&ChainState> {
+        if *block_hash == self.base.best_block_hash {
+            return Some(&self.base);
+        }
+        self.indices
+            .get(block_hash)
+            .map(|index| &self.nodes[*index].chain_state)
+    }
+
+    /// Returns the chain state of the tip with the most cumulative work.
+    pub fn best_chain_state(&self) -> &ChainState {
+        match self.best_tip {
+            Some(index) => &self.nodes[index].chain_state,
+            None => &self.base,
+        }
+    }
+
+    /// Validates the header against its parent and adds it to the tree. Returns whether it
+    /// became the new best tip. Inserting a header that is already in the tree does nothing.
+    pub fn insert(&mut self, header: CircuitBlockHeader) -> Result<bool, HeaderTreeError> {
+        let block_hash = header.compute_block_hash();
+        if self.contains(&block_hash) {
+            return Ok(false);
+        }
+
+        let parent = self.indices.get(&header.prev_block_hash).copied();
+        let mut chain_state = match self.chain_state(&header.prev_block_hash) {
+            Some(chain_state) => chain_state.clone(),
+            None => {
+                return Err(HeaderTreeError::UnknownParent {
+                    block_hash,
+                    prev_block_hash: header.prev_block_hash,
+                })
+            }
+        };
+        chain_state
+            .try_apply_blocks_for_network(vec![header.clone()], self.network)
+            .map_err(HeaderTreeError::InvalidHeader)?;
+
+        let index = self.nodes.len();
+        let is_best = chain_state.total_work > self.best_chain_state().total_work;
+        self.nodes.push(HeaderNode {
+            header,
+            parent,
+            chain_state,
+        });
+        self.indices.insert(block_hash, index);
+        if is_best {
+            self.best_tip = Some(index);
+        }
+
+        Ok(is_best)
+    }
+
+    /// Inserts the headers in order, stopping at the first invalid one.
+    pub fn insert_all(
+        &mut self,
+        headers: impl IntoIterator<Item = CircuitBlockHeader>,
+    ) -> Result<(), HeaderTreeError> {
+        for header in headers {
+            self.insert(header)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the indices of the headers from the base (exclusive) to the block with the given
+    /// hash (inclusive).
+    fn branch(&self, block_hash: &[u8; 32]) -> Result<Vec<usize>, HeaderTreeError> {
+        if *block_hash == self.base.best_block_hash {
+            return Ok(vec![]);
+        }
+        let mut index =
+            self.indices
+                .get(block_hash)
+                .copied()
+                .ok_or(HeaderTreeError::UnknownChainState {
+                    best_block_hash: *block_hash,
+                })?;
+        let mut branch = vec![index];
+        while let Some(parent) = self.nodes[index].parent {
+            branch.push(parent);
+            index = parent;
+        }
+        branch.reverse();
+        Ok(branch)
+    }
+
+    /// Moves `chain_state` and `mmr`, which hold the block hashes of the chain ending at
+    /// `chain_state`'s tip, to the best tip of the tree. Both are rewound to the last block the
+    /// two chains share, then the winning branch is applied on top of it.
+    pub fn reorg(
+        &self,
+        chain_state: &mut ChainState,
+        mmr: &mut MMRNative,
+    ) -> Result<Reorg, HeaderTreeError> {
+        let old_branch = self.branch(&chain_state.best_block_hash)?;
+        let new_branch = match self.best_tip {
+            Some(index) => self.branch(&self.nodes[index].header.compute_block_hash())?,
+            None => vec![],
+        };
+
+        let shared = old_branch
+            .iter()
+            .zip(new_branch.iter())
+            .take_while(|(old, new)| old == new)
+            .count();
+        let ancestor_state = match shared {
+            0 => &self.base,
+            shared => &self.nodes[new_branch[shared - 1]].chain_state,
+        };
+
+        let disconnected = old_branch[shared..]
+            .iter()
+            .rev()
+            .map(|index| self.nodes[*index].chain_state.best_block_hash)
+            .collect();
+        let connected: Vec<CircuitBlockHeader> = new_branch[shared..]
+            .iter()
+            .map(|index| self.nodes[*index].header.clone())
+            .collect();
+
+        *chain_state = ancestor_state.clone();
+        mmr.rewind(mmr.leaf_count() - old_branch[shared..].len() as u32);
+        chain_state
+            .try_apply_blocks_for_network(connected.clone(), self.network)
+            .map_err(HeaderTreeError::InvalidHeader)?;
+        for header in connected.iter() {
+            mmr.append(header.compute_block_hash());
+        }
+
+        Ok(Reorg {
+            common_ancestor: ancestor_state.best_block_hash,
+            disconnected,
+            connected,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::header_chain::check_hash_valid;
+
+    use super::*;
+
+    fn mine_regtest_header(prev_block_hash: [u8; 32], time: u32) -> CircuitBlockHeader {
+        let mut block_header = CircuitBlockHeader {
+            version: 4,
+            prev_block_hash,
+            merkle_root: [0u8; 32],
+            time,
+            bits: BitcoinNetwork::Regtest.constants().max_bits,
+            nonce: 0,
+        };
+        while !check_hash_valid(
+            &block_header.compute_block_hash(),
+            &BitcoinNetwork::Regtest.constants().max_target_bytes,
+        ) {
+            block_header.nonce += 1;
+        }
+        block_header
+    }
+
+    // Mines `count` headers on top of `prev_block_hash`; `time` makes branches distinct.
+    fn mine_branch(prev_block_hash: [u8; 32], time: u32, count: u32) -> Vec<CircuitBlockHeader> {
+        let mut block_headers: Vec<CircuitBlockHeader> = vec![];
+        for i in 0..count {
+            let prev_block_hash = block_headers
+                .last()
+                .map(|block_header| block_header.compute_block_hash())
+                .unwrap_or(prev_block_hash);
+            block_headers.push(mine_regtest_header(prev_block_hash, time + i * 600));
+        }
+        block_headers
+    }
+
+    fn hashes(block_headers: &[CircuitBlockHeader]) -> Vec<[u8; 32]> {
+        block_headers
+            .iter()
+            .map(|block_header| block_header.compute_block_hash())
+            .collect()
+    }
+
+    // Builds a tree on top of a regtest chain of 6 blocks, with the state and MMR of that chain.
+    fn regtest_tree() -> (HeaderTree, ChainState, MMRNative) {
+        let block_headers = mine_branch([0u8; 32], 1296688602, 6);
+        let mut chain_state = ChainState::new_for_network(BitcoinNetwork::Regtest);
+        chain_state.apply_blocks_for_network(block_headers.clone(), BitcoinNetwork::Regtest);
+        let mut mmr = MMRNative::new();
+        for block_hash in hashes(&block_headers) {
+            mmr.append(block_hash);
+        }
+        (
+            HeaderTree::new(chain_state.clone(), BitcoinNetwork::Regtest),
+            chain_state,
+            mmr,
+        )
+    }
+
+    #[test]
+    fn test_header_tree_picks_most_work_tip() {
+        let (mut tree, base, _) = regtest_tree();
+        let branch_a = mine_branch(base.best_block_hash, 1296700000, 3);
+        let branch_b = mine_branch(base.best_block_hash, 1296800000, 4);
+
+        for block_header in branch_a.iter() {
+            assert!(tree.insert(block_header.clone()).unwrap());
+        }
+        // equal work does not replace the first seen tip
+        for block_header in branch_b[..3].iter() {
+            assert!(!tree.insert(block_header.clone()).unwrap());
+        }
+        assert_eq!(
+            tree.best_chain_state().best_block_hash,
+            branch_a[2].compute_block_hash()
+        );
+
+        assert!(tree.insert(branch_b[3].clone()).unwrap());
+        assert!(!tree.insert(branch_b[3].clone()).unwrap());
+        assert_eq!(tree.len(), 7);
+        assert_eq!(tree.best_chain_state().block_height, base.block_height + 4);
+        assert_eq!(
+            tree.best_chain_state().best_block_hash,
+            branch_b[3].compute_block_hash()
+        );
+    }
+
+    #[test]
+    fn test_header_tree_rejects_unknown_parent_and_invalid_header() {
+        let (mut tree, base, _) = regtest_tree();
+        let branch = mine_branch(base.best_block_hash, 1296700000, 2);
+
+        assert_eq!(
+            tree.insert(branch[1].clone()),
+            Err(HeaderTreeError::UnknownParent {
+                block_hash: branch[1].compute_block_hash(),
+                prev_block_hash: branch[0].compute_block_hash(),
+            })
+        );
+
+        let mut invalid_header = branch[0].clone();
+        invalid_header.bits = 0x1d00ffff;
+        assert!(matches!(
+            tree.insert(invalid_header),
+            Err(HeaderTreeError::InvalidHeader(
+                HeaderChainError::InvalidBits { height: 6, .. }
+            ))
+        ));
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn test_header_tree_reorg() {
+        let (mut tree, base, _) = regtest_tree();
+        let branch_a = mine_branch(base.best_block_hash, 1296700000, 3);
+        tree.insert_all(branch_a.clone()).unwrap();
+
+        // a chain state and MMR following branch a
+        let mut chain_state = tree.best_chain_state().clone();
+        let (_, _, mut mmr) = regtest_tree();
+        for block_hash in hashes(&branch_a) {
+            mmr.append(block_hash);
+        }
+
+        // branch b forks off after the first block of branch a and has more work
+        let branch_b = mine_branch(branch_a[0].compute_block_hash(), 1296800000, 3);
+        tree.insert_all(branch_b.clone()).unwrap();
+
+        let reorg = tree.reorg(&mut chain_state, &mut mmr).unwrap();
+
+        assert_eq!(reorg.common_ancestor, branch_a[0].compute_block_hash());
+        assert_eq!(
+            reorg.disconnected,
+            vec![
+                branch_a[2].compute_block_hash(),
+                branch_a[1].compute_block_hash()
+            ]
+        );
+        assert_eq!(reorg.connected, branch_b);
+        assert_eq!(&chain_state, tree.best_chain_state());
+
+        let mut expected_mmr = regtest_tree().2;
+        for block_hash in hashes(&branch_a[..1]).into_iter().chain(hashes(&branch_b)) {
+            expected_mmr.append(block_hash);
+        }
+        assert_eq!(mmr, expected_mmr);
+        for index in 0..mmr.leaf_count() {
+            let (leaf, mmr_proof) = mmr.generate_proof(index);
+            assert!(chain_state.block_hashes_mmr.verify_proof(leaf, &mmr_proof));
+        }
+
+        // the best tip is already followed
+        let reorg = tree.reorg(&mut chain_state, &mut mmr).unwrap();
+        assert_eq!(reorg.common_ancestor, branch_b[2].compute_block_hash());
+        assert!(reorg.disconnected.is_empty());
+        assert!(reorg.connected.is_empty());
+    }
+
+    #[test]
+    fn test_header_tree_reorg_unknown_chain_state() {
+        let (tree, _, mut mmr) = regtest_tree();
+        let mut chain_state = ChainState::new_for_network(BitcoinNetwork::Regtest);
+
+        assert_eq!(
+            tree.reorg(&mut chain_state, &mut mmr),
+            Err(HeaderTreeError::UnknownChainState {
+                best_block_hash: [0u8; 32]
+            })
+        );
+    }
+}
@@ -4,8 +4,10 @@ use header_chain::{
 use zkvm::ZkvmGuest;
 
 pub mod header_chain;
+pub mod header_tree;
 pub mod mmr_guest;
 pub mod mmr_native;
+pub mod mmr_store;
 pub mod utils;
 pub mod zkvm;
 pub use risc0_zkvm;
@@ -49,4 +49,29 @@ impl MMRGuest {
         println!("GUEST: subroots: {:?}", self.subroots);
         self.subroots.get(mmr_proof.subroot_idx) == Some(&current_hash)
     }
+
+    /// Returns the index of the leaf an inclusion proof is for, or `None` if the proof does not
+    /// fit the shape of the MMR. The proof itself is not checked, see [`MMRGuest::verify_proof`].
+    pub fn leaf_index(&self, mmr_proof: &MMRInclusionProof) -> Option<u32> {
+        // Subtrees are ordered from the largest to the smallest, one for each bit set in the size.
+        let mut subtree_start = 0;
+        let mut subroot_idx = 0;
+        for height in (0..32).rev() {
+            let subtree_size = 1u32 << height;
+            if self.size & subtree_size == 0 {
+                continue;
+            }
+            if subroot_idx == mmr_proof.subroot_idx {
+                if mmr_proof.inclusion_proof.len() != height
+                    || mmr_proof.internal_idx >= subtree_size
+                {
+                    return None;
+                }
+                return Some(subtree_start + mmr_proof.internal_idx);
+            }
+            subtree_start += subtree_size;
+            subroot_idx += 1;
+        }
+        None
+    }
 }
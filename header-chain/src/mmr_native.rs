@@ -23,6 +23,26 @@ impl MMRNative {
         self.recalculate_peaks();
     }
 
+    /// Returns the number of leaves in the MMR.
+    pub fn leaf_count(&self) -> u32 {
+        self.nodes[0].len() as u32
+    }
+
+    /// Removes the leaves after the first `leaf_count` ones, leaving the MMR as it was when it
+    /// held `leaf_count` leaves.
+    pub fn rewind(&mut self, leaf_count: u32) {
+        if leaf_count > self.leaf_count() {
+            panic!("Cannot rewind to a larger MMR");
+        }
+        // Level `i` holds a node for every complete subtree of 2^i leaves.
+        for (level, nodes) in self.nodes.iter_mut().enumerate() {
+            nodes.truncate((leaf_count >> level) as usize);
+        }
+        while self.nodes.len() > 1 && self.nodes[self.nodes.len() - 1].is_empty() {
+            self.nodes.pop();
+        }
+    }
+
     /// Recalculates peaks based on the current leaves.
     fn recalculate_peaks(&mut self) {
         let depth = self.nodes.len();
@@ -181,6 +201,54 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_mmr_native_rewind() {
+        for size in 0..42u32 {
+            let mut mmr = MMRNative::new();
+            for i in 0..size {
+                mmr.append([i as u8; 32]);
+            }
+            let expected = mmr.clone();
+
+            for i in size..42 {
+                mmr.append([i as u8 + 100; 32]);
+            }
+            mmr.rewind(size);
+
+            assert_eq!(mmr, expected, "Rewinding to {} leaves failed", size);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot rewind to a larger MMR")]
+    fn test_mmr_native_rewind_fail() {
+        let mut mmr = MMRNative::new();
+        mmr.append([0; 32]);
+        mmr.rewind(2);
+    }
+
+    #[test]
+    fn test_mmr_guest_leaf_index_rejects_malformed_proof() {
+        let mut mmr_native = MMRNative::new();
+        let mut mmr_guest = MMRGuest::new();
+        for i in 0..7 {
+            mmr_native.append([i as u8; 32]);
+            mmr_guest.append([i as u8; 32]);
+        }
+
+        let (_, mut mmr_proof) = mmr_native.generate_proof(5);
+        mmr_proof.subroot_idx = 3;
+        assert_eq!(mmr_guest.leaf_index(&mmr_proof), None);
+
+        let (_, mut mmr_proof) = mmr_native.generate_proof(5);
+        mmr_proof.inclusion_proof.push([0; 32]);
+        assert_eq!(mmr_guest.leaf_index(&mmr_proof), None);
+
+        let (_, mut mmr_proof) = mmr_native.generate_proof(5);
+        mmr_proof.internal_idx = 2;
+        assert_eq!(mmr_guest.leaf_index(&mmr_proof), None);
+    }
+
     #[test]
     fn test_mmr_crosscheck() {
         let mut mmr_native = MMRNative::new();
@@ -214,6 +282,7 @@ mod tests {
                     "Failed to verify proof for leaf {} in guest MMR",
                     j
                 );
+                assert_eq!(mmr_guest.leaf_index(&mmr_proof), Some(j));
             }
         }
     }
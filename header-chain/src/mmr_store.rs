@@ -0,0 +1,323 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+use borsh::BorshDeserialize;
+
+use crate::mmr_native::{MMRInclusionProof, MMRNative};
+
+const LEAVES_FILE: &str = "leaves.bin";
+const SNAPSHOT_FILE: &str = "snapshot.borsh";
+const LEAF_SIZE: u64 = 32;
+
+/// Append-only, on-disk store of an [`MMRNative`] for long-running services.
+///
+/// The store is a directory with two files:
+/// - `leaves.bin`, the log of all leaves (32 bytes each) in append order. Every append is
+///   written here before the in-memory MMR is updated.
+/// - `snapshot.borsh`, an optional borsh encoded [`MMRNative`] holding the first leaves of the
+///   log. Reopening the store loads the snapshot, checks its leaves against the start of the log
+///   and replays only the leaves after it.
+///
+/// Proofs are generated from the in-memory nodes in O(log n) and are the same as the ones of an
+/// [`MMRNative`] built from the same leaves, so they verify against
+/// [`crate::mmr_guest::MMRGuest::verify_proof`].
+#[derive(Debug)]
+pub struct MMRStore {
+    path: PathBuf,
+    leaves: File,
+    mmr: MMRNative,
+    // number of leaves in the snapshot on disk, 0 if there is none
+    snapshot_leaf_count: u32,
+}
+
+impl MMRStore {
+    /// Opens the store in the directory `path`, creating it if it does not exist.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        fs::create_dir_all(&path)?;
+
+        let mut leaves = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path.join(LEAVES_FILE))?;
+        // A crash in the middle of an append leaves a partial leaf at the end of the log.
+        let leaf_count = leaves.metadata()?.len() / LEAF_SIZE;
+        leaves.set_len(leaf_count * LEAF_SIZE)?;
+
+        let mut mmr = match fs::read(path.join(SNAPSHOT_FILE)) {
+            Ok(bytes) => MMRNative::try_from_slice(&bytes)?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => MMRNative::new(),
+            Err(error) => return Err(error),
+        };
+        if mmr.leaf_count() as u64 > leaf_count {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "snapshot holds {} leaves but the log only {}",
+                    mmr.leaf_count(),
+                    leaf_count
+                ),
+            ));
+        }
+
+        leaves.seek(SeekFrom::Start(0))?;
+        let mut bytes = vec![];
+        leaves.read_to_end(&mut bytes)?;
+        let mut log = bytes.chunks_exact(LEAF_SIZE as usize);
+        // The snapshot must be a prefix of the log, e.g. not a leftover of a rewound store.
+        if let Some(index) = mmr.nodes[0]
+            .iter()
+            .zip(log.by_ref())
+            .position(|(snapshot_leaf, log_leaf)| snapshot_leaf != log_leaf)
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("snapshot leaf {} differs from the log", index),
+            ));
+        }
+        let snapshot_leaf_count = mmr.leaf_count();
+        for leaf in log {
+            mmr.append(leaf.try_into().unwrap());
+        }
+
+        Ok(MMRStore {
+            path,
+            leaves,
+            mmr,
+            snapshot_leaf_count,
+        })
+    }
+
+    /// Returns the number of leaves in the store.
+    pub fn leaf_count(&self) -> u32 {
+        self.mmr.leaf_count()
+    }
+
+    /// Returns the in-memory MMR, e.g. to serialize it with serde.
+    pub fn mmr(&self) -> &MMRNative {
+        &self.mmr
+    }
+
+    /// Appends a leaf, persisting it before it is added to the MMR.
+    pub fn append(&mut self, leaf: [u8; 32]) -> io::Result<()> {
+        self.leaves.write_all(&leaf)?;
+        self.leaves.sync_data()?;
+        self.mmr.append(leaf);
+        Ok(())
+    }
+
+    /// Removes the leaves after the first `leaf_count` ones, e.g. the blocks of a reorged chain,
+    /// from the log and the MMR. A snapshot holding removed leaves is deleted first, so it is
+    /// never loaded with a shorter log.
+    pub fn rewind(&mut self, leaf_count: u32) -> io::Result<()> {
+        if leaf_count > self.leaf_count() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "cannot rewind {} leaves to {}",
+                    self.leaf_count(),
+                    leaf_count
+                ),
+            ));
+        }
+        if self.snapshot_leaf_count > leaf_count {
+            fs::remove_file(self.path.join(SNAPSHOT_FILE))?;
+            self.snapshot_leaf_count = 0;
+        }
+        self.leaves.set_len(leaf_count as u64 * LEAF_SIZE)?;
+        self.leaves.sync_data()?;
+        self.mmr.rewind(leaf_count);
+        Ok(())
+    }
+
+    /// Writes a snapshot of the current MMR, so reopening the store does not replay the log up
+    /// to this point. The previous snapshot is replaced atomically.
+    pub fn snapshot(&mut self) -> io::Result<()> {
+        let tmp_path = self.path.join(format!("{}.tmp", SNAPSHOT_FILE));
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(&borsh::to_vec(&self.mmr)?)?;
+        file.sync_all()?;
+        fs::rename(tmp_path, self.path.join(SNAPSHOT_FILE))?;
+        self.snapshot_leaf_count = self.mmr.leaf_count();
+        Ok(())
+    }
+
+    /// Returns the leaf at `index` (the block height, for an MMR of block hashes starting at
+    /// genesis) and its inclusion proof, or `None` if there is no such leaf.
+    pub fn generate_proof(&self, index: u32) -> Option<([u8; 32], MMRInclusionProof)> {
+        if index >= self.leaf_count() {
+            return None;
+        }
+        Some(self.mmr.generate_proof(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mmr_guest::MMRGuest;
+
+    use super::*;
+
+    fn store_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "header-chain-mmr-store-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&path);
+        path
+    }
+
+    #[test]
+    fn test_mmr_store_reopen_and_extend() {
+        let path = store_path("reopen");
+        let mut mmr_guest = MMRGuest::new();
+
+        let mut store = MMRStore::open(&path).unwrap();
+        for i in 0..20 {
+            store.append([i as u8; 32]).unwrap();
+            mmr_guest.append([i as u8; 32]);
+        }
+        store.snapshot().unwrap();
+        for i in 20..30 {
+            store.append([i as u8; 32]).unwrap();
+            mmr_guest.append([i as u8; 32]);
+        }
+        let expected = store.mmr().clone();
+        drop(store);
+
+        let mut store = MMRStore::open(&path).unwrap();
+        assert_eq!(store.mmr(), &expected);
+        for i in 30..42 {
+            store.append([i as u8; 32]).unwrap();
+            mmr_guest.append([i as u8; 32]);
+        }
+
+        assert_eq!(store.leaf_count(), 42);
+        for i in 0..42 {
+            let (leaf, mmr_proof) = store.generate_proof(i).unwrap();
+            assert_eq!(leaf, [i as u8; 32]);
+            assert!(mmr_guest.verify_proof(leaf, &mmr_proof));
+        }
+        assert!(store.generate_proof(42).is_none());
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn test_mmr_store_matches_native() {
+        let path = store_path("native");
+        let mut store = MMRStore::open(&path).unwrap();
+        let mut mmr_native = MMRNative::new();
+        for i in 0..17 {
+            store.append([i as u8; 32]).unwrap();
+            mmr_native.append([i as u8; 32]);
+        }
+
+        for i in 0..17 {
+            assert_eq!(store.generate_proof(i), Some(mmr_native.generate_proof(i)));
+        }
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn test_mmr_store_ignores_partial_leaf() {
+        let path = store_path("partial");
+        let mut store = MMRStore::open(&path).unwrap();
+        for i in 0..3 {
+            store.append([i as u8; 32]).unwrap();
+        }
+        drop(store);
+
+        let mut leaves = OpenOptions::new()
+            .append(true)
+            .open(path.join(LEAVES_FILE))
+            .unwrap();
+        leaves.write_all(&[0xff; 10]).unwrap();
+        drop(leaves);
+
+        let mut store = MMRStore::open(&path).unwrap();
+        assert_eq!(store.leaf_count(), 3);
+        store.append([3; 32]).unwrap();
+        drop(store);
+
+        let store = MMRStore::open(&path).unwrap();
+        assert_eq!(store.generate_proof(3).unwrap().0, [3; 32]);
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn test_mmr_store_rejects_snapshot_ahead_of_log() {
+        let path = store_path("ahead");
+        let mut store = MMRStore::open(&path).unwrap();
+        store.append([0; 32]).unwrap();
+        store.snapshot().unwrap();
+        drop(store);
+        File::create(path.join(LEAVES_FILE)).unwrap();
+
+        let error = MMRStore::open(&path).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn test_mmr_store_rejects_snapshot_not_prefix_of_log() {
+        let path = store_path("prefix");
+        let mut store = MMRStore::open(&path).unwrap();
+        for i in 0..4 {
+            store.append([i as u8; 32]).unwrap();
+        }
+        store.snapshot().unwrap();
+        drop(store);
+        let mut leaves = File::create(path.join(LEAVES_FILE)).unwrap();
+        for i in 0..5 {
+            leaves.write_all(&[i as u8 + 1; 32]).unwrap();
+        }
+        drop(leaves);
+
+        let error = MMRStore::open(&path).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn test_mmr_store_rewind() {
+        let path = store_path("rewind");
+        let mut store = MMRStore::open(&path).unwrap();
+        let mut mmr_native = MMRNative::new();
+        for i in 0..10 {
+            store.append([i as u8; 32]).unwrap();
+        }
+        store.snapshot().unwrap();
+        assert!(store.rewind(11).is_err());
+
+        // the reorged leaves are replaced by other ones, the snapshot holding them is dropped
+        store.rewind(6).unwrap();
+        for i in 0..6 {
+            mmr_native.append([i as u8; 32]);
+        }
+        assert_eq!(store.mmr(), &mmr_native);
+        for i in 6..9 {
+            store.append([i as u8 + 100; 32]).unwrap();
+            mmr_native.append([i as u8 + 100; 32]);
+        }
+        assert!(!path.join(SNAPSHOT_FILE).exists());
+        drop(store);
+
+        let store = MMRStore::open(&path).unwrap();
+        assert_eq!(store.mmr(), &mmr_native);
+        for i in 0..9 {
+            assert_eq!(store.generate_proof(i), Some(mmr_native.generate_proof(i)));
+        }
+
+        fs::remove_dir_all(&path).unwrap();
+    }
+}
@@ -0,0 +1,404 @@
+use std::str::FromStr;
+
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_crypto_primitives::snark::SNARK;
+use ark_ff::{PrimeField, Zero};
+use ark_groth16::{Groth16, Proof, VerifyingKey};
+use bitvm::{
+    chunk::api::type_conversion_utils::RawProof,
+    groth16::risc0::{decode_risc0_seal, encode_risc0_seal, risc0_public_input},
+};
+use num_bigint::BigUint;
+use risc0_groth16::Seal;
+use serde::{Deserialize, Serialize};
+
+use crate::calculate_succinct_output_prefix;
+
+/// Output of `docker::stark_to_succinct` for a final-spv receipt, together with its journal.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Risc0Groth16Receipt {
+    pub seal: Seal,
+    pub journal: Vec<u8>,
+}
+
+/// Verifying key of the risc0 to BitVM Groth16 circuit in the snarkjs `verification_key.json`
+/// format: decimal coordinates, projective with z = 1, and Fq2 elements as [c0, c1].
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct VerifyingKeyJson {
+    pub protocol: String,
+    pub curve: String,
+    pub nPublic: usize,
+    pub vk_alpha_1: Vec<String>,
+    pub vk_beta_2: Vec<Vec<String>>,
+    pub vk_gamma_2: Vec<Vec<String>>,
+    pub vk_delta_2: Vec<Vec<String>>,
+    pub IC: Vec<Vec<String>>,
+}
+
+impl TryFrom<&VerifyingKeyJson> for VerifyingKey<Bn254> {
+    type Error = String;
+
+    fn try_from(json: &VerifyingKeyJson) -> Result<Self, Self::Error> {
+        if json.protocol != "groth16" || json.curve != "bn128" {
+            return Err(format!(
+                "Unsupported verifying key: {} on {}",
+                json.protocol, json.curve
+            ));
+        }
+        if json.IC.len() != json.nPublic + 1 {
+            return Err("Verifying key must have a base for every public input".to_string());
+        }
+
+        Ok(VerifyingKey {
+            alpha_g1: g1_from_json(&json.vk_alpha_1)?,
+            beta_g2: g2_from_json(&json.vk_beta_2)?,
+            gamma_g2: g2_from_json(&json.vk_gamma_2)?,
+            delta_g2: g2_from_json(&json.vk_delta_2)?,
+            gamma_abc_g1: json
+                .IC
+                .iter()
+                .map(|point| g1_from_json(point))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl From<&VerifyingKey<Bn254>> for VerifyingKeyJson {
+    fn from(vk: &VerifyingKey<Bn254>) -> Self {
+        VerifyingKeyJson {
+            protocol: "groth16".to_string(),
+            curve: "bn128".to_string(),
+            nPublic: vk.gamma_abc_g1.len() - 1,
+            vk_alpha_1: g1_to_json(&vk.alpha_g1),
+            vk_beta_2: g2_to_json(&vk.beta_g2),
+            vk_gamma_2: g2_to_json(&vk.gamma_g2),
+            vk_delta_2: g2_to_json(&vk.delta_g2),
+            IC: vk.gamma_abc_g1.iter().map(g1_to_json).collect(),
+        }
+    }
+}
+
+pub fn verifying_key_from_json(json: &str) -> Result<VerifyingKey<Bn254>, String> {
+    let vk_json: VerifyingKeyJson =
+        serde_json::from_str(json).map_err(|e| format!("Invalid verifying key json: {e}"))?;
+    VerifyingKey::try_from(&vk_json)
+}
+
+/// Groth16 proof in the snarkjs `proof.json` format, with the same encoding as `VerifyingKeyJson`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProofJson {
+    pub pi_a: Vec<String>,
+    pub pi_b: Vec<Vec<String>>,
+    pub pi_c: Vec<String>,
+    pub protocol: String,
+    pub curve: String,
+}
+
+impl TryFrom<&ProofJson> for Proof<Bn254> {
+    type Error = String;
+
+    fn try_from(json: &ProofJson) -> Result<Self, Self::Error> {
+        if json.protocol != "groth16" || json.curve != "bn128" {
+            return Err(format!(
+                "Unsupported proof: {} on {}",
+                json.protocol, json.curve
+            ));
+        }
+
+        Ok(Proof {
+            a: g1_from_json(&json.pi_a)?,
+            b: g2_from_json(&json.pi_b)?,
+            c: g1_from_json(&json.pi_c)?,
+        })
+    }
+}
+
+pub fn proof_from_json(json: &str) -> Result<Proof<Bn254>, String> {
+    let proof_json: ProofJson =
+        serde_json::from_str(json).map_err(|e| format!("Invalid proof json: {e}"))?;
+    Proof::try_from(&proof_json)
+}
+
+/// The single public input of the risc0 to BitVM Groth16 circuit for the final-spv guest, see
+/// `risc0_public_input`. The constants digest is
+/// Sha256(control_root, pre_state_digest, post_state_digest, id_bn254_fr).
+pub fn final_spv_public_input(method_id: &[u8; 32], journal: &[u8]) -> Fr {
+    risc0_public_input(&calculate_succinct_output_prefix(method_id), journal)
+}
+
+/// Converts a risc0 `Seal` to an arkworks proof, through the byte encoding the bridge reads
+/// proofs in (see `decode_risc0_seal`).
+pub fn proof_from_seal(seal: &Seal) -> Result<Proof<Bn254>, String> {
+    if seal.a.len() != 2
+        || seal.b.len() != 2
+        || seal.b.iter().any(|b| b.len() != 2)
+        || seal.c.len() != 2
+    {
+        return Err("Seal must contain the affine coordinates of A, B and C".to_string());
+    }
+    let coordinates = [
+        &seal.a[0],
+        &seal.a[1],
+        &seal.b[0][0],
+        &seal.b[0][1],
+        &seal.b[1][0],
+        &seal.b[1][1],
+        &seal.c[0],
+        &seal.c[1],
+    ];
+    if coordinates.iter().any(|coordinate| coordinate.len() != 32) {
+        return Err("Seal coordinates must be 32 bytes long".to_string());
+    }
+
+    decode_risc0_seal(&coordinates.concat())
+}
+
+/// Inverse of `proof_from_seal`.
+pub fn seal_from_proof(proof: &Proof<Bn254>) -> Seal {
+    let coordinates: Vec<Vec<u8>> = encode_risc0_seal(proof)
+        .chunks(32)
+        .map(|coordinate| coordinate.to_vec())
+        .collect();
+    Seal {
+        a: coordinates[0..2].to_vec(),
+        b: vec![coordinates[2..4].to_vec(), coordinates[4..6].to_vec()],
+        c: coordinates[6..8].to_vec(),
+    }
+}
+
+/// Builds the `RawProof` the operator asserts on chain from a final-spv receipt. Fails if the
+/// proof does not verify, as the assertions of an invalid proof can be disproven.
+pub fn final_spv_raw_proof(
+    receipt: &Risc0Groth16Receipt,
+    method_id: &[u8; 32],
+    vk: &VerifyingKey<Bn254>,
+) -> Result<RawProof, String> {
+    if vk.gamma_abc_g1.len() != 2 {
+        return Err("Verifying key must have a single public input".to_string());
+    }
+
+    let raw_proof = RawProof {
+        proof: proof_from_seal(&receipt.seal)?,
+        public: vec![final_spv_public_input(method_id, &receipt.journal)],
+        vk: vk.clone(),
+    };
+    match Groth16::<Bn254>::verify(vk, &raw_proof.public, &raw_proof.proof) {
+        Ok(true) => Ok(raw_proof),
+        _ => Err("Proof does not verify for the journal and method id".to_string()),
+    }
+}
+
+fn fq_from_decimal(value: &str) -> Result<Fq, String> {
+    let value = BigUint::from_str(value).map_err(|e| format!("Invalid coordinate: {e}"))?;
+    if value >= Fq::MODULUS.into() {
+        return Err("Coordinate is not a field element".to_string());
+    }
+    Ok(Fq::from(value))
+}
+
+fn fq_to_decimal(value: &Fq) -> String {
+    BigUint::from(value.into_bigint()).to_string()
+}
+
+fn g1_from_json(point: &[String]) -> Result<G1Affine, String> {
+    if point.len() != 3 || fq_from_decimal(&point[2])? != Fq::from(1u8) {
+        return Err("G1 point must be [x, y, 1]".to_string());
+    }
+    let point = G1Affine::new_unchecked(fq_from_decimal(&point[0])?, fq_from_decimal(&point[1])?);
+    if !point.is_on_curve() {
+        return Err("G1 point is not on the curve".to_string());
+    }
+    Ok(point)
+}
+
+fn g1_to_json(point: &G1Affine) -> Vec<String> {
+    vec![
+        fq_to_decimal(&point.x),
+        fq_to_decimal(&point.y),
+        "1".to_string(),
+    ]
+}
+
+fn g2_from_json(point: &[Vec<String>]) -> Result<G2Affine, String> {
+    let fq2 = |coordinate: &[String]| -> Result<Fq2, String> {
+        if coordinate.len() != 2 {
+            return Err("Fq2 element must be [c0, c1]".to_string());
+        }
+        Ok(Fq2::new(
+            fq_from_decimal(&coordinate[0])?,
+            fq_from_decimal(&coordinate[1])?,
+        ))
+    };
+    if point.len() != 3 || fq2(&point[2])? != Fq2::new(Fq::from(1u8), Fq::zero()) {
+        return Err("G2 point must be [x, y, [1, 0]]".to_string());
+    }
+    let point = G2Affine::new_unchecked(fq2(&point[0])?, fq2(&point[1])?);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err("G2 point is not on the curve".to_string());
+    }
+    Ok(point)
+}
+
+fn g2_to_json(point: &G2Affine) -> Vec<Vec<String>> {
+    vec![
+        vec![fq_to_decimal(&point.x.c0), fq_to_decimal(&point.x.c1)],
+        vec![fq_to_decimal(&point.y.c0), fq_to_decimal(&point.y.c1)],
+        vec!["1".to_string(), "0".to_string()],
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_crypto_primitives::snark::CircuitSpecificSetupSNARK;
+    use ark_relations::{
+        lc,
+        r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError, Variable},
+    };
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
+    use bitvm::chunk::{
+        api::{
+            api_generate_full_tapscripts, api_generate_partial_script, generate_signatures,
+            num_assertions, num_pubs, validate_assertions, PublicKeys,
+        },
+        keys::ChunkSecretKeys,
+    };
+    use risc0_zkvm::compute_image_id;
+
+    use super::*;
+
+    const FINAL_SPV_JOURNAL: [u8; 32] = [0x2a; 32];
+    const MOCK_SECRET: &str = "a138982ce17ac813d505a5b40b665d404e9528e7";
+
+    // Stands in for the risc0 to BitVM Groth16 circuit: proves knowledge of the public input.
+    #[derive(Clone, Copy)]
+    struct PublicInputCircuit {
+        public_input: Fr,
+    }
+
+    impl ConstraintSynthesizer<Fr> for PublicInputCircuit {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let witness = cs.new_witness_variable(|| Ok(self.public_input))?;
+            let public_input = cs.new_input_variable(|| Ok(self.public_input))?;
+            cs.enforce_constraint(lc!() + witness, lc!() + Variable::One, lc!() + public_input)
+        }
+    }
+
+    fn final_spv_method_id() -> [u8; 32] {
+        compute_image_id(include_bytes!("../elfs/mainnet-final-spv-guest.bin"))
+            .unwrap()
+            .as_bytes()
+            .try_into()
+            .unwrap()
+    }
+
+    // Receipt of the final-spv image and the verifying key of the circuit it was proven with,
+    // deterministic so that it can serve as a fixture.
+    fn fixture_receipt(method_id: &[u8; 32]) -> (Risc0Groth16Receipt, VerifyingKey<Bn254>) {
+        let mut rng = StdRng::seed_from_u64(0);
+        let circuit = PublicInputCircuit {
+            public_input: final_spv_public_input(method_id, &FINAL_SPV_JOURNAL),
+        };
+        let (pk, vk) = Groth16::<Bn254>::setup(circuit, &mut rng).unwrap();
+        let proof = Groth16::<Bn254>::prove(&pk, circuit, &mut rng).unwrap();
+
+        let receipt = Risc0Groth16Receipt {
+            seal: seal_from_proof(&proof),
+            journal: FINAL_SPV_JOURNAL.to_vec(),
+        };
+        let vk_json = serde_json::to_string(&VerifyingKeyJson::from(&vk)).unwrap();
+        (receipt, verifying_key_from_json(&vk_json).unwrap())
+    }
+
+    #[test]
+    fn test_seal_conversion() {
+        let method_id = final_spv_method_id();
+        let (receipt, vk) = fixture_receipt(&method_id);
+
+        let proof = proof_from_seal(&receipt.seal).unwrap();
+        assert_eq!(seal_from_proof(&proof), receipt.seal);
+        assert_eq!(
+            VerifyingKey::try_from(&VerifyingKeyJson::from(&vk)).unwrap(),
+            vk
+        );
+
+        let mut seal = receipt.seal.clone();
+        seal.b[0].swap(0, 1);
+        assert!(proof_from_seal(&seal).is_err());
+        seal.b[0].pop();
+        assert!(proof_from_seal(&seal).is_err());
+    }
+
+    #[test]
+    fn test_final_spv_raw_proof() {
+        let method_id = final_spv_method_id();
+        let (receipt, vk) = fixture_receipt(&method_id);
+
+        let raw_proof = final_spv_raw_proof(&receipt, &method_id, &vk).unwrap();
+        assert_eq!(raw_proof.public.len(), num_pubs(&vk));
+        assert_eq!(
+            raw_proof.public[0],
+            final_spv_public_input(&method_id, &FINAL_SPV_JOURNAL)
+        );
+
+        let mut tampered_receipt = receipt.clone();
+        tampered_receipt.journal[0] ^= 1;
+        assert!(final_spv_raw_proof(&tampered_receipt, &method_id, &vk).is_err());
+        assert!(final_spv_raw_proof(&receipt, &[0; 32], &vk).is_err());
+    }
+
+    // Proof and verifying key from the risc0-groth16 test data, generated with snarkjs rather than
+    // arkworks.
+    fn risc0_groth16_fixture() -> RawProof {
+        let public: Vec<String> =
+            serde_json::from_str(include_str!("../data/risc0-groth16/public.json")).unwrap();
+        RawProof {
+            proof: proof_from_json(include_str!("../data/risc0-groth16/proof.json")).unwrap(),
+            public: public
+                .iter()
+                .map(|input| Fr::from(BigUint::from_str(input).unwrap()))
+                .collect(),
+            vk: verifying_key_from_json(include_str!(
+                "../data/risc0-groth16/verification_key.json"
+            ))
+            .unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_risc0_groth16_fixture_verifies() {
+        let raw_proof = risc0_groth16_fixture();
+        assert_eq!(raw_proof.public, vec![Fr::from(33u8)]);
+        assert!(
+            Groth16::<Bn254>::verify(&raw_proof.vk, &raw_proof.public, &raw_proof.proof).unwrap()
+        );
+
+        // the seal the bridge reads the proof from converts back to the same proof
+        let proof = proof_from_seal(&seal_from_proof(&raw_proof.proof)).unwrap();
+        assert_eq!(proof, raw_proof.proof);
+        assert!(Groth16::<Bn254>::verify(&raw_proof.vk, &raw_proof.public, &proof).unwrap());
+        assert!(!Groth16::<Bn254>::verify(&raw_proof.vk, &[Fr::from(34u8)], &proof).unwrap());
+    }
+
+    #[test]
+    #[ignore = "generates all disprove scripts, which takes several minutes"]
+    fn test_risc0_groth16_fixture_passes_validate_assertions() {
+        let raw_proof = risc0_groth16_fixture();
+        let vk = raw_proof.vk;
+
+        let num_pubs = num_pubs(&vk);
+        let secrets = ChunkSecretKeys::from_strings(
+            num_pubs,
+            &(0..num_assertions(num_pubs))
+                .map(|index| format!("{MOCK_SECRET}{:04x}", index))
+                .collect::<Vec<String>>(),
+        );
+        let public_keys: PublicKeys = secrets.public_keys().into();
+        let disprove_scripts =
+            api_generate_full_tapscripts(public_keys.clone(), &api_generate_partial_script(&vk));
+
+        let signatures =
+            generate_signatures(raw_proof.proof, raw_proof.public, &vk, &secrets).unwrap();
+        assert!(validate_assertions(&vk, signatures, public_keys, &disprove_scripts).is_none());
+    }
+}
@@ -12,6 +12,7 @@ use sha2::Sha256;
 use std::{env, fs};
 
 pub mod docker;
+pub mod groth16;
 
 const HEADER_CHAIN_GUEST_ELF: &[u8] = {
     match option_env!("BITCOIN_NETWORK") {